@@ -0,0 +1,749 @@
+//! Bulk export of the indexed data for a range of blocks, meant to be loaded in
+//! external analytics tools. The records are streamed block by block as
+//! newline-delimited JSON or CSV, without going through the GraphQL limits.
+//! The blocks are only looked up as the stream is polled.
+
+use crate::db::{
+    indexing::{ExplorerAddress, ExplorerBlock, ExplorerTransaction},
+    ExplorerDb, Ref,
+};
+use chain_impl_mockchain::{
+    block::{ChainLength, Epoch, HeaderHash},
+    certificate::Certificate,
+    vote::Payload,
+};
+use futures::{Stream, StreamExt as _};
+use jormungandr_lib::interfaces::CertificateToBech32Error;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, sync::Arc};
+use thiserror::Error;
+use warp::{
+    http::StatusCode,
+    hyper::Body,
+    reply::{self, Response},
+    Filter, Rejection, Reply,
+};
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("epoch {0} not found")]
+    EpochNotFound(Epoch),
+    #[error("invalid range: {from} is greater than {to}")]
+    InvalidRange { from: u32, to: u32 },
+    #[error("a range can be given either by chain length or by epoch, not both")]
+    AmbiguousRange,
+    #[error("the tip {0} is not indexed")]
+    TipNotIndexed(HeaderHash),
+    #[error("no block indexed at chain length {0}")]
+    BlockNotIndexed(ChainLength),
+    #[error("could not encode the certificate of the transaction {transaction}")]
+    Certificate {
+        transaction: String,
+        #[source]
+        source: CertificateToBech32Error,
+    },
+    #[error("could not serialize a record")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Ndjson,
+    Csv,
+}
+
+/// Query parameters of the export endpoints. The bounds are inclusive, and
+/// default to the whole main branch.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExportQuery {
+    from: Option<u32>,
+    to: Option<u32>,
+    from_epoch: Option<Epoch>,
+    to_epoch: Option<Epoch>,
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ExportKind {
+    Blocks,
+    Transactions,
+    Certificates,
+    Votes,
+}
+
+trait ExportRecord: Serialize {
+    const HEADER: &'static [&'static str];
+
+    fn csv_rows(&self) -> Vec<Vec<String>>;
+}
+
+#[derive(Serialize)]
+struct BlockRecord {
+    id: String,
+    chain_length: u32,
+    epoch: u32,
+    slot: u32,
+    parent: String,
+    producer: Option<String>,
+    transactions: usize,
+    total_input: u64,
+    total_output: u64,
+}
+
+#[derive(Serialize)]
+struct IoRecord {
+    address: String,
+    value: u64,
+}
+
+#[derive(Serialize)]
+struct TransactionRecord {
+    block: String,
+    chain_length: u32,
+    epoch: u32,
+    slot: u32,
+    id: String,
+    offset_in_block: u32,
+    inputs: Vec<IoRecord>,
+    outputs: Vec<IoRecord>,
+    certificate: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct CertificateRecord {
+    block: String,
+    chain_length: u32,
+    epoch: u32,
+    slot: u32,
+    transaction: String,
+    kind: &'static str,
+    certificate: String,
+}
+
+#[derive(Serialize)]
+struct VoteRecord {
+    block: String,
+    chain_length: u32,
+    epoch: u32,
+    slot: u32,
+    transaction: String,
+    voter: String,
+    vote_plan: String,
+    proposal_index: u8,
+    payload_type: &'static str,
+    /// only known for public votes
+    choice: Option<u8>,
+}
+
+impl ExportRecord for BlockRecord {
+    const HEADER: &'static [&'static str] = &[
+        "id",
+        "chain_length",
+        "epoch",
+        "slot",
+        "parent",
+        "producer",
+        "transactions",
+        "total_input",
+        "total_output",
+    ];
+
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.id.clone(),
+            self.chain_length.to_string(),
+            self.epoch.to_string(),
+            self.slot.to_string(),
+            self.parent.clone(),
+            self.producer.clone().unwrap_or_default(),
+            self.transactions.to_string(),
+            self.total_input.to_string(),
+            self.total_output.to_string(),
+        ]]
+    }
+}
+
+impl ExportRecord for TransactionRecord {
+    const HEADER: &'static [&'static str] = &[
+        "block",
+        "chain_length",
+        "epoch",
+        "slot",
+        "transaction",
+        "offset_in_block",
+        "certificate",
+        "direction",
+        "index",
+        "address",
+        "value",
+    ];
+
+    /// one row per input and output, so the transactions can be loaded in a
+    /// flat table. Transactions without any inputs or outputs still get a row.
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        let common = vec![
+            self.block.clone(),
+            self.chain_length.to_string(),
+            self.epoch.to_string(),
+            self.slot.to_string(),
+            self.id.clone(),
+            self.offset_in_block.to_string(),
+            self.certificate.unwrap_or_default().to_string(),
+        ];
+
+        let io = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| ("input", index, input))
+            .chain(
+                self.outputs
+                    .iter()
+                    .enumerate()
+                    .map(|(index, output)| ("output", index, output)),
+            );
+
+        let mut rows: Vec<Vec<String>> = io
+            .map(|(direction, index, record)| {
+                let mut row = common.clone();
+                row.extend([
+                    direction.to_string(),
+                    index.to_string(),
+                    record.address.clone(),
+                    record.value.to_string(),
+                ]);
+                row
+            })
+            .collect();
+
+        if rows.is_empty() {
+            let mut row = common;
+            row.extend(std::iter::repeat(String::new()).take(4));
+            rows.push(row);
+        }
+
+        rows
+    }
+}
+
+impl ExportRecord for CertificateRecord {
+    const HEADER: &'static [&'static str] = &[
+        "block",
+        "chain_length",
+        "epoch",
+        "slot",
+        "transaction",
+        "kind",
+        "certificate",
+    ];
+
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.block.clone(),
+            self.chain_length.to_string(),
+            self.epoch.to_string(),
+            self.slot.to_string(),
+            self.transaction.clone(),
+            self.kind.to_string(),
+            self.certificate.clone(),
+        ]]
+    }
+}
+
+impl ExportRecord for VoteRecord {
+    const HEADER: &'static [&'static str] = &[
+        "block",
+        "chain_length",
+        "epoch",
+        "slot",
+        "transaction",
+        "voter",
+        "vote_plan",
+        "proposal_index",
+        "payload_type",
+        "choice",
+    ];
+
+    fn csv_rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.block.clone(),
+            self.chain_length.to_string(),
+            self.epoch.to_string(),
+            self.slot.to_string(),
+            self.transaction.clone(),
+            self.voter.clone(),
+            self.vote_plan.clone(),
+            self.proposal_index.to_string(),
+            self.payload_type.to_string(),
+            self.choice.map(|c| c.to_string()).unwrap_or_default(),
+        ]]
+    }
+}
+
+pub fn filter(
+    db: ExplorerDb,
+    address_bech32_prefix: String,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let with_db = warp::any().map(move || db.clone());
+    let with_prefix = warp::any().map(move || address_bech32_prefix.clone());
+
+    let export = |kind: ExportKind| {
+        warp::get()
+            .and(warp::query::<ExportQuery>())
+            .and(with_db.clone())
+            .and(with_prefix.clone())
+            .and_then(move |query, db, prefix| handle_export(kind, query, db, prefix))
+    };
+
+    let blocks = warp::path!("blocks").and(export(ExportKind::Blocks));
+    let transactions = warp::path!("transactions").and(export(ExportKind::Transactions));
+    let certificates = warp::path!("certificates").and(export(ExportKind::Certificates));
+    let votes = warp::path!("votes").and(export(ExportKind::Votes));
+
+    warp::path!("export" / ..)
+        .and(blocks.or(transactions).or(certificates).or(votes))
+        .boxed()
+}
+
+async fn handle_export(
+    kind: ExportKind,
+    query: ExportQuery,
+    db: ExplorerDb,
+    prefix: String,
+) -> Result<Response, Infallible> {
+    let (tip, state_ref) = db.get_tip().await;
+    let tip_chain_length = match state_ref.state().blocks.lookup(&tip) {
+        Some(block) => block.chain_length(),
+        None => {
+            let error = ExportError::TipNotIndexed(tip);
+            tracing::error!(%error, "cannot export");
+            return Ok(
+                reply::with_status(error.to_string(), StatusCode::INTERNAL_SERVER_ERROR)
+                    .into_response(),
+            );
+        }
+    };
+
+    let (from, to) = match resolve_range(&state_ref, &query, tip_chain_length) {
+        Ok(range) => range,
+        Err(error) => {
+            return Ok(
+                reply::with_status(error.to_string(), StatusCode::BAD_REQUEST).into_response(),
+            )
+        }
+    };
+
+    let format = query.format;
+    let lookup = move |chain_length: ChainLength| {
+        let state = state_ref.state();
+        state
+            .get_block_hash(chain_length)
+            .and_then(|hash| state.blocks.lookup(&hash).map(Arc::clone))
+            .ok_or(ExportError::BlockNotIndexed(chain_length))
+    };
+    let body = export_stream(kind, format, from, to, prefix, lookup).inspect(|chunk| {
+        if let Err(error) = chunk {
+            tracing::error!(%error, "export interrupted");
+        }
+    });
+
+    let content_type = match format {
+        ExportFormat::Ndjson => "application/x-ndjson",
+        ExportFormat::Csv => "text/csv",
+    };
+
+    Ok(reply::with_header(
+        Response::new(Body::wrap_stream(body)),
+        "content-type",
+        content_type,
+    )
+    .into_response())
+}
+
+/// The chunks of the export, one per block of the range after the CSV header.
+/// A block is only looked up when its chunk is polled. The stream ends with
+/// an error if a record cannot be made, which aborts the response.
+fn export_stream<F>(
+    kind: ExportKind,
+    format: ExportFormat,
+    from: ChainLength,
+    to: ChainLength,
+    prefix: String,
+    lookup: F,
+) -> impl Stream<Item = Result<String, ExportError>>
+where
+    F: Fn(ChainLength) -> Result<Arc<ExplorerBlock>, ExportError>,
+{
+    let header = match format {
+        ExportFormat::Ndjson => None,
+        ExportFormat::Csv => Some(csv_line(match kind {
+            ExportKind::Blocks => BlockRecord::HEADER,
+            ExportKind::Transactions => TransactionRecord::HEADER,
+            ExportKind::Certificates => CertificateRecord::HEADER,
+            ExportKind::Votes => VoteRecord::HEADER,
+        })),
+    };
+
+    let chunks = futures::stream::iter(u32::from(from)..=u32::from(to))
+        .map(move |chain_length| {
+            let block = lookup(chain_length.into())?;
+            let context = RecordContext {
+                block: &block,
+                prefix: &prefix,
+            };
+
+            match kind {
+                ExportKind::Blocks => render(&[context.block_record()], format),
+                ExportKind::Transactions => render(&context.transaction_records(), format),
+                ExportKind::Certificates => render(&context.certificate_records()?, format),
+                ExportKind::Votes => render(&context.vote_records(), format),
+            }
+        })
+        // nothing is sent after an error
+        .scan(false, |failed, chunk| {
+            let next = (!*failed).then_some(chunk);
+            *failed = next.as_ref().map_or(true, Result::is_err);
+            futures::future::ready(next)
+        });
+
+    futures::stream::iter(header.map(Ok)).chain(chunks)
+}
+
+fn resolve_range(
+    state_ref: &Ref,
+    query: &ExportQuery,
+    tip: ChainLength,
+) -> Result<(ChainLength, ChainLength), ExportError> {
+    let state = state_ref.state();
+
+    let by_epoch = query.from_epoch.is_some() || query.to_epoch.is_some();
+    let by_chain_length = query.from.is_some() || query.to.is_some();
+
+    let (from, to) = match (by_chain_length, by_epoch) {
+        (true, true) => return Err(ExportError::AmbiguousRange),
+        (_, false) => (
+            query
+                .from
+                .map(ChainLength::from)
+                .unwrap_or_else(|| 0.into()),
+            query.to.map(ChainLength::from).unwrap_or(tip),
+        ),
+        (false, true) => {
+            let chain_length_of = |epoch: Epoch, first: bool| {
+                state
+                    .get_epoch(epoch)
+                    .map(|data| {
+                        if first {
+                            data.first_block
+                        } else {
+                            data.last_block
+                        }
+                    })
+                    .and_then(|hash| state.blocks.lookup(&hash).map(|b| b.chain_length()))
+                    .ok_or(ExportError::EpochNotFound(epoch))
+            };
+
+            let from = match query.from_epoch {
+                Some(epoch) => chain_length_of(epoch, true)?,
+                None => 0.into(),
+            };
+            let to = match query.to_epoch {
+                Some(epoch) => chain_length_of(epoch, false)?,
+                None => tip,
+            };
+            (from, to)
+        }
+    };
+
+    if from > to {
+        return Err(ExportError::InvalidRange {
+            from: from.into(),
+            to: to.into(),
+        });
+    }
+
+    Ok((from, std::cmp::min(u32::from(to), u32::from(tip)).into()))
+}
+
+struct RecordContext<'a> {
+    block: &'a ExplorerBlock,
+    prefix: &'a str,
+}
+
+impl<'a> RecordContext<'a> {
+    fn address(&self, address: &ExplorerAddress) -> String {
+        match address {
+            ExplorerAddress::New(addr) => {
+                chain_addr::AddressReadable::from_address(self.prefix, addr).to_string()
+            }
+            ExplorerAddress::Old(addr) => addr.to_string(),
+        }
+    }
+
+    fn block_record(&self) -> BlockRecord {
+        use crate::db::indexing::BlockProducer;
+
+        BlockRecord {
+            id: self.block.id().to_string(),
+            chain_length: self.block.chain_length().into(),
+            epoch: self.block.date().epoch,
+            slot: self.block.date().slot_id,
+            parent: self.block.parent_hash.to_string(),
+            producer: match self.block.producer() {
+                BlockProducer::None => None,
+                BlockProducer::StakePool(pool) => Some(pool.to_string()),
+                BlockProducer::BftLeader(leader) => Some(leader.as_public_key().to_string()),
+            },
+            transactions: self.block.transactions.len(),
+            total_input: self.block.total_input.0,
+            total_output: self.block.total_output.0,
+        }
+    }
+
+    /// the transactions of the block, in the order they were included
+    fn transactions(&self) -> Vec<&'a ExplorerTransaction> {
+        let mut transactions: Vec<_> = self.block.transactions.values().collect();
+        transactions.sort_unstable_by_key(|tx| tx.offset_in_block);
+        transactions
+    }
+
+    fn transaction_records(&self) -> Vec<TransactionRecord> {
+        self.transactions()
+            .into_iter()
+            .map(|tx| TransactionRecord {
+                block: self.block.id().to_string(),
+                chain_length: self.block.chain_length().into(),
+                epoch: self.block.date().epoch,
+                slot: self.block.date().slot_id,
+                id: tx.id().to_string(),
+                offset_in_block: tx.offset_in_block,
+                inputs: tx
+                    .inputs()
+                    .iter()
+                    .map(|input| IoRecord {
+                        address: self.address(&input.address),
+                        value: input.value.0,
+                    })
+                    .collect(),
+                outputs: tx
+                    .outputs()
+                    .iter()
+                    .map(|output| IoRecord {
+                        address: self.address(&output.address),
+                        value: output.value.0,
+                    })
+                    .collect(),
+                certificate: tx.certificate.as_ref().map(certificate_kind),
+            })
+            .collect()
+    }
+
+    fn certificate_records(&self) -> Result<Vec<CertificateRecord>, ExportError> {
+        self.transactions()
+            .into_iter()
+            .filter_map(|tx| tx.certificate.as_ref().map(|cert| (tx, cert)))
+            .map(|(tx, cert)| {
+                let certificate = jormungandr_lib::interfaces::Certificate::from(cert.clone())
+                    .to_bech32m()
+                    .map_err(|source| ExportError::Certificate {
+                        transaction: tx.id().to_string(),
+                        source,
+                    })?;
+                Ok(CertificateRecord {
+                    block: self.block.id().to_string(),
+                    chain_length: self.block.chain_length().into(),
+                    epoch: self.block.date().epoch,
+                    slot: self.block.date().slot_id,
+                    transaction: tx.id().to_string(),
+                    kind: certificate_kind(cert),
+                    certificate,
+                })
+            })
+            .collect()
+    }
+
+    fn vote_records(&self) -> Vec<VoteRecord> {
+        self.transactions()
+            .into_iter()
+            .filter_map(|tx| match &tx.certificate {
+                Some(Certificate::VoteCast(vote_cast)) => Some((tx, vote_cast)),
+                _ => None,
+            })
+            .map(|(tx, vote_cast)| {
+                let (payload_type, choice) = match vote_cast.payload() {
+                    Payload::Public { choice } => ("public", Some(choice.as_byte())),
+                    Payload::Private { .. } => ("private", None),
+                };
+
+                VoteRecord {
+                    block: self.block.id().to_string(),
+                    chain_length: self.block.chain_length().into(),
+                    epoch: self.block.date().epoch,
+                    slot: self.block.date().slot_id,
+                    transaction: tx.id().to_string(),
+                    voter: tx
+                        .inputs()
+                        .first()
+                        .map(|input| self.address(&input.address))
+                        .unwrap_or_default(),
+                    vote_plan: vote_cast.vote_plan().to_string(),
+                    proposal_index: vote_cast.proposal_index(),
+                    payload_type,
+                    choice,
+                }
+            })
+            .collect()
+    }
+}
+
+fn certificate_kind(certificate: &Certificate) -> &'static str {
+    match certificate {
+        Certificate::StakeDelegation(_) => "stake_delegation",
+        Certificate::OwnerStakeDelegation(_) => "owner_stake_delegation",
+        Certificate::PoolRegistration(_) => "pool_registration",
+        Certificate::PoolRetirement(_) => "pool_retirement",
+        Certificate::PoolUpdate(_) => "pool_update",
+        Certificate::VotePlan(_) => "vote_plan",
+        Certificate::VoteCast(_) => "vote_cast",
+        Certificate::VoteTally(_) => "vote_tally",
+        Certificate::UpdateProposal(_) => "update_proposal",
+        Certificate::UpdateVote(_) => "update_vote",
+        Certificate::MintToken(_) => "mint_token",
+        Certificate::EvmMapping(_) => "evm_mapping",
+    }
+}
+
+fn render<R: ExportRecord>(records: &[R], format: ExportFormat) -> Result<String, ExportError> {
+    let mut out = String::new();
+
+    for record in records {
+        match format {
+            ExportFormat::Ndjson => {
+                out.push_str(&serde_json::to_string(record)?);
+                out.push('\n');
+            }
+            ExportFormat::Csv => {
+                for row in record.csv_rows() {
+                    out.push_str(&csv_line(&row));
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| csv_escape(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn transaction(inputs: Vec<IoRecord>, outputs: Vec<IoRecord>) -> TransactionRecord {
+        TransactionRecord {
+            block: "block".to_string(),
+            chain_length: 1,
+            epoch: 0,
+            slot: 1,
+            id: "tx".to_string(),
+            offset_in_block: 0,
+            inputs,
+            outputs,
+            certificate: None,
+        }
+    }
+
+    fn io(address: &str, value: u64) -> IoRecord {
+        IoRecord {
+            address: address.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_escaped() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_line(&["a", "b\nc"]), "a,\"b\nc\"\n");
+    }
+
+    #[test]
+    fn transactions_have_one_csv_row_per_input_and_output() {
+        let record = transaction(vec![io("in", 10)], vec![io("out1", 4), io("out2", 6)]);
+        let csv = render(&[record], ExportFormat::Csv).unwrap();
+        assert_eq!(
+            csv,
+            "block,1,0,1,tx,0,,input,0,in,10\n\
+             block,1,0,1,tx,0,,output,0,out1,4\n\
+             block,1,0,1,tx,0,,output,1,out2,6\n"
+        );
+
+        let csv = render(&[transaction(vec![], vec![])], ExportFormat::Csv).unwrap();
+        assert_eq!(csv, "block,1,0,1,tx,0,,,,,\n");
+        assert_eq!(
+            csv.trim_end().split(',').count(),
+            TransactionRecord::HEADER.len()
+        );
+    }
+
+    #[test]
+    fn ndjson_has_one_line_per_record() {
+        let records = [
+            transaction(vec![], vec![]),
+            transaction(vec![io("in", 1)], vec![]),
+        ];
+        let ndjson = render(&records, ExportFormat::Ndjson).unwrap();
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["inputs"][0]["value"], 1);
+    }
+
+    #[test]
+    fn export_stops_at_the_first_missing_block() {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&lookups);
+        let stream = export_stream(
+            ExportKind::Blocks,
+            ExportFormat::Csv,
+            0.into(),
+            999.into(),
+            String::new(),
+            move |chain_length| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Err(ExportError::BlockNotIndexed(chain_length))
+            },
+        );
+        let chunks: Vec<_> = futures::executor::block_on(stream.collect());
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].as_ref().unwrap(), &csv_line(BlockRecord::HEADER));
+        assert!(matches!(chunks[1], Err(ExportError::BlockNotIndexed(_))));
+        // the blocks after the missing one are never looked up
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+    }
+}
@@ -2,7 +2,7 @@ use super::{
     config_param::ConfigParams,
     error::ApiError,
     extract_context,
-    scalars::{PayloadType, PoolId, PublicKey, TimeOffsetSeconds, VotePlanId},
+    scalars::{PayloadType, PoolId, PublicKey, TimeOffsetSeconds, Value, VotePlanId},
     Address, BftLeader, BlockDate, ExplorerAddress, Pool, Proposal, TaxType,
 };
 use async_graphql::{Context, FieldResult, Object, Union};
@@ -264,6 +264,28 @@ impl MintToken {
     pub async fn name(&self) -> String {
         format!("{:?}", self.0.name)
     }
+
+    /// The identifier of the minted token
+    pub async fn token_id(&self) -> String {
+        chain_impl_mockchain::tokens::identifier::TokenIdentifier {
+            token_name: self.0.name.clone(),
+            policy_hash: self.0.policy.hash(),
+        }
+        .to_string()
+    }
+
+    /// The account receiving the minted tokens
+    pub async fn to(&self, context: &Context<'_>) -> Address {
+        let discrimination = extract_context(context).db.blockchain_config.discrimination;
+        Address::from(&ExplorerAddress::New(chain_addr::Address(
+            discrimination,
+            chain_addr::Kind::Account(self.0.to.clone().into()),
+        )))
+    }
+
+    pub async fn value(&self) -> Value {
+        Value(self.0.value)
+    }
 }
 
 #[Object]
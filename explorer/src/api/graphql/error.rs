@@ -14,4 +14,6 @@ pub enum ApiError {
     InvalidCursor(String),
     #[error("invalid address {0}")]
     InvalidAddress(String),
+    #[error("invalid token identifier {0}")]
+    InvalidTokenId(String),
 }
@@ -14,13 +14,14 @@ use self::{
     scalars::{
         BlockCount, ChainLength, EpochNumber, ExternalProposalId, IndexCursor, NonZero,
         PayloadType, PoolCount, PoolId, PublicKey, Slot, TransactionCount, Value, VoteOptionRange,
-        VotePlanId, VotePlanStatusCount, Weight,
+        VotePlanId, VotePlanStatusCount, VoteStatusCount, Weight,
     },
 };
 use crate::db::{
     indexing::{
-        BlockProducer, EpochData, ExplorerAddress, ExplorerBlock, ExplorerTransaction,
-        ExplorerVote, ExplorerVotePlan, ExplorerVoteTally, StakePoolData,
+        BlockProducer, EpochData, ExplorerAddress, ExplorerBlock, ExplorerToken,
+        ExplorerTransaction, ExplorerVote, ExplorerVotePlan, ExplorerVoteTally,
+        ExplorerVoteTurnout, ExplorerVotingPower, StakePoolData,
     },
     persistent_sequence::PersistentSequence,
     ExplorerDb, Settings as ChainSettings,
@@ -36,6 +37,7 @@ use chain_impl_mockchain::{
     certificate,
     fragment::FragmentId,
     key::BftLeaderId,
+    stake::StakeControl,
     vote::{EncryptedVote, ProofOfCorrectVote},
 };
 use std::{
@@ -222,7 +224,8 @@ impl Branch {
     ) -> FieldResult<
         Connection<IndexCursor, VotePlanStatus, ConnectionFields<VotePlanStatusCount>, EmptyFields>,
     > {
-        let mut vote_plans = self.state.state().get_vote_plans();
+        let state = self.state.state();
+        let mut vote_plans = state.get_vote_plans();
 
         vote_plans.sort_unstable_by_key(|(id, _data)| id.clone());
 
@@ -273,7 +276,10 @@ impl Branch {
                                 let (_pool_id, vote_plan_data) =
                                     &vote_plans[usize::try_from(i).unwrap()];
                                 (
-                                    VotePlanStatus::vote_plan_from_data(Arc::clone(vote_plan_data)),
+                                    VotePlanStatus::vote_plan_from_data(
+                                        Arc::clone(vote_plan_data),
+                                        state.stake_control(),
+                                    ),
                                     i,
                                 )
                             })
@@ -293,6 +299,16 @@ impl Branch {
         .await
     }
 
+    /// The native tokens minted in this branch, ordered by token identifier
+    pub async fn all_tokens(&self) -> Vec<Token> {
+        let mut tokens = self.state.state().get_tokens();
+        tokens.sort_unstable_by(|(a, _), (b, _)| a.to_string().cmp(&b.to_string()));
+        tokens
+            .into_iter()
+            .map(|(_id, token)| Token(token))
+            .collect()
+    }
+
     pub async fn all_stake_pools(
         &self,
         first: Option<i32>,
@@ -952,6 +968,32 @@ impl Address {
     async fn delegation(&self, _context: &Context<'_>) -> FieldResult<Pool> {
         Err(ApiError::Unimplemented.into())
     }
+
+    /// All the votes cast by this address in the main branch, across every vote plan
+    async fn votes(&self, context: &Context<'_>) -> Vec<VoterParticipation> {
+        let (_, state_ref) = extract_context(context).db.get_tip().await;
+        let state = state_ref.state();
+        // the voting power is the same for all the votes of a vote plan
+        let mut voting_powers = std::collections::HashMap::new();
+
+        state
+            .get_votes_by_address(&self.id)
+            .into_iter()
+            .map(|(vote_plan, proposal_index, vote)| {
+                let proposal = &vote_plan.proposals[proposal_index as usize];
+                let voting_power = *voting_powers
+                    .entry(vote_plan.id.clone())
+                    .or_insert_with(|| vote_plan.voting_power(state.stake_control()).of(&self.id));
+                VoterParticipation {
+                    vote_plan_id: VotePlanId::from(vote_plan.id.clone()),
+                    proposal_id: ExternalProposalId::from(proposal.proposal_id.clone()),
+                    proposal_index: proposal_index.into(),
+                    payload: VotePayloadStatus::from(vote.as_ref()),
+                    voting_power: Value::from(voting_power),
+                }
+            })
+            .collect()
+    }
 }
 
 pub struct TaxType(chain_impl_mockchain::rewards::TaxType);
@@ -1004,6 +1046,69 @@ impl Proposal {
     }
 }
 
+/// An account the token was minted to
+#[derive(SimpleObject)]
+pub struct TokenMint {
+    address: Address,
+    /// total amount of the token minted to the account
+    minted: Value,
+}
+
+/// A native token minted in the blockchain
+pub struct Token(Arc<ExplorerToken>);
+
+impl Token {
+    async fn from_id(id: String, context: &Context<'_>) -> FieldResult<Token> {
+        let token_id: chain_impl_mockchain::tokens::identifier::TokenIdentifier = id
+            .parse()
+            .map_err(|_| -> FieldError { ApiError::InvalidTokenId(id.clone()).into() })?;
+        extract_context(context)
+            .db
+            .get_token_by_id(&token_id)
+            .await
+            .map(Token)
+            .ok_or_else(|| ApiError::NotFound(format!("Token with id {} not found", id)).into())
+    }
+}
+
+#[Object]
+impl Token {
+    /// The token identifier, made of the minting policy hash and the token name
+    pub async fn id(&self) -> String {
+        self.0.id.to_string()
+    }
+
+    /// Total amount of the token minted so far
+    pub async fn supply(&self) -> Value {
+        Value(self.0.supply)
+    }
+
+    /// The accounts the token was minted to, with the total amount minted to each.
+    /// No transaction output carries tokens, so these are the token holders
+    pub async fn minted_to(&self, context: &Context<'_>) -> Vec<TokenMint> {
+        let discrimination = extract_context(context).db.blockchain_config.discrimination;
+        self.0
+            .minted_to
+            .iter()
+            .map(|(account, value)| TokenMint {
+                address: Address::from(&ExplorerAddress::New(chain_addr::Address(
+                    discrimination,
+                    chain_addr::Kind::Account(account.clone().into()),
+                ))),
+                minted: Value(**value),
+            })
+            .collect()
+    }
+
+    /// The mint token transactions of this token, oldest first
+    pub async fn mints(&self) -> Vec<Transaction> {
+        (0..self.0.mints.len())
+            .filter_map(|i| self.0.mints.get(i))
+            .map(|id| Transaction::from_valid_id(**id))
+            .collect()
+    }
+}
+
 #[derive(Clone)]
 pub struct Pool {
     id: certificate::PoolId,
@@ -1288,6 +1393,23 @@ pub enum VotePayloadStatus {
     Private(VotePayloadPrivateStatus),
 }
 
+impl From<&ExplorerVote> for VotePayloadStatus {
+    fn from(vote: &ExplorerVote) -> Self {
+        match vote {
+            ExplorerVote::Public(choice) => VotePayloadStatus::Public(VotePayloadPublicStatus {
+                choice: choice.as_byte().into(),
+            }),
+            ExplorerVote::Private {
+                proof,
+                encrypted_vote,
+            } => VotePayloadStatus::Private(VotePayloadPrivateStatus {
+                proof: proof.clone(),
+                encrypted_vote: encrypted_vote.clone(),
+            }),
+        }
+    }
+}
+
 // TODO do proper vote tally
 #[derive(Clone, SimpleObject)]
 pub struct TallyPublicStatus {
@@ -1307,6 +1429,58 @@ pub enum TallyStatus {
     Private(TallyPrivateStatus),
 }
 
+/// Participation in a proposal. The voting power is the stake of the voters at the
+/// time of the tally, or the current one if the proposal was not tallied yet
+#[derive(Clone, SimpleObject)]
+pub struct VoteTurnout {
+    votes_cast: VoteStatusCount,
+    voting_power_used: Value,
+    voting_power_available: Value,
+    /// ratio between the voting power used and the available one
+    participation: f64,
+}
+
+impl From<ExplorerVoteTurnout> for VoteTurnout {
+    fn from(turnout: ExplorerVoteTurnout) -> Self {
+        let participation = if turnout.voting_power_available == 0 {
+            0.0
+        } else {
+            turnout.voting_power_used as f64 / turnout.voting_power_available as f64
+        };
+
+        VoteTurnout {
+            votes_cast: turnout.votes_cast.into(),
+            voting_power_used: turnout.voting_power_used.into(),
+            voting_power_available: turnout.voting_power_available.into(),
+            participation,
+        }
+    }
+}
+
+/// Aggregated participation over all the proposals of a vote plan
+#[derive(Clone, SimpleObject)]
+pub struct VotePlanTurnout {
+    /// number of distinct addresses that voted for at least one proposal
+    voters: VoteStatusCount,
+    votes_cast: VoteStatusCount,
+    /// voting power of the distinct voters at the time of the tally, or the
+    /// current one if the vote plan was not tallied yet
+    voting_power_used: Value,
+    voting_power_available: Value,
+    participation: f64,
+}
+
+#[derive(Clone, SimpleObject)]
+pub struct VoterParticipation {
+    vote_plan_id: VotePlanId,
+    proposal_id: ExternalProposalId,
+    proposal_index: i32,
+    payload: VotePayloadStatus,
+    /// voting power of the voter at the time of the tally, or the current one
+    /// if the vote plan was not tallied yet
+    voting_power: Value,
+}
+
 #[derive(Clone, SimpleObject)]
 pub struct VotePlanStatus {
     id: VotePlanId,
@@ -1315,6 +1489,7 @@ pub struct VotePlanStatus {
     committee_end: BlockDate,
     payload_type: PayloadType,
     proposals: Vec<VoteProposalStatus>,
+    turnout: VotePlanTurnout,
 }
 
 impl VotePlanStatus {
@@ -1324,18 +1499,22 @@ impl VotePlanStatus {
     ) -> FieldResult<Self> {
         let vote_plan_id = chain_impl_mockchain::certificate::VotePlanId::from_str(&vote_plan_id.0)
             .map_err(|err| -> FieldError { ApiError::InvalidAddress(err.to_string()).into() })?;
-        if let Some(vote_plan) = extract_context(context)
-            .db
-            .get_vote_plan_by_id(&vote_plan_id)
-            .await
-        {
-            return Ok(Self::vote_plan_from_data(vote_plan));
+        let db = &extract_context(context).db;
+        if let Some(vote_plan) = db.get_vote_plan_by_id(&vote_plan_id).await {
+            let (_, state_ref) = db.get_tip().await;
+            return Ok(Self::vote_plan_from_data(
+                vote_plan,
+                state_ref.state().stake_control(),
+            ));
         }
 
         Err(ApiError::NotFound(format!("Vote plan with id {} not found", vote_plan_id)).into())
     }
 
-    pub fn vote_plan_from_data(vote_plan: Arc<ExplorerVotePlan>) -> Self {
+    pub fn vote_plan_from_data(vote_plan: Arc<ExplorerVotePlan>, stake: &StakeControl) -> Self {
+        let voting_power = vote_plan.voting_power(stake);
+        let turnout = VotePlanTurnout::compute(&vote_plan, &voting_power);
+
         let ExplorerVotePlan {
             id,
             vote_start,
@@ -1343,6 +1522,7 @@ impl VotePlanStatus {
             committee_end,
             payload_type,
             proposals,
+            voting_power: _,
         } = (*vote_plan).clone();
 
         VotePlanStatus {
@@ -1354,6 +1534,12 @@ impl VotePlanStatus {
             proposals: proposals
                 .into_iter()
                 .map(|proposal| VoteProposalStatus {
+                    turnout: voting_power.turnout(&proposal).into(),
+                    winning_options: proposal
+                        .tally
+                        .as_ref()
+                        .map(|tally| tally.winning_options().into_iter().map(i32::from).collect())
+                        .unwrap_or_default(),
                     proposal_id: ExternalProposalId::from(proposal.proposal_id),
                     options: VoteOptionRange::from(proposal.options),
                     tally: proposal.tally.map(|tally| match tally {
@@ -1373,27 +1559,47 @@ impl VotePlanStatus {
                     votes: proposal
                         .votes
                         .iter()
-                        .map(|(key, vote)| match vote.as_ref() {
-                            ExplorerVote::Public(choice) => VoteStatus {
-                                address: key.into(),
-                                payload: VotePayloadStatus::Public(VotePayloadPublicStatus {
-                                    choice: choice.as_byte().into(),
-                                }),
-                            },
-                            ExplorerVote::Private {
-                                proof,
-                                encrypted_vote,
-                            } => VoteStatus {
-                                address: key.into(),
-                                payload: VotePayloadStatus::Private(VotePayloadPrivateStatus {
-                                    proof: proof.clone(),
-                                    encrypted_vote: encrypted_vote.clone(),
-                                }),
-                            },
+                        .map(|(key, vote)| VoteStatus {
+                            address: key.into(),
+                            payload: VotePayloadStatus::from(vote.as_ref()),
                         })
                         .collect(),
                 })
                 .collect(),
+            turnout,
+        }
+    }
+}
+
+impl VotePlanTurnout {
+    fn compute(vote_plan: &ExplorerVotePlan, voting_power: &ExplorerVotingPower) -> Self {
+        let mut voters = std::collections::HashSet::new();
+        let mut votes_cast = 0u64;
+
+        for proposal in vote_plan.proposals.iter() {
+            for (address, _vote) in proposal.votes.iter() {
+                votes_cast += 1;
+                voters.insert(address.clone());
+            }
+        }
+
+        let voting_power_used = voters.iter().fold(0u64, |used, address| {
+            used.saturating_add(voting_power.of(address))
+        });
+        let voting_power_available = voting_power.available;
+
+        let participation = if voting_power_available == 0 {
+            0.0
+        } else {
+            voting_power_used as f64 / voting_power_available as f64
+        };
+
+        VotePlanTurnout {
+            voters: (voters.len() as u64).into(),
+            votes_cast: votes_cast.into(),
+            voting_power_used: voting_power_used.into(),
+            voting_power_available: voting_power_available.into(),
+            participation,
         }
     }
 }
@@ -1409,6 +1615,8 @@ pub struct VoteProposalStatus {
     proposal_id: ExternalProposalId,
     options: VoteOptionRange,
     tally: Option<TallyStatus>,
+    turnout: VoteTurnout,
+    winning_options: Vec<i32>,
     votes: Vec<VoteStatus>,
 }
 
@@ -1426,6 +1634,16 @@ impl VoteProposalStatus {
         self.tally.as_ref()
     }
 
+    pub async fn turnout(&self) -> &VoteTurnout {
+        &self.turnout
+    }
+
+    /// The options with the highest tally result, more than one in case of a tie.
+    /// Empty while the proposal is not tallied or if no stake was cast.
+    pub async fn winning_options(&self) -> &Vec<i32> {
+        &self.winning_options
+    }
+
     pub async fn votes(
         &self,
         first: Option<i32>,
@@ -1573,6 +1791,10 @@ impl Query {
     ) -> FieldResult<VotePlanStatus> {
         VotePlanStatus::vote_plan_from_id(VotePlanId(id), context).await
     }
+
+    pub async fn token(&self, context: &Context<'_>, id: String) -> FieldResult<Token> {
+        Token::from_id(id, context).await
+    }
 }
 
 pub struct Subscription;
@@ -1603,3 +1825,36 @@ pub struct EContext {
 fn extract_context<'a>(context: &Context<'a>) -> &'a EContext {
     context.data_unchecked::<EContext>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::indexing::test_utils::{vote_plan, voting_power};
+
+    #[test]
+    fn vote_plan_turnout_counts_distinct_voters() {
+        let vote_plan = vote_plan(1, &[&[(1, 0), (2, 1)], &[(2, 0), (3, 1)]]);
+        // the snapshot of the tally, the third voter had no stake left
+        let snapshot = voting_power(&[(1, 10), (2, 5)], 60);
+
+        let turnout = VotePlanTurnout::compute(&vote_plan, &snapshot);
+        assert_eq!(turnout.voters.0, "3");
+        assert_eq!(turnout.votes_cast.0, "4");
+        assert_eq!(
+            turnout.voting_power_used.0,
+            chain_impl_mockchain::value::Value(15)
+        );
+        assert_eq!(
+            turnout.voting_power_available.0,
+            chain_impl_mockchain::value::Value(60)
+        );
+        assert!((turnout.participation - 0.25).abs() < f64::EPSILON);
+
+        let turnout = VotePlanTurnout::compute(&vote_plan, &voting_power(&[], 0));
+        assert_eq!(
+            turnout.voting_power_used.0,
+            chain_impl_mockchain::value::Value(0)
+        );
+        assert!(turnout.participation.abs() < f64::EPSILON);
+    }
+}
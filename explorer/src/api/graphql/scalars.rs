@@ -90,6 +90,7 @@ impl ScalarType for PoolId {
     }
 }
 
+#[derive(Clone)]
 pub struct Value(pub InternalValue);
 
 #[Scalar]
@@ -240,6 +241,7 @@ impl ScalarType for VotePlanCount {
     }
 }
 
+#[derive(Clone)]
 pub struct VoteStatusCount(pub String);
 
 #[Scalar]
@@ -1,3 +1,4 @@
+mod export;
 pub mod graphql;
 
 use self::graphql::EContext;
@@ -74,7 +75,10 @@ pub fn filter(
     )
     .limit_depth(settings.query_depth_limit)
     .limit_complexity(settings.query_complexity_limit)
-    .data(EContext { db, settings })
+    .data(EContext {
+        db: db.clone(),
+        settings: settings.clone(),
+    })
     .finish();
 
     let graphql_post = async_graphql_warp::graphql(schema.clone())
@@ -95,9 +99,12 @@ pub fn filter(
 
     let playground = warp::path!("playground").and(graphql_playground).boxed();
 
+    let export = export::filter(db, settings.address_bech32_prefix);
+
     subscription
         .or(graphql)
         .or(playground)
+        .or(export)
         .with(warp::filters::trace::trace(|info| {
             use http_zipkin::get_trace_context;
             use tracing::field::Empty;
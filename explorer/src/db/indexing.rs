@@ -11,6 +11,7 @@ use chain_impl_mockchain::{
     fragment::{ConfigParams, Fragment, FragmentId},
     header::{BlockDate, ChainLength, Epoch, HeaderId as HeaderHash},
     key::BftLeaderId,
+    tokens::identifier::TokenIdentifier,
     transaction::{InputEnum, TransactionSlice, Witness},
     value::Value,
     vote::{Choice, EncryptedVote, Options, PayloadType, ProofOfCorrectVote, Weight},
@@ -35,6 +36,8 @@ pub type StakePool = Hamt<PoolId, StakePoolData>;
 
 pub type VotePlans = Hamt<VotePlanId, ExplorerVotePlan>;
 
+pub type Tokens = Hamt<TokenIdentifier, ExplorerToken>;
+
 #[derive(Clone)]
 pub struct StakePoolData {
     pub registration: PoolRegistration,
@@ -42,6 +45,18 @@ pub struct StakePoolData {
     // TODO: Track updates here too?
 }
 
+/// Native token state, updated by the mint token fragments of every block
+#[derive(Clone)]
+pub struct ExplorerToken {
+    pub id: TokenIdentifier,
+    pub supply: Value,
+    /// total amount minted to each account, its balance as tokens are never
+    /// transferred
+    pub minted_to: Hamt<Identifier, Value>,
+    /// mint token fragments, in chain order
+    pub mints: PersistentSequence<FragmentId>,
+}
+
 /// Block with unified inputs the metadata needed in the queries
 #[derive(Clone)]
 pub struct ExplorerBlock {
@@ -107,6 +122,9 @@ pub struct ExplorerVotePlan {
     pub committee_end: BlockDate,
     pub payload_type: PayloadType,
     pub proposals: Vec<ExplorerVoteProposal>,
+    /// Voting power of the voters, snapshotted with the stake distribution at
+    /// the time of the tally
+    pub voting_power: Option<Arc<ExplorerVotingPower>>,
 }
 
 #[derive(Clone)]
@@ -126,6 +144,21 @@ pub struct ExplorerVoteProposal {
     pub votes: Hamt<ExplorerAddress, ExplorerVote>,
 }
 
+/// Voting power of the voters of a vote plan for a given stake distribution
+#[derive(Clone, Default)]
+pub struct ExplorerVotingPower {
+    pub voters: HashMap<ExplorerAddress, u64>,
+    pub available: u64,
+}
+
+/// Participation figures of a proposal for a given stake distribution
+#[derive(Clone, Copy, Default)]
+pub struct ExplorerVoteTurnout {
+    pub votes_cast: u64,
+    pub voting_power_used: u64,
+    pub voting_power_available: u64,
+}
+
 // TODO do proper vote tally
 #[derive(Clone)]
 pub enum ExplorerVoteTally {
@@ -271,6 +304,17 @@ impl ExplorerBlock {
                             &current_block_txs,
                         ))
                     }
+                    Fragment::MintToken(tx) => {
+                        let tx = tx.as_slice();
+                        Some(ExplorerTransaction::from(
+                            &context,
+                            &fragment_id,
+                            &tx,
+                            Some(Certificate::MintToken(tx.payload().into_payload())),
+                            offset,
+                            &current_block_txs,
+                        ))
+                    }
                     Fragment::OldUtxoDeclaration(decl) => {
                         let outputs = decl
                             .addrs
@@ -467,3 +511,61 @@ impl ExplorerAddress {
         }
     }
 }
+
+/// Vote plan fixtures shared by the tests of the vote plan queries
+#[cfg(test)]
+pub mod test_utils {
+    use super::*;
+    use chain_crypto::{Ed25519, SecretKey};
+
+    pub fn voter(id: u8) -> ExplorerAddress {
+        let key = SecretKey::<Ed25519>::from_binary(&[id; 32])
+            .unwrap()
+            .to_public();
+        ExplorerAddress::New(Address(Discrimination::Test, chain_addr::Kind::Single(key)))
+    }
+
+    /// A public vote plan with two options per proposal, `proposals[i]`
+    /// holding the `(voter, choice)` votes of the proposal `i`
+    pub fn vote_plan(id: u8, proposals: &[&[(u8, u8)]]) -> ExplorerVotePlan {
+        let date = BlockDate {
+            epoch: 0,
+            slot_id: 0,
+        };
+        ExplorerVotePlan {
+            id: jormungandr_lib::crypto::hash::Hash::from([id; 32]).into(),
+            vote_start: date,
+            vote_end: date,
+            committee_end: date,
+            payload_type: PayloadType::Public,
+            proposals: proposals
+                .iter()
+                .enumerate()
+                .map(|(index, votes)| ExplorerVoteProposal {
+                    proposal_id: ExternalProposalId::from([index as u8; 32]),
+                    options: Options::new_length(2).unwrap(),
+                    tally: None,
+                    votes: votes.iter().fold(Hamt::new(), |votes, (voter_id, choice)| {
+                        votes
+                            .insert(
+                                voter(*voter_id),
+                                Arc::new(ExplorerVote::Public(Choice::new(*choice))),
+                            )
+                            .unwrap()
+                    }),
+                })
+                .collect(),
+            voting_power: None,
+        }
+    }
+
+    pub fn voting_power(voters: &[(u8, u64)], available: u64) -> ExplorerVotingPower {
+        ExplorerVotingPower {
+            voters: voters
+                .iter()
+                .map(|(voter_id, power)| (voter(*voter_id), *power))
+                .collect(),
+            available,
+        }
+    }
+}
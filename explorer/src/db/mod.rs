@@ -8,8 +8,8 @@ use self::{
     error::{BlockNotFound, ExplorerError as Error},
     indexing::{
         Addresses, Blocks, ChainLengths, EpochData, Epochs, ExplorerAddress, ExplorerBlock,
-        ExplorerVote, ExplorerVotePlan, ExplorerVoteProposal, StakePool, StakePoolBlocks,
-        StakePoolData, Transactions, VotePlans,
+        ExplorerToken, ExplorerVote, ExplorerVotePlan, ExplorerVoteProposal, ExplorerVotingPower,
+        Hamt, StakePool, StakePoolBlocks, StakePoolData, Tokens, Transactions, VotePlans,
     },
     persistent_sequence::PersistentSequence,
 };
@@ -24,6 +24,8 @@ use chain_impl_mockchain::{
     fee::LinearFee,
     fragment::{ConfigParams, Fragment, FragmentId},
     stake::{Stake, StakeControl},
+    tokens::identifier::TokenIdentifier,
+    value::Value,
     vote::PayloadType,
 };
 use futures::prelude::*;
@@ -90,6 +92,7 @@ pub struct State {
     stake_pool_blocks: StakePoolBlocks,
     vote_plans: VotePlans,
     stake_control: StakeControl,
+    tokens: Tokens,
 }
 
 #[derive(Clone)]
@@ -135,6 +138,7 @@ impl ExplorerDb {
             apply_block_to_stake_pools(StakePool::new(), StakePoolBlocks::new(), &block);
         let stake_control = apply_block_to_stake_control(StakeControl::new(), &block);
         let vote_plans = apply_block_to_vote_plans(VotePlans::new(), &block, &stake_control);
+        let tokens = apply_block_to_tokens(Tokens::new(), &block);
 
         let initial_state = State {
             transactions,
@@ -146,6 +150,7 @@ impl ExplorerDb {
             stake_pool_blocks,
             vote_plans,
             stake_control,
+            tokens,
         };
 
         let block0_id = block0.id();
@@ -193,6 +198,7 @@ impl ExplorerDb {
             stake_pool_blocks,
             vote_plans,
             stake_control,
+            tokens,
         } = previous_state.state().clone();
 
         let explorer_block = ExplorerBlock::resolve_from(
@@ -227,6 +233,7 @@ impl ExplorerDb {
                         &stake_control,
                     ),
                     stake_control,
+                    tokens: apply_block_to_tokens(tokens, &explorer_block),
                 },
             )
             .await;
@@ -418,6 +425,16 @@ impl ExplorerDb {
         None
     }
 
+    pub async fn get_token_by_id(&self, token_id: &TokenIdentifier) -> Option<Arc<ExplorerToken>> {
+        for (_hash, state_ref) in self.multiverse.tips().await.iter() {
+            if let Some(b) = state_ref.state().tokens.lookup(token_id) {
+                return Some(Arc::clone(b));
+            }
+        }
+
+        None
+    }
+
     pub async fn get_branch(&self, hash: &HeaderHash) -> Option<multiverse::Ref> {
         self.multiverse.get_ref(hash).await
     }
@@ -612,6 +629,7 @@ fn apply_block_to_vote_plans(
                                     votes: Default::default(),
                                 })
                                 .collect(),
+                            voting_power: None,
                         }),
                     )
                     .unwrap(),
@@ -678,13 +696,15 @@ fn apply_block_to_vote_plans(
                 }
                 Certificate::VoteTally(vote_tally) => vote_plans
                     .update(vote_tally.id(), |vote_plan| {
+                        let voting_power = ExplorerVotingPower::new(vote_plan, stake);
                         let proposals = match vote_tally.tally_type() {
                             PayloadType::Public => vote_plan
                                 .proposals
                                 .clone()
                                 .into_iter()
                                 .map(|mut proposal| {
-                                    proposal.tally = Some(compute_public_tally(&proposal, stake));
+                                    proposal.tally =
+                                        Some(compute_public_tally(&proposal, &voting_power));
 
                                     proposal
                                 })
@@ -711,6 +731,7 @@ fn apply_block_to_vote_plans(
 
                         let vote_plan = ExplorerVotePlan {
                             proposals,
+                            voting_power: Some(Arc::new(voting_power)),
                             ..(**vote_plan).clone()
                         };
                         Ok::<_, std::convert::Infallible>(Some(Arc::new(vote_plan)))
@@ -724,6 +745,54 @@ fn apply_block_to_vote_plans(
     vote_plans
 }
 
+fn apply_block_to_tokens(mut tokens: Tokens, block: &ExplorerBlock) -> Tokens {
+    let mut mints: Vec<_> = block
+        .transactions
+        .values()
+        .filter_map(|tx| match &tx.certificate {
+            Some(Certificate::MintToken(mint_token)) => Some((tx, mint_token)),
+            _ => None,
+        })
+        .collect();
+    // keep the mint history in chain order
+    mints.sort_unstable_by_key(|(tx, _)| tx.offset_in_block);
+
+    for (tx, mint_token) in mints {
+        let token_id = TokenIdentifier {
+            token_name: mint_token.name.clone(),
+            policy_hash: mint_token.policy.hash(),
+        };
+        let value = mint_token.value;
+        let to = mint_token.to.clone();
+
+        tokens = tokens.insert_or_update_simple(
+            token_id.clone(),
+            Arc::new(ExplorerToken {
+                id: token_id,
+                supply: value,
+                minted_to: Hamt::new()
+                    .insert(to.clone(), Arc::new(value))
+                    .expect("no mints of a new token"),
+                mints: PersistentSequence::new().append(tx.id()),
+            }),
+            |token| {
+                Some(Arc::new(ExplorerToken {
+                    id: token.id.clone(),
+                    supply: Value(token.supply.0.saturating_add(value.0)),
+                    minted_to: token.minted_to.insert_or_update_simple(
+                        to.clone(),
+                        Arc::new(value),
+                        |minted| Some(Arc::new(Value(minted.0.saturating_add(value.0)))),
+                    ),
+                    mints: token.mints.append(tx.id()),
+                }))
+            },
+        );
+    }
+
+    tokens
+}
+
 fn apply_block_to_stake_control(
     mut stake_control: StakeControl,
     block: &ExplorerBlock,
@@ -821,6 +890,41 @@ impl State {
             .collect()
     }
 
+    /// Get all the votes cast by the given address, across every vote plan known in
+    /// this state, as `(vote plan, proposal index, vote)`
+    pub fn get_votes_by_address(
+        &self,
+        address: &ExplorerAddress,
+    ) -> Vec<(Arc<ExplorerVotePlan>, u8, Arc<ExplorerVote>)> {
+        let mut votes = Vec::new();
+
+        for (_id, vote_plan) in self.vote_plans.iter() {
+            for (index, proposal) in vote_plan.proposals.iter().enumerate() {
+                if let Some(vote) = proposal.votes.lookup(address) {
+                    // a vote plan can't have more than 256 proposals
+                    votes.push((Arc::clone(vote_plan), index as u8, Arc::clone(vote)));
+                }
+            }
+        }
+
+        votes
+    }
+
+    pub fn get_tokens(&self) -> Vec<(TokenIdentifier, Arc<ExplorerToken>)> {
+        self.tokens
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    pub fn get_epoch(&self, epoch: Epoch) -> Option<EpochData> {
+        self.epochs.lookup(&epoch).map(|e| e.as_ref().clone())
+    }
+
+    pub fn stake_control(&self) -> &StakeControl {
+        &self.stake_control
+    }
+
     pub fn get_stake_pools(&self) -> Vec<(PoolId, Arc<StakePoolData>)> {
         self.stake_pool_data
             .iter()
@@ -837,6 +941,12 @@ impl State {
             .map(|txs| PersistentSequence::clone(txs))
     }
 
+    pub fn get_block_hash(&self, chain_length: ChainLength) -> Option<HeaderHash> {
+        self.chain_lengths
+            .lookup(&chain_length)
+            .map(|b| *b.as_ref())
+    }
+
     // Get the hashes of all blocks in the range [from, to)
     // the ChainLength is returned to for easy of use in the case where
     // `to` is greater than the max
@@ -857,3 +967,103 @@ impl State {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_impl_mockchain::{
+        account::Identifier,
+        tokens::{minting_policy::MintingPolicy, name::TokenName},
+    };
+    use jormungandr_automation::jormungandr::Block0ConfigurationBuilder;
+    use jormungandr_lib::interfaces::{Initial, InitialToken};
+    use std::convert::TryFrom;
+
+    fn account_id(wallet: &thor::Wallet) -> Identifier {
+        match wallet.address().1.kind() {
+            chain_addr::Kind::Account(key) => key.clone().into(),
+            _ => panic!("expected an account wallet"),
+        }
+    }
+
+    #[test]
+    fn block0_tokens_are_indexed() {
+        let alice = thor::Wallet::default();
+        let bob = thor::Wallet::default();
+        let policy = MintingPolicy::new();
+        let token_id = TokenIdentifier {
+            token_name: TokenName::try_from(b"token".to_vec()).unwrap(),
+            policy_hash: policy.hash(),
+        };
+
+        let block0 = Block0ConfigurationBuilder::new()
+            .with_initial(vec![Initial::Token(InitialToken {
+                token_id: token_id.clone().into(),
+                policy: policy.into(),
+                to: vec![
+                    alice.to_initial_token(10),
+                    bob.to_initial_token(5),
+                    alice.to_initial_token(1),
+                ],
+            })])
+            .build()
+            .to_block();
+        let db = ExplorerDb::bootstrap(block0).unwrap();
+
+        let token = futures::executor::block_on(db.get_token_by_id(&token_id)).unwrap();
+        assert_eq!(token.supply, Value(16));
+        assert_eq!(token.mints.len(), 3);
+        assert_eq!(
+            **token.minted_to.lookup(&account_id(&alice)).unwrap(),
+            Value(11)
+        );
+        assert_eq!(
+            **token.minted_to.lookup(&account_id(&bob)).unwrap(),
+            Value(5)
+        );
+        for i in 0..token.mints.len() {
+            let mint = token.mints.get(i).unwrap();
+            let blocks = futures::executor::block_on(db.find_blocks_by_transaction(mint));
+            assert_eq!(blocks.len(), 1);
+        }
+    }
+
+    #[test]
+    fn votes_are_found_by_address() {
+        use indexing::test_utils::{vote_plan, voter};
+
+        let block0 = Block0ConfigurationBuilder::new().build().to_block();
+        let db = ExplorerDb::bootstrap(block0).unwrap();
+        let (_, state_ref) = futures::executor::block_on(db.get_tip());
+        let mut state = state_ref.state().clone();
+        for vote_plan in [
+            vote_plan(1, &[&[(1, 0), (2, 1)], &[(2, 0)]]),
+            vote_plan(2, &[&[(3, 1)], &[(1, 1)]]),
+        ] {
+            state.vote_plans = state
+                .vote_plans
+                .insert(vote_plan.id.clone(), Arc::new(vote_plan))
+                .unwrap();
+        }
+
+        let mut votes: Vec<_> = state
+            .get_votes_by_address(&voter(1))
+            .into_iter()
+            .map(|(vote_plan, proposal_index, vote)| {
+                let choice = match vote.as_ref() {
+                    ExplorerVote::Public(choice) => choice.as_byte(),
+                    ExplorerVote::Private { .. } => panic!("expected a public vote"),
+                };
+                (vote_plan.id.clone(), proposal_index, choice)
+            })
+            .collect();
+        votes.sort_by_key(|(_, proposal_index, _)| *proposal_index);
+        assert_eq!(
+            votes,
+            vec![(vote_plan(1, &[]).id, 0, 0), (vote_plan(2, &[]).id, 1, 1),]
+        );
+
+        assert_eq!(state.get_votes_by_address(&voter(2)).len(), 2);
+        assert!(state.get_votes_by_address(&voter(4)).is_empty());
+    }
+}
@@ -1,6 +1,12 @@
 use super::indexing::ExplorerVoteProposal;
-use crate::db::indexing::{ExplorerVote, ExplorerVoteTally};
-use chain_impl_mockchain::{certificate::DecryptedPrivateTallyProposal, stake::StakeControl};
+use crate::db::indexing::{
+    ExplorerAddress, ExplorerVote, ExplorerVotePlan, ExplorerVoteTally, ExplorerVoteTurnout,
+    ExplorerVotingPower,
+};
+use chain_impl_mockchain::{
+    certificate::DecryptedPrivateTallyProposal, stake::StakeControl, vote::Weight,
+};
+use std::borrow::Cow;
 
 pub fn compute_private_tally(
     proposal: &ExplorerVoteProposal,
@@ -14,25 +20,23 @@ pub fn compute_private_tally(
 
 pub fn compute_public_tally(
     proposal: &ExplorerVoteProposal,
-    stake: &StakeControl,
+    voting_power: &ExplorerVotingPower,
 ) -> ExplorerVoteTally {
     let mut results = vec![0u64; proposal.options.choice_range().end as usize];
 
     for (address, vote) in proposal.votes.iter() {
-        if let Some(account_id) = address.to_single_account() {
-            if let Some(stake) = stake.by(&account_id) {
-                match vote.as_ref() {
-                    ExplorerVote::Public(choice) => {
-                        let index = choice.as_byte() as usize;
-                        results[index] = results[index].saturating_add(stake.into());
-                    }
-                    ExplorerVote::Private {
-                        proof: _,
-                        encrypted_vote: _,
-                    } => {
-                        unreachable!("internal error: found private vote when computing tally for public proposal")
-                    }
-                }
+        match vote.as_ref() {
+            ExplorerVote::Public(choice) => {
+                let index = choice.as_byte() as usize;
+                results[index] = results[index].saturating_add(voting_power.of(address));
+            }
+            ExplorerVote::Private {
+                proof: _,
+                encrypted_vote: _,
+            } => {
+                unreachable!(
+                    "internal error: found private vote when computing tally for public proposal"
+                )
             }
         }
     }
@@ -42,3 +46,168 @@ pub fn compute_public_tally(
         options: proposal.options.clone(),
     }
 }
+
+impl ExplorerVotingPower {
+    /// Take the voting power of the voters of the vote plan, the explorer only
+    /// tracks stake for single addresses so any other kind of voter is
+    /// considered to have no stake
+    pub fn new(vote_plan: &ExplorerVotePlan, stake: &StakeControl) -> Self {
+        let voters = vote_plan
+            .proposals
+            .iter()
+            .flat_map(|proposal| proposal.votes.iter())
+            .map(|(address, _vote)| {
+                let power = address
+                    .to_single_account()
+                    .and_then(|account_id| stake.by(&account_id))
+                    .map(u64::from)
+                    .unwrap_or(0);
+                (address.clone(), power)
+            })
+            .collect();
+
+        ExplorerVotingPower {
+            voters,
+            available: stake.total().into(),
+        }
+    }
+
+    /// Voting power of the given voter, none if it did not vote
+    pub fn of(&self, address: &ExplorerAddress) -> u64 {
+        self.voters.get(address).copied().unwrap_or(0)
+    }
+
+    pub fn turnout(&self, proposal: &ExplorerVoteProposal) -> ExplorerVoteTurnout {
+        let voting_power_used = proposal.votes.iter().fold(0u64, |used, (address, _vote)| {
+            used.saturating_add(self.of(address))
+        });
+
+        ExplorerVoteTurnout {
+            votes_cast: proposal.votes.size() as u64,
+            voting_power_used,
+            voting_power_available: self.available,
+        }
+    }
+}
+
+impl ExplorerVotePlan {
+    /// The voting power snapshotted at the tally, or the current one if the
+    /// vote plan was not tallied yet
+    pub fn voting_power(&self, stake: &StakeControl) -> Cow<'_, ExplorerVotingPower> {
+        match &self.voting_power {
+            Some(voting_power) => Cow::Borrowed(voting_power.as_ref()),
+            None => Cow::Owned(ExplorerVotingPower::new(self, stake)),
+        }
+    }
+}
+
+impl ExplorerVoteTally {
+    /// The weight of every option, `None` if the tally is private and the results
+    /// were not decrypted yet
+    pub fn results(&self) -> Option<&[Weight]> {
+        match self {
+            ExplorerVoteTally::Public { results, .. } => Some(results),
+            ExplorerVoteTally::Private { results, .. } => results.as_deref(),
+        }
+    }
+
+    /// The options with the highest weight. There can be more than one in case
+    /// of a tie, and none if nobody voted or the results are not known yet.
+    pub fn winning_options(&self) -> Vec<u8> {
+        let results = match self.results() {
+            Some(results) => results,
+            None => return Vec::new(),
+        };
+
+        let max = results.iter().copied().map(u64::from).max().unwrap_or(0);
+
+        if max == 0 {
+            return Vec::new();
+        }
+
+        results
+            .iter()
+            .enumerate()
+            .filter(|(_, weight)| u64::from(**weight) == max)
+            .map(|(index, _)| index as u8)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::indexing::test_utils::{vote_plan, voter, voting_power};
+    use std::sync::Arc;
+
+    fn tally(results: &[u64]) -> ExplorerVoteTally {
+        ExplorerVoteTally::Public {
+            results: results.iter().copied().map(Weight::from).collect(),
+            options: chain_impl_mockchain::vote::Options::new_length(results.len() as u8).unwrap(),
+        }
+    }
+
+    #[test]
+    fn turnout_uses_the_voting_power_of_the_voters() {
+        let vote_plan = vote_plan(1, &[&[(1, 0), (2, 1)], &[(3, 1)]]);
+        let voting_power = voting_power(&[(1, 10), (2, 5), (3, 7)], 100);
+
+        let turnout = voting_power.turnout(&vote_plan.proposals[0]);
+        assert_eq!(turnout.votes_cast, 2);
+        assert_eq!(turnout.voting_power_used, 15);
+        assert_eq!(turnout.voting_power_available, 100);
+
+        let turnout = voting_power.turnout(&vote_plan.proposals[1]);
+        assert_eq!(turnout.votes_cast, 1);
+        assert_eq!(turnout.voting_power_used, 7);
+    }
+
+    #[test]
+    fn public_tally_uses_the_voting_power_of_the_voters() {
+        let vote_plan = vote_plan(1, &[&[(1, 0), (2, 1), (3, 1), (4, 0)]]);
+        // the fourth voter has no stake
+        let voting_power = voting_power(&[(1, 10), (2, 5), (3, 7)], 100);
+
+        let tally = compute_public_tally(&vote_plan.proposals[0], &voting_power);
+        let results: Vec<u64> = tally
+            .results()
+            .unwrap()
+            .iter()
+            .copied()
+            .map(u64::from)
+            .collect();
+        assert_eq!(results, vec![10, 12]);
+    }
+
+    #[test]
+    fn winning_options_are_the_highest_results() {
+        assert_eq!(tally(&[3, 7, 5]).winning_options(), vec![1]);
+        assert_eq!(tally(&[7, 2, 7]).winning_options(), vec![0, 2]);
+        assert!(tally(&[0, 0]).winning_options().is_empty());
+
+        let private = ExplorerVoteTally::Private {
+            results: None,
+            options: chain_impl_mockchain::vote::Options::new_length(2).unwrap(),
+        };
+        assert!(private.winning_options().is_empty());
+    }
+
+    #[test]
+    fn the_voting_power_snapshot_is_used_once_tallied() {
+        let stake = StakeControl::new();
+        let mut vote_plan = vote_plan(1, &[&[(1, 0)]]);
+
+        // not tallied, with the current stake distribution
+        let current = vote_plan.voting_power(&stake);
+        assert!(matches!(current, Cow::Owned(_)));
+        assert_eq!(current.of(&voter(1)), 0);
+        assert_eq!(current.available, 0);
+
+        vote_plan.voting_power = Some(Arc::new(voting_power(&[(1, 10)], 20)));
+        let snapshot = vote_plan.voting_power(&stake);
+        assert!(matches!(snapshot, Cow::Borrowed(_)));
+        assert_eq!(snapshot.of(&voter(1)), 10);
+        assert_eq!(snapshot.of(&voter(2)), 0);
+        assert_eq!(snapshot.available, 20);
+    }
+}
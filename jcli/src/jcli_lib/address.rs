@@ -1,6 +1,7 @@
-use crate::jcli_lib::utils::key_parser::parse_pub_key;
+use crate::jcli_lib::utils::{key_parser::parse_pub_key, multisig};
 use chain_addr::{AddressReadable, Discrimination, Kind};
 use chain_crypto::{bech32::Bech32 as _, AsymmetricPublicKey, Ed25519, PublicKey};
+use std::path::PathBuf;
 use structopt::StructOpt;
 use thiserror::Error;
 
@@ -16,6 +17,9 @@ pub enum Address {
 
     /// Create an account address from a single public key.
     Account(AccountArgs),
+
+    /// Create the account address of a multisig declaration.
+    Multisig(MultisigArgs),
 }
 
 #[derive(StructOpt)]
@@ -39,6 +43,16 @@ pub struct DiscriminationData {
     prefix: String,
 }
 
+impl DiscriminationData {
+    pub fn discrimination(&self) -> Discrimination {
+        mk_discrimination(self.testing)
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
 #[derive(StructOpt)]
 pub struct SingleArgs {
     /// A public key in bech32 encoding with the key type prefix.
@@ -63,10 +77,21 @@ pub struct AccountArgs {
     discrimination_data: DiscriminationData,
 }
 
+#[derive(StructOpt)]
+pub struct MultisigArgs {
+    /// The multisig declaration, a YAML file with the `threshold` and the
+    /// ordered list of the `owners` public keys in bech32.
+    #[structopt(name = "DECLARATION")]
+    declaration: PathBuf,
+
+    #[structopt(flatten)]
+    discrimination_data: DiscriminationData,
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("multisig addresses are not supported")]
-    MultisigAddressNotSupported,
+    #[error(transparent)]
+    Multisig(#[from] multisig::Error),
 }
 
 impl Address {
@@ -94,6 +119,14 @@ impl Address {
                 account_args.key,
                 account_args.discrimination_data.testing,
             ),
+            Address::Multisig(multisig_args) => {
+                let declaration = multisig::Declaration::load(&multisig_args.declaration)?;
+                mk_address(
+                    &multisig_args.discrimination_data.prefix,
+                    mk_discrimination(multisig_args.discrimination_data.testing),
+                    Kind::Multisig(declaration.identifier()),
+                )
+            }
         }
         Ok(())
     }
@@ -113,7 +146,7 @@ fn address_info(address: &AddressReadable) -> Result<(), Error> {
     match kind {
         Kind::Single(single) => println!("public key: {}", single.to_bech32_str()),
         Kind::Account(account) => println!("account: {}", account.to_bech32_str()),
-        Kind::Multisig(_) => return Err(Error::MultisigAddressNotSupported),
+        Kind::Multisig(id) => println!("multisig identifier: {}", hex::encode(id)),
         Kind::Group(pubk, groupk) => {
             println!("public key: {}", pubk.to_bech32_str());
             println!("group key:  {}", groupk.to_bech32_str());
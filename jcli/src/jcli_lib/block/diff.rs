@@ -0,0 +1,317 @@
+use crate::jcli_lib::block::{load_configuration, validate::certificate_kind, Error};
+use chain_impl_mockchain::{certificate, tokens::identifier};
+use jormungandr_lib::interfaces::{Block0Configuration, Initial};
+use serde_json::Value as JsonValue;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::PathBuf,
+};
+use structopt::StructOpt;
+
+/// The genesis are compared by their meaning rather than by their encoding:
+/// the funds are compared per address whatever the entries they are spread
+/// over, and the certificates and the tokens whatever their order.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Diff {
+    /// the genesis file or block 0 to compare from
+    #[structopt(parse(from_os_str))]
+    old: PathBuf,
+
+    /// the genesis file or block 0 to compare to
+    #[structopt(parse(from_os_str))]
+    new: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+    Modified { old: String, new: String },
+}
+
+/// a difference between two genesis, `path` locating it in the YAML file
+#[derive(Debug, PartialEq, Eq)]
+pub struct Difference {
+    pub path: String,
+    pub change: Change,
+}
+
+impl Diff {
+    pub fn exec(self) -> Result<(), Error> {
+        let old = load_configuration(&Some(self.old))?;
+        let new = load_configuration(&Some(self.new))?;
+        for difference in diff(&old, &new) {
+            println!("{}", difference);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.change {
+            Change::Added(value) => write!(f, "+ {}: {}", self.path, value),
+            Change::Removed(value) => write!(f, "- {}: {}", self.path, value),
+            Change::Modified { old, new } => write!(f, "~ {}: {} -> {}", self.path, old, new),
+        }
+    }
+}
+
+pub fn diff(old: &Block0Configuration, new: &Block0Configuration) -> Vec<Difference> {
+    let mut differences = Vec::new();
+
+    let settings = |genesis: &Block0Configuration| {
+        serde_json::to_value(&genesis.blockchain_configuration)
+            .expect("blockchain configuration is always serializable")
+    };
+    diff_json(
+        "blockchain_configuration".to_owned(),
+        &settings(old),
+        &settings(new),
+        &mut differences,
+    );
+
+    let (old, new) = (InitialContent::new(old), InitialContent::new(new));
+    diff_maps("initial.fund", &old.funds, &new.funds, &mut differences);
+    diff_maps(
+        "initial.legacy_fund",
+        &old.legacy_funds,
+        &new.legacy_funds,
+        &mut differences,
+    );
+    diff_maps("initial.token", &old.tokens, &new.tokens, &mut differences);
+    for (cert, label) in &old.certificates {
+        if !new.certificates.contains_key(cert) {
+            differences.push(Difference {
+                path: "initial.cert".to_owned(),
+                change: Change::Removed(label.clone()),
+            });
+        }
+    }
+    for (cert, label) in &new.certificates {
+        if !old.certificates.contains_key(cert) {
+            differences.push(Difference {
+                path: "initial.cert".to_owned(),
+                change: Change::Added(label.clone()),
+            });
+        }
+    }
+
+    differences
+}
+
+fn render(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn diff_json(path: String, old: &JsonValue, new: &JsonValue, differences: &mut Vec<Difference>) {
+    match (old, new) {
+        (JsonValue::Object(old), JsonValue::Object(new)) => {
+            let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                let path = format!("{}.{}", path, key);
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff_json(path, old, new, differences),
+                    (Some(old), None) => differences.push(Difference {
+                        path,
+                        change: Change::Removed(render(old)),
+                    }),
+                    (None, Some(new)) => differences.push(Difference {
+                        path,
+                        change: Change::Added(render(new)),
+                    }),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        // the order of the items is meaningful, as for the BFT leaders
+        (JsonValue::Array(old), JsonValue::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                let path = format!("{}[{}]", path, index);
+                match (old.get(index), new.get(index)) {
+                    (Some(old), Some(new)) => diff_json(path, old, new, differences),
+                    (Some(old), None) => differences.push(Difference {
+                        path,
+                        change: Change::Removed(render(old)),
+                    }),
+                    (None, Some(new)) => differences.push(Difference {
+                        path,
+                        change: Change::Added(render(new)),
+                    }),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        (old, new) if old != new => differences.push(Difference {
+            path,
+            change: Change::Modified {
+                old: render(old),
+                new: render(new),
+            },
+        }),
+        _ => (),
+    }
+}
+
+fn diff_maps(
+    path: &str,
+    old: &BTreeMap<String, u64>,
+    new: &BTreeMap<String, u64>,
+    differences: &mut Vec<Difference>,
+) {
+    let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        let change = match (old.get(key), new.get(key)) {
+            (Some(old), Some(new)) if old == new => continue,
+            (Some(old), Some(new)) => Change::Modified {
+                old: old.to_string(),
+                new: new.to_string(),
+            },
+            (Some(old), None) => Change::Removed(old.to_string()),
+            (None, Some(new)) => Change::Added(new.to_string()),
+            (None, None) => unreachable!(),
+        };
+        differences.push(Difference {
+            path: format!("{}.{}", path, key),
+            change,
+        });
+    }
+}
+
+/// the initial fragments of a genesis, regardless of their order
+#[derive(Default)]
+struct InitialContent {
+    funds: BTreeMap<String, u64>,
+    legacy_funds: BTreeMap<String, u64>,
+    tokens: BTreeMap<String, u64>,
+    /// the certificates, with their description
+    certificates: BTreeMap<String, String>,
+}
+
+impl InitialContent {
+    fn new(genesis: &Block0Configuration) -> Self {
+        let mut content = InitialContent::default();
+        let add = |map: &mut BTreeMap<String, u64>, key: String, value: u64| {
+            let total = map.entry(key).or_insert(0);
+            *total = total.saturating_add(value);
+        };
+        for initial in &genesis.initial {
+            match initial {
+                Initial::Fund(utxos) => {
+                    for utxo in utxos {
+                        add(
+                            &mut content.funds,
+                            utxo.address.to_string(),
+                            utxo.value.into(),
+                        );
+                    }
+                }
+                Initial::LegacyFund(utxos) => {
+                    for utxo in utxos {
+                        add(
+                            &mut content.legacy_funds,
+                            utxo.address.to_string(),
+                            utxo.value.into(),
+                        );
+                    }
+                }
+                Initial::Token(token) => {
+                    let token_id = identifier::TokenIdentifier::from(token.token_id.clone());
+                    for destination in &token.to {
+                        add(
+                            &mut content.tokens,
+                            format!("{}.{}", token_id, destination.address),
+                            destination.value.into(),
+                        );
+                    }
+                }
+                Initial::Cert(cert) => {
+                    let kind = certificate_kind(&cert.0);
+                    let label = match &cert.0 {
+                        certificate::SignedCertificate::PoolRegistration(registration, _) => {
+                            format!("{} {}", kind, registration.to_id())
+                        }
+                        certificate::SignedCertificate::VotePlan(vote_plan, _) => {
+                            format!("{} {}", kind, vote_plan.to_id())
+                        }
+                        _ => format!("{} {}", kind, cert),
+                    };
+                    content.certificates.insert(cert.to_string(), label);
+                }
+            }
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jcli_lib::block::test_utils::example;
+    use jormungandr_lib::interfaces::Value;
+
+    #[test]
+    fn identical_genesis_have_no_difference() {
+        assert!(diff(&example(), &example()).is_empty());
+    }
+
+    #[test]
+    fn block0_roundtrip_has_no_difference() {
+        let genesis = example();
+        let decoded = Block0Configuration::from_block(&genesis.to_block()).unwrap();
+        assert_eq!(diff(&genesis, &decoded), Vec::new());
+    }
+
+    #[test]
+    fn settings_changes_are_listed() {
+        let old = example();
+        let mut new = example();
+        new.blockchain_configuration.total_reward_supply = None;
+        new.blockchain_configuration.consensus_leader_ids.pop();
+        let differences = diff(&old, &new);
+        let paths: Vec<_> = differences
+            .iter()
+            .map(|difference| difference.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "blockchain_configuration.consensus_leader_ids[1]",
+                "blockchain_configuration.total_reward_supply"
+            ]
+        );
+        assert!(
+            matches!(differences[1].change, Change::Removed(ref value) if value == "100000000000000")
+        );
+    }
+
+    #[test]
+    fn funds_are_compared_per_address() {
+        let old = example();
+        let mut new = example();
+        // the same funds split over two entries
+        if let Initial::Fund(utxos) = &mut new.initial[0] {
+            let second = utxos.pop().unwrap();
+            new.initial.push(Initial::Fund(vec![second]));
+        }
+        assert!(diff(&old, &new).is_empty());
+
+        if let Initial::Fund(utxos) = &mut new.initial[0] {
+            utxos[0].value = Value::from(1);
+        }
+        let differences = diff(&old, &new);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(
+            differences[0].change,
+            Change::Modified {
+                old: "10000".to_owned(),
+                new: "1".to_owned()
+            }
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use crate::jcli_lib::utils::io;
+mod diff;
+#[cfg(test)]
+mod test_utils;
+mod validate;
+
+use crate::jcli_lib::utils::{io, output_format};
 use chain_core::{
     packer::Codec,
     property::{Block as _, Deserialize, ReadError, Serialize, WriteError},
@@ -11,7 +16,7 @@ use jormungandr_lib::interfaces::{
     block0_configuration_documented_example, Block0Configuration, Block0ConfigurationError,
 };
 use std::{
-    io::{BufRead, Write},
+    io::{BufRead, Read as _, Write},
     path::PathBuf,
 };
 use structopt::StructOpt;
@@ -44,6 +49,10 @@ pub enum Error {
     GenesisSerializationFailed(#[source] serde_yaml::Error),
     #[error("failed to build genesis from block 0")]
     BuildingGenesisFromBlock0Failed(#[from] Block0ConfigurationError),
+    #[error("the genesis has {errors} error(s) and {warnings} warning(s)")]
+    GenesisInvalid { errors: usize, warnings: usize },
+    #[error("formatting output failed")]
+    OutputFormatFailed(#[from] output_format::Error),
 }
 
 impl Genesis {
@@ -56,6 +65,8 @@ impl Genesis {
             Genesis::Encode(create_arguments) => encode_block_0(create_arguments),
             Genesis::Decode(info_arguments) => decode_block_0(info_arguments),
             Genesis::Hash(hash_arguments) => print_hash(hash_arguments),
+            Genesis::Validate(validate) => validate.exec(),
+            Genesis::Diff(diff) => diff.exec(),
         }
     }
 }
@@ -105,6 +116,13 @@ pub enum Genesis {
 
     /// print the block hash (aka the block id) of the block 0
     Hash(Input),
+
+    /// check a genesis file or block 0 for mistakes, printing the
+    /// diagnostics and a summary of the initial stake and token supply
+    Validate(validate::Validate),
+
+    /// compare two genesis files or blocks 0, whatever their encoding
+    Diff(diff::Diff),
 }
 
 #[derive(StructOpt)]
@@ -126,6 +144,11 @@ impl Input {
         let reader = self.open()?;
         load_block(reader)
     }
+
+    /// load the genesis from either its YAML file or the encoded block 0
+    pub fn load_configuration(&self) -> Result<Block0Configuration, Error> {
+        load_configuration(&self.input_file)
+    }
 }
 
 pub fn open_block_file(input_file: &Option<PathBuf>) -> Result<impl BufRead, Error> {
@@ -139,6 +162,24 @@ pub fn load_block(block_reader: impl BufRead) -> Result<Block, Error> {
     Block::deserialize(&mut Codec::new(block_reader)).map_err(Error::BlockFileCorrupted)
 }
 
+pub fn load_configuration(input_file: &Option<PathBuf>) -> Result<Block0Configuration, Error> {
+    let mut bytes = Vec::new();
+    open_block_file(input_file)?
+        .read_to_end(&mut bytes)
+        .map_err(|source| Error::InputInvalid {
+            source,
+            path: input_file.clone().unwrap_or_default(),
+        })?;
+    // an encoded block is full of hashes and signatures, it is never valid UTF-8
+    match std::str::from_utf8(&bytes) {
+        Ok(yaml) => serde_yaml::from_str(yaml).map_err(Error::GenesisFileCorrupted),
+        Err(_) => {
+            let block = load_block(bytes.as_slice())?;
+            Ok(Block0Configuration::from_block(&block)?)
+        }
+    }
+}
+
 #[derive(StructOpt)]
 pub struct Common {
     #[structopt(flatten)]
@@ -0,0 +1,8 @@
+use jormungandr_lib::interfaces::{
+    block0_configuration_documented_example_block, Block0Configuration,
+};
+
+/// the documented example of the genesis file, a valid block0 configuration
+pub fn example() -> Block0Configuration {
+    Block0Configuration::from_block(&block0_configuration_documented_example_block()).unwrap()
+}
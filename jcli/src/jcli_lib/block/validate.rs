@@ -0,0 +1,600 @@
+use crate::jcli_lib::{
+    block::{Error, Input},
+    utils::OutputFormat,
+};
+use chain_addr::Discrimination;
+use chain_core::property::Block as _;
+use chain_impl_mockchain::{
+    account::DelegationType, certificate, certificate::PoolId, chaintypes::ConsensusVersion,
+    fragment::Fragment, ledger::Ledger,
+};
+use jormungandr_lib::{
+    interfaces::{Block0Configuration, Initial, StakeDistribution, TokenIdentifier, Value},
+    time::SecondsSinceUnixEpoch,
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    convert::TryFrom,
+    fmt,
+};
+use structopt::StructOpt;
+
+/// maximum number of outputs of a single initial fund or legacy fund entry
+const MAX_ENTRIES_PER_INITIAL: usize = 254;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Validate {
+    #[structopt(flatten)]
+    input: Input,
+
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+
+    /// fail on warnings too
+    #[structopt(long)]
+    deny_warnings: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// a mistake found in the genesis, `location` being the path of the faulty
+/// field in the YAML file
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub location: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Supply {
+    pub outputs: usize,
+    pub total: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenSupply {
+    pub token_id: TokenIdentifier,
+    pub holders: usize,
+    pub total: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block0_hash: Option<String>,
+    pub block0_date: SecondsSinceUnixEpoch,
+    pub consensus: &'static str,
+    pub discrimination: &'static str,
+    pub consensus_leaders: usize,
+    pub slots_per_epoch: u32,
+    pub slot_duration: u8,
+    pub epoch_duration: u64,
+    pub initial_funds: Supply,
+    pub legacy_funds: Supply,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub treasury: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_reward_supply: Option<Value>,
+    pub certificates: BTreeMap<&'static str, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stake: Option<StakeDistribution>,
+    pub tokens: Vec<TokenSupply>,
+}
+
+impl Validate {
+    pub fn exec(self) -> Result<(), Error> {
+        let genesis = self.input.load_configuration()?;
+        let (diagnostics, summary) = validate(&genesis);
+
+        for diagnostic in &diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+        let summary = serde_json::to_value(&summary).expect("summary is always serializable");
+        println!("{}", self.output_format.format_json(summary)?);
+
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+        if errors > 0 || (self.deny_warnings && warnings > 0) {
+            return Err(Error::GenesisInvalid { errors, warnings });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity, self.code, self.location, self.message
+        )
+    }
+}
+
+#[derive(Default)]
+struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    fn push(
+        &mut self,
+        severity: Severity,
+        code: &'static str,
+        location: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.0.push(Diagnostic {
+            severity,
+            code,
+            location: location.into(),
+            message: message.into(),
+        })
+    }
+
+    fn error(
+        &mut self,
+        code: &'static str,
+        location: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.push(Severity::Error, code, location, message)
+    }
+
+    fn warning(
+        &mut self,
+        code: &'static str,
+        location: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.push(Severity::Warning, code, location, message)
+    }
+
+    fn has_errors(&self) -> bool {
+        self.0
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+}
+
+fn add_value(
+    total: &mut Option<Value>,
+    value: Value,
+    diagnostics: &mut Diagnostics,
+    location: &str,
+) {
+    if let Some(sum) = total {
+        match sum.checked_add(value) {
+            Ok(new_sum) => *sum = new_sum,
+            Err(_) => {
+                diagnostics.error(
+                    "funds-overflow",
+                    location,
+                    "the total of the initial funds overflows",
+                );
+                *total = None;
+            }
+        }
+    }
+}
+
+pub(super) fn certificate_kind(cert: &certificate::SignedCertificate) -> &'static str {
+    match cert {
+        certificate::SignedCertificate::StakeDelegation(..) => "stake_delegation",
+        certificate::SignedCertificate::OwnerStakeDelegation(..) => "owner_stake_delegation",
+        certificate::SignedCertificate::PoolRegistration(..) => "pool_registration",
+        certificate::SignedCertificate::PoolRetirement(..) => "pool_retirement",
+        certificate::SignedCertificate::PoolUpdate(..) => "pool_update",
+        certificate::SignedCertificate::VotePlan(..) => "vote_plan",
+        certificate::SignedCertificate::VoteTally(..) => "vote_tally",
+        certificate::SignedCertificate::UpdateProposal(..) => "update_proposal",
+        certificate::SignedCertificate::UpdateVote(..) => "update_vote",
+        certificate::SignedCertificate::EvmMapping(..) => "evm_mapping",
+    }
+}
+
+fn delegated_pools(delegation: &DelegationType) -> Vec<PoolId> {
+    match delegation {
+        DelegationType::NonDelegated => Vec::new(),
+        DelegationType::Full(pool_id) => vec![pool_id.clone()],
+        DelegationType::Ratio(ratio) => ratio
+            .pools()
+            .iter()
+            .map(|(pool_id, _)| pool_id.clone())
+            .collect(),
+    }
+}
+
+/// Check the genesis for the mistakes that would prevent the node from
+/// starting or the blockchain from making progress, and summarize it.
+pub fn validate(genesis: &Block0Configuration) -> (Vec<Diagnostic>, Summary) {
+    let mut diagnostics = Diagnostics::default();
+    let config = &genesis.blockchain_configuration;
+
+    let slots_per_epoch = u32::from(config.slots_per_epoch);
+    let slot_duration = u8::from(config.slot_duration);
+    let epoch_stability_depth = u32::from(config.epoch_stability_depth);
+    let kes_update_speed = u32::from(config.kes_update_speed);
+
+    if config.block0_consensus == ConsensusVersion::Bft && config.consensus_leader_ids.is_empty() {
+        diagnostics.error(
+            "bft-without-leaders",
+            "blockchain_configuration.consensus_leader_ids",
+            "the BFT consensus requires at least one leader, no block could be created",
+        );
+    }
+    let mut leaders = HashSet::new();
+    for (index, leader) in config.consensus_leader_ids.iter().enumerate() {
+        if !leaders.insert(leader) {
+            diagnostics.warning(
+                "duplicate-leader",
+                format!("blockchain_configuration.consensus_leader_ids[{}]", index),
+                "the leader is already listed",
+            );
+        }
+    }
+    if epoch_stability_depth == 0 {
+        diagnostics.warning(
+            "zero-stability-depth",
+            "blockchain_configuration.epoch_stability_depth",
+            "with a stability depth of 0 the nodes can never switch to a competing branch",
+        );
+    } else if config.block0_consensus == ConsensusVersion::GenesisPraos
+        && epoch_stability_depth > slots_per_epoch
+    {
+        diagnostics.warning(
+            "stability-depth-exceeds-epoch",
+            "blockchain_configuration.epoch_stability_depth",
+            format!(
+                "the stability depth ({}) is larger than an epoch ({} slots), \
+                 the stake distribution electing the leaders could be rolled back",
+                epoch_stability_depth, slots_per_epoch
+            ),
+        );
+    }
+    if kes_update_speed < u32::from(slot_duration) {
+        diagnostics.warning(
+            "kes-update-faster-than-slots",
+            "blockchain_configuration.kes_update_speed",
+            format!(
+                "the KES key evolves every {} seconds, faster than the slots ({} seconds)",
+                kes_update_speed, slot_duration
+            ),
+        );
+    }
+
+    let mut initial_funds = Some(Value::from(0));
+    let mut initial_outputs = 0;
+    let mut legacy_funds = Some(Value::from(0));
+    let mut legacy_outputs = 0;
+    let mut certificates = BTreeMap::new();
+    let mut registered_pools = HashSet::new();
+    let mut delegations = Vec::new();
+    let mut tokens: BTreeMap<TokenIdentifier, (usize, Value)> = BTreeMap::new();
+
+    for (index, initial) in genesis.initial.iter().enumerate() {
+        let location = format!("initial[{}]", index);
+        match initial {
+            Initial::Fund(utxos) => {
+                check_entries_count(&mut diagnostics, &location, utxos.len());
+                initial_outputs += utxos.len();
+                for (output, utxo) in utxos.iter().enumerate() {
+                    let location = format!("{}.fund[{}]", location, output);
+                    if utxo.address.1 .0 != config.discrimination {
+                        diagnostics.error(
+                            "discrimination-mismatch",
+                            &location,
+                            format!(
+                                "the address {} is not for the {} discrimination of the blockchain",
+                                utxo.address,
+                                discrimination_name(config.discrimination)
+                            ),
+                        );
+                    }
+                    if u64::from(utxo.value) == 0 {
+                        diagnostics.warning("zero-value-fund", &location, "the fund is empty");
+                    }
+                    add_value(&mut initial_funds, utxo.value, &mut diagnostics, &location);
+                }
+            }
+            Initial::LegacyFund(utxos) => {
+                check_entries_count(&mut diagnostics, &location, utxos.len());
+                legacy_outputs += utxos.len();
+                for (output, utxo) in utxos.iter().enumerate() {
+                    let location = format!("{}.legacy_fund[{}]", location, output);
+                    if u64::from(utxo.value) == 0 {
+                        diagnostics.warning("zero-value-fund", &location, "the fund is empty");
+                    }
+                    add_value(&mut legacy_funds, utxo.value, &mut diagnostics, &location);
+                }
+            }
+            Initial::Cert(cert) => {
+                let location = format!("{}.cert", location);
+                *certificates.entry(certificate_kind(&cert.0)).or_insert(0) += 1;
+                match &cert.0 {
+                    certificate::SignedCertificate::PoolRegistration(registration, _) => {
+                        if !registered_pools.insert(registration.to_id()) {
+                            diagnostics.error(
+                                "duplicate-pool",
+                                &location,
+                                format!(
+                                    "the stake pool {} is already registered",
+                                    registration.to_id()
+                                ),
+                            );
+                        }
+                    }
+                    certificate::SignedCertificate::StakeDelegation(delegation, _) => {
+                        delegations
+                            .push((location, delegated_pools(delegation.get_delegation_type())));
+                    }
+                    certificate::SignedCertificate::VotePlan(..) => (),
+                    cert => diagnostics.error(
+                        "unsupported-certificate",
+                        &location,
+                        format!(
+                            "a {} certificate cannot be part of the block 0",
+                            certificate_kind(cert).replace('_', " ")
+                        ),
+                    ),
+                }
+            }
+            Initial::Token(token) => {
+                let location = format!("{}.token", location);
+                if let Err(e) = <Vec<Fragment>>::try_from(initial) {
+                    diagnostics.error("invalid-token", &location, e.to_string());
+                }
+                let (holders, total) = tokens
+                    .entry(token.token_id.clone())
+                    .or_insert((0, Value::from(0)));
+                for destination in &token.to {
+                    *holders += 1;
+                    *total = total.saturating_add(destination.value);
+                }
+            }
+        }
+    }
+
+    for (location, pools) in delegations {
+        for pool_id in pools {
+            if !registered_pools.contains(&pool_id) {
+                diagnostics.error(
+                    "unknown-pool",
+                    &location,
+                    format!(
+                        "the stake is delegated to {}, a stake pool not registered in the block 0",
+                        pool_id
+                    ),
+                );
+            }
+        }
+    }
+    if config.block0_consensus == ConsensusVersion::GenesisPraos && registered_pools.is_empty() {
+        diagnostics.error(
+            "genesis-without-stake-pools",
+            "initial",
+            "the Genesis Praos consensus requires stake pools, none is registered",
+        );
+    }
+
+    match (initial_funds, config.total_reward_supply) {
+        (Some(funds), Some(supply)) if funds > supply => diagnostics.warning(
+            "funds-exceed-reward-supply",
+            "blockchain_configuration.total_reward_supply",
+            format!(
+                "the initial funds ({}) exceed the total reward supply ({})",
+                funds, supply
+            ),
+        ),
+        _ => (),
+    }
+    let reward_supply = config.total_reward_supply.map_or(0, u64::from);
+    match (&config.reward_parameters, reward_supply) {
+        (Some(_), 0) => diagnostics.warning(
+            "rewards-without-supply",
+            "blockchain_configuration.total_reward_supply",
+            "reward parameters are set but the reward pot is empty, no reward will be distributed",
+        ),
+        (None, supply) if supply > 0 => diagnostics.warning(
+            "supply-without-rewards",
+            "blockchain_configuration.reward_parameters",
+            "the reward pot is set but no reward parameters are, it will never be distributed",
+        ),
+        _ => (),
+    }
+
+    // building the block panics on the errors found so far, and the ledger
+    // would only report the first of them
+    let mut block0_hash = None;
+    let mut stake = None;
+    if !diagnostics.has_errors() {
+        let block = genesis.to_block();
+        match Ledger::new(block.id(), block.fragments()) {
+            Ok(ledger) => {
+                let distribution = ledger.get_stake_distribution();
+                let delegated: u64 = distribution
+                    .to_pools
+                    .values()
+                    .map(|pool| pool.stake.total.0)
+                    .sum();
+                if config.block0_consensus == ConsensusVersion::GenesisPraos && delegated == 0 {
+                    diagnostics.error(
+                        "no-delegated-stake",
+                        "initial",
+                        "no stake is delegated to the stake pools, none could create blocks",
+                    );
+                }
+                stake = Some(StakeDistribution {
+                    dangling: distribution.dangling.into(),
+                    unassigned: distribution.unassigned.into(),
+                    pools: distribution
+                        .to_pools
+                        .iter()
+                        .map(|(pool_id, pool)| (pool_id.clone().into(), pool.stake.total.into()))
+                        .collect(),
+                });
+            }
+            Err(e) => {
+                let mut message = e.to_string();
+                let mut source = std::error::Error::source(&e);
+                while let Some(cause) = source {
+                    message = format!("{}: {}", message, cause);
+                    source = cause.source();
+                }
+                diagnostics.error("ledger-rejected", "initial", message);
+            }
+        }
+        block0_hash = Some(block.id().to_string());
+    }
+
+    let summary = Summary {
+        block0_hash,
+        block0_date: config.block0_date,
+        consensus: match config.block0_consensus {
+            ConsensusVersion::Bft => "bft",
+            ConsensusVersion::GenesisPraos => "genesis",
+        },
+        discrimination: discrimination_name(config.discrimination),
+        consensus_leaders: config.consensus_leader_ids.len(),
+        slots_per_epoch,
+        slot_duration,
+        epoch_duration: u64::from(slots_per_epoch) * u64::from(slot_duration),
+        initial_funds: Supply {
+            outputs: initial_outputs,
+            total: initial_funds.unwrap_or_else(|| Value::from(u64::MAX)),
+        },
+        legacy_funds: Supply {
+            outputs: legacy_outputs,
+            total: legacy_funds.unwrap_or_else(|| Value::from(u64::MAX)),
+        },
+        treasury: config.treasury,
+        total_reward_supply: config.total_reward_supply,
+        certificates,
+        stake,
+        tokens: tokens
+            .into_iter()
+            .map(|(token_id, (holders, total))| TokenSupply {
+                token_id,
+                holders,
+                total,
+            })
+            .collect(),
+    };
+    (diagnostics.0, summary)
+}
+
+fn check_entries_count(diagnostics: &mut Diagnostics, location: &str, count: usize) {
+    if count == 0 {
+        diagnostics.error("empty-fund", location, "the entry holds no fund");
+    } else if count > MAX_ENTRIES_PER_INITIAL {
+        diagnostics.error(
+            "fund-too-large",
+            location,
+            format!(
+                "the entry holds {} funds, at most {} are allowed, spread them over several entries",
+                count, MAX_ENTRIES_PER_INITIAL
+            ),
+        );
+    }
+}
+
+fn discrimination_name(discrimination: Discrimination) -> &'static str {
+    match discrimination {
+        Discrimination::Production => "production",
+        Discrimination::Test => "test",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jcli_lib::block::test_utils::example;
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<&'static str> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect()
+    }
+
+    #[test]
+    fn documented_example_is_valid() {
+        let (diagnostics, summary) = validate(&example());
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert!(summary.block0_hash.is_some());
+        assert_eq!(summary.consensus_leaders, 2);
+        assert_eq!(summary.initial_funds.outputs, 2);
+        assert_eq!(u64::from(summary.initial_funds.total), 20_000);
+        let token_totals: Vec<u64> = summary
+            .tokens
+            .iter()
+            .map(|token| token.total.into())
+            .collect();
+        assert_eq!(token_totals.iter().sum::<u64>(), 150 + 255 + 22 + 66);
+    }
+
+    #[test]
+    fn bft_requires_leaders() {
+        let mut genesis = example();
+        genesis
+            .blockchain_configuration
+            .consensus_leader_ids
+            .clear();
+        let (diagnostics, _) = validate(&genesis);
+        assert_eq!(codes(&diagnostics), vec!["bft-without-leaders"]);
+    }
+
+    #[test]
+    fn empty_fund_is_reported_without_building_the_block() {
+        let mut genesis = example();
+        genesis.initial.push(Initial::Fund(Vec::new()));
+        let (diagnostics, summary) = validate(&genesis);
+        assert_eq!(codes(&diagnostics), vec!["empty-fund"]);
+        assert_eq!(diagnostics[0].location, "initial[3]");
+        assert!(summary.block0_hash.is_none());
+    }
+
+    #[test]
+    fn funds_must_match_the_discrimination() {
+        let mut genesis = example();
+        genesis.blockchain_configuration.discrimination =
+            match genesis.blockchain_configuration.discrimination {
+                Discrimination::Production => Discrimination::Test,
+                Discrimination::Test => Discrimination::Production,
+            };
+        let (diagnostics, _) = validate(&genesis);
+        assert_eq!(
+            codes(&diagnostics),
+            vec!["discrimination-mismatch", "discrimination-mismatch"]
+        );
+        assert_eq!(diagnostics[1].location, "initial[0].fund[1]");
+    }
+
+    #[test]
+    fn funds_over_the_reward_supply_are_reported() {
+        let mut genesis = example();
+        genesis.blockchain_configuration.total_reward_supply = Some(Value::from(10_000));
+        let (diagnostics, _) = validate(&genesis);
+        assert_eq!(codes(&diagnostics), vec!["funds-exceed-reward-supply"]);
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(feature = "evm")]
 mod new_evm_mapping;
+mod new_mint_token;
 mod new_owner_stake_delegation;
 mod new_stake_delegation;
 mod new_stake_pool_registration;
@@ -97,6 +98,10 @@ pub enum Error {
     TooManyVotePlanProposals { actual: usize, max: usize },
     #[error("invalid certificate, expecting a vote plan one")]
     NotVotePlanCertificate,
+    #[error("invalid certificate, expecting a mint token one")]
+    NotMintTokenCertificate,
+    #[error("invalid token name, expecting at most 32 hex encoded bytes")]
+    InvalidTokenName,
     #[error("invalid vote plan certificate configuration")]
     VotePlanConfig(#[source] serde_yaml::Error),
     #[error("invalid base64 encoded bytes")]
@@ -175,6 +180,11 @@ pub enum NewArgs {
     UpdateProposal(new_update_proposal::UpdateProposal),
     /// create a vote cast certificate
     VoteCast(new_vote_cast::VoteCastCmd),
+    /// create a mint token certificate
+    ///
+    /// the tokens are minted under the default minting policy and credited
+    /// to the given account once the certificate is included in the ledger
+    MintToken(new_mint_token::MintToken),
     #[cfg(feature = "evm")]
     /// create an EVM address mapping certificate
     EvmMapping(new_evm_mapping::EvmMapCmd),
@@ -210,6 +220,7 @@ impl NewArgs {
             NewArgs::VoteCast(args) => args.exec()?,
             NewArgs::UpdateVote(args) => args.exec()?,
             NewArgs::UpdateProposal(args) => args.exec()?,
+            NewArgs::MintToken(args) => args.exec()?,
             #[cfg(feature = "evm")]
             NewArgs::EvmMapping(args) => args.exec()?,
         }
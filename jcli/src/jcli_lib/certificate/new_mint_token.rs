@@ -0,0 +1,97 @@
+use crate::jcli_lib::{
+    certificate::{write_cert, Error},
+    utils::key_parser::parse_pub_key,
+};
+use chain_crypto::{Ed25519, PublicKey};
+use chain_impl_mockchain::{
+    certificate::{self, Certificate},
+    tokens::{minting_policy::MintingPolicy, name::TokenName},
+};
+use jormungandr_lib::interfaces::{Certificate as CertificateType, Value};
+use std::{convert::TryFrom, path::PathBuf};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct MintToken {
+    /// hex encoded name of the token to mint, at most 32 bytes long
+    #[structopt(long, parse(try_from_str = parse_token_name))]
+    name: TokenName,
+
+    /// the public key of the account receiving the minted tokens
+    #[structopt(long, parse(try_from_str = parse_pub_key))]
+    to: PublicKey<Ed25519>,
+
+    /// the amount of tokens to mint
+    #[structopt(long)]
+    value: Value,
+
+    /// write the output to the given file or print it to the standard output if not defined
+    #[structopt(short = "o", long = "output")]
+    output: Option<PathBuf>,
+}
+
+fn parse_token_name(s: &str) -> Result<TokenName, Error> {
+    let bytes = hex::decode(s).map_err(|_| Error::InvalidTokenName)?;
+    TokenName::try_from(bytes).map_err(|_| Error::InvalidTokenName)
+}
+
+impl MintToken {
+    pub fn exec(self) -> Result<(), Error> {
+        write_cert(self.output.as_deref(), self.certificate())
+    }
+
+    fn certificate(&self) -> CertificateType {
+        CertificateType(Certificate::MintToken(certificate::MintToken {
+            name: self.name.clone(),
+            policy: MintingPolicy::new(),
+            to: self.to.clone().into(),
+            value: self.value.into(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_names_are_hex_and_bounded() {
+        assert_eq!(
+            parse_token_name("6c1e8abc").unwrap().as_ref(),
+            &[0x6c, 0x1e, 0x8a, 0xbc]
+        );
+        assert!(parse_token_name(&"ab".repeat(32)).is_ok());
+        assert!(matches!(
+            parse_token_name(&"ab".repeat(33)),
+            Err(Error::InvalidTokenName)
+        ));
+        assert!(matches!(
+            parse_token_name("not hex"),
+            Err(Error::InvalidTokenName)
+        ));
+    }
+
+    #[test]
+    fn mint_token_certificate_roundtrip() {
+        let mint_token = MintToken {
+            name: parse_token_name("6c1e8abc").unwrap(),
+            to: parse_pub_key(
+                "ed25519_pk1g53asm6l4gcwk2pm5ylr092umaur5yes47rqv7ng5yl525x8g8mq5nk7x7",
+            )
+            .unwrap(),
+            value: 100.into(),
+            output: None,
+        };
+        let encoded = mint_token.certificate().to_bech32m().unwrap();
+
+        let decoded: CertificateType = encoded.parse().unwrap();
+        match decoded.0 {
+            Certificate::MintToken(decoded) => {
+                assert_eq!(decoded.name, mint_token.name);
+                assert_eq!(decoded.value, mint_token.value.into());
+            }
+            _ => panic!("expected a mint token certificate"),
+        }
+    }
+}
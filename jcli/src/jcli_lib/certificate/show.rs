@@ -1,4 +1,5 @@
 mod stake_pool_id;
+mod token_id;
 mod vote_plan_id;
 
 use crate::jcli_lib::certificate::Error;
@@ -11,6 +12,8 @@ pub enum ShowArgs {
     StakePoolId(stake_pool_id::GetStakePoolId),
     /// get the vote plan id from the given vote plan certificate
     VotePlanId(vote_plan_id::GetVotePlanId),
+    /// get the token identifier from the given mint token certificate
+    TokenId(token_id::GetTokenId),
 }
 
 impl ShowArgs {
@@ -18,6 +21,7 @@ impl ShowArgs {
         match self {
             ShowArgs::StakePoolId(args) => args.exec(),
             ShowArgs::VotePlanId(args) => args.exec(),
+            ShowArgs::TokenId(args) => args.exec(),
         }
     }
 }
@@ -0,0 +1,174 @@
+use crate::jcli_lib::{
+    rest::{
+        self,
+        v0::{governance::request_update_proposal, message::post_fragment},
+        RestArgs,
+    },
+    transaction::{self, simplified::make_certificate_transaction},
+    utils::{
+        io,
+        key_parser::{self, parse_ed25519_secret_key},
+    },
+};
+use chain_crypto::{Ed25519, PublicKey};
+use chain_impl_mockchain::certificate::{self, Certificate, UpdateProposalId};
+use jormungandr_lib::interfaces::{BlockDate, ConfigParams};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("could not read the secret key file '{path}'")]
+    SecretKeyFileReadFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("the secret key is not valid")]
+    SecretKeyMalformed(#[from] key_parser::Error),
+    #[error("could not read the config changes file")]
+    ConfigFileReadFailed(#[source] std::io::Error),
+    #[error("the config changes file is not valid")]
+    ConfigFileMalformed(#[from] serde_yaml::Error),
+    #[error("the update proposal {0} is not pending on the node")]
+    ProposalNotPending(UpdateProposalId),
+    #[error("could not make the transaction")]
+    Transaction(#[from] transaction::Error),
+    #[error("could not submit the transaction")]
+    Rest(#[from] rest::Error),
+}
+
+/// Propose changes of the blockchain settings and vote for them, as a BFT
+/// leader. The certificate is signed with the leader key and posted to the
+/// node in a transaction paying its fees.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Governance {
+    /// propose changes of the settings, prints the ID of the proposal
+    Propose(Propose),
+    /// vote for a pending update proposal
+    Vote(Vote),
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Propose {
+    /// the file with the config param changes, in the format of
+    /// `jcli certificate new update-proposal`. If omitted it will be read
+    /// from the standard input.
+    #[structopt(name = "CONFIG_FILE")]
+    config_file: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    submission: Submission,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Vote {
+    /// the ID of the proposal to vote for
+    #[structopt(name = "PROPOSAL_ID")]
+    proposal_id: UpdateProposalId,
+
+    #[structopt(flatten)]
+    submission: Submission,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Submission {
+    /// the file with the bech32 secret key of the BFT leader
+    #[structopt(long)]
+    secret: PathBuf,
+
+    /// the file with the bech32 secret key of the account paying the fees,
+    /// the account of the BFT leader key if omitted
+    #[structopt(long)]
+    fees_secret: Option<PathBuf>,
+
+    /// the slot the transaction is valid until, for example 3.14
+    #[structopt(long)]
+    valid_until: BlockDate,
+
+    #[structopt(flatten)]
+    rest_args: RestArgs,
+}
+
+impl Governance {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Governance::Propose(propose) => propose.exec(),
+            Governance::Vote(vote) => vote.exec(),
+        }
+    }
+}
+
+impl Propose {
+    fn exec(self) -> Result<(), Error> {
+        let reader = io::open_file_read(&self.config_file).map_err(Error::ConfigFileReadFailed)?;
+        let changes: ConfigParams = serde_yaml::from_reader(reader)?;
+        let proposal_id = self.submission.submit(|proposer| {
+            Certificate::UpdateProposal(certificate::UpdateProposal::new(
+                changes.into(),
+                proposer.into(),
+            ))
+        })?;
+        println!("{}", proposal_id);
+        Ok(())
+    }
+}
+
+impl Vote {
+    fn exec(self) -> Result<(), Error> {
+        let proposal_id = self.proposal_id;
+        request_update_proposal(self.submission.rest_args.clone(), &proposal_id.to_string())
+            .map_err(|error| {
+                if error.is_not_found() {
+                    Error::ProposalNotPending(proposal_id)
+                } else {
+                    error.into()
+                }
+            })?;
+
+        let fragment_id = self.submission.submit(|voter| {
+            Certificate::UpdateVote(certificate::UpdateVote::new(proposal_id, voter.into()))
+        })?;
+        println!("{}", fragment_id);
+        Ok(())
+    }
+}
+
+impl Submission {
+    /// sign the certificate made out of the BFT leader public key with the
+    /// leader secret key, post it and return the ID of the fragment
+    fn submit<F>(self, make_certificate: F) -> Result<String, Error>
+    where
+        F: FnOnce(PublicKey<Ed25519>) -> Certificate,
+    {
+        let leader_key_str = read_key(&self.secret)?;
+        let leader_key = parse_ed25519_secret_key(&leader_key_str)?;
+        let payer_key = match &self.fees_secret {
+            Some(path) => parse_ed25519_secret_key(&read_key(path)?)?,
+            None => parse_ed25519_secret_key(&leader_key_str)?,
+        };
+
+        let certificate = make_certificate(leader_key.to_public());
+        let transaction = make_certificate_transaction(
+            certificate.into(),
+            &payer_key,
+            &leader_key_str,
+            self.valid_until,
+            self.rest_args.clone(),
+        )?;
+        let fragment = transaction.fragment()?;
+        Ok(post_fragment(self.rest_args, fragment)?)
+    }
+}
+
+fn read_key(path: &Path) -> Result<String, Error> {
+    io::read_line(&Some(path)).map_err(|source| Error::SecretKeyFileReadFailed {
+        source,
+        path: path.to_path_buf(),
+    })
+}
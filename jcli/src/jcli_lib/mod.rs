@@ -3,10 +3,13 @@ pub mod auto_completion;
 pub mod block;
 pub mod certificate;
 pub mod debug;
+pub mod governance;
 pub mod key;
 pub mod rest;
+pub mod secret;
 pub mod transaction;
 pub mod vote;
+pub mod wallet;
 
 pub mod utils;
 
@@ -55,6 +58,12 @@ pub enum JCliCommand {
     Utils(utils::Utils),
     /// Vote related operations
     Votes(vote::Vote),
+    /// HD wallet: mnemonics, key derivation and discovery of used addresses
+    Wallet(wallet::Wallet),
+    /// Encrypt and decrypt the node secret files
+    Secret(secret::Secret),
+    /// Propose changes of the blockchain settings and vote for them
+    Governance(governance::Governance),
 }
 
 impl JCli {
@@ -87,6 +96,9 @@ impl JCliCommand {
             AutoCompletion(auto_completion) => auto_completion.exec::<Self>()?,
             Utils(utils) => utils.exec()?,
             Votes(vote) => vote.exec()?,
+            Wallet(wallet) => wallet.exec()?,
+            Secret(secret) => secret.exec()?,
+            Governance(governance) => governance.exec()?,
         };
         Ok(())
     }
@@ -21,6 +21,16 @@ pub struct RestArgs {
     /// certificate CA is not present within the webpki certificate bundle.
     #[structopt(long, name = "PATH", env = "JORMUNGANDR_TLS_CERT_PATH")]
     tls_cert_path: Option<PathBuf>,
+    /// An optional PEM file holding the client certificate and its private key,
+    /// for the nodes requiring the clients to authenticate over TLS.
+    #[structopt(long, env = "JORMUNGANDR_TLS_CLIENT_IDENTITY_PATH")]
+    tls_client_identity_path: Option<PathBuf>,
+    /// bearer token sent in the `Authorization` header of every request
+    #[structopt(long, env = "JORMUNGANDR_RESTAPI_TOKEN", conflicts_with = "api-key")]
+    token: Option<String>,
+    /// API key sent in the `X-Api-Key` header of every request
+    #[structopt(long, env = "JORMUNGANDR_RESTAPI_KEY")]
+    api_key: Option<String>,
 }
 
 pub struct RestClient {
@@ -45,6 +55,10 @@ pub enum Error {
     CertIo(#[source] std::io::Error),
     #[error("expected a valid PEM-encoded certificate")]
     Pem(#[source] reqwest::Error),
+    #[error("expected a valid PEM-encoded client certificate and private key")]
+    Identity(#[source] reqwest::Error),
+    #[error("the credentials are not valid as an HTTP header value")]
+    InvalidCredentials(#[source] reqwest::header::InvalidHeaderValue),
     #[error("failed to build an HTTP client")]
     Client(#[source] reqwest::Error),
     #[error("invalid request")]
@@ -57,6 +71,8 @@ pub enum Error {
     Text(#[source] reqwest::Error),
     #[error("connection with the node timed out")]
     Timeout,
+    #[error("node rejected request because of missing or insufficient credentials")]
+    Unauthorized(#[source] reqwest::Error),
     #[error("node rejected request because of invalid parameters")]
     InvalidParams(#[source] reqwest::Error),
     #[error("node internal error")]
@@ -69,11 +85,18 @@ pub enum Error {
 
 impl RestArgs {
     pub fn client(self) -> Result<RestClient, Error> {
-        use reqwest::{blocking::ClientBuilder, Certificate};
+        use reqwest::{
+            blocking::ClientBuilder,
+            header::{HeaderMap, HeaderValue, AUTHORIZATION},
+            Certificate, Identity,
+        };
         use std::{fs::File, io::Read};
 
         let Self {
             tls_cert_path,
+            tls_client_identity_path,
+            token,
+            api_key,
             host,
             debug,
         } = self;
@@ -97,6 +120,33 @@ impl RestArgs {
             client_builder
         };
 
+        // load client certificate
+        let client_builder = if let Some(path) = tls_client_identity_path {
+            let mut buf = Vec::new();
+            File::open(path)
+                .map_err(Error::CertIo)?
+                .read_to_end(&mut buf)
+                .map_err(Error::CertIo)?;
+            let identity = Identity::from_pem(&buf).map_err(Error::Identity)?;
+            client_builder.use_rustls_tls().identity(identity)
+        } else {
+            client_builder
+        };
+
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(Error::InvalidCredentials)?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        if let Some(api_key) = api_key {
+            let mut value = HeaderValue::from_str(&api_key).map_err(Error::InvalidCredentials)?;
+            value.set_sensitive(true);
+            headers.insert("x-api-key", value);
+        }
+        let client_builder = client_builder.default_headers(headers);
+
         let client = client_builder.build().map_err(Error::Client)?;
 
         let rest_client = RestClient {
@@ -200,7 +250,11 @@ impl RestRequestBuilder {
                 if e.is_timeout() {
                     Error::Timeout
                 } else if let Some(status) = e.status() {
-                    if status.is_client_error() {
+                    if status == reqwest::StatusCode::UNAUTHORIZED
+                        || status == reqwest::StatusCode::FORBIDDEN
+                    {
+                        Error::Unauthorized(e)
+                    } else if status.is_client_error() {
                         Error::InvalidParams(e)
                     } else if status.is_server_error() {
                         Error::Internal(e)
@@ -48,6 +48,17 @@ impl From<ReadYamlError> for Error {
     }
 }
 
+impl Error {
+    /// the node answered that the requested resource does not exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Error::RequestError(config::Error::InvalidParams(e))
+                if e.status() == Some(reqwest::StatusCode::NOT_FOUND)
+        )
+    }
+}
+
 impl Rest {
     pub fn exec(self) -> Result<(), Error> {
         match self {
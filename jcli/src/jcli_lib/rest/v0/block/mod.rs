@@ -4,6 +4,8 @@ use structopt::StructOpt;
 mod next_id;
 mod subcommand;
 
+pub use self::{next_id::request_next_ids, subcommand::request_block};
+
 #[derive(StructOpt)]
 #[structopt(rename_all = "kebab-case")]
 pub struct Block {
@@ -24,14 +24,27 @@ impl NextId {
 }
 
 fn exec_get(args: RestArgs, block_id: String, count: Option<usize>) -> Result<(), Error> {
+    for block_id in request_next_ids(args, &block_id, count)? {
+        println!("{}", block_id);
+    }
+    Ok(())
+}
+
+/// the hex-encoded ids of the descendants of `block_id` in the main chain,
+/// at most `count` of them
+pub fn request_next_ids(
+    args: RestArgs,
+    block_id: &str,
+    count: Option<usize>,
+) -> Result<Vec<String>, Error> {
     let response = args
         .client()?
-        .get(&["v0", "block", &block_id, "next_id"])
+        .get(&["v0", "block", block_id, "next_id"])
         .query(&[("count", count)])
         .execute()?
         .bytes()?;
-    for block_id in response.chunks(Blake2b256::HASH_SIZE) {
-        println!("{}", hex::encode(block_id));
-    }
-    Ok(())
+    Ok(response
+        .chunks(Blake2b256::HASH_SIZE)
+        .map(hex::encode)
+        .collect())
 }
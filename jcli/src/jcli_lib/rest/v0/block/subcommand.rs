@@ -1,5 +1,6 @@
 use super::next_id::NextId;
 use crate::jcli_lib::rest::{Error, RestArgs};
+use bytes::Bytes;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -24,11 +25,16 @@ impl Subcommand {
 }
 
 fn exec_get(block_id: String, args: RestArgs) -> Result<(), Error> {
-    let response = args
-        .client()?
-        .get(&["v0", "block", &block_id])
-        .execute()?
-        .bytes()?;
+    let response = request_block(args, &block_id)?;
     println!("{}", hex::encode(&response));
     Ok(())
 }
+
+/// the binary encoded block
+pub fn request_block(args: RestArgs, block_id: &str) -> Result<Bytes, Error> {
+    Ok(args
+        .client()?
+        .get(&["v0", "block", block_id])
+        .execute()?
+        .bytes()?)
+}
@@ -0,0 +1,64 @@
+use crate::jcli_lib::{
+    rest::{Error, RestArgs},
+    utils::OutputFormat,
+};
+use jormungandr_lib::interfaces::UpdateProposalStatus;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Governance {
+    /// Update proposals pending in the ledger
+    Proposals(Proposals),
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Proposals {
+    /// Get the pending update proposals with their votes and status
+    Get {
+        #[structopt(flatten)]
+        args: RestArgs,
+        /// hex-encoded ID of a proposal, to only get this one
+        proposal_id: Option<String>,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+}
+
+impl Governance {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Governance::Proposals(proposals) => proposals.exec(),
+        }
+    }
+}
+
+impl Proposals {
+    pub fn exec(self) -> Result<(), Error> {
+        let Proposals::Get {
+            args,
+            proposal_id,
+            output_format,
+        } = self;
+        let mut path = vec!["v0", "governance", "proposals"];
+        if let Some(proposal_id) = &proposal_id {
+            path.push(proposal_id);
+        }
+        let response = args.client()?.get(&path).execute()?.json()?;
+        let formatted = output_format.format_json(response)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+}
+
+pub fn request_update_proposal(
+    args: RestArgs,
+    proposal_id: &str,
+) -> Result<UpdateProposalStatus, Error> {
+    Ok(args
+        .client()?
+        .get(&["v0", "governance", "proposals", proposal_id])
+        .execute()?
+        .json()?)
+}
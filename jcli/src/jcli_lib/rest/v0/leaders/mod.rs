@@ -9,6 +9,10 @@ use structopt::StructOpt;
 pub enum Leaders {
     /// Leadership log operations
     Logs(GetLogs),
+    /// Leadership schedule of the current and next epochs
+    Schedule(GetSchedule),
+    /// Assemble a candidate block on the tip without publishing it
+    DryRun(PostDryRun),
 }
 
 #[derive(StructOpt)]
@@ -23,6 +27,32 @@ pub enum GetLogs {
     },
 }
 
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum GetSchedule {
+    /// Get the slots where the node is leader in the current epoch, and in
+    /// the next epoch if its stake distribution is already known
+    Get {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum PostDryRun {
+    /// Assemble a block with the fragments of the mempool, leaving the
+    /// mempool untouched, and report how long it took
+    Post {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+}
+
 impl Leaders {
     pub fn exec(self) -> Result<(), Error> {
         match self {
@@ -30,6 +60,14 @@ impl Leaders {
                 args,
                 output_format,
             }) => get_logs(args, output_format),
+            Leaders::Schedule(GetSchedule::Get {
+                args,
+                output_format,
+            }) => get_schedule(args, output_format),
+            Leaders::DryRun(PostDryRun::Post {
+                args,
+                output_format,
+            }) => post_dry_run(args, output_format),
         }
     }
 }
@@ -44,3 +82,25 @@ fn get_logs(args: RestArgs, output_format: OutputFormat) -> Result<(), Error> {
     println!("{}", formatted);
     Ok(())
 }
+
+fn get_schedule(args: RestArgs, output_format: OutputFormat) -> Result<(), Error> {
+    let response = args
+        .client()?
+        .get(&["v0", "leaders", "schedule"])
+        .execute()?
+        .json()?;
+    let formatted = output_format.format_json(response)?;
+    println!("{}", formatted);
+    Ok(())
+}
+
+fn post_dry_run(args: RestArgs, output_format: OutputFormat) -> Result<(), Error> {
+    let response = args
+        .client()?
+        .post(&["v0", "leaders", "dry_run"])
+        .execute()?
+        .json()?;
+    let formatted = output_format.format_json(response)?;
+    println!("{}", formatted);
+    Ok(())
+}
@@ -1,6 +1,7 @@
 pub mod account;
-mod block;
+pub mod block;
 mod diagnostic;
+pub mod governance;
 mod leaders;
 pub mod message;
 mod network;
@@ -11,9 +12,10 @@ mod shutdown;
 mod stake;
 mod stake_pool;
 mod stake_pools;
-mod tip;
-mod utxo;
-mod vote;
+pub mod tip;
+mod tokens;
+pub mod utxo;
+pub mod vote;
 
 use crate::jcli_lib::rest::Error;
 use structopt::StructOpt;
@@ -25,6 +27,8 @@ pub enum V0 {
     Account(account::Account),
     /// Block operations
     Block(block::Block),
+    /// Update proposals and their votes
+    Governance(governance::Governance),
     /// Node leaders operations
     Leaders(leaders::Leaders),
     /// Message sending
@@ -45,6 +49,8 @@ pub enum V0 {
     Shutdown(shutdown::Shutdown),
     /// Blockchain tip information
     Tip(tip::Tip),
+    /// Native token supply information
+    Tokens(tokens::Tokens),
     /// UTXO information
     Utxo(utxo::Utxo),
     /// System diagnostic information
@@ -60,6 +66,7 @@ impl V0 {
         match self {
             V0::Account(account) => account.exec(),
             V0::Block(block) => block.exec(),
+            V0::Governance(governance) => governance.exec(),
             V0::Leaders(leaders) => leaders.exec(),
             V0::Message(message) => message.exec(),
             V0::Network(network) => network.exec(),
@@ -70,6 +77,7 @@ impl V0 {
             V0::StakePools(stake_pools) => stake_pools.exec(),
             V0::Shutdown(shutdown) => shutdown.exec(),
             V0::Tip(tip) => tip.exec(),
+            V0::Tokens(tokens) => tokens.exec(),
             V0::Utxo(utxo) => utxo.exec(),
             V0::Diagnostic(diagnostic) => diagnostic.exec(),
             V0::Rewards(rewards) => rewards.exec(),
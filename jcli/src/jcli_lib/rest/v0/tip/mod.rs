@@ -16,8 +16,12 @@ impl Tip {
         let args = match self {
             Tip::Get { args } => args,
         };
-        let response = args.client()?.get(&["v0", "tip"]).execute()?.text()?;
-        println!("{}", response);
+        println!("{}", request_tip(args)?);
         Ok(())
     }
 }
+
+/// the hex-encoded ID of the tip block
+pub fn request_tip(args: RestArgs) -> Result<String, Error> {
+    Ok(args.client()?.get(&["v0", "tip"]).execute()?.text()?)
+}
@@ -0,0 +1,30 @@
+use crate::jcli_lib::{
+    rest::{Error, RestArgs},
+    utils::OutputFormat,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Tokens {
+    /// Get the tokens held in the ledger with their total supply
+    Get {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+}
+
+impl Tokens {
+    pub fn exec(self) -> Result<(), Error> {
+        let Tokens::Get {
+            args,
+            output_format,
+        } = self;
+        let response = args.client()?.get(&["v0", "tokens"]).execute()?.json()?;
+        let formatted = output_format.format_json(response)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use crate::jcli_lib::{
     rest::{Error, RestArgs},
     utils::OutputFormat,
 };
+use jormungandr_lib::interfaces::UTxOInfo;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -36,18 +37,21 @@ impl Utxo {
             args,
             output_format,
         } = self.subcommand;
-        let response = args
-            .client()?
-            .get(&[
-                "v0",
-                "utxo",
-                &self.fragment_id,
-                &self.output_index.to_string(),
-            ])
-            .execute()?
-            .json()?;
-        let formatted = output_format.format_json(response)?;
+        let response = request_utxo(args, &self.fragment_id, self.output_index)?;
+        let formatted = output_format.format_json(serde_json::to_value(response)?)?;
         println!("{}", formatted);
         Ok(())
     }
 }
+
+pub fn request_utxo(
+    args: RestArgs,
+    fragment_id: &str,
+    output_index: u8,
+) -> Result<UTxOInfo, Error> {
+    args.client()?
+        .get(&["v0", "utxo", fragment_id, &output_index.to_string()])
+        .execute()?
+        .json()
+        .map_err(Into::into)
+}
@@ -3,6 +3,7 @@ mod committees;
 mod plans;
 
 use self::active::Active;
+pub use self::plans::request_active_vote_plans;
 use crate::jcli_lib::rest::Error;
 use structopt::StructOpt;
 
@@ -2,6 +2,7 @@ use crate::jcli_lib::{
     rest::{Error, RestArgs},
     utils::OutputFormat,
 };
+use jormungandr_lib::interfaces::VotePlanStatus;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -32,3 +33,11 @@ impl Plans {
         Ok(())
     }
 }
+
+pub fn request_active_vote_plans(args: RestArgs) -> Result<Vec<VotePlanStatus>, Error> {
+    Ok(args
+        .client()?
+        .get(&["v0", "vote", "active", "plans"])
+        .execute()?
+        .json()?)
+}
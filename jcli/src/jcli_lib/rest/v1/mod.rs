@@ -1,3 +1,4 @@
+mod p2p;
 mod vote;
 
 use crate::jcli_lib::rest::Error;
@@ -6,12 +7,14 @@ use structopt::StructOpt;
 #[derive(StructOpt)]
 #[structopt(rename_all = "kebab-case")]
 pub enum V1 {
+    P2p(p2p::P2p),
     Vote(vote::Vote),
 }
 
 impl V1 {
     pub fn exec(self) -> Result<(), Error> {
         match self {
+            V1::P2p(p2p) => p2p.exec(),
             V1::Vote(vote) => vote.exec(),
         }
     }
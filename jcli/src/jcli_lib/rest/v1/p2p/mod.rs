@@ -0,0 +1,184 @@
+use crate::jcli_lib::{
+    rest::{Error, RestArgs},
+    utils::OutputFormat,
+};
+use jormungandr_lib::{
+    interfaces::{BanRequest, ConnectRequest, PeerTarget},
+    time::Duration,
+};
+use multiaddr::Multiaddr;
+use structopt::StructOpt;
+
+/// Manage the peers of the node
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum P2p {
+    /// List the banned peers
+    Bans {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+    /// Ban a peer, disconnecting it and refusing its connections
+    Ban {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+        /// hex-encoded node id of the peer, or IP address to ban all
+        /// the peers connecting from it
+        target: PeerTarget,
+        /// duration of the ban (e.g. `1h 30m`), the ban is permanent
+        /// if not set
+        #[structopt(long)]
+        duration: Option<Duration>,
+        /// reason of the ban, kept for reference
+        #[structopt(long)]
+        reason: Option<String>,
+    },
+    /// Lift the ban of a peer
+    Unban {
+        #[structopt(flatten)]
+        args: RestArgs,
+        /// hex-encoded node id or IP address the ban was set on
+        target: PeerTarget,
+    },
+    /// Lift the quarantine of a peer, making it available again
+    LiftQuarantine {
+        #[structopt(flatten)]
+        args: RestArgs,
+        /// hex-encoded node id of the peer
+        id: String,
+    },
+    /// Connect to a peer
+    Connect {
+        #[structopt(flatten)]
+        args: RestArgs,
+        /// address of the peer, e.g. `/ip4/127.0.0.1/tcp/3000`
+        #[structopt(long)]
+        address: Multiaddr,
+        /// hex-encoded node id of the peer
+        #[structopt(long)]
+        id: String,
+    },
+    /// Disconnect a peer
+    Disconnect {
+        #[structopt(flatten)]
+        args: RestArgs,
+        /// hex-encoded node id of the peer
+        id: String,
+    },
+    /// List the pinned peers
+    Pinned {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+    /// Pin a peer, so it is never evicted nor quarantined
+    Pin {
+        #[structopt(flatten)]
+        args: RestArgs,
+        /// hex-encoded node id of the peer
+        id: String,
+    },
+    /// Unpin a peer
+    Unpin {
+        #[structopt(flatten)]
+        args: RestArgs,
+        /// hex-encoded node id of the peer
+        id: String,
+    },
+}
+
+impl P2p {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            P2p::Bans {
+                args,
+                output_format,
+            } => {
+                let response = args
+                    .client()?
+                    .get(&["v1", "network", "p2p", "bans"])
+                    .execute()?
+                    .json()?;
+                let formatted = output_format.format_json(response)?;
+                println!("{}", formatted);
+            }
+            P2p::Ban {
+                args,
+                output_format,
+                target,
+                duration,
+                reason,
+            } => {
+                let request = BanRequest {
+                    target,
+                    duration,
+                    reason,
+                };
+                let response = args
+                    .client()?
+                    .post(&["v1", "network", "p2p", "bans"])
+                    .json(&request)
+                    .execute()?
+                    .json()?;
+                let formatted = output_format.format_json(response)?;
+                println!("{}", formatted);
+            }
+            P2p::Unban { args, target } => {
+                args.client()?
+                    .delete(&["v1", "network", "p2p", "bans", &target.to_string()])
+                    .execute()?;
+                println!("Success");
+            }
+            P2p::LiftQuarantine { args, id } => {
+                args.client()?
+                    .delete(&["v1", "network", "p2p", "quarantined", &id])
+                    .execute()?;
+                println!("Success");
+            }
+            P2p::Connect { args, address, id } => {
+                let request = ConnectRequest { address, id };
+                args.client()?
+                    .post(&["v1", "network", "p2p", "connections"])
+                    .json(&request)
+                    .execute()?;
+                println!("Success");
+            }
+            P2p::Disconnect { args, id } => {
+                args.client()?
+                    .delete(&["v1", "network", "p2p", "connections", &id])
+                    .execute()?;
+                println!("Success");
+            }
+            P2p::Pinned {
+                args,
+                output_format,
+            } => {
+                let response = args
+                    .client()?
+                    .get(&["v1", "network", "p2p", "pinned"])
+                    .execute()?
+                    .json()?;
+                let formatted = output_format.format_json(response)?;
+                println!("{}", formatted);
+            }
+            P2p::Pin { args, id } => {
+                args.client()?
+                    .post(&["v1", "network", "p2p", "pinned", &id])
+                    .execute()?;
+                println!("Success");
+            }
+            P2p::Unpin { args, id } => {
+                args.client()?
+                    .delete(&["v1", "network", "p2p", "pinned", &id])
+                    .execute()?;
+                println!("Success");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::jcli_lib::utils::{
+    io,
+    output_file::{self, OutputFile},
+};
+use jormungandr_lib::{
+    crypto::encrypted::{
+        EncryptedSecret, EncryptedSecretError, DEFAULT_ITERATIONS, PASSPHRASE_ENV_VAR,
+    },
+    interfaces::NodeSecret,
+};
+use rand::rngs::OsRng;
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    InvalidOutput(#[from] output_file::Error),
+    #[error("input is not a valid node secret")]
+    InvalidSecret(#[source] serde_yaml::Error),
+    #[error("input is not a valid encrypted node secret")]
+    InvalidEncryptedSecret(#[source] serde_yaml::Error),
+    #[error("could not read the passphrase keyfile '{path}'")]
+    KeyfileRead {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("the passphrase is empty")]
+    EmptyPassphrase,
+    #[error("the passphrases do not match")]
+    PassphraseMismatch,
+    #[error(transparent)]
+    EncryptedSecret(#[from] EncryptedSecretError),
+}
+
+/// Encrypt and decrypt the node secret files
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Secret {
+    /// encrypt a node secret file with a passphrase
+    Encrypt(Encrypt),
+    /// decrypt an encrypted node secret file
+    Decrypt(Decrypt),
+}
+
+#[derive(StructOpt)]
+pub struct PassphraseArgs {
+    /// read the passphrase from the given keyfile instead of the
+    /// `JORMUNGANDR_SECRET_PASSPHRASE` environment variable or an interactive prompt
+    #[structopt(long = "passphrase-file")]
+    passphrase_file: Option<PathBuf>,
+}
+
+#[derive(StructOpt)]
+pub struct Encrypt {
+    /// the node secret file to encrypt, or the standard input if not provided
+    #[structopt(long = "input")]
+    input: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    passphrase: PassphraseArgs,
+
+    /// number of PBKDF2 iterations deriving the encryption key from the passphrase,
+    /// at least 10000, 100000 if not provided
+    #[structopt(long = "iterations")]
+    iterations: Option<u32>,
+
+    #[structopt(flatten)]
+    output_file: OutputFile,
+}
+
+#[derive(StructOpt)]
+pub struct Decrypt {
+    /// the encrypted node secret file, or the standard input if not provided
+    #[structopt(long = "input")]
+    input: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    passphrase: PassphraseArgs,
+
+    #[structopt(flatten)]
+    output_file: OutputFile,
+}
+
+impl Secret {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Secret::Encrypt(args) => args.exec(),
+            Secret::Decrypt(args) => args.exec(),
+        }
+    }
+}
+
+impl Encrypt {
+    fn exec(self) -> Result<(), Error> {
+        let secret = read_input(&self.input)?;
+        serde_yaml::from_slice::<NodeSecret>(&secret).map_err(Error::InvalidSecret)?;
+        let passphrase = self.passphrase.read(true)?;
+        let encrypted = EncryptedSecret::encrypt(
+            &mut OsRng,
+            passphrase.as_bytes(),
+            self.iterations.unwrap_or(DEFAULT_ITERATIONS),
+            &secret,
+        )?;
+        let mut output = self.output_file.open()?;
+        serde_yaml::to_writer(&mut output, &encrypted).map_err(Error::InvalidEncryptedSecret)?;
+        writeln!(output)?;
+        Ok(())
+    }
+}
+
+impl Decrypt {
+    fn exec(self) -> Result<(), Error> {
+        let input = read_input(&self.input)?;
+        let encrypted: EncryptedSecret =
+            serde_yaml::from_slice(&input).map_err(Error::InvalidEncryptedSecret)?;
+        let passphrase = self.passphrase.read(false)?;
+        let secret = encrypted.decrypt(passphrase.as_bytes())?;
+        self.output_file.open()?.write_all(&secret)?;
+        Ok(())
+    }
+}
+
+impl PassphraseArgs {
+    fn read(&self, confirm: bool) -> Result<Zeroizing<String>, Error> {
+        let passphrase = if let Some(path) = &self.passphrase_file {
+            let passphrase = Zeroizing::new(std::fs::read_to_string(path).map_err(|source| {
+                Error::KeyfileRead {
+                    source,
+                    path: path.clone(),
+                }
+            })?);
+            Zeroizing::new(passphrase.trim_end_matches(&['\r', '\n'][..]).to_owned())
+        } else if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV_VAR) {
+            Zeroizing::new(passphrase)
+        } else {
+            let passphrase =
+                Zeroizing::new(rpassword::prompt_password("Introduce the passphrase:\n")?);
+            if confirm {
+                let confirmation =
+                    Zeroizing::new(rpassword::prompt_password("Confirm the passphrase:\n")?);
+                if *passphrase != *confirmation {
+                    return Err(Error::PassphraseMismatch);
+                }
+            }
+            passphrase
+        };
+        if passphrase.is_empty() {
+            return Err(Error::EmptyPassphrase);
+        }
+        Ok(passphrase)
+    }
+}
+
+fn read_input(input: &Option<PathBuf>) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let mut buf = Zeroizing::new(Vec::new());
+    io::open_file_read(input)?.read_to_end(&mut buf)?;
+    Ok(buf)
+}
@@ -0,0 +1,188 @@
+use crate::jcli_lib::{
+    rest::{
+        v0::{
+            account::request_account_information, settings::request_settings, utxo::request_utxo,
+        },
+        RestArgs,
+    },
+    transaction::{
+        coin_selection::{self, Strategy},
+        common,
+        staging::Staging,
+        Error,
+    },
+    utils::{AccountId, UtxoPointer},
+};
+use chain_impl_mockchain::transaction::{Output, OutputPolicy};
+use jormungandr_lib::interfaces;
+use rand::{rngs::OsRng, SeedableRng};
+use rand_chacha::ChaChaRng;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Build {
+    #[structopt(flatten)]
+    pub common: common::CommonTransaction,
+
+    /// output to pay, in the form ADDRESS:VALUE. Can be given multiple times
+    #[structopt(long = "to", name = "ADDRESS:VALUE", required = true)]
+    pub outputs: Vec<OutputArg>,
+
+    /// the address to send the change to
+    #[structopt(long)]
+    pub change: interfaces::Address,
+
+    /// UTxO of the sender that can be spent, in the form
+    /// FRAGMENT_ID:OUTPUT_INDEX. Can be given multiple times. The node
+    /// is queried for their value and to check they are still unspent.
+    #[structopt(long = "utxo")]
+    pub utxos: Vec<UtxoPointer>,
+
+    /// account of the sender, used for what the UTxOs don't cover
+    #[structopt(long)]
+    pub from_account: Option<interfaces::Address>,
+
+    /// coin selection algorithm: largest-first or random-improve
+    #[structopt(long, default_value = "random-improve")]
+    pub strategy: Strategy,
+
+    /// the slot this transaction should be valid until, for example 3.14
+    #[structopt(long)]
+    pub valid_until: interfaces::BlockDate,
+
+    /// print the summary of the transaction without writing the staging file
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    #[structopt(flatten)]
+    pub rest_args: RestArgs,
+}
+
+pub struct OutputArg {
+    address: interfaces::Address,
+    value: interfaces::Value,
+}
+
+impl FromStr for OutputArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, value) = s
+            .rsplit_once(':')
+            .ok_or_else(|| format!("invalid output '{}', expected ADDRESS:VALUE", s))?;
+        Ok(OutputArg {
+            address: address
+                .parse()
+                .map_err(|e| format!("invalid address '{}': {}", address, e))?,
+            value: value
+                .parse()
+                .map_err(|e| format!("invalid value '{}': {}", value, e))?,
+        })
+    }
+}
+
+impl Build {
+    pub fn exec(self) -> Result<(), Error> {
+        let settings = request_settings(self.rest_args.clone())?;
+        let fee_algorithm = settings.fees;
+
+        let mut utxos = Vec::with_capacity(self.utxos.len());
+        for pointer in &self.utxos {
+            let utxo = request_utxo(
+                self.rest_args.clone(),
+                &pointer.fragment_id.to_string(),
+                pointer.output_index,
+            )
+            .map_err(|source| Error::UtxoNotAvailable {
+                source,
+                pointer: *pointer,
+            })?;
+            utxos.push((*pointer, *utxo.associated_fund()));
+        }
+
+        let account_balance = match &self.from_account {
+            Some(account) => {
+                let account_id = AccountId::try_from_str(&account.to_string())?;
+                let state = request_account_information(self.rest_args.clone(), account_id)?;
+                Some(u64::from(*state.value()))
+            }
+            None => None,
+        };
+
+        let candidates: Vec<u64> = utxos.iter().map(|(_, value)| u64::from(*value)).collect();
+        let outputs: Vec<u64> = self
+            .outputs
+            .iter()
+            .map(|output| u64::from(output.value))
+            .collect();
+        let mut rng = ChaChaRng::from_rng(OsRng)?;
+        let selection = coin_selection::select(
+            self.strategy,
+            &candidates,
+            account_balance,
+            &outputs,
+            &fee_algorithm,
+            &mut rng,
+        )?;
+
+        let mut transaction = Staging::new();
+        for index in &selection.utxos {
+            let (pointer, value) = utxos[*index];
+            transaction.add_input(interfaces::TransactionInput {
+                input: interfaces::TransactionInputType::Utxo(
+                    pointer.fragment_id.into(),
+                    pointer.output_index,
+                ),
+                value,
+            })?;
+        }
+        if let (Some(account), Some(value)) = (&self.from_account, selection.account) {
+            transaction.add_account(account.clone(), value.into())?;
+        }
+        for output in &self.outputs {
+            transaction.add_output(Output {
+                address: output.address.clone().into(),
+                value: output.value.into(),
+            })?;
+        }
+        transaction.set_expiry_date(self.valid_until)?;
+        transaction
+            .balance_inputs_outputs(&fee_algorithm, OutputPolicy::One(self.change.into()))?;
+
+        if self.dry_run {
+            print_summary(&transaction, &fee_algorithm)
+        } else {
+            self.common.store(&transaction)
+        }
+    }
+}
+
+fn print_summary(
+    transaction: &Staging,
+    fee_algorithm: &impl chain_impl_mockchain::fee::FeeAlgorithm,
+) -> Result<(), Error> {
+    println!("inputs:");
+    for input in transaction.inputs() {
+        match input.input {
+            interfaces::TransactionInputType::Utxo(fragment_id, index) => println!(
+                "  - utxo {}:{} {}",
+                jormungandr_lib::crypto::hash::Hash::from(fragment_id),
+                index,
+                input.value
+            ),
+            interfaces::TransactionInputType::Account(_) => {
+                println!("  - account {}", input.value)
+            }
+        }
+    }
+    println!("outputs:");
+    for output in transaction.outputs() {
+        println!("  - {} {}", output.address(), output.value());
+    }
+    println!("input: {}", transaction.total_input()?);
+    println!("output: {}", transaction.total_output()?);
+    println!("fee: {}", transaction.fees(fee_algorithm));
+    Ok(())
+}
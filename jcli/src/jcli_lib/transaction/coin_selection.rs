@@ -0,0 +1,295 @@
+use chain_impl_mockchain::fee::FeeAlgorithm;
+use rand::{seq::SliceRandom, Rng};
+use structopt::clap::arg_enum;
+use thiserror::Error;
+
+/// the number of inputs and outputs of a transaction is encoded on one byte
+const MAX_INPUTS_OUTPUTS: usize = u8::MAX as usize;
+
+arg_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Strategy {
+        LargestFirst,
+        RandomImprove,
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("not enough funds: {available} available, {needed} needed")]
+    InsufficientFunds { available: u64, needed: u64 },
+    #[error("the transaction needs more than {max} inputs and outputs")]
+    TooManyInputsOutputs { max: usize },
+    #[error("the total value of the transaction overflows")]
+    ValueOverflow,
+}
+
+/// The inputs selected to pay for the outputs of a transaction
+#[derive(Debug, PartialEq, Eq)]
+pub struct Selection {
+    /// indices of the selected UTxOs in the candidates
+    pub utxos: Vec<usize>,
+    /// value taken from the account, if any. It is always taken last and
+    /// covers exactly what is missing, so no change is needed for it.
+    pub account: Option<u64>,
+}
+
+struct Selector<'a, F> {
+    candidates: &'a [u64],
+    outputs_total: u64,
+    outputs: usize,
+    fee_algorithm: &'a F,
+    selected: Vec<usize>,
+    selected_total: u64,
+}
+
+/// Select the UTxOs to spend from `candidates` (their values) to pay for
+/// `outputs` and the fees, leaving room for a change output. If the UTxOs are
+/// not enough, the rest is taken from the account balance when one is given.
+pub fn select<F, R>(
+    strategy: Strategy,
+    candidates: &[u64],
+    account_balance: Option<u64>,
+    outputs: &[u64],
+    fee_algorithm: &F,
+    rng: &mut R,
+) -> Result<Selection, Error>
+where
+    F: FeeAlgorithm,
+    R: Rng,
+{
+    let outputs_total = outputs
+        .iter()
+        .try_fold(0u64, |total, output| total.checked_add(*output))
+        .ok_or(Error::ValueOverflow)?;
+    let mut selector = Selector {
+        candidates,
+        outputs_total,
+        outputs: outputs.len(),
+        fee_algorithm,
+        selected: Vec::new(),
+        selected_total: 0,
+    };
+
+    match strategy {
+        Strategy::LargestFirst => selector.largest_first()?,
+        Strategy::RandomImprove => selector.random_improve(rng)?,
+    }
+
+    if selector.is_covered() {
+        return Ok(Selection {
+            utxos: selector.selected,
+            account: None,
+        });
+    }
+
+    // every UTxO is used and it is still not enough, the account pays
+    // exactly what is missing
+    let inputs = selector.selected.len() + usize::from(account_balance.is_some());
+    let needed = selector.needed(inputs, 0).ok_or(Error::ValueOverflow)?;
+    let missing = needed.saturating_sub(selector.selected_total);
+    match account_balance {
+        Some(balance) if balance >= missing => {
+            selector.check_size(inputs, 0)?;
+            Ok(Selection {
+                utxos: selector.selected,
+                account: Some(missing),
+            })
+        }
+        // the balance is less than what is missing, so the sum is less than
+        // what is needed and cannot overflow
+        balance => Err(Error::InsufficientFunds {
+            available: selector.selected_total + balance.unwrap_or(0),
+            needed,
+        }),
+    }
+}
+
+impl<'a, F: FeeAlgorithm> Selector<'a, F> {
+    fn fee(&self, inputs: usize, extra_outputs: usize) -> u64 {
+        let inputs = inputs.min(MAX_INPUTS_OUTPUTS) as u8;
+        let outputs = (self.outputs + extra_outputs).min(MAX_INPUTS_OUTPUTS) as u8;
+        self.fee_algorithm.calculate(None, inputs, outputs).0
+    }
+
+    /// the value needed to pay for the outputs and the fees, `None` if it
+    /// overflows
+    fn needed(&self, inputs: usize, extra_outputs: usize) -> Option<u64> {
+        self.outputs_total
+            .checked_add(self.fee(inputs, extra_outputs))
+    }
+
+    fn check_size(&self, inputs: usize, extra_outputs: usize) -> Result<(), Error> {
+        if inputs > MAX_INPUTS_OUTPUTS || self.outputs + extra_outputs > MAX_INPUTS_OUTPUTS {
+            Err(Error::TooManyInputsOutputs {
+                max: MAX_INPUTS_OUTPUTS,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// the selection pays for the outputs and the fees, either exactly or
+    /// with enough left to pay for a change output
+    fn is_covered(&self) -> bool {
+        let inputs = self.selected.len();
+        // a total that overflows cannot be paid for
+        Some(self.selected_total) == self.needed(inputs, 0)
+            || self
+                .needed(inputs, 1)
+                .map_or(false, |needed| self.selected_total >= needed)
+    }
+
+    fn add(&mut self, index: usize) -> Result<(), Error> {
+        self.check_size(self.selected.len() + 1, 1)?;
+        self.selected_total = self
+            .selected_total
+            .checked_add(self.candidates[index])
+            .ok_or(Error::ValueOverflow)?;
+        self.selected.push(index);
+        Ok(())
+    }
+
+    fn remaining(&self) -> Vec<usize> {
+        (0..self.candidates.len())
+            .filter(|index| !self.selected.contains(index))
+            .collect()
+    }
+
+    /// take the biggest UTxOs first until the outputs and fees are paid for.
+    /// This minimizes the number of inputs, hence the fees.
+    fn largest_first(&mut self) -> Result<(), Error> {
+        let mut remaining = self.remaining();
+        remaining.sort_by_key(|index| std::cmp::Reverse(self.candidates[*index]));
+        for index in remaining {
+            if self.is_covered() {
+                break;
+            }
+            self.add(index)?;
+        }
+        Ok(())
+    }
+
+    /// take random UTxOs until the outputs are paid for, then keep adding
+    /// random ones while it brings the total closer to twice the outputs,
+    /// without going over three times. This keeps the change in the same
+    /// order of magnitude as the payments, so the UTxO set doesn't fill up
+    /// with dust. The fees are then covered with the largest UTxOs left.
+    fn random_improve<R: Rng>(&mut self, rng: &mut R) -> Result<(), Error> {
+        let mut remaining = self.remaining();
+        remaining.shuffle(rng);
+
+        let mut remaining = remaining.into_iter();
+        while self.selected_total < self.outputs_total {
+            match remaining.next() {
+                Some(index) => self.add(index)?,
+                None => break,
+            }
+        }
+
+        let ideal = self.outputs_total.saturating_mul(2);
+        let maximum = self.outputs_total.saturating_mul(3);
+        for index in remaining {
+            let total = match self.selected_total.checked_add(self.candidates[index]) {
+                Some(total) if total <= maximum => total,
+                _ => continue,
+            };
+            if ideal.abs_diff(total) >= ideal.abs_diff(self.selected_total) {
+                break;
+            }
+            self.add(index)?;
+        }
+
+        self.largest_first()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_impl_mockchain::fee::LinearFee;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    fn run(
+        strategy: Strategy,
+        candidates: &[u64],
+        account: Option<u64>,
+        outputs: &[u64],
+    ) -> Result<Selection, Error> {
+        let fee = LinearFee::new(10, 2, 0);
+        let mut rng = ChaChaRng::from_seed([0; 32]);
+        select(strategy, candidates, account, outputs, &fee, &mut rng)
+    }
+
+    #[test]
+    fn largest_first_takes_biggest_utxos() {
+        let selection = run(Strategy::LargestFirst, &[5, 100, 50, 200], None, &[150]).unwrap();
+        assert_eq!(selection.utxos, vec![3]);
+        assert_eq!(selection.account, None);
+    }
+
+    #[test]
+    fn selection_leaves_room_for_change_fee() {
+        // 150 + fee(1 input, 2 outputs) = 166 > 165
+        let selection = run(Strategy::LargestFirst, &[165, 10], None, &[150]).unwrap();
+        assert_eq!(selection.utxos, vec![0, 1]);
+    }
+
+    #[test]
+    fn exact_selection_needs_no_change() {
+        // 150 + fee(1 input, 1 output) = 164
+        let selection = run(Strategy::LargestFirst, &[164, 10], None, &[150]).unwrap();
+        assert_eq!(selection.utxos, vec![0]);
+    }
+
+    #[test]
+    fn account_covers_what_is_missing() {
+        let selection = run(Strategy::LargestFirst, &[100], Some(1000), &[150]).unwrap();
+        assert_eq!(selection.utxos, vec![0]);
+        // 150 + fee(2 inputs, 1 output) - 100
+        assert_eq!(selection.account, Some(66));
+    }
+
+    #[test]
+    fn insufficient_funds() {
+        assert_eq!(
+            run(Strategy::LargestFirst, &[100], Some(10), &[150]),
+            Err(Error::InsufficientFunds {
+                available: 110,
+                needed: 166
+            })
+        );
+    }
+
+    #[test]
+    fn overflowing_values_are_refused() {
+        assert_eq!(
+            run(Strategy::LargestFirst, &[100], None, &[u64::MAX, 1]),
+            Err(Error::ValueOverflow)
+        );
+        assert_eq!(
+            run(Strategy::LargestFirst, &[100], None, &[u64::MAX]),
+            Err(Error::ValueOverflow)
+        );
+        assert_eq!(
+            run(
+                Strategy::LargestFirst,
+                &[u64::MAX - 30, u64::MAX],
+                None,
+                &[u64::MAX - 5]
+            ),
+            Err(Error::ValueOverflow)
+        );
+    }
+
+    #[test]
+    fn random_improve_covers_outputs_and_fees() {
+        let candidates = [10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+        let selection = run(Strategy::RandomImprove, &candidates, None, &[120]).unwrap();
+        let total: u64 = selection.utxos.iter().map(|i| candidates[*i]).sum();
+        let inputs = selection.utxos.len() as u64;
+        assert!(total == 120 + 10 + 2 * (inputs + 1) || total >= 120 + 10 + 2 * (inputs + 2));
+        assert!(total <= 360 + 100);
+    }
+}
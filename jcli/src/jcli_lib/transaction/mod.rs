@@ -5,14 +5,17 @@ mod add_input;
 pub mod add_output;
 mod add_witness;
 mod auth;
+mod build;
+mod coin_selection;
 mod common;
 pub mod finalize;
 mod info;
 mod mk_witness;
+mod multisig_witness;
 pub mod new;
 mod seal;
 mod set_expiry_date;
-mod simplified;
+pub mod simplified;
 mod staging;
 
 use self::staging::StagingKind;
@@ -20,7 +23,7 @@ use crate::{
     block,
     jcli_lib::{
         certificate,
-        utils::{key_parser, output_format},
+        utils::{key_parser, multisig, output_format, UtxoPointer},
     },
     rest, utils,
 };
@@ -73,12 +76,20 @@ pub enum Transaction {
     Info(info::Info),
     /// create witnesses
     MakeWitness(mk_witness::MkWitness),
+    /// create the partial witness of one of the owners of a multisig input
+    MakeMultisigWitness(multisig_witness::MkMultisigWitness),
+    /// combine the partial witnesses of the owners into the witness of
+    /// a multisig input
+    CombineMultisigWitness(multisig_witness::CombineMultisigWitness),
     /// make auth
     Auth(auth::Auth),
     /// get the message format out of a sealed transaction
     ToMessage(common::CommonTransaction),
     /// send a transaction from one account to another (simplified method)
     MakeTransaction(simplified::MakeTransaction),
+    /// build a balanced transaction, selecting the inputs to spend among
+    /// the sender's UTxOs and account
+    Build(build::Build),
 }
 
 type StaticStr = &'static str;
@@ -105,6 +116,8 @@ pub enum Error {
     },
     #[error("could not process secret file '{0}'")]
     SecretKeyReadFailed(#[from] key_parser::Error),
+    #[error(transparent)]
+    Multisig(#[from] multisig::Error),
     /*
     SecretFileReadFailed { source: std::io::Error, path: PathBuf }
         = @{{ let _ = source; format_args!("could not read secret file '{}'", path.display()) }},
@@ -234,6 +247,15 @@ pub enum Error {
 
     #[error("cannot finalize the payload without a validity end date set")]
     CannotFinalizeWithoutValidUntil,
+
+    #[error("UTxO '{pointer}' is not available")]
+    UtxoNotAvailable {
+        #[source]
+        source: rest::Error,
+        pointer: UtxoPointer,
+    },
+    #[error(transparent)]
+    CoinSelection(#[from] coin_selection::Error),
 }
 
 /*
@@ -261,9 +283,12 @@ impl Transaction {
             Transaction::DataForWitness(common) => display_data_for_witness(common),
             Transaction::Info(info) => info.exec(),
             Transaction::MakeWitness(mk_witness) => mk_witness.exec(),
+            Transaction::MakeMultisigWitness(mk_witness) => mk_witness.exec(),
+            Transaction::CombineMultisigWitness(combine) => combine.exec(),
             Transaction::Auth(auth) => auth.exec(),
             Transaction::ToMessage(common) => display_message(common),
             Transaction::MakeTransaction(send) => send.exec(),
+            Transaction::Build(build) => build.exec(),
             Transaction::SetExpiryDate(set_expiry_date) => set_expiry_date.exec(),
         }
     }
@@ -0,0 +1,154 @@
+use crate::jcli_lib::{
+    transaction::Error,
+    utils::{
+        io,
+        key_parser::read_ed25519_secret_key_from_file,
+        multisig::{self, Declaration, PartialWitness},
+    },
+};
+use bech32::{self, ToBase32 as _};
+use chain_core::property::Serialize as _;
+use chain_impl_mockchain::{
+    account::SpendingCounter, accounting::account::spending::SpendingCounterIncreasing,
+    header::HeaderId, transaction::TransactionSignDataHash,
+};
+use std::{io::Write, path::PathBuf};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct MultisigInput {
+    /// the multisig declaration of the account being spent from
+    #[structopt(long = "declaration")]
+    pub declaration: PathBuf,
+
+    /// the hash of the block0, the first block of the blockchain
+    #[structopt(long = "genesis-block-hash", parse(try_from_str))]
+    pub genesis_block_hash: HeaderId,
+
+    /// the counter value for every time the multisig account is being utilized.
+    #[structopt(long = "account-spending-counter")]
+    pub account_spending_counter: u32,
+
+    /// lane to use for the spending counter. Each lane has an independent
+    /// spending counter value.
+    /// If unsure, leave blank and lane 0 will be used
+    #[structopt(long)]
+    pub account_spending_counter_lane: Option<usize>,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct MkMultisigWitness {
+    /// the Transaction ID of the witness to sign
+    #[structopt(name = "TRANSACTION_ID")]
+    pub sign_data_hash: TransactionSignDataHash,
+
+    /// the file path to the file to write the partial witness in.
+    /// If omitted it will be printed to the standard output.
+    pub output: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub input: MultisigInput,
+
+    /// the file path to the file to read the owner's signing key from.
+    /// If omitted it will be read from the standard input.
+    pub secret: Option<PathBuf>,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct CombineMultisigWitness {
+    /// the Transaction ID of the witness to sign
+    #[structopt(name = "TRANSACTION_ID")]
+    pub sign_data_hash: TransactionSignDataHash,
+
+    #[structopt(flatten)]
+    pub input: MultisigInput,
+
+    /// the file path to the file to write the witness in.
+    /// If omitted it will be printed to the standard output.
+    #[structopt(long = "output")]
+    pub output: Option<PathBuf>,
+
+    /// the partial witnesses of the owners, at least as many as the threshold
+    #[structopt(name = "PARTIAL_WITNESS", required = true)]
+    pub partial_witnesses: Vec<PathBuf>,
+}
+
+impl MultisigInput {
+    fn spending_counter(&self) -> Result<SpendingCounter, Error> {
+        let lane = self.account_spending_counter_lane.unwrap_or_default();
+        if lane > SpendingCounterIncreasing::LANES {
+            return Err(Error::MakeWitnessAccountInvalidCounterLane {
+                max: SpendingCounterIncreasing::LANES,
+                actual: lane,
+            });
+        }
+        Ok(SpendingCounter::new(lane, self.account_spending_counter))
+    }
+}
+
+impl MkMultisigWitness {
+    pub fn exec(self) -> Result<(), Error> {
+        let declaration = Declaration::load(&self.input.declaration)?;
+        let secret_key = read_ed25519_secret_key_from_file(&self.secret)?;
+        let index = declaration
+            .owner_index(&secret_key.to_public())
+            .ok_or(multisig::Error::NotAnOwner)?;
+
+        let data = multisig::witness_data(
+            &self.input.genesis_block_hash,
+            &self.sign_data_hash,
+            self.input.spending_counter()?,
+        );
+        let partial = PartialWitness::new(index, secret_key.to_public(), secret_key.sign(&data));
+
+        let mut writer =
+            io::open_file_write(&self.output).map_err(|source| Error::WitnessFileWriteFailed {
+                source,
+                path: self.output.clone().unwrap_or_default(),
+            })?;
+        writeln!(writer, "{}", partial.to_bech32_str()?).map_err(|source| {
+            Error::WitnessFileWriteFailed {
+                source,
+                path: self.output.clone().unwrap_or_default(),
+            }
+        })
+    }
+}
+
+impl CombineMultisigWitness {
+    pub fn exec(self) -> Result<(), Error> {
+        let declaration = Declaration::load(&self.input.declaration)?;
+        let spending_counter = self.input.spending_counter()?;
+        let data = multisig::witness_data(
+            &self.input.genesis_block_hash,
+            &self.sign_data_hash,
+            spending_counter,
+        );
+
+        let partial_witnesses = self
+            .partial_witnesses
+            .iter()
+            .map(|path| PartialWitness::read(path).map(|partial| (partial, path.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let witness = declaration.combine(&data, spending_counter, partial_witnesses)?;
+
+        let mut writer =
+            io::open_file_write(&self.output).map_err(|source| Error::WitnessFileWriteFailed {
+                source,
+                path: self.output.clone().unwrap_or_default(),
+            })?;
+        let bytes = witness
+            .serialize_as_vec()
+            .map_err(Error::WitnessFileSerializationFailed)?;
+
+        let bech32 = bech32::encode("witness", &bytes.to_base32(), bech32::Variant::Bech32)?;
+        writeln!(writer, "{}", bech32).map_err(|source| Error::WitnessFileWriteFailed {
+            source,
+            path: self.output.clone().unwrap_or_default(),
+        })
+    }
+}
@@ -13,7 +13,10 @@ use chain_addr::Kind;
 use chain_core::property::FromStr;
 use chain_crypto::{Ed25519, Ed25519Extended, PublicKey, SecretKey};
 use chain_impl_mockchain::{
-    account::SpendingCounter, fee::FeeAlgorithm, key::EitherEd25519SecretKey, transaction::Output,
+    account::SpendingCounter,
+    fee::FeeAlgorithm,
+    key::EitherEd25519SecretKey,
+    transaction::{Output, OutputPolicy},
 };
 use jormungandr_lib::{interfaces, interfaces::SettingsDto};
 use rand::{rngs::OsRng, SeedableRng};
@@ -223,3 +226,53 @@ pub fn make_transaction(
 
     Ok(transaction)
 }
+
+/// Make a transaction holding `certificate`, the fees being paid by the
+/// account of `payer_key` and the certificate being signed with the
+/// bech32 encoded `signing_key`. The transaction is ready to be posted.
+pub fn make_certificate_transaction(
+    certificate: interfaces::Certificate,
+    payer_key: &EitherEd25519SecretKey,
+    signing_key: &str,
+    valid_until: interfaces::BlockDate,
+    rest_args: RestArgs,
+) -> Result<Staging, Error> {
+    let settings = rest::v0::settings::request_settings(rest_args.clone())?;
+    let payer: interfaces::Address = chain_addr::Address(
+        settings.discrimination,
+        Kind::Account(payer_key.to_public()),
+    )
+    .into();
+
+    let stage = |value: interfaces::Value| -> Result<Staging, Error> {
+        let mut transaction = Staging::new();
+        transaction.add_account(payer.clone(), value)?;
+        transaction.set_extra(certificate.clone())?;
+        transaction.set_expiry_date(valid_until)?;
+        Ok(transaction)
+    };
+    // the fees do not depend on the value of the input
+    let fees = stage(chain_impl_mockchain::value::Value::zero().into())?.fees(&settings.fees);
+    let mut transaction = stage(fees.into())?;
+    transaction.balance_inputs_outputs(&settings.fees, OutputPolicy::Forget)?;
+
+    let block0_hash = chain_impl_mockchain::chaintypes::HeaderId::from_str(&settings.block0_hash)
+        .map_err(|_| Error::InvalidBlock0HeaderHash)?;
+    let transaction_sign_data_hash = transaction.transaction_sign_data_hash()?;
+    let account_state = rest::v0::account::request_account_information(
+        rest_args,
+        AccountId::try_from_str(&payer.to_string())?,
+    )?;
+    let witness = transaction::mk_witness::make_witness(
+        &WitnessType::Account,
+        &block0_hash,
+        &transaction_sign_data_hash,
+        Some(SpendingCounter::from(account_state.counters()[0])),
+        payer_key,
+    )?;
+    transaction.add_witness(witness)?;
+    transaction.seal()?;
+    transaction.set_auth(&[signing_key.to_owned()])?;
+
+    Ok(transaction)
+}
@@ -13,6 +13,14 @@ fn id_from_pub(pk: PublicKey<Ed25519>) -> account::Identifier {
     account::Identifier::from(pk)
 }
 
+impl From<PublicKey<Ed25519>> for AccountId {
+    fn from(pk: PublicKey<Ed25519>) -> Self {
+        Self {
+            account: id_from_pub(pk),
+        }
+    }
+}
+
 impl AccountId {
     // accept either an address with the account kind
     // or a ed25519 publickey
@@ -1,11 +1,13 @@
 pub mod account_id;
 pub mod io;
 pub mod key_parser;
+pub mod multisig;
 pub mod output_file;
 pub mod output_format;
+pub mod utxo_pointer;
 pub mod vote;
 
-pub use self::{account_id::AccountId, output_format::OutputFormat};
+pub use self::{account_id::AccountId, output_format::OutputFormat, utxo_pointer::UtxoPointer};
 use structopt::StructOpt;
 use thiserror::Error;
 
@@ -0,0 +1,458 @@
+use crate::jcli_lib::utils::{io, key_parser::parse_pub_key};
+use bech32::{FromBase32 as _, ToBase32 as _};
+use chain_crypto::{Ed25519, PublicKey, Signature, Verification};
+use chain_impl_mockchain::{
+    account::SpendingCounter,
+    header::HeaderId,
+    multisig::{self, DeclElement, DeclarationError, Index, TreeIndex, WitnessBuilder},
+    transaction::{TransactionSignDataHash, Witness, WitnessMultisigData},
+};
+use serde::Deserialize;
+use std::{
+    convert::TryInto,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// the multisig witnesses can only address owners with a one byte index in
+/// a flat declaration
+pub const MAX_OWNERS: usize = 8;
+
+const PARTIAL_WITNESS_HRP: &str = "partialwitness";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("could not read multisig declaration file '{path}'")]
+    DeclarationReadFailed {
+        #[source]
+        source: io::ReadYamlError,
+        path: PathBuf,
+    },
+    #[error("invalid owner public key '{key}'")]
+    DeclarationOwnerInvalid {
+        #[source]
+        source: chain_crypto::bech32::Error,
+        key: String,
+    },
+    #[error("multisig declaration has {owners} owners, it should have between 1 and {max}")]
+    DeclarationOwnerCountInvalid { owners: usize, max: usize },
+    #[error("owner '{key}' is declared more than once")]
+    DeclarationOwnerDuplicated { key: String },
+    #[error(
+        "threshold should be between 1 and the number of owners ({owners}), found {threshold}"
+    )]
+    DeclarationThresholdInvalid { threshold: u8, owners: usize },
+    #[error("invalid multisig declaration: {0:?}")]
+    DeclarationInvalid(DeclarationError),
+    #[error("the signing key is not one of the multisig owners")]
+    NotAnOwner,
+    #[error("could not read partial witness file '{path}'")]
+    PartialWitnessReadFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("malformed partial witness in '{path}'")]
+    PartialWitnessMalformed { path: PathBuf },
+    #[error("the partial witness in '{path}' is not signed by the owner at index {index}")]
+    PartialWitnessOwnerMismatch { index: u8, path: PathBuf },
+    #[error("the partial witness in '{path}' doesn't sign this transaction")]
+    PartialWitnessSignatureInvalid { path: PathBuf },
+    #[error("could not encode the partial witness")]
+    PartialWitnessEncodingFailed(#[from] bech32::Error),
+    #[error("the owner at index {index} has more than one partial witness")]
+    PartialWitnessDuplicated { index: u8 },
+    #[error("not enough partial witnesses: {actual}, the threshold is {threshold}")]
+    ThresholdNotReached { actual: usize, threshold: u8 },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeclarationFile {
+    threshold: u8,
+    owners: Vec<String>,
+}
+
+/// Declaration of a M-of-N multisig account: the owners' public keys, in a
+/// fixed order, and the number of them that need to sign to spend from it.
+///
+/// It is read from a YAML file shared between the owners:
+///
+/// ```yaml
+/// threshold: 2
+/// owners:
+///   - ed25519_pk1...
+///   - ed25519_pk1...
+///   - ed25519_pk1...
+/// ```
+pub struct Declaration {
+    threshold: u8,
+    owners: Vec<PublicKey<Ed25519>>,
+    declaration: multisig::Declaration,
+}
+
+/// The signature of the transaction by one of the multisig owners. Each owner
+/// produces one, and any `threshold` of them can be combined into the final
+/// witness.
+pub struct PartialWitness {
+    index: u8,
+    public_key: PublicKey<Ed25519>,
+    signature: Signature<WitnessMultisigData, Ed25519>,
+}
+
+impl Declaration {
+    pub fn new(threshold: u8, owners: Vec<PublicKey<Ed25519>>) -> Result<Self, Error> {
+        if owners.is_empty() || owners.len() > MAX_OWNERS {
+            return Err(Error::DeclarationOwnerCountInvalid {
+                owners: owners.len(),
+                max: MAX_OWNERS,
+            });
+        }
+        if threshold == 0 || threshold as usize > owners.len() {
+            return Err(Error::DeclarationThresholdInvalid {
+                threshold,
+                owners: owners.len(),
+            });
+        }
+        for (i, owner) in owners.iter().enumerate() {
+            if owners[..i].contains(owner) {
+                return Err(Error::DeclarationOwnerDuplicated {
+                    key: chain_crypto::bech32::Bech32::to_bech32_str(owner),
+                });
+            }
+        }
+
+        let declaration = multisig::Declaration::new(
+            threshold as usize,
+            owners.iter().map(DeclElement::from_publickey).collect(),
+        )
+        .map_err(Error::DeclarationInvalid)?;
+
+        Ok(Declaration {
+            threshold,
+            owners,
+            declaration,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file: DeclarationFile =
+            io::read_yaml(&Some(path.as_ref())).map_err(|source| Error::DeclarationReadFailed {
+                source,
+                path: path.as_ref().to_path_buf(),
+            })?;
+
+        let owners = file
+            .owners
+            .iter()
+            .map(|key| {
+                parse_pub_key(key).map_err(|source| Error::DeclarationOwnerInvalid {
+                    source,
+                    key: key.clone(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Self::new(file.threshold, owners)
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// The identifier of the multisig account, as used in its address, the
+    /// way the ledger identifies the multisig declaration.
+    pub fn identifier(&self) -> [u8; 32] {
+        self.declaration
+            .to_identifier()
+            .as_ref()
+            .try_into()
+            .expect("multisig identifier size doesn't match address kind")
+    }
+
+    pub fn owner_index(&self, key: &PublicKey<Ed25519>) -> Option<u8> {
+        self.owners
+            .iter()
+            .position(|owner| owner == key)
+            .map(|index| index as u8)
+    }
+
+    /// Combine the partial witnesses of the owners into the witness of the
+    /// multisig input. Every partial witness is checked against the declaration
+    /// and the data to sign first.
+    pub fn combine(
+        &self,
+        witness_data: &WitnessMultisigData,
+        spending_counter: SpendingCounter,
+        partial_witnesses: Vec<(PartialWitness, PathBuf)>,
+    ) -> Result<Witness, Error> {
+        let mut partial_witnesses = partial_witnesses;
+        partial_witnesses.sort_by_key(|(partial, _)| partial.index);
+
+        for window in partial_witnesses.windows(2) {
+            if window[0].0.index == window[1].0.index {
+                return Err(Error::PartialWitnessDuplicated {
+                    index: window[0].0.index,
+                });
+            }
+        }
+
+        if partial_witnesses.len() < self.threshold as usize {
+            return Err(Error::ThresholdNotReached {
+                actual: partial_witnesses.len(),
+                threshold: self.threshold,
+            });
+        }
+
+        let mut builder = WitnessBuilder::new();
+        for (partial, path) in partial_witnesses {
+            if self.owners.get(partial.index as usize) != Some(&partial.public_key) {
+                return Err(Error::PartialWitnessOwnerMismatch {
+                    index: partial.index,
+                    path,
+                });
+            }
+            if partial.signature.verify(&partial.public_key, witness_data) != Verification::Success
+            {
+                return Err(Error::PartialWitnessSignatureInvalid { path });
+            }
+
+            // the index is lower than MAX_OWNERS, checked on the declaration
+            let index = Index::from_u8(partial.index).expect("valid owner index");
+            builder.append(TreeIndex::D1(index), partial.public_key, partial.signature);
+        }
+
+        Ok(Witness::Multisig(spending_counter, builder.finalize()))
+    }
+}
+
+pub fn witness_data(
+    genesis_block_hash: &HeaderId,
+    sign_data_hash: &TransactionSignDataHash,
+    spending_counter: SpendingCounter,
+) -> WitnessMultisigData {
+    WitnessMultisigData::new(genesis_block_hash, sign_data_hash, spending_counter)
+}
+
+impl PartialWitness {
+    pub fn new(
+        index: u8,
+        public_key: PublicKey<Ed25519>,
+        signature: Signature<WitnessMultisigData, Ed25519>,
+    ) -> Self {
+        PartialWitness {
+            index,
+            public_key,
+            signature,
+        }
+    }
+
+    pub fn to_bech32_str(&self) -> Result<String, Error> {
+        let mut bytes = vec![self.index];
+        bytes.extend_from_slice(self.public_key.as_ref());
+        bytes.extend_from_slice(self.signature.as_ref());
+
+        bech32::encode(
+            PARTIAL_WITNESS_HRP,
+            bytes.to_base32(),
+            bech32::Variant::Bech32,
+        )
+        .map_err(Into::into)
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let bech32_str =
+            io::read_line(&Some(&path)).map_err(|source| Error::PartialWitnessReadFailed {
+                source,
+                path: path.clone(),
+            })?;
+
+        Self::from_bech32_str(bech32_str.trim()).ok_or(Error::PartialWitnessMalformed { path })
+    }
+
+    fn from_bech32_str(s: &str) -> Option<Self> {
+        const PUBLIC_KEY_SIZE: usize = 32;
+
+        let (hrp, data, _variant) = bech32::decode(s).ok()?;
+        if hrp != PARTIAL_WITNESS_HRP {
+            return None;
+        }
+        let bytes = Vec::<u8>::from_base32(&data).ok()?;
+        let (index, rest) = bytes.split_first()?;
+        if rest.len() < PUBLIC_KEY_SIZE {
+            return None;
+        }
+        let (public_key, signature) = rest.split_at(PUBLIC_KEY_SIZE);
+
+        Some(PartialWitness {
+            index: *index,
+            public_key: PublicKey::from_binary(public_key).ok()?,
+            signature: Signature::from_binary(signature).ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_addr::{Address, Discrimination, Kind};
+    use chain_crypto::SecretKey;
+    use chain_impl_mockchain::transaction::TransactionSignDataHash;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    fn owners(count: usize) -> Vec<SecretKey<Ed25519>> {
+        let mut rng = ChaChaRng::from_seed([0; 32]);
+        (0..count).map(|_| SecretKey::generate(&mut rng)).collect()
+    }
+
+    fn hash<T: std::str::FromStr>(byte: &str) -> T
+    where
+        T::Err: std::fmt::Debug,
+    {
+        byte.repeat(32).parse().unwrap()
+    }
+
+    fn sign_data() -> (WitnessMultisigData, SpendingCounter) {
+        let spending_counter = SpendingCounter::zero();
+        let data = witness_data(
+            &hash::<HeaderId>("01"),
+            &hash::<TransactionSignDataHash>("02"),
+            spending_counter,
+        );
+        (data, spending_counter)
+    }
+
+    fn partial(declaration: &Declaration, key: &SecretKey<Ed25519>) -> PartialWitness {
+        let (data, _) = sign_data();
+        let index = declaration.owner_index(&key.to_public()).unwrap();
+        PartialWitness::new(index, key.to_public(), key.sign(&data))
+    }
+
+    #[test]
+    fn address_matches_the_ledger_declaration() {
+        let keys = owners(3);
+        let declaration =
+            Declaration::new(2, keys.iter().map(SecretKey::to_public).collect()).unwrap();
+
+        let ledger_declaration = multisig::Declaration::new(
+            2,
+            keys.iter()
+                .map(|key| DeclElement::from_publickey(&key.to_public()))
+                .collect(),
+        )
+        .unwrap();
+        let address = Address(
+            Discrimination::Test,
+            Kind::Multisig(declaration.identifier()),
+        );
+
+        match address.kind() {
+            Kind::Multisig(identifier) => {
+                assert_eq!(&identifier[..], ledger_declaration.to_identifier().as_ref())
+            }
+            _ => panic!("expected a multisig address"),
+        }
+    }
+
+    #[test]
+    fn declaration_is_validated() {
+        let keys: Vec<_> = owners(3).iter().map(SecretKey::to_public).collect();
+        assert!(matches!(
+            Declaration::new(0, keys.clone()),
+            Err(Error::DeclarationThresholdInvalid { .. })
+        ));
+        assert!(matches!(
+            Declaration::new(4, keys.clone()),
+            Err(Error::DeclarationThresholdInvalid { .. })
+        ));
+        assert!(matches!(
+            Declaration::new(1, vec![keys[0].clone(), keys[0].clone()]),
+            Err(Error::DeclarationOwnerDuplicated { .. })
+        ));
+    }
+
+    #[test]
+    fn partial_witnesses_are_combined() {
+        let keys = owners(3);
+        let declaration =
+            Declaration::new(2, keys.iter().map(SecretKey::to_public).collect()).unwrap();
+        let (data, spending_counter) = sign_data();
+
+        let partials = vec![&keys[2], &keys[0]]
+            .into_iter()
+            .map(|key| {
+                let encoded = partial(&declaration, key).to_bech32_str().unwrap();
+                let decoded = PartialWitness::from_bech32_str(&encoded).unwrap();
+                (decoded, PathBuf::new())
+            })
+            .collect();
+
+        let witness = declaration
+            .combine(&data, spending_counter, partials)
+            .unwrap();
+        assert!(matches!(witness, Witness::Multisig(_, _)));
+    }
+
+    #[test]
+    fn invalid_partial_witnesses_are_rejected() {
+        let keys = owners(3);
+        let declaration =
+            Declaration::new(2, keys.iter().map(SecretKey::to_public).collect()).unwrap();
+        let (data, spending_counter) = sign_data();
+
+        let result = declaration.combine(
+            &data,
+            spending_counter,
+            vec![(partial(&declaration, &keys[0]), PathBuf::new())],
+        );
+        assert!(matches!(result, Err(Error::ThresholdNotReached { .. })));
+
+        let result = declaration.combine(
+            &data,
+            spending_counter,
+            vec![
+                (partial(&declaration, &keys[1]), PathBuf::new()),
+                (partial(&declaration, &keys[1]), PathBuf::new()),
+            ],
+        );
+        assert!(matches!(
+            result,
+            Err(Error::PartialWitnessDuplicated { index: 1 })
+        ));
+
+        let mut forged = partial(&declaration, &keys[1]);
+        forged.index = 0;
+        let result = declaration.combine(
+            &data,
+            spending_counter,
+            vec![
+                (forged, PathBuf::new()),
+                (partial(&declaration, &keys[2]), PathBuf::new()),
+            ],
+        );
+        assert!(matches!(
+            result,
+            Err(Error::PartialWitnessOwnerMismatch { index: 0, .. })
+        ));
+
+        let other_data = witness_data(
+            &hash::<HeaderId>("03"),
+            &hash::<TransactionSignDataHash>("02"),
+            spending_counter,
+        );
+        let result = declaration.combine(
+            &other_data,
+            spending_counter,
+            vec![
+                (partial(&declaration, &keys[0]), PathBuf::new()),
+                (partial(&declaration, &keys[1]), PathBuf::new()),
+            ],
+        );
+        assert!(matches!(
+            result,
+            Err(Error::PartialWitnessSignatureInvalid { .. })
+        ));
+    }
+}
@@ -0,0 +1,32 @@
+use chain_impl_mockchain::{fragment::FragmentId, transaction::TransactionIndex};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// Reference to a transaction output, as `FRAGMENT_ID:OUTPUT_INDEX`
+#[derive(Debug, Clone, Copy)]
+pub struct UtxoPointer {
+    pub fragment_id: FragmentId,
+    pub output_index: TransactionIndex,
+}
+
+#[derive(Debug, Error)]
+#[error("invalid UTxO pointer '{0}', expected FRAGMENT_ID:OUTPUT_INDEX")]
+pub struct Error(String);
+
+impl FromStr for UtxoPointer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (fragment_id, output_index) = s.split_once(':').ok_or_else(|| Error(s.to_string()))?;
+        Ok(UtxoPointer {
+            fragment_id: fragment_id.parse().map_err(|_| Error(s.to_string()))?,
+            output_index: output_index.parse().map_err(|_| Error(s.to_string()))?,
+        })
+    }
+}
+
+impl fmt::Display for UtxoPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.fragment_id, self.output_index)
+    }
+}
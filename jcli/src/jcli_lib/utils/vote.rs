@@ -1,7 +1,8 @@
 use crate::jcli_lib::utils::io;
+use chain_vote::tally::EncryptedTally;
 use jormungandr_lib::{
     crypto::hash::Hash,
-    interfaces::{serde_base64_bytes, VotePlanStatus},
+    interfaces::{serde_base64_bytes, PrivateTallyState, Tally, VotePlanStatus},
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -57,6 +58,40 @@ pub fn get_vote_plan_by_id<P: AsRef<Path>>(
     }
 }
 
+#[derive(Debug, Error)]
+pub enum EncryptedTallyError {
+    #[error("failed to read encrypted tally bytes")]
+    Read,
+    #[error("expected encrypted private tally, found {found}")]
+    NotEncrypted { found: &'static str },
+}
+
+// Read the encrypted tallies of the proposals of a private vote plan, as
+// exported between the start of the tally and its decryption.
+pub fn encrypted_tallies(
+    vote_plan: &VotePlanStatus,
+) -> Result<Vec<EncryptedTally>, EncryptedTallyError> {
+    vote_plan
+        .proposals
+        .iter()
+        .map(|proposal| match &proposal.tally {
+            Tally::Private {
+                state:
+                    PrivateTallyState::Encrypted {
+                        encrypted_tally, ..
+                    },
+            } => EncryptedTally::from_bytes(encrypted_tally.as_ref())
+                .ok_or(EncryptedTallyError::Read),
+            Tally::Public { .. } => Err(EncryptedTallyError::NotEncrypted {
+                found: "public tally",
+            }),
+            Tally::Private { .. } => Err(EncryptedTallyError::NotEncrypted {
+                found: "private decrypted tally",
+            }),
+        })
+        .collect()
+}
+
 #[derive(Debug, Error)]
 pub enum SharesError {
     #[error("I/O error")]
@@ -87,6 +122,19 @@ pub struct MemberVotePlanShares(Vec<TallyDecryptShare>);
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VotePlanDecryptShares(Vec<Vec<TallyDecryptShare>>);
 
+impl TallyDecryptShare {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl MemberVotePlanShares {
+    /// the shares of the member, one per proposal of the vote plan
+    pub fn shares(&self) -> &[TallyDecryptShare] {
+        &self.0
+    }
+}
+
 impl TryFrom<TallyDecryptShare> for chain_vote::TallyDecryptShare {
     type Error = SharesError;
 
@@ -0,0 +1,924 @@
+use super::Error;
+use crate::jcli_lib::{
+    rest::{
+        v0::{
+            block::{request_block, request_next_ids},
+            settings::request_settings,
+            tip::request_tip,
+            vote::request_active_vote_plans,
+        },
+        RestArgs,
+    },
+    utils::{
+        io,
+        vote::{self, EncryptedTallyError, VotePlanError},
+        OutputFormat,
+    },
+};
+use chain_core::{
+    packer::Codec,
+    property::{Deserialize as _, DeserializeFromSlice as _},
+};
+use chain_impl_mockchain::{
+    block::{Block, BlockDate},
+    certificate::{VotePlanId, VoteTally},
+    fragment::Fragment,
+    transaction::InputEnum,
+    vote::{EncryptedVote, Payload, PayloadType, ProofOfCorrectVote},
+};
+use chain_vote::{
+    tally::{batch_decrypt, EncryptedTally},
+    Ballot, Crs, ElectionPublicKey,
+};
+use jormungandr_lib::{
+    crypto::{account::Identifier, hash::Hash},
+    interfaces::{PrivateTallyState, Tally, TallyResult, VotePlanStatus},
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+
+/// Check a published vote plan result against the vote casts and the tally
+/// certificate recorded in the chain.
+///
+/// The votes are recounted from the vote cast fragments. For a private vote
+/// plan, the encrypted ballots are aggregated and compared with the encrypted
+/// tally, every decryption share of the tally certificate is verified against
+/// the encrypted tally and the member key, and the tally is decrypted again.
+/// For a public vote plan the tally is recomputed. Both need the voting power
+/// of the voters, without which the audit is not verified. When the encrypted
+/// tallies of a private vote plan are not available, the ballots aggregated
+/// with the voting power of the voters stand for them. The report is printed
+/// and the command fails if any check did not pass.
+///
+/// The chain is read from the REST API of a node or from exported fragments,
+/// the explorer is not a source of the audit.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Audit {
+    /// Fetch the vote plan from the node and scan the blocks of its voting
+    /// period.
+    Node {
+        #[structopt(flatten)]
+        audit: AuditArgs,
+        /// The path to json-encoded vote plan status as published after the
+        /// tally, for a vote plan that is no longer active in the node.
+        #[structopt(long)]
+        vote_plan: Option<PathBuf>,
+        #[structopt(flatten)]
+        args: RestArgs,
+    },
+    /// Read the vote plan and the fragments from exported files.
+    Files {
+        #[structopt(flatten)]
+        audit: AuditArgs,
+        /// The path to json-encoded vote plan status as published after the
+        /// tally.
+        #[structopt(long)]
+        vote_plan: PathBuf,
+        /// The path to the hex-encoded fragments, one per line, holding the
+        /// vote casts and the tally certificate.
+        #[structopt(long)]
+        fragments: PathBuf,
+    },
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct AuditArgs {
+    /// The id of the vote plan to audit.
+    #[structopt(long)]
+    vote_plan_id: Hash,
+    /// The path to json-encoded vote plan status exported before the tally,
+    /// holding the encrypted tallies of a private vote plan. Without them,
+    /// the tallies are aggregated again from the ballots with `--weights`.
+    #[structopt(long)]
+    encrypted_vote_plan: Option<PathBuf>,
+    /// The path to a json object mapping the hex-encoded account of the
+    /// voters to their voting power at the time of the tally.
+    #[structopt(long)]
+    weights: Option<PathBuf>,
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+/// the vote casts and the tally of a vote plan, as recorded in the chain
+#[derive(Default)]
+pub struct ChainRecord {
+    /// the last vote of every voter account, per proposal
+    votes: BTreeMap<u8, BTreeMap<String, Vote>>,
+    tally: Option<VoteTally>,
+}
+
+pub enum Vote {
+    Public(u8),
+    Private {
+        encrypted_vote: EncryptedVote,
+        proof: ProofOfCorrectVote,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditReport {
+    pub vote_plan_id: Hash,
+    pub private: bool,
+    pub tally_certificate_found: bool,
+    pub proposals: Vec<ProposalAudit>,
+    pub issues: Vec<String>,
+    pub verified: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProposalAudit {
+    pub index: u8,
+    /// the number of voters found in the chain
+    pub votes_cast: usize,
+    pub published_votes_cast: usize,
+    pub published_result: Option<Vec<u64>>,
+    pub recomputed_result: Option<Vec<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_result: Option<Vec<u64>>,
+    /// whether the encrypted ballots add up to the encrypted tally
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ballots_aggregated: Option<bool>,
+    /// the indexes of the committee members whose share is not valid
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invalid_shares: Vec<usize>,
+    pub issues: Vec<String>,
+}
+
+impl Audit {
+    pub fn exec(self) -> Result<(), Error> {
+        let (audit, vote_plan, record) = match self {
+            Audit::Node {
+                audit,
+                vote_plan,
+                args,
+            } => {
+                let vote_plan = match vote_plan {
+                    Some(path) => vote::get_vote_plan_by_id(Some(path), Some(&audit.vote_plan_id))?,
+                    None => request_active_vote_plans(args.clone())?
+                        .into_iter()
+                        .find(|vote_plan| vote_plan.id == audit.vote_plan_id)
+                        .ok_or(VotePlanError::VotePlanIdNotFound)?,
+                };
+                let record = scan_chain(args, &vote_plan)?;
+                (audit, vote_plan, record)
+            }
+            Audit::Files {
+                audit,
+                vote_plan,
+                fragments,
+            } => {
+                let vote_plan =
+                    vote::get_vote_plan_by_id(Some(vote_plan), Some(&audit.vote_plan_id))?;
+                let record = read_fragments(&fragments, &audit.vote_plan_id.into())?;
+                (audit, vote_plan, record)
+            }
+        };
+        audit.exec(vote_plan, record)
+    }
+}
+
+impl AuditArgs {
+    fn exec(self, vote_plan: VotePlanStatus, record: ChainRecord) -> Result<(), Error> {
+        let weights: Option<BTreeMap<String, u64>> = match &self.weights {
+            Some(path) => Some(serde_json::from_reader(io::open_file_read(&Some(path))?)?),
+            None => None,
+        };
+        let report = match vote_plan.payload {
+            PayloadType::Public => audit_public(&vote_plan, &record, weights.as_ref()),
+            PayloadType::Private => {
+                let encrypted_tallies = match &self.encrypted_vote_plan {
+                    Some(path) => Some(
+                        read_encrypted_tallies(&vote::get_vote_plan_by_id(
+                            Some(path),
+                            Some(&self.vote_plan_id),
+                        )?)?
+                        .ok_or(Error::EncryptedTallyMissing)?,
+                    ),
+                    None => read_encrypted_tallies(&vote_plan)?,
+                };
+                audit_private(
+                    &vote_plan,
+                    encrypted_tallies.as_deref(),
+                    &record,
+                    weights.as_ref(),
+                )?
+            }
+        };
+
+        let output = self
+            .output_format
+            .format_json(serde_json::to_value(&report)?)?;
+        println!("{}", output);
+        if report.verified {
+            Ok(())
+        } else {
+            Err(Error::AuditFailed)
+        }
+    }
+}
+
+impl ChainRecord {
+    pub fn record(&mut self, vote_plan_id: &VotePlanId, fragment: &Fragment) {
+        match fragment {
+            Fragment::VoteCast(tx) => {
+                let tx = tx.as_slice();
+                let vote_cast = tx.payload().into_payload();
+                if vote_cast.vote_plan() != vote_plan_id {
+                    return;
+                }
+                // a vote is cast from a single account, the only input
+                let voter = match tx.inputs().iter().next().map(|input| input.to_enum()) {
+                    Some(InputEnum::AccountInput(account, _)) => account.to_single_account(),
+                    _ => None,
+                };
+                if let Some(voter) = voter {
+                    let vote = match vote_cast.payload() {
+                        Payload::Public { choice } => Vote::Public(choice.as_byte()),
+                        Payload::Private {
+                            encrypted_vote,
+                            proof,
+                        } => Vote::Private {
+                            encrypted_vote: encrypted_vote.clone(),
+                            proof: proof.clone(),
+                        },
+                    };
+                    self.votes
+                        .entry(vote_cast.proposal_index())
+                        .or_default()
+                        .insert(Identifier::from(voter).to_hex(), vote);
+                }
+            }
+            Fragment::VoteTally(tx) => {
+                let vote_tally = tx.as_slice().payload().into_payload();
+                if vote_tally.id() == vote_plan_id {
+                    self.tally = Some(vote_tally);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn votes_cast(&self, proposal_index: u8) -> usize {
+        self.votes.get(&proposal_index).map_or(0, BTreeMap::len)
+    }
+
+    fn votes(&self, proposal_index: u8) -> impl Iterator<Item = (&String, &Vote)> {
+        self.votes.get(&proposal_index).into_iter().flatten()
+    }
+}
+
+impl AuditReport {
+    fn new(vote_plan: &VotePlanStatus, record: &ChainRecord) -> Self {
+        let mut issues = Vec::new();
+        let tally_certificate_found = record.tally.is_some();
+        if !tally_certificate_found {
+            issues.push("no tally certificate found for the vote plan".to_owned());
+        }
+        let proposals = vote_plan
+            .proposals
+            .iter()
+            .map(|proposal| {
+                let votes_cast = record.votes_cast(proposal.index);
+                let published_result = match &proposal.tally {
+                    Tally::Public { result }
+                    | Tally::Private {
+                        state: PrivateTallyState::Decrypted { result },
+                    } => Some(result.results()),
+                    Tally::Private { .. } => None,
+                };
+                let mut issues = Vec::new();
+                if votes_cast != proposal.votes_cast {
+                    issues.push(format!(
+                        "{} votes found in the chain but {} published",
+                        votes_cast, proposal.votes_cast
+                    ));
+                }
+                if published_result.is_none() {
+                    issues.push("the published tally is not decrypted".to_owned());
+                }
+                ProposalAudit {
+                    index: proposal.index,
+                    votes_cast,
+                    published_votes_cast: proposal.votes_cast,
+                    published_result,
+                    recomputed_result: None,
+                    certificate_result: None,
+                    ballots_aggregated: None,
+                    invalid_shares: Vec::new(),
+                    issues,
+                }
+            })
+            .collect();
+        AuditReport {
+            vote_plan_id: vote_plan.id,
+            private: vote_plan.payload == PayloadType::Private,
+            tally_certificate_found,
+            proposals,
+            issues,
+            verified: false,
+        }
+    }
+
+    /// the voting power of the voters is needed to recompute the tally
+    fn weights_missing(&mut self) {
+        self.issues.push(
+            "the tally is not recomputed without the voting power of the voters, see --weights"
+                .to_owned(),
+        );
+    }
+
+    fn conclude(mut self) -> Self {
+        self.verified = self.issues.is_empty()
+            && self
+                .proposals
+                .iter()
+                .all(|proposal| proposal.issues.is_empty());
+        self
+    }
+}
+
+impl ProposalAudit {
+    /// record the recomputed result, and an issue if it differs from the
+    /// published one
+    fn recomputed(&mut self, result: Vec<u64>) {
+        if let Some(published) = &self.published_result {
+            if published != &result {
+                self.issues
+                    .push("the recomputed result differs from the published one".to_owned());
+            }
+        }
+        self.recomputed_result = Some(result);
+    }
+}
+
+pub fn audit_public(
+    vote_plan: &VotePlanStatus,
+    record: &ChainRecord,
+    weights: Option<&BTreeMap<String, u64>>,
+) -> AuditReport {
+    let mut report = AuditReport::new(vote_plan, record);
+    let weights = match weights {
+        Some(weights) => weights,
+        None => {
+            report.weights_missing();
+            return report.conclude();
+        }
+    };
+    for (audit, proposal) in report.proposals.iter_mut().zip(&vote_plan.proposals) {
+        let mut result = vec![0u64; proposal.options.end as usize];
+        for (voter, vote) in record.votes(proposal.index) {
+            let weight = weights.get(voter).copied().unwrap_or(0);
+            let total = match vote {
+                Vote::Public(choice) => result.get_mut(*choice as usize),
+                Vote::Private { .. } => None,
+            };
+            match total {
+                Some(total) => *total = total.saturating_add(weight),
+                None => audit
+                    .issues
+                    .push(format!("the vote of {} is not a valid choice", voter)),
+            }
+        }
+        audit.recomputed(result);
+    }
+    report.conclude()
+}
+
+/// audit a private vote plan against its encrypted tallies, or against the
+/// tallies aggregated again from the ballots when only the voting power of
+/// the voters is known
+pub fn audit_private(
+    vote_plan: &VotePlanStatus,
+    encrypted_tallies: Option<&[EncryptedTally]>,
+    record: &ChainRecord,
+    weights: Option<&BTreeMap<String, u64>>,
+) -> Result<AuditReport, Error> {
+    let mut report = AuditReport::new(vote_plan, record);
+    if let Some(encrypted_tallies) = encrypted_tallies {
+        if encrypted_tallies.len() != report.proposals.len() {
+            report.issues.push(format!(
+                "{} encrypted tallies for {} proposals",
+                encrypted_tallies.len(),
+                report.proposals.len()
+            ));
+            return Ok(report.conclude());
+        }
+    }
+    let aggregated_tallies =
+        weights.map(|weights| aggregate_ballots(vote_plan, record, weights, &mut report));
+    let encrypted_tallies = match (encrypted_tallies, &aggregated_tallies) {
+        (Some(encrypted_tallies), Some(aggregated_tallies)) => {
+            compare_tallies(encrypted_tallies, aggregated_tallies, &mut report);
+            encrypted_tallies
+        }
+        (Some(encrypted_tallies), None) => {
+            report.weights_missing();
+            encrypted_tallies
+        }
+        (None, Some(aggregated_tallies)) => aggregated_tallies.as_slice(),
+        (None, None) => return Err(Error::EncryptedTallyMissing),
+    };
+
+    let decrypted_tally = match record.tally.as_ref().map(VoteTally::tally_decrypted) {
+        Some(Some(decrypted_tally)) => decrypted_tally,
+        Some(None) => {
+            report
+                .issues
+                .push("the tally certificate is not private".to_owned());
+            return Ok(report.conclude());
+        }
+        None => return Ok(report.conclude()),
+    };
+
+    let member_keys = &vote_plan.committee_member_keys;
+    let mut validated_tallies = Vec::new();
+    for ((audit, encrypted_tally), certificate) in report
+        .proposals
+        .iter_mut()
+        .zip(encrypted_tallies)
+        .zip(decrypted_tally.iter())
+    {
+        let shares = &certificate.decrypt_shares;
+        audit.certificate_result = Some(certificate.tally_result.to_vec());
+        if shares.len() != member_keys.len() {
+            audit.issues.push(format!(
+                "{} decryption shares for {} committee members",
+                shares.len(),
+                member_keys.len()
+            ));
+            continue;
+        }
+        audit.invalid_shares = member_keys
+            .iter()
+            .zip(shares.iter())
+            .enumerate()
+            .filter(|(_, (key, share))| {
+                encrypted_tally
+                    .clone()
+                    .validate_partial_decryptions(
+                        std::slice::from_ref(*key),
+                        std::slice::from_ref(*share),
+                    )
+                    .is_err()
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if !audit.invalid_shares.is_empty() {
+            audit
+                .issues
+                .push("some decryption shares are not valid".to_owned());
+            continue;
+        }
+        match encrypted_tally
+            .clone()
+            .validate_partial_decryptions(member_keys, shares)
+        {
+            Ok(validated_tally) => validated_tallies.push((audit.index, validated_tally)),
+            Err(_) => audit
+                .issues
+                .push("the decryption shares are not valid".to_owned()),
+        }
+    }
+
+    let (indexes, validated_tallies): (Vec<_>, Vec<_>) = validated_tallies.into_iter().unzip();
+    let decrypted_tallies = batch_decrypt(validated_tallies)?;
+    for (index, decrypted_tally) in indexes.into_iter().zip(decrypted_tallies) {
+        let result = TallyResult::from(decrypted_tally).results();
+        let audit = report
+            .proposals
+            .iter_mut()
+            .find(|audit| audit.index == index)
+            .expect("the validated tallies come from the proposals");
+        if audit.certificate_result.as_ref() != Some(&result) {
+            audit
+                .issues
+                .push("the decrypted result differs from the tally certificate".to_owned());
+        }
+        audit.recomputed(result);
+    }
+    Ok(report.conclude())
+}
+
+/// aggregate the encrypted ballots of every proposal with the voting power of
+/// the voters, as the ledger does
+fn aggregate_ballots(
+    vote_plan: &VotePlanStatus,
+    record: &ChainRecord,
+    weights: &BTreeMap<String, u64>,
+    report: &mut AuditReport,
+) -> Vec<EncryptedTally> {
+    let crs = Crs::from_hash(VotePlanId::from(vote_plan.id).as_ref());
+    let election_key = ElectionPublicKey::from_participants(&vote_plan.committee_member_keys);
+    report
+        .proposals
+        .iter_mut()
+        .zip(&vote_plan.proposals)
+        .map(|(audit, proposal)| {
+            let mut tally = EncryptedTally::new(
+                proposal.options.end as usize,
+                election_key.clone(),
+                crs.clone(),
+            );
+            for (voter, vote) in record.votes(proposal.index) {
+                let ballot = match vote {
+                    Vote::Private {
+                        encrypted_vote,
+                        proof,
+                    } => Ballot::try_from_vote_and_proof(
+                        encrypted_vote.as_inner().clone(),
+                        proof.as_inner(),
+                        &crs,
+                        &election_key,
+                    )
+                    .ok(),
+                    Vote::Public(_) => None,
+                };
+                match ballot {
+                    Some(ballot) => tally.add(&ballot, weights.get(voter).copied().unwrap_or(0)),
+                    None => audit
+                        .issues
+                        .push(format!("the vote of {} is not a valid ballot", voter)),
+                }
+            }
+            tally
+        })
+        .collect()
+}
+
+/// compare the aggregated ballots of every proposal with its encrypted tally
+fn compare_tallies(
+    encrypted_tallies: &[EncryptedTally],
+    aggregated_tallies: &[EncryptedTally],
+    report: &mut AuditReport,
+) {
+    for ((audit, encrypted_tally), aggregated_tally) in report
+        .proposals
+        .iter_mut()
+        .zip(encrypted_tallies)
+        .zip(aggregated_tallies)
+    {
+        let aggregated = aggregated_tally.to_bytes() == encrypted_tally.to_bytes();
+        if !aggregated {
+            audit
+                .issues
+                .push("the encrypted ballots do not add up to the encrypted tally".to_owned());
+        }
+        audit.ballots_aggregated = Some(aggregated);
+    }
+}
+
+/// the maximum number of block ids the node returns at once
+const NEXT_IDS_MAX: usize = 100;
+
+/// list the block ids of the main chain, find the first block of the voting
+/// period by bisecting on the block dates, then record the fragments of the
+/// blocks dated within the voting and the tally periods. Only the blocks of
+/// these periods and the ones probed by the bisection are fetched.
+fn scan_chain(args: RestArgs, vote_plan: &VotePlanStatus) -> Result<ChainRecord, Error> {
+    let vote_plan_id = vote_plan.id.into();
+    let vote_start: BlockDate = vote_plan.vote_start.into();
+    let committee_end: BlockDate = vote_plan.committee_end.into();
+
+    let tip = request_tip(args.clone())?;
+    let mut block_ids = vec![request_settings(args.clone())?.block0_hash];
+    while block_ids.last() != Some(&tip) {
+        let last = block_ids.last().expect("block 0 is listed");
+        let next_ids = request_next_ids(args.clone(), last, Some(NEXT_IDS_MAX))?;
+        let complete = next_ids.len() < NEXT_IDS_MAX;
+        block_ids.extend(next_ids);
+        if complete {
+            break;
+        }
+    }
+
+    let request = |block_id: &str| -> Result<Block, Error> {
+        let bytes = request_block(args.clone(), block_id)?;
+        Block::deserialize(&mut Codec::new(bytes.as_ref())).map_err(Error::BlockMalformed)
+    };
+    // the block dates only grow along the chain
+    let (mut start, mut end) = (0, block_ids.len());
+    while start < end {
+        let middle = start + (end - start) / 2;
+        if request(&block_ids[middle])?.header().block_date() < vote_start {
+            start = middle + 1;
+        } else {
+            end = middle;
+        }
+    }
+
+    let mut record = ChainRecord::default();
+    for block_id in &block_ids[start..] {
+        let block = request(block_id)?;
+        if block.header().block_date() >= committee_end {
+            break;
+        }
+        for fragment in block.fragments() {
+            record.record(&vote_plan_id, fragment);
+        }
+    }
+    Ok(record)
+}
+
+fn read_fragments(path: &Path, vote_plan_id: &VotePlanId) -> Result<ChainRecord, Error> {
+    let mut record = ChainRecord::default();
+    let reader = io::open_file_read(&Some(path)).map_err(|source| Error::InputInvalid {
+        source,
+        path: path.to_path_buf(),
+    })?;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let bytes = hex::decode(line.trim())?;
+        let fragment = Fragment::deserialize_from_slice(&mut Codec::new(bytes.as_slice()))
+            .map_err(|source| Error::FragmentMalformed {
+                line: index + 1,
+                source,
+            })?;
+        record.record(vote_plan_id, &fragment);
+    }
+    Ok(record)
+}
+
+/// the encrypted tallies of the vote plan, if it was exported before the
+/// tally
+fn read_encrypted_tallies(
+    vote_plan: &VotePlanStatus,
+) -> Result<Option<Vec<EncryptedTally>>, Error> {
+    match vote::encrypted_tallies(vote_plan) {
+        Ok(encrypted_tallies) => Ok(Some(encrypted_tallies)),
+        Err(EncryptedTallyError::NotEncrypted { .. }) => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jcli_lib::vote::test_utils;
+    use chain_core::property::FromStr;
+    use chain_impl_mockchain::{
+        certificate::{DecryptedPrivateTally, DecryptedPrivateTallyProposal},
+        tokens::identifier::TokenIdentifier,
+    };
+    use chain_vote::MemberPublicKey;
+    use jormungandr_lib::interfaces::{BlockDate, VoteProposalStatus};
+
+    fn voter(byte: u8) -> String {
+        hex::encode([byte; 32])
+    }
+
+    fn vote_plan(
+        payload: PayloadType,
+        committee_member_keys: Vec<MemberPublicKey>,
+        tally: Tally,
+        votes_cast: usize,
+    ) -> VotePlanStatus {
+        VotePlanStatus {
+            id: Hash::from([1u8; 32]),
+            payload,
+            vote_start: BlockDate::new(0, 0),
+            vote_end: BlockDate::new(0, 1),
+            committee_end: BlockDate::new(0, 2),
+            committee_member_keys,
+            proposals: vec![VoteProposalStatus {
+                index: 0,
+                proposal_id: Hash::from([2u8; 32]),
+                options: 0..2,
+                tally,
+                votes_cast,
+            }],
+            voting_token: TokenIdentifier::from_str(
+                "00000000000000000000000000000000000000000000000000000000.00000000",
+            )
+            .unwrap()
+            .into(),
+        }
+    }
+
+    fn public_vote_plan(results: Vec<u64>, votes_cast: usize) -> VotePlanStatus {
+        let tally = Tally::Public {
+            result: TallyResult {
+                results,
+                options: 0..2,
+            },
+        };
+        vote_plan(PayloadType::Public, vec![], tally, votes_cast)
+    }
+
+    fn record() -> ChainRecord {
+        let mut votes = BTreeMap::new();
+        votes.insert(voter(1), Vote::Public(0));
+        votes.insert(voter(2), Vote::Public(1));
+        votes.insert(voter(3), Vote::Public(1));
+        let mut record = ChainRecord::default();
+        record.votes.insert(0, votes);
+        record.tally = Some(VoteTally::new_public(Hash::from([1u8; 32]).into()));
+        record
+    }
+
+    fn weights() -> BTreeMap<String, u64> {
+        vec![(voter(1), 10), (voter(2), 5), (voter(3), 7)]
+            .into_iter()
+            .collect()
+    }
+
+    /// a private vote plan tallied by a committee of two members, with the
+    /// votes of `record_votes` encrypted
+    struct PrivateVote {
+        vote_plan: VotePlanStatus,
+        encrypted_tally: EncryptedTally,
+        record: ChainRecord,
+    }
+
+    impl PrivateVote {
+        fn new() -> Self {
+            let vote_plan_id = VotePlanId::from(Hash::from([1u8; 32]));
+            let mut committee = test_utils::Committee::new();
+            let mut encrypted_tally = committee.encrypted_tally();
+            let mut record = ChainRecord::default();
+            let weights = weights();
+            for (voter, vote) in record_votes() {
+                let (encrypted_vote, proof, ballot) = committee.vote(vote as usize);
+                encrypted_tally.add(&ballot, weights[&voter]);
+                record.votes.entry(0).or_default().insert(
+                    voter,
+                    Vote::Private {
+                        encrypted_vote,
+                        proof,
+                    },
+                );
+            }
+
+            let decrypt_shares = committee.decrypt_shares(&encrypted_tally);
+            let tally_result = vec![10, 12];
+            let decrypted_tally = DecryptedPrivateTally::new(vec![DecryptedPrivateTallyProposal {
+                decrypt_shares: decrypt_shares.into_boxed_slice(),
+                tally_result: tally_result.clone().into_boxed_slice(),
+            }])
+            .unwrap();
+            record.tally = Some(VoteTally::new_private(vote_plan_id, decrypted_tally));
+
+            let tally = Tally::Private {
+                state: PrivateTallyState::Decrypted {
+                    result: TallyResult {
+                        results: tally_result,
+                        options: 0..2,
+                    },
+                },
+            };
+            PrivateVote {
+                vote_plan: vote_plan(PayloadType::Private, committee.member_keys, tally, 3),
+                encrypted_tally,
+                record,
+            }
+        }
+
+        fn audit(&self, weights: Option<&BTreeMap<String, u64>>) -> AuditReport {
+            audit_private(
+                &self.vote_plan,
+                Some(std::slice::from_ref(&self.encrypted_tally)),
+                &self.record,
+                weights,
+            )
+            .unwrap()
+        }
+    }
+
+    fn record_votes() -> Vec<(String, u8)> {
+        vec![(voter(1), 0), (voter(2), 1), (voter(3), 1)]
+    }
+
+    #[test]
+    fn public_tally_is_recomputed() {
+        let report = audit_public(
+            &public_vote_plan(vec![10, 12], 3),
+            &record(),
+            Some(&weights()),
+        );
+        assert_eq!(report.proposals[0].recomputed_result, Some(vec![10, 12]));
+        assert!(report.proposals[0].issues.is_empty());
+        assert!(report.verified);
+    }
+
+    #[test]
+    fn public_discrepancies_are_reported() {
+        let report = audit_public(
+            &public_vote_plan(vec![10, 13], 4),
+            &record(),
+            Some(&weights()),
+        );
+        assert_eq!(report.proposals[0].votes_cast, 3);
+        assert_eq!(report.proposals[0].issues.len(), 2);
+        assert!(!report.verified);
+    }
+
+    #[test]
+    fn public_tally_is_unverified_without_weights() {
+        let report = audit_public(&public_vote_plan(vec![10, 12], 3), &record(), None);
+        assert_eq!(report.proposals[0].recomputed_result, None);
+        assert_eq!(report.issues.len(), 1);
+        assert!(!report.verified);
+    }
+
+    #[test]
+    fn private_tally_is_recomputed() {
+        let report = PrivateVote::new().audit(Some(&weights()));
+        let proposal = &report.proposals[0];
+        assert_eq!(proposal.ballots_aggregated, Some(true));
+        assert!(proposal.invalid_shares.is_empty());
+        assert_eq!(proposal.certificate_result, Some(vec![10, 12]));
+        assert_eq!(proposal.recomputed_result, Some(vec![10, 12]));
+        assert!(report.verified);
+    }
+
+    #[test]
+    fn private_tally_is_unverified_without_weights() {
+        let report = PrivateVote::new().audit(None);
+        assert_eq!(report.proposals[0].ballots_aggregated, None);
+        assert_eq!(report.proposals[0].recomputed_result, Some(vec![10, 12]));
+        assert!(!report.verified);
+    }
+
+    #[test]
+    fn private_tally_is_recomputed_from_the_ballots() {
+        let private_vote = PrivateVote::new();
+        let report = audit_private(
+            &private_vote.vote_plan,
+            None,
+            &private_vote.record,
+            Some(&weights()),
+        )
+        .unwrap();
+        let proposal = &report.proposals[0];
+        assert_eq!(proposal.ballots_aggregated, None);
+        assert!(proposal.invalid_shares.is_empty());
+        assert_eq!(proposal.recomputed_result, Some(vec![10, 12]));
+        assert!(report.verified);
+
+        assert!(matches!(
+            audit_private(&private_vote.vote_plan, None, &private_vote.record, None),
+            Err(Error::EncryptedTallyMissing)
+        ));
+    }
+
+    #[test]
+    fn private_ballots_not_adding_up_are_reported() {
+        let private_vote = PrivateVote::new();
+        let mut weights = weights();
+        weights.insert(voter(3), 8);
+        let report = private_vote.audit(Some(&weights));
+        assert_eq!(report.proposals[0].ballots_aggregated, Some(false));
+        assert!(!report.verified);
+
+        // a ballot dropped from the chain record
+        let mut private_vote = PrivateVote::new();
+        private_vote
+            .record
+            .votes
+            .get_mut(&0)
+            .unwrap()
+            .remove(&voter(1));
+        let report = private_vote.audit(Some(&self::weights()));
+        assert_eq!(report.proposals[0].ballots_aggregated, Some(false));
+        assert!(!report.verified);
+    }
+
+    #[test]
+    fn private_invalid_shares_are_reported() {
+        let mut private_vote = PrivateVote::new();
+        let vote_plan_id = VotePlanId::from(private_vote.vote_plan.id);
+        let decrypted = private_vote
+            .record
+            .tally
+            .as_ref()
+            .and_then(VoteTally::tally_decrypted)
+            .unwrap();
+        let proposal = decrypted.iter().next().unwrap();
+        // the share of the first member given twice
+        let shares = vec![
+            proposal.decrypt_shares[0].clone(),
+            proposal.decrypt_shares[0].clone(),
+        ];
+        let tally = DecryptedPrivateTally::new(vec![DecryptedPrivateTallyProposal {
+            decrypt_shares: shares.into_boxed_slice(),
+            tally_result: proposal.tally_result.clone(),
+        }])
+        .unwrap();
+        private_vote.record.tally = Some(VoteTally::new_private(vote_plan_id, tally));
+
+        let report = private_vote.audit(Some(&weights()));
+        assert_eq!(report.proposals[0].ballots_aggregated, Some(true));
+        assert_eq!(report.proposals[0].invalid_shares, vec![1]);
+        assert_eq!(report.proposals[0].recomputed_result, None);
+        assert!(!report.verified);
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     jcli_lib::utils::{
         key_parser,
         output_file::{self, OutputFile},
-        vote::{SharesError, VotePlanError},
+        vote::{EncryptedTallyError, SharesError, VotePlanError},
     },
     rest,
 };
@@ -10,9 +10,12 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 use thiserror::Error;
 
+mod audit;
 mod committee;
 mod election_public_key;
 mod tally;
+#[cfg(test)]
+mod test_utils;
 
 pub use tally::MergedVotePlan;
 
@@ -87,6 +90,37 @@ pub enum Error {
     },
     #[error(transparent)]
     MergeError(#[from] tally::merge_results::Error),
+    #[error("the member key is not a committee member key of the vote plan")]
+    NotCommitteeMember,
+    #[error("{found} communication keys given for {expected} committee members")]
+    CommunicationKeyCountMismatch { found: usize, expected: usize },
+    #[error("the tally ceremony is incomplete: {missing} member(s) missing and {invalid} share file(s) rejected")]
+    CeremonyIncomplete { missing: usize, invalid: usize },
+    #[error("could not sign the tally certificate")]
+    CertificateSign(#[source] crate::jcli_lib::certificate::Error),
+    #[error("the encrypted tallies or the voting power of the voters are needed to audit a private vote plan, pass the vote plan status exported before the tally or the weights")]
+    EncryptedTallyMissing,
+    #[error("could not decode a block of the node")]
+    BlockMalformed(#[source] chain_core::property::ReadError),
+    #[error("the fragment at line {line} is not valid")]
+    FragmentMalformed {
+        line: usize,
+        #[source]
+        source: chain_core::property::ReadError,
+    },
+    #[error("the audit of the vote plan did not pass")]
+    AuditFailed,
+    #[error("could not make the private tally")]
+    DecryptedPrivateTally(#[from] chain_impl_mockchain::certificate::DecryptedPrivateTallyError),
+}
+
+impl From<EncryptedTallyError> for Error {
+    fn from(error: EncryptedTallyError) -> Self {
+        match error {
+            EncryptedTallyError::Read => Error::EncryptedTallyRead,
+            EncryptedTallyError::NotEncrypted { found } => Error::PrivateTallyExpected { found },
+        }
+    }
 }
 
 #[derive(StructOpt)]
@@ -98,6 +132,8 @@ pub enum Vote {
     ElectionKey(election_public_key::ElectionPublicKey),
     /// Perform decryption of private voting tally
     Tally(tally::Tally),
+    /// Check a published vote plan result against the chain
+    Audit(audit::Audit),
 }
 
 impl Vote {
@@ -106,6 +142,7 @@ impl Vote {
             Vote::Committee(cmd) => cmd.exec(),
             Vote::ElectionKey(cmd) => cmd.exec(),
             Vote::Tally(cmd) => cmd.exec(),
+            Vote::Audit(cmd) => cmd.exec(),
         }
     }
 }
@@ -0,0 +1,725 @@
+use super::Error;
+use crate::jcli_lib::{
+    certificate::committee_vote_tally_sign,
+    utils::{
+        io,
+        vote::{self, encrypted_tallies, MemberVotePlanShares, SharesError},
+    },
+};
+use chain_crypto::{
+    bech32::Bech32,
+    ec::{GroupElement, Scalar},
+};
+use chain_impl_mockchain::{
+    certificate::{Certificate, DecryptedPrivateTally, DecryptedPrivateTallyProposal, VoteTally},
+    transaction::Transaction,
+};
+use chain_vote::{
+    committee::{
+        MemberCommunicationKey, MemberCommunicationPublicKey, MemberPublicKey, MemberSecretKey,
+    },
+    tally::{batch_decrypt, EncryptedTally},
+};
+use cryptoxide::{blake2b::Blake2b, digest::Digest};
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{self, PrivateTallyState, Tally, TallyResult, VotePlanStatus},
+};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+
+/// Tally a private vote plan with the committee members working apart: each
+/// member publishes a signed share file, the coordinator checks them all and
+/// makes the tally certificate out of them.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Ceremony {
+    /// Create the decryption shares of a committee member, signed with its
+    /// communication key.
+    Share(Share),
+    /// Check the share files of the committee members and report the missing
+    /// and invalid ones.
+    Verify(Verify),
+    /// Check the share files, decrypt the vote plan and make the private tally
+    /// certificate.
+    Finalize(Finalize),
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Share {
+    /// The path to json-encoded vote plan to decrypt. If this parameter is not
+    /// specified, the vote plan will be read from standard input.
+    #[structopt(long)]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan to decrypt.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
+    /// The path to bech32-encoded member secret key.
+    #[structopt(long)]
+    key: PathBuf,
+    /// The path to bech32-encoded communication secret key of the member,
+    /// signing the share file.
+    #[structopt(long)]
+    communication_key: PathBuf,
+    /// The path of the share file, the standard output if not specified.
+    #[structopt(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct ShareFiles {
+    /// The path to json-encoded vote plan to decrypt. If this parameter is not
+    /// specified, the vote plan will be read from standard input.
+    #[structopt(long)]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan to decrypt.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
+    /// The bech32-encoded communication public keys of all the committee
+    /// members, in the order their member keys were generated with. The share
+    /// file of a member must be signed with its communication key.
+    #[structopt(
+        long = "communication-key",
+        parse(try_from_str = MemberCommunicationPublicKey::try_from_bech32_str),
+        required = true
+    )]
+    communication_keys: Vec<MemberCommunicationPublicKey>,
+    /// The paths to the share files of the committee members.
+    #[structopt(required = true)]
+    shares: Vec<PathBuf>,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Verify {
+    #[structopt(flatten)]
+    share_files: ShareFiles,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Finalize {
+    #[structopt(flatten)]
+    share_files: ShareFiles,
+    /// The path to write the json-encoded decrypted vote plan to.
+    #[structopt(long)]
+    result: Option<PathBuf>,
+    /// The path to the bech32-encoded ed25519 secret key of a committee member
+    /// of the vote plan, signing the tally certificate. If not specified the
+    /// certificate is left unsigned and must be signed with
+    /// `jcli certificate sign` before being added to a transaction.
+    #[structopt(long)]
+    committee_key: Option<PathBuf>,
+    /// The path to write the tally certificate to, the standard output if not
+    /// specified.
+    #[structopt(long)]
+    output: Option<PathBuf>,
+}
+
+/// The decryption shares of a committee member for all the proposals of a
+/// vote plan, signed with the communication key of the member. The signature
+/// covers the vote plan id, the member index and the shares.
+#[derive(Serialize, Deserialize)]
+pub struct SignedMemberShares {
+    vote_plan_id: Hash,
+    member_index: usize,
+    shares: MemberVotePlanShares,
+    /// the bech32-encoded communication public key of the member
+    signer: String,
+    signature: CommunicationSignature,
+}
+
+/// A Schnorr signature made with a member communication key, hex-encoded.
+///
+/// With the secret key `a` and the public key `A = a.G` of the member, the
+/// signer picks a random nonce `k` and publishes the commitment `R = k.G`
+/// with the response `s = k + c.a`. The challenge `c` is the 64 bytes
+/// Blake2b hash of a domain separation tag, `R`, `A` and the signed data,
+/// reduced modulo the group order. The signature is valid when
+/// `s.G = R + c.A`.
+#[derive(Serialize, Deserialize)]
+pub struct CommunicationSignature {
+    commitment: String,
+    response: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShareFileError {
+    #[error("cannot be read")]
+    Read(#[from] std::io::Error),
+    #[error("is not a share file")]
+    Json(#[from] serde_json::Error),
+    #[error("is for the vote plan {0}")]
+    VotePlanMismatch(Hash),
+    #[error("is for the member {0} but the committee has {1} members")]
+    MemberIndexOutOfRange(usize, usize),
+    #[error("is for the member {0}, which already has a valid share file")]
+    Duplicate(usize),
+    #[error("has an invalid signature")]
+    InvalidSignature,
+    #[error("is not signed by the communication key of the member {0}")]
+    SignerMismatch(usize),
+    #[error("has {found} shares but the vote plan has {expected} proposals")]
+    ProposalCountMismatch { found: usize, expected: usize },
+    #[error("has a malformed share")]
+    Share(#[from] SharesError),
+    #[error("has an invalid share for the proposal {0}")]
+    ShareValidationFailed(usize),
+}
+
+/// the outcome of checking the share files, member by member
+pub struct CeremonyReport {
+    members: usize,
+    accepted: BTreeMap<usize, AcceptedShares>,
+    rejected: Vec<(PathBuf, ShareFileError)>,
+}
+
+struct AcceptedShares {
+    path: PathBuf,
+    signer: String,
+    shares: Vec<chain_vote::TallyDecryptShare>,
+}
+
+impl Ceremony {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Ceremony::Share(cmd) => cmd.exec(),
+            Ceremony::Verify(cmd) => cmd.exec(),
+            Ceremony::Finalize(cmd) => cmd.exec(),
+        }
+    }
+}
+
+impl Share {
+    fn exec(self) -> Result<(), Error> {
+        let vote_plan =
+            vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
+        let line = io::read_line(&Some(&self.key))?;
+        let decryption_key = MemberSecretKey::try_from_bech32_str(&line)?;
+        let public_key = decryption_key.to_public().to_bech32_str();
+        let member_index = vote_plan
+            .committee_member_keys
+            .iter()
+            .position(|key| key.to_bech32_str() == public_key)
+            .ok_or(Error::NotCommitteeMember)?;
+        let line = io::read_line(&Some(&self.communication_key))?;
+        let communication_key = MemberCommunicationKey::try_from_bech32_str(&line)?;
+
+        let mut rng = rand::thread_rng();
+        let shares = encrypted_tallies(&vote_plan)?
+            .iter()
+            .map(|tally| tally.partial_decrypt(&mut rng, &decryption_key))
+            .collect::<Vec<_>>();
+        let signed = SignedMemberShares::new(
+            &mut rng,
+            vote_plan.id,
+            member_index,
+            shares,
+            &communication_key,
+        )?;
+
+        let mut output = io::open_file_write(&self.output)?;
+        writeln!(output, "{}", serde_json::to_string_pretty(&signed)?)?;
+        Ok(())
+    }
+}
+
+impl Verify {
+    fn exec(self) -> Result<(), Error> {
+        let vote_plan = self.share_files.vote_plan()?;
+        let report = self.share_files.check(&vote_plan)?;
+        print!("{}", report);
+        report.ensure_complete()
+    }
+}
+
+impl Finalize {
+    fn exec(self) -> Result<(), Error> {
+        let mut vote_plan = self.share_files.vote_plan()?;
+        let report = self.share_files.check(&vote_plan)?;
+        // the standard output may be taken by the certificate
+        eprint!("{}", report);
+        report.ensure_complete()?;
+
+        let mut shares: Vec<Vec<chain_vote::TallyDecryptShare>> =
+            vote_plan.proposals.iter().map(|_| Vec::new()).collect();
+        for accepted in report.accepted.into_values() {
+            for (proposal_shares, share) in shares.iter_mut().zip(accepted.shares) {
+                proposal_shares.push(share);
+            }
+        }
+
+        let validated_tallies = encrypted_tallies(&vote_plan)?
+            .into_iter()
+            .zip(shares.iter())
+            .map(|(tally, shares)| {
+                tally
+                    .validate_partial_decryptions(&vote_plan.committee_member_keys, shares)
+                    .map_err(SharesError::ValidationFailed)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let decrypted_tallies = batch_decrypt(validated_tallies)?;
+
+        let mut tallies = Vec::with_capacity(shares.len());
+        for ((proposal, shares), decrypted_tally) in vote_plan
+            .proposals
+            .iter_mut()
+            .zip(shares)
+            .zip(decrypted_tallies)
+        {
+            let result: TallyResult = decrypted_tally.into();
+            tallies.push(DecryptedPrivateTallyProposal {
+                decrypt_shares: shares.into_boxed_slice(),
+                tally_result: result.results().into_boxed_slice(),
+            });
+            proposal.tally = Tally::Private {
+                state: PrivateTallyState::Decrypted { result },
+            };
+        }
+        let vote_tally =
+            VoteTally::new_private(vote_plan.id.into(), DecryptedPrivateTally::new(tallies)?);
+        let certificate = match &self.committee_key {
+            Some(path) => {
+                let key = io::read_line(&Some(path))?;
+                let builder = Transaction::block0_payload_builder(&vote_tally);
+                let signed = committee_vote_tally_sign(vote_tally, &key, builder)
+                    .map_err(Error::CertificateSign)?;
+                interfaces::SignedCertificate::from(signed).to_string()
+            }
+            None => interfaces::Certificate::from(Certificate::VoteTally(vote_tally)).to_string(),
+        };
+
+        if let Some(path) = &self.result {
+            let mut result = io::open_file_write(&Some(path))?;
+            writeln!(result, "{}", serde_json::to_string_pretty(&vote_plan)?)?;
+        }
+        let mut output = io::open_file_write(&self.output)?;
+        writeln!(output, "{}", certificate)?;
+        Ok(())
+    }
+}
+
+impl ShareFiles {
+    fn vote_plan(&self) -> Result<VotePlanStatus, Error> {
+        Ok(vote::get_vote_plan_by_id(
+            self.vote_plan.as_ref(),
+            self.vote_plan_id.as_ref(),
+        )?)
+    }
+
+    fn check(&self, vote_plan: &VotePlanStatus) -> Result<CeremonyReport, Error> {
+        let members = vote_plan.committee_member_keys.len();
+        if self.communication_keys.len() != members {
+            return Err(Error::CommunicationKeyCountMismatch {
+                found: self.communication_keys.len(),
+                expected: members,
+            });
+        }
+        let tallies = encrypted_tallies(vote_plan)?;
+        let mut report = CeremonyReport {
+            members,
+            accepted: BTreeMap::new(),
+            rejected: Vec::new(),
+        };
+        for path in &self.shares {
+            match read_share_file(path).and_then(|signed| {
+                signed.check(
+                    vote_plan.id,
+                    &tallies,
+                    &vote_plan.committee_member_keys,
+                    &self.communication_keys,
+                )
+            }) {
+                Ok((member_index, _)) if report.accepted.contains_key(&member_index) => report
+                    .rejected
+                    .push((path.clone(), ShareFileError::Duplicate(member_index))),
+                Ok((member_index, accepted)) => {
+                    report.accepted.insert(
+                        member_index,
+                        AcceptedShares {
+                            path: path.clone(),
+                            ..accepted
+                        },
+                    );
+                }
+                Err(error) => report.rejected.push((path.clone(), error)),
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl SignedMemberShares {
+    pub fn new<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        vote_plan_id: Hash,
+        member_index: usize,
+        shares: Vec<chain_vote::TallyDecryptShare>,
+        communication_key: &MemberCommunicationKey,
+    ) -> Result<Self, Error> {
+        let shares = MemberVotePlanShares::from(shares);
+        let data = signed_data(vote_plan_id, member_index, &shares);
+        let signature = CommunicationSignature::sign(rng, communication_key, &data)?;
+        Ok(SignedMemberShares {
+            vote_plan_id,
+            member_index,
+            shares,
+            signer: communication_key.to_public().to_bech32_str(),
+            signature,
+        })
+    }
+
+    /// check the signature of the shares, then the shares themselves
+    /// against the encrypted tallies and the key of the member. Returns the
+    /// index of the member with its shares.
+    fn check(
+        self,
+        vote_plan_id: Hash,
+        tallies: &[EncryptedTally],
+        member_keys: &[MemberPublicKey],
+        communication_keys: &[MemberCommunicationPublicKey],
+    ) -> Result<(usize, AcceptedShares), ShareFileError> {
+        self.verify_signature(vote_plan_id, communication_keys)?;
+        let member_key =
+            member_keys
+                .get(self.member_index)
+                .ok_or(ShareFileError::MemberIndexOutOfRange(
+                    self.member_index,
+                    member_keys.len(),
+                ))?;
+        let shares = self.shares.shares();
+        if shares.len() != tallies.len() {
+            return Err(ShareFileError::ProposalCountMismatch {
+                found: shares.len(),
+                expected: tallies.len(),
+            });
+        }
+
+        let shares = shares
+            .iter()
+            .cloned()
+            .map(chain_vote::TallyDecryptShare::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        for (proposal, (tally, share)) in tallies.iter().zip(shares.iter()).enumerate() {
+            tally
+                .clone()
+                .validate_partial_decryptions(
+                    std::slice::from_ref(member_key),
+                    std::slice::from_ref(share),
+                )
+                .map_err(|_| ShareFileError::ShareValidationFailed(proposal))?;
+        }
+
+        Ok((
+            self.member_index,
+            AcceptedShares {
+                path: PathBuf::new(),
+                signer: self.signer,
+                shares,
+            },
+        ))
+    }
+
+    /// check that the file is signed by the communication key of its member
+    fn verify_signature(
+        &self,
+        vote_plan_id: Hash,
+        communication_keys: &[MemberCommunicationPublicKey],
+    ) -> Result<(), ShareFileError> {
+        if self.vote_plan_id != vote_plan_id {
+            return Err(ShareFileError::VotePlanMismatch(self.vote_plan_id));
+        }
+        let communication_key = communication_keys.get(self.member_index).ok_or(
+            ShareFileError::MemberIndexOutOfRange(self.member_index, communication_keys.len()),
+        )?;
+        if self.signer != communication_key.to_bech32_str() {
+            return Err(ShareFileError::SignerMismatch(self.member_index));
+        }
+        let data = signed_data(self.vote_plan_id, self.member_index, &self.shares);
+        if !self.signature.verify(communication_key, &data) {
+            return Err(ShareFileError::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+impl CommunicationSignature {
+    fn sign<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        communication_key: &MemberCommunicationKey,
+        data: &[u8],
+    ) -> Result<Self, Error> {
+        let secret =
+            Scalar::from_bytes(&communication_key.to_bytes()).ok_or(Error::InvalidSecretKey)?;
+        let nonce = Scalar::random(rng);
+        let commitment = &GroupElement::generator() * &nonce;
+        let challenge = challenge(&commitment, &communication_key.to_public(), data);
+        let response = &nonce + &(&challenge * &secret);
+        Ok(CommunicationSignature {
+            commitment: hex::encode(commitment.to_bytes()),
+            response: hex::encode(response.to_bytes()),
+        })
+    }
+
+    fn verify(&self, communication_key: &MemberCommunicationPublicKey, data: &[u8]) -> bool {
+        let decode = |s: &str| hex::decode(s).ok();
+        let commitment = decode(&self.commitment).and_then(|b| GroupElement::from_bytes(&b));
+        let response = decode(&self.response).and_then(|b| Scalar::from_bytes(&b));
+        let public_key = GroupElement::from_bytes(&communication_key.to_bytes());
+        match (commitment, response, public_key) {
+            (Some(commitment), Some(response), Some(public_key)) => {
+                let challenge = challenge(&commitment, communication_key, data);
+                &GroupElement::generator() * &response == &commitment + &(&public_key * &challenge)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// the challenge of the signature, hashing the commitment, the public key
+/// and the signed data
+fn challenge(
+    commitment: &GroupElement,
+    communication_key: &MemberCommunicationPublicKey,
+    data: &[u8],
+) -> Scalar {
+    let mut hash = [0u8; 64];
+    let mut digest = Blake2b::new(hash.len());
+    digest.input(b"jcli tally ceremony share signature");
+    digest.input(&commitment.to_bytes());
+    digest.input(&communication_key.to_bytes());
+    digest.input(data);
+    digest.result(&mut hash);
+    // reduce the 512 bits little-endian hash modulo the group order, limb by
+    // limb from the most significant one, so that the challenge is uniform
+    let radix = &Scalar::from_u64(1 << 32) * &Scalar::from_u64(1 << 32);
+    hash.chunks(8)
+        .rev()
+        .fold(Scalar::from_u64(0), |challenge, limb| {
+            let limb = <[u8; 8]>::try_from(limb).expect("the hash is made of 8 bytes limbs");
+            &(&challenge * &radix) + &Scalar::from_u64(u64::from_le_bytes(limb))
+        })
+}
+
+impl CeremonyReport {
+    pub fn missing(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.members).filter(move |index| !self.accepted.contains_key(index))
+    }
+
+    fn ensure_complete(&self) -> Result<(), Error> {
+        let missing = self.missing().count();
+        if missing > 0 || !self.rejected.is_empty() {
+            return Err(Error::CeremonyIncomplete {
+                missing,
+                invalid: self.rejected.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CeremonyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for index in 0..self.members {
+            match self.accepted.get(&index) {
+                Some(accepted) => writeln!(
+                    f,
+                    "member {}: valid, signed by {} ({})",
+                    index,
+                    accepted.signer,
+                    accepted.path.display()
+                )?,
+                None => writeln!(f, "member {}: missing", index)?,
+            }
+        }
+        for (path, error) in &self.rejected {
+            writeln!(f, "rejected {}: {}", path.display(), error)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_share_file(path: &Path) -> Result<SignedMemberShares, ShareFileError> {
+    Ok(serde_json::from_reader(io::open_file_read(&Some(path))?)?)
+}
+
+/// the data signed by a committee member: the vote plan id, the member index
+/// and the length-prefixed shares
+fn signed_data(vote_plan_id: Hash, member_index: usize, shares: &MemberVotePlanShares) -> Vec<u8> {
+    let vote_plan_id: [u8; 32] = vote_plan_id.into_hash().into();
+    let mut data = vote_plan_id.to_vec();
+    data.extend_from_slice(&(member_index as u64).to_be_bytes());
+    for share in shares.shares() {
+        data.extend_from_slice(&(share.as_bytes().len() as u64).to_be_bytes());
+        data.extend_from_slice(share.as_bytes());
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jcli_lib::vote::test_utils::{self, public_keys};
+    use rand_chacha::ChaChaRng;
+
+    fn vote_plan_id() -> Hash {
+        Hash::from([1u8; 32])
+    }
+
+    /// a committee of two members and the encrypted tally of a proposal with
+    /// one vote, with their decryption shares
+    struct Committee {
+        rng: ChaChaRng,
+        communication_keys: Vec<MemberCommunicationKey>,
+        member_keys: Vec<MemberPublicKey>,
+        tally: EncryptedTally,
+        shares: Vec<chain_vote::TallyDecryptShare>,
+    }
+
+    impl Committee {
+        fn new() -> Self {
+            let mut committee = test_utils::Committee::new();
+            let mut tally = committee.encrypted_tally();
+            let (_, _, ballot) = committee.vote(1);
+            tally.add(&ballot, 1);
+            let shares = committee.decrypt_shares(&tally);
+            Committee {
+                rng: committee.rng,
+                communication_keys: committee.communication_keys,
+                member_keys: committee.member_keys,
+                tally,
+                shares,
+            }
+        }
+
+        /// the share file of `member_index` holding the share of `share_of`,
+        /// signed with the communication key of `signer`
+        fn share_file(
+            &mut self,
+            member_index: usize,
+            share_of: usize,
+            signer: usize,
+        ) -> SignedMemberShares {
+            SignedMemberShares::new(
+                &mut self.rng,
+                vote_plan_id(),
+                member_index,
+                vec![self.shares[share_of].clone()],
+                &self.communication_keys[signer],
+            )
+            .unwrap()
+        }
+
+        fn check(&self, signed: SignedMemberShares) -> Result<usize, ShareFileError> {
+            signed
+                .check(
+                    vote_plan_id(),
+                    std::slice::from_ref(&self.tally),
+                    &self.member_keys,
+                    &public_keys(&self.communication_keys),
+                )
+                .map(|(member_index, _)| member_index)
+        }
+    }
+
+    #[test]
+    fn member_shares_are_accepted() {
+        let mut committee = Committee::new();
+        for member_index in 0..2 {
+            let signed = committee.share_file(member_index, member_index, member_index);
+            let json = serde_json::to_string(&signed).unwrap();
+            let decoded: SignedMemberShares = serde_json::from_str(&json).unwrap();
+            assert_eq!(committee.check(decoded).unwrap(), member_index);
+        }
+    }
+
+    #[test]
+    fn tampered_shares_are_rejected() {
+        let mut committee = Committee::new();
+        let signed = committee.share_file(0, 0, 0);
+        assert!(matches!(
+            signed.verify_signature(
+                Hash::from([0u8; 32]),
+                &public_keys(&committee.communication_keys)
+            ),
+            Err(ShareFileError::VotePlanMismatch(_))
+        ));
+
+        // the shares replaced after signing
+        let mut signed = committee.share_file(0, 0, 0);
+        signed.shares = committee.share_file(0, 1, 0).shares;
+        assert!(matches!(
+            committee.check(signed),
+            Err(ShareFileError::InvalidSignature)
+        ));
+
+        // the share of another member, signed by the member
+        let signed = committee.share_file(0, 1, 0);
+        assert!(matches!(
+            committee.check(signed),
+            Err(ShareFileError::ShareValidationFailed(0))
+        ));
+    }
+
+    #[test]
+    fn signatures_only_verify_with_the_signing_key() {
+        let mut committee = Committee::new();
+        let data = b"member shares";
+        let signature = CommunicationSignature::sign(
+            &mut committee.rng,
+            &committee.communication_keys[0],
+            data,
+        )
+        .unwrap();
+        let public_keys = public_keys(&committee.communication_keys);
+        assert!(signature.verify(&public_keys[0], data));
+        assert!(!signature.verify(&public_keys[1], data));
+        assert!(!signature.verify(&public_keys[0], b"other shares"));
+
+        // signed by the first member, claiming to be from the second one
+        let mut signed = committee.share_file(1, 1, 0);
+        signed.signer = public_keys[1].to_bech32_str();
+        assert!(matches!(
+            committee.check(signed),
+            Err(ShareFileError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn shares_of_the_wrong_member_are_rejected() {
+        let mut committee = Committee::new();
+        // signed by the first member for the second one
+        let signed = committee.share_file(1, 1, 0);
+        assert!(matches!(
+            committee.check(signed),
+            Err(ShareFileError::SignerMismatch(1))
+        ));
+
+        // the member index changed after signing
+        let mut signed = committee.share_file(0, 0, 0);
+        signed.member_index = 1;
+        signed.signer = committee.communication_keys[1].to_public().to_bech32_str();
+        assert!(matches!(
+            committee.check(signed),
+            Err(ShareFileError::InvalidSignature)
+        ));
+
+        let signed = committee.share_file(2, 0, 0);
+        assert!(matches!(
+            committee.check(signed),
+            Err(ShareFileError::MemberIndexOutOfRange(2, 2))
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+mod ceremony;
 mod decrypt_tally;
 mod decryption_shares;
 pub(crate) mod merge_results;
@@ -27,6 +28,9 @@ pub enum Tally {
     /// The tally data will be printed in json encoding on standard output. There order of the
     /// result is unspecified.
     MergeResults(merge_results::MergeVotePlan),
+    /// Tally a private vote plan out of share files signed by each committee
+    /// member, checking them before decrypting.
+    Ceremony(ceremony::Ceremony),
 }
 
 impl Tally {
@@ -36,6 +40,7 @@ impl Tally {
             Tally::DecryptResults(cmd) => cmd.exec(),
             Tally::MergeShares(cmd) => cmd.exec(),
             Tally::MergeResults(cmd) => cmd.exec(),
+            Tally::Ceremony(cmd) => cmd.exec(),
         }
     }
 }
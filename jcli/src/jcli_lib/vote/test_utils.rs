@@ -0,0 +1,82 @@
+use chain_impl_mockchain::vote::{EncryptedVote, ProofOfCorrectVote};
+use chain_vote::{
+    committee::{MemberCommunicationKey, MemberCommunicationPublicKey},
+    tally::EncryptedTally,
+    Ballot, Crs, ElectionPublicKey, MemberPublicKey, MemberState, TallyDecryptShare,
+};
+use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+
+/// number of options of the proposals voted in the tests
+pub const OPTIONS: usize = 2;
+
+/// a committee of two members, with a threshold of two, for the vote plan
+/// `[1u8; 32]`
+pub struct Committee {
+    pub rng: ChaChaRng,
+    pub crs: Crs,
+    pub communication_keys: Vec<MemberCommunicationKey>,
+    pub members: Vec<MemberState>,
+    pub member_keys: Vec<MemberPublicKey>,
+    pub election_key: ElectionPublicKey,
+}
+
+impl Committee {
+    pub fn new() -> Self {
+        let mut rng = ChaChaRng::from_seed([0; 32]);
+        let crs = Crs::from_hash(&[1u8; 32]);
+        let communication_keys: Vec<_> = (0..2)
+            .map(|_| MemberCommunicationKey::new(&mut rng))
+            .collect();
+        let communication_public_keys = public_keys(&communication_keys);
+        let members: Vec<_> = (0..2)
+            .map(|index| MemberState::new(&mut rng, 2, &crs, &communication_public_keys, index))
+            .collect();
+        let member_keys: Vec<_> = members.iter().map(MemberState::public_key).collect();
+        let election_key = ElectionPublicKey::from_participants(&member_keys);
+        Committee {
+            rng,
+            crs,
+            communication_keys,
+            members,
+            member_keys,
+            election_key,
+        }
+    }
+
+    /// an empty tally of a proposal, encrypted with the election key
+    pub fn encrypted_tally(&self) -> EncryptedTally {
+        EncryptedTally::new(OPTIONS, self.election_key.clone(), self.crs.clone())
+    }
+
+    /// the encrypted vote for `option` and its proof, with the ballot they
+    /// make
+    pub fn vote(&mut self, option: usize) -> (EncryptedVote, ProofOfCorrectVote, Ballot) {
+        let (encrypted_vote, proof) = chain_impl_mockchain::vote::encrypt_vote(
+            &mut self.rng,
+            &self.crs,
+            &self.election_key,
+            chain_vote::Vote::new(OPTIONS, option),
+        );
+        let ballot = Ballot::try_from_vote_and_proof(
+            encrypted_vote.as_inner().clone(),
+            proof.as_inner(),
+            &self.crs,
+            &self.election_key,
+        )
+        .unwrap();
+        (encrypted_vote, proof, ballot)
+    }
+
+    /// the decryption shares of `tally`, in the order of the members
+    pub fn decrypt_shares(&mut self, tally: &EncryptedTally) -> Vec<TallyDecryptShare> {
+        let rng = &mut self.rng;
+        self.members
+            .iter()
+            .map(|member| tally.partial_decrypt(rng, member.secret_key()))
+            .collect()
+    }
+}
+
+pub fn public_keys(keys: &[MemberCommunicationKey]) -> Vec<MemberCommunicationPublicKey> {
+    keys.iter().map(MemberCommunicationKey::to_public).collect()
+}
@@ -0,0 +1,209 @@
+use super::{
+    hd::{AccountKey, Path, Role},
+    mk_address, Error, WalletArgs,
+};
+use crate::jcli_lib::{
+    address::DiscriminationData,
+    rest::{
+        v0::{account::request_account_information, utxo::request_utxo},
+        RestArgs,
+    },
+    utils::{OutputFormat, UtxoPointer},
+};
+use chain_addr::Kind;
+use jormungandr_lib::interfaces::{UTxOInfo, Value};
+use serde::Serialize;
+use std::collections::HashMap;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Discover {
+    #[structopt(flatten)]
+    wallet: WalletArgs,
+
+    /// number of consecutive unused keys after which the scan of a role stops
+    #[structopt(long, default_value = "20")]
+    gap_limit: u32,
+
+    /// UTxO to check for ownership by the wallet, in the form
+    /// FRAGMENT_ID:OUTPUT_INDEX. The node cannot be queried for the UTxOs of
+    /// an address, so the candidates have to be given (e.g. from an explorer).
+    #[structopt(long = "utxo")]
+    utxos: Vec<UtxoPointer>,
+
+    #[structopt(flatten)]
+    discrimination_data: DiscriminationData,
+
+    #[structopt(flatten)]
+    args: RestArgs,
+
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct Discovery {
+    accounts: Vec<DiscoveredAccount>,
+    utxos: Vec<DiscoveredUtxo>,
+    total: Value,
+}
+
+#[derive(Serialize)]
+struct DiscoveredAccount {
+    path: String,
+    address: String,
+    value: Value,
+    counters: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct DiscoveredUtxo {
+    path: String,
+    address: String,
+    transaction_id: String,
+    index_in_transaction: u8,
+    value: Value,
+}
+
+impl Discover {
+    pub fn exec(self) -> Result<(), Error> {
+        let account = self.wallet.root_key()?.account(self.wallet.account)?;
+
+        let accounts = self.discover_accounts(&account)?;
+        let utxos = self.discover_utxos(&account)?;
+        let total = accounts
+            .iter()
+            .map(|account| account.value)
+            .chain(utxos.iter().map(|utxo| utxo.value))
+            .fold(Value::from(0), Value::saturating_add);
+
+        let discovery = Discovery {
+            accounts,
+            utxos,
+            total,
+        };
+        let formatted = self
+            .output_format
+            .format_json(serde_json::to_value(discovery)?)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+
+    /// query the account keys in order until `gap_limit` consecutive ones
+    /// are unknown to the node
+    fn discover_accounts(&self, account: &AccountKey) -> Result<Vec<DiscoveredAccount>, Error> {
+        let mut discovered = Vec::new();
+        let mut gap = 0;
+        let mut index = 0;
+        while gap < self.gap_limit {
+            let public_key = account.public_key(Role::Account, index)?;
+            match request_account_information(self.args.clone(), public_key.clone().into()) {
+                Ok(state) => {
+                    gap = 0;
+                    discovered.push(DiscoveredAccount {
+                        path: account.path(Role::Account, index).to_string(),
+                        address: mk_address(&self.discrimination_data, Kind::Account(public_key))
+                            .to_string(),
+                        value: *state.value(),
+                        counters: state.counters(),
+                    });
+                }
+                Err(e) if e.is_not_found() => gap += 1,
+                Err(e) => return Err(e.into()),
+            }
+            index += 1;
+        }
+        Ok(discovered)
+    }
+
+    /// match the given UTxOs against the UTxO and change keys of the wallet.
+    /// The keys are derived up to `gap_limit` after the last one found.
+    fn discover_utxos(&self, account: &AccountKey) -> Result<Vec<DiscoveredUtxo>, Error> {
+        let mut pending = Vec::new();
+        for pointer in &self.utxos {
+            match request_utxo(
+                self.args.clone(),
+                &pointer.fragment_id.to_string(),
+                pointer.output_index,
+            ) {
+                Ok(utxo) => pending.push(utxo),
+                // already spent, or not a UTxO of this blockchain
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let mut keys = DerivedKeys::new(account, self.gap_limit)?;
+        let mut discovered = Vec::new();
+        loop {
+            let mut found = Vec::new();
+            pending.retain(|utxo: &UTxOInfo| {
+                let spending_key = match utxo.address().1.kind() {
+                    Kind::Single(key) | Kind::Group(key, _) => key.clone(),
+                    _ => return true,
+                };
+                match keys.find(spending_key.as_ref()) {
+                    Some(path) => {
+                        found.push(path);
+                        discovered.push(DiscoveredUtxo {
+                            path: path.to_string(),
+                            address: utxo.address().to_string(),
+                            transaction_id: utxo.transaction_id().to_string(),
+                            index_in_transaction: utxo.index_in_transaction(),
+                            value: *utxo.associated_fund(),
+                        });
+                        false
+                    }
+                    None => true,
+                }
+            });
+            if found.is_empty() {
+                break;
+            }
+            for path in found {
+                keys.derive_up_to(
+                    path.role,
+                    path.index.saturating_add(1).saturating_add(self.gap_limit),
+                )?;
+            }
+        }
+        Ok(discovered)
+    }
+}
+
+/// The public keys of the UTxO and change roles derived so far, indexed by
+/// their bytes.
+struct DerivedKeys<'a> {
+    account: &'a AccountKey,
+    derived: HashMap<Role, u32>,
+    keys: HashMap<Vec<u8>, Path>,
+}
+
+impl<'a> DerivedKeys<'a> {
+    fn new(account: &'a AccountKey, gap_limit: u32) -> Result<Self, Error> {
+        let mut keys = DerivedKeys {
+            account,
+            derived: HashMap::new(),
+            keys: HashMap::new(),
+        };
+        keys.derive_up_to(Role::Utxo, gap_limit)?;
+        keys.derive_up_to(Role::Change, gap_limit)?;
+        Ok(keys)
+    }
+
+    fn find(&self, public_key: &[u8]) -> Option<Path> {
+        self.keys.get(public_key).copied()
+    }
+
+    fn derive_up_to(&mut self, role: Role, end: u32) -> Result<(), Error> {
+        let start = self.derived.get(&role).copied().unwrap_or(0);
+        for index in start..end {
+            let public_key = self.account.public_key(role, index)?;
+            self.keys
+                .insert(public_key.as_ref().to_vec(), self.account.path(role, index));
+        }
+        self.derived.insert(role, end.max(start));
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use super::Error;
+use bip39::Mnemonic;
+use chain_crypto::{Ed25519, Ed25519Extended, PublicKey, SecretKey};
+use cryptoxide::{hmac::Hmac, pbkdf2::pbkdf2, sha2::Sha512};
+use ed25519_bip32::{DerivationScheme, XPrv, XPRV_SIZE};
+use std::fmt::{self, Display};
+use structopt::clap::arg_enum;
+
+/// the purpose of the derivation path, as defined in BIP44
+const PURPOSE: u32 = 44;
+/// the coin type of the derivation path, as registered in SLIP-0044
+const COIN_TYPE: u32 = 1815;
+const HARDENED: u32 = 0x8000_0000;
+const PBKDF2_ITERATIONS: u32 = 4096;
+
+arg_enum! {
+    /// the role of a key in the wallet, it is the `chain` level of the
+    /// derivation path.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Role {
+        Utxo,
+        Change,
+        Account,
+    }
+}
+
+impl Role {
+    fn chain(self) -> u32 {
+        match self {
+            Role::Utxo => 0,
+            Role::Change => 1,
+            Role::Account => 2,
+        }
+    }
+}
+
+/// A derivation path of the wallet: `m/44'/1815'/account'/role/index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Path {
+    pub account: u32,
+    pub role: Role,
+    pub index: u32,
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "m/{}'/{}'/{}'/{}/{}",
+            PURPOSE,
+            COIN_TYPE,
+            self.account,
+            self.role.chain(),
+            self.index
+        )
+    }
+}
+
+/// The root key of the wallet, computed from the mnemonic entropy and the
+/// optional passphrase the same way the Icarus wallets do.
+pub struct RootKey(XPrv);
+
+/// The key at the `account'` level of the derivation path, from which every
+/// key of the account is derived without further hardening.
+pub struct AccountKey {
+    account: u32,
+    key: XPrv,
+}
+
+impl RootKey {
+    pub fn from_mnemonic(mnemonic: &Mnemonic, passphrase: &str) -> Self {
+        let mut mac = Hmac::new(Sha512::new(), passphrase.as_bytes());
+        let mut bytes = [0; XPRV_SIZE];
+        pbkdf2(
+            &mut mac,
+            &mnemonic.to_entropy(),
+            PBKDF2_ITERATIONS,
+            &mut bytes,
+        );
+
+        RootKey(XPrv::normalize_bytes_force3rd(bytes))
+    }
+
+    pub fn account(&self, account: u32) -> Result<AccountKey, Error> {
+        let key = self
+            .0
+            .derive(DerivationScheme::V2, HARDENED | PURPOSE)
+            .derive(DerivationScheme::V2, HARDENED | COIN_TYPE)
+            .derive(DerivationScheme::V2, HARDENED | check_index(account)?);
+        Ok(AccountKey { account, key })
+    }
+}
+
+impl AccountKey {
+    pub fn path(&self, role: Role, index: u32) -> Path {
+        Path {
+            account: self.account,
+            role,
+            index,
+        }
+    }
+
+    /// derive the signing key at the given role and index. The key is
+    /// returned as an extended ed25519 key, the chain code is not needed to
+    /// sign transactions.
+    pub fn secret_key(&self, role: Role, index: u32) -> Result<SecretKey<Ed25519Extended>, Error> {
+        let key = self
+            .key
+            .derive(DerivationScheme::V2, role.chain())
+            .derive(DerivationScheme::V2, check_index(index)?);
+        Ok(SecretKey::from_binary(&key.as_ref()[..64])
+            .expect("a derived key is always a valid extended secret key"))
+    }
+
+    pub fn public_key(&self, role: Role, index: u32) -> Result<PublicKey<Ed25519>, Error> {
+        self.secret_key(role, index).map(|key| key.to_public())
+    }
+}
+
+/// the account and key indices are given without the hardened bit, a value
+/// with this bit set would silently select another derivation path.
+fn check_index(index: u32) -> Result<u32, Error> {
+    if index < HARDENED {
+        Ok(index)
+    } else {
+        Err(Error::DerivationIndexInvalid { index })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_bip32::XPub;
+
+    // test vectors of CIP-3, Icarus master key generation
+    const ICARUS_MNEMONIC: &str =
+        "eight country switch draw meat scout mystery blade tip drift useless good keep usage title";
+
+    fn root_key(passphrase: &str) -> RootKey {
+        RootKey::from_mnemonic(&Mnemonic::parse(ICARUS_MNEMONIC).unwrap(), passphrase)
+    }
+
+    #[test]
+    fn mnemonic_entropy_vectors() {
+        // test vectors of the BIP39 reference implementation
+        let mnemonic = Mnemonic::from_entropy(&[0; 16]).unwrap();
+        assert_eq!(
+            mnemonic.to_string(),
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        );
+        let mnemonic = Mnemonic::from_entropy(&[0x7f; 16]).unwrap();
+        assert_eq!(
+            mnemonic.to_string(),
+            "legal winner thank year wave sausage worth useful legal winner thank yellow"
+        );
+        assert_eq!(mnemonic.to_entropy(), vec![0x7f; 16]);
+    }
+
+    #[test]
+    fn icarus_master_key_vectors() {
+        assert_eq!(
+            hex::encode(root_key("").0.as_ref()),
+            "c065afd2832cd8b087c4d9ab7011f481ee1e0721e78ea5dd609f3ab3f156d245\
+             d176bd8fd4ec60b4731c3918a2a72a0226c0cd119ec35b47e4d55884667f552a\
+             23f7fdcd4a10c6cd2c7393ac61d877873e248f417634aa3d812af327ffe9d620"
+        );
+        assert_eq!(
+            hex::encode(root_key("foo").0.as_ref()),
+            "70531039904019351e1afb361cd1b312a4d0565d4ff9f8062d38acf4b15cce41\
+             d7b5738d9c893feea55512a3004acb0d222c35d3e3d5cde943a15a9824cbac59\
+             443cf67e589614076ba01e354b1a432e0e6db3b59e37fc56b5fb0222970a010e"
+        );
+    }
+
+    #[test]
+    fn soft_derivation_matches_the_account_public_key() {
+        let account = root_key("").account(0).unwrap();
+        let account_public: XPub = account.key.public();
+
+        for role in [Role::Utxo, Role::Change, Role::Account] {
+            for index in [0, 1, 42] {
+                let derived = account_public
+                    .derive(DerivationScheme::V2, role.chain())
+                    .unwrap()
+                    .derive(DerivationScheme::V2, index)
+                    .unwrap();
+                assert_eq!(
+                    account.public_key(role, index).unwrap().as_ref(),
+                    derived.public_key_slice()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hardened_indices_are_rejected() {
+        let root = root_key("");
+        assert!(root.account(HARDENED - 1).is_ok());
+        assert!(matches!(
+            root.account(HARDENED),
+            Err(Error::DerivationIndexInvalid { index: HARDENED })
+        ));
+
+        let account = root.account(0).unwrap();
+        assert!(account.secret_key(Role::Utxo, HARDENED - 1).is_ok());
+        assert!(matches!(
+            account.public_key(Role::Utxo, u32::MAX),
+            Err(Error::DerivationIndexInvalid { index: u32::MAX })
+        ));
+    }
+}
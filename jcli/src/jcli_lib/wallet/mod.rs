@@ -0,0 +1,195 @@
+mod discover;
+mod hd;
+
+use crate::jcli_lib::{
+    address::DiscriminationData,
+    rest,
+    utils::{output_file, output_format},
+};
+use bip39::Mnemonic;
+use chain_addr::{AddressReadable, Kind};
+use chain_crypto::bech32::Bech32 as _;
+use rand::{rngs::OsRng, RngCore};
+use std::{io::Write, path::PathBuf};
+use structopt::StructOpt;
+use thiserror::Error;
+
+use self::hd::{Role, RootKey};
+
+/// the number of words a BIP39 mnemonic can have
+const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("error while using random source")]
+    Rand(#[from] rand::Error),
+    #[error(transparent)]
+    InvalidOutput(#[from] output_file::Error),
+    #[error("invalid number of words {0}, expected one of 12, 15, 18, 21 or 24")]
+    InvalidWordCount(usize),
+    #[error("invalid mnemonic phrase")]
+    InvalidMnemonic(#[from] bip39::Error),
+    #[error("could not read mnemonic file '{path}'")]
+    MnemonicReadFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    Rest(#[from] rest::Error),
+    #[error("formatting output failed")]
+    OutputFormatFailed(#[from] output_format::Error),
+    #[error("could not serialize the discovered addresses")]
+    Serialization(#[from] serde_json::Error),
+    #[error("invalid derivation index {index}, it should be lower than 2^31")]
+    DerivationIndexInvalid { index: u32 },
+}
+
+/// Hierarchical deterministic wallet tooling: BIP39 mnemonics and
+/// BIP44 derivation of the account and UTxO keys
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Wallet {
+    /// generate a new mnemonic phrase
+    New(New),
+    /// derive a key, or its address, from a mnemonic phrase
+    Derive(Derive),
+    /// scan a node for the used addresses of the wallet and their balance
+    Discover(discover::Discover),
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct New {
+    /// number of words of the mnemonic phrase: 12, 15, 18, 21 or 24
+    #[structopt(long, default_value = "24")]
+    words: usize,
+
+    #[structopt(flatten)]
+    output_file: output_file::OutputFile,
+}
+
+/// The mnemonic phrase and passphrase the wallet keys are derived from
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct WalletArgs {
+    /// file with the mnemonic phrase. If omitted, it will be prompted for
+    #[structopt(long)]
+    mnemonic: Option<PathBuf>,
+
+    /// prompt for the optional BIP39 passphrase protecting the wallet
+    #[structopt(long)]
+    passphrase: bool,
+
+    /// index of the account of the wallet
+    #[structopt(long, default_value = "0")]
+    account: u32,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Derive {
+    #[structopt(flatten)]
+    wallet: WalletArgs,
+
+    /// role of the key: utxo, change or account
+    #[structopt(long, default_value = "account")]
+    role: Role,
+
+    /// index of the key in its role
+    #[structopt(long, default_value = "0")]
+    index: u32,
+
+    /// output the public key instead of the secret key
+    #[structopt(long, conflicts_with = "address")]
+    public: bool,
+
+    /// output the address of the key instead of the secret key
+    #[structopt(long)]
+    address: bool,
+
+    #[structopt(flatten)]
+    discrimination_data: DiscriminationData,
+
+    #[structopt(flatten)]
+    output_file: output_file::OutputFile,
+}
+
+impl Wallet {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Wallet::New(args) => args.exec(),
+            Wallet::Derive(args) => args.exec(),
+            Wallet::Discover(args) => args.exec(),
+        }
+    }
+}
+
+impl New {
+    fn exec(self) -> Result<(), Error> {
+        if !MNEMONIC_WORD_COUNTS.contains(&self.words) {
+            return Err(Error::InvalidWordCount(self.words));
+        }
+        // every 3 words encode 32 bits of entropy
+        let mut entropy = vec![0; self.words / 3 * 4];
+        OsRng.try_fill_bytes(&mut entropy)?;
+        let mnemonic = Mnemonic::from_entropy(&entropy)?;
+
+        let mut output = self.output_file.open()?;
+        writeln!(output, "{}", mnemonic)?;
+        Ok(())
+    }
+}
+
+impl WalletArgs {
+    fn root_key(&self) -> Result<RootKey, Error> {
+        let phrase = match &self.mnemonic {
+            Some(path) => {
+                std::fs::read_to_string(path).map_err(|source| Error::MnemonicReadFailed {
+                    source,
+                    path: path.clone(),
+                })?
+            }
+            None => rpassword::prompt_password("Introduce the mnemonic phrase:\n")?,
+        };
+        let mnemonic = Mnemonic::parse(phrase.trim())?;
+
+        let passphrase = if self.passphrase {
+            rpassword::prompt_password("Introduce the passphrase:\n")?
+        } else {
+            String::new()
+        };
+
+        Ok(RootKey::from_mnemonic(&mnemonic, &passphrase))
+    }
+}
+
+impl Derive {
+    fn exec(self) -> Result<(), Error> {
+        let account = self.wallet.root_key()?.account(self.wallet.account)?;
+
+        let output_str = if self.address {
+            let public_key = account.public_key(self.role, self.index)?;
+            let kind = match self.role {
+                Role::Account => Kind::Account(public_key),
+                Role::Utxo | Role::Change => Kind::Single(public_key),
+            };
+            mk_address(&self.discrimination_data, kind).to_string()
+        } else if self.public {
+            account.public_key(self.role, self.index)?.to_bech32_str()
+        } else {
+            account.secret_key(self.role, self.index)?.to_bech32_str()
+        };
+
+        let mut output = self.output_file.open()?;
+        writeln!(output, "{}", output_str)?;
+        Ok(())
+    }
+}
+
+fn mk_address(discrimination_data: &DiscriminationData, kind: Kind) -> AddressReadable {
+    let address = chain_addr::Address(discrimination_data.discrimination(), kind);
+    AddressReadable::from_address(discrimination_data.prefix(), &address)
+}
@@ -0,0 +1,221 @@
+//! passphrase encrypted container for the secrets kept on disk
+//!
+//! The secret is encrypted with ChaCha20-Poly1305, with a key derived from
+//! the passphrase with PBKDF2-HMAC-SHA512. The parameters needed to derive
+//! the key again are stored in clear next to the ciphertext so they can be
+//! strengthened without breaking the existing containers.
+//!
+
+use cryptoxide::{chacha20poly1305::ChaCha20Poly1305, hmac::Hmac, pbkdf2::pbkdf2, sha2::Sha512};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// the environment variable the passphrase is read from by jcli and the node
+/// if no other source is given
+pub const PASSPHRASE_ENV_VAR: &str = "JORMUNGANDR_SECRET_PASSPHRASE";
+
+const FORMAT_VERSION: u32 = 1;
+/// authenticated with the ciphertext so a container cannot be mistaken for
+/// another kind of encrypted data
+const ADDITIONAL_DATA: &[u8] = b"jormungandr encrypted secret v1";
+pub const DEFAULT_ITERATIONS: u32 = 100_000;
+/// fewer iterations make the passphrase too cheap to brute force, and PBKDF2
+/// is not defined for 0 iterations
+pub const MIN_ITERATIONS: u32 = 10_000;
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const KEY_SIZE: usize = 32;
+const TAG_SIZE: usize = 16;
+
+/// a secret encrypted with a passphrase, serialized in a human readable
+/// format next to the parameters required to decrypt it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptedSecret {
+    version: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+    tag: String,
+}
+
+#[derive(Debug, Error)]
+pub enum EncryptedSecretError {
+    #[error("unsupported encrypted secret format version {0}")]
+    UnsupportedVersion(u32),
+    #[error("invalid hexadecimal encoding of the {field}")]
+    Hex {
+        field: &'static str,
+        #[source]
+        source: hex::FromHexError,
+    },
+    #[error("invalid length of the {0}")]
+    InvalidLength(&'static str),
+    #[error(
+        "{0} key derivation iterations, at least {} are required",
+        MIN_ITERATIONS
+    )]
+    TooFewIterations(u32),
+    #[error("cannot decrypt the secret, the passphrase is wrong or the file is corrupted")]
+    Decryption,
+}
+
+impl EncryptedSecret {
+    /// encrypt the `secret` with a key derived from the `passphrase`
+    pub fn encrypt<R>(
+        rng: &mut R,
+        passphrase: &[u8],
+        iterations: u32,
+        secret: &[u8],
+    ) -> Result<Self, EncryptedSecretError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        check_iterations(iterations)?;
+        let mut salt = [0; SALT_SIZE];
+        let mut nonce = [0; NONCE_SIZE];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce);
+
+        let key = derive_key(passphrase, &salt, iterations);
+        let mut ciphertext = vec![0; secret.len()];
+        let mut tag = [0; TAG_SIZE];
+        ChaCha20Poly1305::new(&key[..], &nonce, ADDITIONAL_DATA).encrypt(
+            secret,
+            &mut ciphertext,
+            &mut tag,
+        );
+
+        Ok(EncryptedSecret {
+            version: FORMAT_VERSION,
+            iterations,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+            tag: hex::encode(tag),
+        })
+    }
+
+    /// number of PBKDF2 iterations deriving the key, to encrypt an updated
+    /// secret with the same strength
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// decrypt the secret, the returned buffer is zeroed when dropped
+    pub fn decrypt(&self, passphrase: &[u8]) -> Result<Zeroizing<Vec<u8>>, EncryptedSecretError> {
+        if self.version != FORMAT_VERSION {
+            return Err(EncryptedSecretError::UnsupportedVersion(self.version));
+        }
+        check_iterations(self.iterations)?;
+        let salt = decode_hex("salt", &self.salt)?;
+        let nonce = decode_hex("nonce", &self.nonce)?;
+        let ciphertext = decode_hex("ciphertext", &self.ciphertext)?;
+        let tag = decode_hex("tag", &self.tag)?;
+        if nonce.len() != NONCE_SIZE {
+            return Err(EncryptedSecretError::InvalidLength("nonce"));
+        }
+        if tag.len() != TAG_SIZE {
+            return Err(EncryptedSecretError::InvalidLength("tag"));
+        }
+
+        let key = derive_key(passphrase, &salt, self.iterations);
+        let mut secret = Zeroizing::new(vec![0; ciphertext.len()]);
+        if ChaCha20Poly1305::new(&key[..], &nonce, ADDITIONAL_DATA).decrypt(
+            &ciphertext,
+            &mut secret[..],
+            &tag,
+        ) {
+            Ok(secret)
+        } else {
+            Err(EncryptedSecretError::Decryption)
+        }
+    }
+}
+
+fn check_iterations(iterations: u32) -> Result<(), EncryptedSecretError> {
+    if iterations < MIN_ITERATIONS {
+        return Err(EncryptedSecretError::TooFewIterations(iterations));
+    }
+    Ok(())
+}
+
+fn derive_key(passphrase: &[u8], salt: &[u8], iterations: u32) -> Zeroizing<[u8; KEY_SIZE]> {
+    let mut mac = Hmac::new(Sha512::new(), passphrase);
+    let mut key = Zeroizing::new([0; KEY_SIZE]);
+    pbkdf2(&mut mac, salt, iterations, &mut key[..]);
+    key
+}
+
+fn decode_hex(field: &'static str, value: &str) -> Result<Vec<u8>, EncryptedSecretError> {
+    hex::decode(value).map_err(|source| EncryptedSecretError::Hex { field, source })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+
+    #[test]
+    fn decrypt_with_the_same_passphrase() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let secret = b"bft:\n  signing_key: ed25519_sk1...";
+        let encrypted =
+            EncryptedSecret::encrypt(&mut rng, b"passphrase", MIN_ITERATIONS, secret).unwrap();
+
+        let serialized = serde_yaml::to_string(&encrypted).unwrap();
+        let encrypted: EncryptedSecret = serde_yaml::from_str(&serialized).unwrap();
+
+        let decrypted = encrypted.decrypt(b"passphrase").unwrap();
+        assert_eq!(decrypted.as_slice(), secret.as_ref());
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let encrypted =
+            EncryptedSecret::encrypt(&mut rng, b"passphrase", MIN_ITERATIONS, b"secret").unwrap();
+
+        assert!(matches!(
+            encrypted.decrypt(b"wrong passphrase"),
+            Err(EncryptedSecretError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let mut encrypted =
+            EncryptedSecret::encrypt(&mut rng, b"passphrase", MIN_ITERATIONS, b"secret").unwrap();
+        let mut ciphertext = hex::decode(&encrypted.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        encrypted.ciphertext = hex::encode(ciphertext);
+
+        assert!(matches!(
+            encrypted.decrypt(b"passphrase"),
+            Err(EncryptedSecretError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn too_few_iterations_are_rejected() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        for iterations in [0, MIN_ITERATIONS - 1] {
+            assert!(matches!(
+                EncryptedSecret::encrypt(&mut rng, b"passphrase", iterations, b"secret"),
+                Err(EncryptedSecretError::TooFewIterations(n)) if n == iterations
+            ));
+        }
+
+        let mut encrypted =
+            EncryptedSecret::encrypt(&mut rng, b"passphrase", MIN_ITERATIONS, b"secret").unwrap();
+        encrypted.iterations = 0;
+        assert!(matches!(
+            encrypted.decrypt(b"passphrase"),
+            Err(EncryptedSecretError::TooFewIterations(0))
+        ));
+    }
+}
@@ -24,8 +24,14 @@
 //! It provides the same interfaces as for the identifier in the
 //! `key` module but limited to Account only.
 //!
+//! # Encrypted secrets
+//!
+//! A passphrase encrypted container for the secrets kept on disk,
+//! like the node's leader keys.
+//!
 
 pub mod account;
+pub mod encrypted;
 pub mod hash;
 pub mod key;
 pub(crate) mod serde;
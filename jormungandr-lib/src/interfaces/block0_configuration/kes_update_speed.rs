@@ -72,6 +72,12 @@ impl From<KesUpdateSpeed> for ConfigParam {
     }
 }
 
+impl From<KesUpdateSpeed> for u32 {
+    fn from(kes_update_speed: KesUpdateSpeed) -> u32 {
+        kes_update_speed.0
+    }
+}
+
 impl fmt::Display for KesUpdateSpeed {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Duration::new(self.0 as u64, 0).fmt(f)
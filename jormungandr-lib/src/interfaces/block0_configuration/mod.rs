@@ -158,6 +158,14 @@ pub fn block0_configuration_documented_example() -> String {
     )
 }
 
+/// the block0 made of the documented example of the genesis file
+pub fn block0_configuration_documented_example_block() -> Block {
+    let block0_configuration: Block0Configuration =
+        serde_yaml::from_str(&block0_configuration_documented_example())
+            .expect("the documented example is a valid block0 configuration");
+    block0_configuration.to_block()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -9,6 +9,8 @@ pub struct LogEntry {
     pub format: String,
     pub level: String,
     pub output: LogOutput,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_collector_endpoint: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,7 +6,8 @@ mod secret;
 pub use log::{Log, LogEntry, LogOutput};
 pub use mempool::{LogMaxEntries, Mempool, PersistentLog, PoolMaxEntries};
 pub use node::{
-    Cors, CorsOrigin, JRpc, LayersConfig, NodeConfig, NodeId, P2p, Policy, PreferredListConfig,
-    Rest, Tls, TopicsOfInterest, TrustedPeer,
+    Cors, CorsOrigin, Health, JRpc, LayersConfig, NodeConfig, NodeId, P2p, Policy,
+    PreferredListConfig, RateLimit, RateLimits, RateLimitsConfig, RequestLimits, Rest, RestAuth,
+    RestCredential, RestRole, Tls, TopicsOfInterest, TrustedPeer,
 };
 pub use secret::{Bft, GenesisPraos, NodeSecret};
@@ -20,11 +20,61 @@ pub struct Rest {
     /// Enables CORS if provided
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cors: Option<Cors>,
+    /// Thresholds of the readiness probe, the defaults are used if not provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Health>,
+    /// Requires the clients to authenticate if provided, all the endpoints
+    /// are open to anyone otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<RestAuth>,
+    /// Limits on the rate and the size of the requests, nothing is limited
+    /// if not provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<RequestLimits>,
+    /// Maximum number of fragments in a batch posted to `v1/fragments`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fragments_per_batch: Option<usize>,
+}
+
+/// thresholds of the readiness probe served at `/health/ready`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Health {
+    /// the node is not ready if the slot of its tip started longer ago,
+    /// defaults to `no_blockchain_updates_warning_interval`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tip_age: Option<Duration>,
+    /// the node is not ready with fewer connected peers, defaults to 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_connected_peers: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct JRpc {
     pub listen: SocketAddr,
+    /// Limits on the rate and the size of the requests, nothing is limited
+    /// if not provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<RequestLimits>,
+}
+
+/// limits on the requests served by the REST API or the JSON-RPC server,
+/// the requests over the rate limits are answered with 429 and the ones with
+/// a body too large with 413
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RequestLimits {
+    /// requests per second accepted from a single IP address, bursts of up
+    /// to one second worth of requests are allowed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_ip_requests_per_second: Option<u32>,
+    /// requests per second accepted from all the clients together
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_requests_per_second: Option<u32>,
+    /// maximum size of a request body in bytes, 10 MiB if not set. The
+    /// requests whose body length is not announced are rejected if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_body_size: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -34,6 +84,44 @@ pub struct Tls {
     pub cert_file: String,
     /// Path to server private key file, must be PKCS8 with single PEM-encoded, unencrypted key
     pub priv_key_file: String,
+    /// Path to the PEM-encoded certificates of the CAs trusted to sign client certificates.
+    /// If provided, clients must present a valid certificate to connect
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ca_file: Option<String>,
+}
+
+/// authentication and authorization of the REST API clients
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RestAuth {
+    /// Path to a YAML file listing the accepted bearer tokens and API keys,
+    /// as a sequence of `RestCredential`
+    pub credentials_file: PathBuf,
+    /// Role granted to the requests without credentials, they are rejected if not provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anonymous_role: Option<RestRole>,
+}
+
+/// a token accepted by the REST API, sent either as a bearer token in the
+/// `Authorization` header or as an API key in the `X-Api-Key` header
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RestCredential {
+    pub token: String,
+    pub role: RestRole,
+}
+
+/// the endpoints a REST API client has access to, each role including the
+/// ones before it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RestRole {
+    /// the endpoints reading the node and blockchain state
+    ReadOnly,
+    /// the fragment submission endpoints
+    Submit,
+    /// the endpoints controlling the node: shutdown, peer management and diagnostics
+    Admin,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -224,6 +312,42 @@ pub struct P2p {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network_stuck_check: Option<Duration>,
+
+    /// limits on the rate of fragments and block announcements the peers
+    /// may send to the node, there are no limits if not provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limits: Option<RateLimitsConfig>,
+}
+
+/// Limit on the rate of items received. Bursts of up to one second worth
+/// of traffic are allowed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items_per_second: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_per_second: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimits {
+    #[serde(default)]
+    pub fragments: RateLimit,
+    #[serde(default)]
+    pub block_announcements: RateLimit,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitsConfig {
+    /// limits applied to every peer
+    #[serde(default)]
+    pub per_peer: RateLimits,
+    /// limits applied to all the peers together
+    #[serde(default)]
+    pub total: RateLimits,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
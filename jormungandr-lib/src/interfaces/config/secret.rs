@@ -23,4 +23,7 @@ pub struct GenesisPraos {
     pub node_id: Hash,
     pub sig_key: SigningKey<SumEd25519_12>,
     pub vrf_key: SigningKey<RistrettoGroup2HashDh>,
+    /// the period `sig_key` has been evolved to, kept up to date by the node
+    #[serde(default)]
+    pub kes_period: u32,
 }
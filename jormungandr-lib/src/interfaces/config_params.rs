@@ -89,6 +89,18 @@ impl From<ConfigParams> for ConfigParamsLib {
     }
 }
 
+impl TryFrom<ConfigParamsLib> for ConfigParams {
+    type Error = FromConfigParamError;
+    fn try_from(config: ConfigParamsLib) -> Result<Self, Self::Error> {
+        config
+            .iter()
+            .cloned()
+            .map(ConfigParam::try_from)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
 impl From<ConfigParam> for ConfigParamLib {
     fn from(config: ConfigParam) -> Self {
         match config {
@@ -228,6 +240,17 @@ mod test {
         let _: ConfigParams = serde_yaml::from_str(&config_params_documented_example()).unwrap();
     }
 
+    #[test]
+    fn config_params_convert_back() {
+        let config = ConfigParams::new(vec![
+            ConfigParam::BlockContentMaxSize(1000.into()),
+            ConfigParam::ProposalExpiration(5.into()),
+            ConfigParam::TransactionMaxExpiryEpochs(2),
+        ]);
+        let lib = ConfigParamsLib::from(config.clone());
+        assert_eq!(ConfigParams::try_from(lib).unwrap(), config);
+    }
+
     quickcheck! {
         fn serde_encode_decode(config: ConfigParam) -> bool {
             let s = serde_yaml::to_string(&config).unwrap();
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+/// the outcome of a liveness or readiness probe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// whether all the checks passed
+    pub healthy: bool,
+    pub checks: Vec<HealthCheck>,
+}
+
+/// a condition verified by a probe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub name: HealthCheckName,
+    pub passed: bool,
+    /// what was found, to explain a failure
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCheckName {
+    /// the blockchain tip can be read
+    Tip,
+    /// the node has completed its bootstrap and runs all its services
+    Bootstrap,
+    /// the slot of the tip started recently enough
+    TipFreshness,
+    /// enough peers are connected
+    Peers,
+    /// files can be written in the storage directory
+    Storage,
+}
+
+impl HealthReport {
+    pub fn new(checks: Vec<HealthCheck>) -> Self {
+        HealthReport {
+            healthy: checks.iter().all(|check| check.passed),
+            checks,
+        }
+    }
+
+    /// the checks that did not pass
+    pub fn failures(&self) -> impl Iterator<Item = &HealthCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+}
+
+impl HealthCheck {
+    pub fn passed(name: HealthCheckName, message: impl Into<String>) -> Self {
+        HealthCheck {
+            name,
+            passed: true,
+            message: message.into(),
+        }
+    }
+
+    pub fn failed(name: HealthCheckName, message: impl Into<String>) -> Self {
+        HealthCheck {
+            name,
+            passed: false,
+            message: message.into(),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+
+/// the evolution state of the KES signing key of the node's Genesis Praos
+/// leader
+///
+/// The key evolves at a fixed pace from the start of the blockchain and can
+/// only sign for a limited number of periods, the node cannot produce
+/// blocks anymore once the key is expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KesKeyStatus {
+    /// the period the key has been evolved to
+    period: u32,
+    /// the number of periods the key can sign for
+    max_periods: u32,
+    /// when the key will be evolved to the next period
+    period_end: SystemTime,
+    /// when the key cannot be used anymore
+    expiry: SystemTime,
+    /// the time left until the expiry of the key
+    remaining_lifetime: Duration,
+}
+
+impl KesKeyStatus {
+    pub fn new(
+        period: u32,
+        max_periods: u32,
+        period_end: SystemTime,
+        expiry: SystemTime,
+        remaining_lifetime: Duration,
+    ) -> Self {
+        KesKeyStatus {
+            period,
+            max_periods,
+            period_end,
+            expiry,
+            remaining_lifetime,
+        }
+    }
+
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+
+    pub fn max_periods(&self) -> u32 {
+        self.max_periods
+    }
+
+    pub fn period_end(&self) -> &SystemTime {
+        &self.period_end
+    }
+
+    pub fn expiry(&self) -> &SystemTime {
+        &self.expiry
+    }
+
+    pub fn remaining_lifetime(&self) -> &Duration {
+        &self.remaining_lifetime
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{crypto::hash::Hash, interfaces::BlockDate, time::SystemTime};
+use crate::{
+    crypto::hash::Hash,
+    interfaces::BlockDate,
+    time::{Duration, SystemTime},
+};
 use serde::{Deserialize, Serialize};
 
 /// log identifier in the leadership log. Can be used to update
@@ -20,6 +24,26 @@ pub enum LeadershipLogStatus {
     },
 }
 
+/// measures taken while assembling the contents of a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockAssembly {
+    /// time spent from the start of the block preparation to the signed block
+    pub total_time: Duration,
+    /// time spent selecting the fragments from the mempool
+    pub fragment_selection_time: Duration,
+    pub fragment_count: usize,
+    pub content_size: u32,
+}
+
+/// a candidate block assembled on the tip but neither signed nor published
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockDryRun {
+    pub date: BlockDate,
+    pub parent: Hash,
+    pub chain_length: u32,
+    pub assembly: BlockAssembly,
+}
+
 /// provides information regarding events in the leadership schedule
 ///
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +53,12 @@ pub struct LeadershipLog {
     scheduled_at_date: BlockDate,
     wake_at_time: Option<SystemTime>,
     finished_at_time: Option<SystemTime>,
+    #[serde(default)]
+    deadline: Option<SystemTime>,
+    #[serde(default)]
+    hard_deadline: Option<SystemTime>,
+    #[serde(default)]
+    block_assembly: Option<BlockAssembly>,
     status: LeadershipLogStatus,
 }
 
@@ -40,6 +70,9 @@ impl LeadershipLog {
             scheduled_at_date,
             wake_at_time: None,
             finished_at_time: None,
+            deadline: None,
+            hard_deadline: None,
+            block_assembly: None,
             status: LeadershipLogStatus::Pending,
         }
     }
@@ -64,6 +97,17 @@ impl LeadershipLog {
     pub fn finished_at_time(&self) -> &Option<SystemTime> {
         &self.finished_at_time
     }
+    /// the time by which the block should have been built, the end of the slot
+    pub fn deadline(&self) -> &Option<SystemTime> {
+        &self.deadline
+    }
+    /// the time after which the fragment selection is abandoned
+    pub fn hard_deadline(&self) -> &Option<SystemTime> {
+        &self.hard_deadline
+    }
+    pub fn block_assembly(&self) -> &Option<BlockAssembly> {
+        &self.block_assembly
+    }
     pub fn status(&self) -> &LeadershipLogStatus {
         &self.status
     }
@@ -97,6 +141,17 @@ impl LeadershipLog {
         self.finished_at_time = Some(SystemTime::now())
     }
 
+    /// set the deadlines the leadership event has to build its block in.
+    pub fn set_deadlines(&mut self, deadline: SystemTime, hard_deadline: SystemTime) {
+        self.deadline = Some(deadline);
+        self.hard_deadline = Some(hard_deadline);
+    }
+
+    /// record how the block of the leadership event was assembled.
+    pub fn set_block_assembly(&mut self, block_assembly: BlockAssembly) {
+        self.block_assembly = Some(block_assembly)
+    }
+
     /// set the leadership log status.
     pub fn set_status(&mut self, status: LeadershipLogStatus) {
         self.status = status
@@ -0,0 +1,50 @@
+use crate::{interfaces::BlockDate, time::SystemTime};
+use serde::{Deserialize, Serialize};
+
+/// the slots of an epoch where the node's leader is elected
+///
+/// Unlike the leadership logs, the schedule is computed on request for the
+/// whole epoch, including the slots already past.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeadershipSchedule {
+    epoch: u32,
+    slots: Vec<LeadershipScheduleSlot>,
+}
+
+/// a slot of the leadership schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LeadershipScheduleSlot {
+    scheduled_at_date: BlockDate,
+    scheduled_at_time: SystemTime,
+}
+
+impl LeadershipSchedule {
+    pub fn new(epoch: u32, slots: Vec<LeadershipScheduleSlot>) -> Self {
+        LeadershipSchedule { epoch, slots }
+    }
+
+    pub fn epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    pub fn slots(&self) -> &[LeadershipScheduleSlot] {
+        &self.slots
+    }
+}
+
+impl LeadershipScheduleSlot {
+    pub fn new(scheduled_at_date: BlockDate, scheduled_at_time: SystemTime) -> Self {
+        LeadershipScheduleSlot {
+            scheduled_at_date,
+            scheduled_at_time,
+        }
+    }
+
+    pub fn scheduled_at_date(&self) -> &BlockDate {
+        &self.scheduled_at_date
+    }
+
+    pub fn scheduled_at_time(&self) -> &SystemTime {
+        &self.scheduled_at_time
+    }
+}
@@ -150,6 +150,14 @@ impl From<MintToken> for certificate::MintToken {
     }
 }
 
+/// Circulating supply of a token, summed over all the accounts holding it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenSupply {
+    pub token_id: TokenIdentifier,
+    pub supply: Value,
+    pub holders: usize,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
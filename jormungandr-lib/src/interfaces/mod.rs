@@ -15,10 +15,14 @@ mod fragment_log;
 mod fragment_log_persistent;
 mod fragments_batch;
 mod fragments_processing_summary;
+mod health;
+mod kes_key;
 mod leadership_log;
+mod leadership_schedule;
 mod linear_fee;
 mod mint_token;
 mod old_address;
+mod peer_rules;
 mod peer_stats;
 mod ratio;
 mod reward_parameters;
@@ -32,6 +36,7 @@ mod tax_type;
 mod transaction_input;
 mod transaction_output;
 mod transaction_witness;
+mod update_proposal;
 mod utxo_info;
 mod value;
 mod vote;
@@ -48,7 +53,9 @@ pub use self::{
     },
     committee::CommitteeIdDef,
     config::*,
-    config_params::{config_params_documented_example, ConfigParam, ConfigParams},
+    config_params::{
+        config_params_documented_example, ConfigParam, ConfigParams, FromConfigParamError,
+    },
     evm_transaction::EvmTransaction,
     fragment::FragmentDef,
     fragment_log::{FragmentLog, FragmentOrigin, FragmentStatus},
@@ -61,11 +68,20 @@ pub use self::{
     fragments_processing_summary::{
         FragmentRejectionReason, FragmentsProcessingSummary, RejectedFragmentInfo,
     },
-    leadership_log::{LeadershipLog, LeadershipLogId, LeadershipLogStatus},
+    health::{HealthCheck, HealthCheckName, HealthReport},
+    kes_key::KesKeyStatus,
+    leadership_log::{
+        BlockAssembly, BlockDryRun, LeadershipLog, LeadershipLogId, LeadershipLogStatus,
+    },
+    leadership_schedule::{LeadershipSchedule, LeadershipScheduleSlot},
     linear_fee::{LinearFeeDef, PerCertificateFeeDef, PerVoteCertificateFeeDef},
-    mint_token::TokenIdentifier,
+    mint_token::{TokenIdentifier, TokenSupply},
     old_address::OldAddress,
-    peer_stats::{PeerRecord, PeerStats, Subscription},
+    peer_rules::{BanRequest, ConnectRequest, PeerBan, PeerTarget, PeerTargetFromStrError},
+    peer_stats::{
+        PeerRecord, PeerReport, PeerReportReason, PeerReputation, PeerStats, PeerTraffic,
+        Subscription, TrafficStats,
+    },
     ratio::{ParseRatioError, Ratio},
     reward_parameters::RewardParams,
     rewards_info::EpochRewardsInfo,
@@ -78,6 +94,7 @@ pub use self::{
     transaction_input::{TransactionInput, TransactionInputType},
     transaction_output::TransactionOutput,
     transaction_witness::TransactionWitness,
+    update_proposal::{UpdateProposalPhase, UpdateProposalStatus},
     utxo_info::{UTxOInfo, UTxOOutputInfo},
     value::{Value, ValueDef},
     vote::{
@@ -0,0 +1,114 @@
+use crate::{
+    interfaces::NodeId,
+    time::{Duration, SystemTime},
+};
+use multiaddr::Multiaddr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, net::IpAddr, str::FromStr};
+use thiserror::Error;
+
+/// A peer designated by its node id, or all the peers connecting from an
+/// IP address.
+///
+/// The node id is hex-encoded, as shown by the network endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerTarget {
+    Id(NodeId),
+    Ip(IpAddr),
+}
+
+#[derive(Debug, Error)]
+#[error("'{0}' is neither a hex-encoded node id nor an IP address")]
+pub struct PeerTargetFromStrError(String);
+
+impl FromStr for PeerTarget {
+    type Err = PeerTargetFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(ip) = s.parse() {
+            return Ok(PeerTarget::Ip(ip));
+        }
+        NodeId::from_hex(s)
+            .map(PeerTarget::Id)
+            .map_err(|_| PeerTargetFromStrError(s.to_owned()))
+    }
+}
+
+impl fmt::Display for PeerTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PeerTarget::Id(id) => id.to_hex().fmt(f),
+            PeerTarget::Ip(ip) => ip.fmt(f),
+        }
+    }
+}
+
+impl Serialize for PeerTarget {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PeerTarget {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A ban set by the node operator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct PeerBan {
+    pub target: PeerTarget,
+    pub since: SystemTime,
+    /// the ban is permanent if not set
+    #[serde(default)]
+    pub until: Option<SystemTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct BanRequest {
+    pub target: PeerTarget,
+    /// the ban is permanent if not set
+    #[serde(default)]
+    pub duration: Option<Duration>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct ConnectRequest {
+    /// the address of the peer, with a TCP component
+    pub address: Multiaddr,
+    /// the hex-encoded node id of the peer, checked during the handshake
+    pub id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peer_target_from_str() {
+        assert_eq!(
+            "127.0.0.1".parse::<PeerTarget>().unwrap(),
+            PeerTarget::Ip([127, 0, 0, 1].into())
+        );
+        let id = "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20";
+        let target: PeerTarget = id.parse().unwrap();
+        assert!(matches!(target, PeerTarget::Id(_)));
+        assert_eq!(target.to_string(), id);
+        assert!("127.0.0.1:3000".parse::<PeerTarget>().is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::time::SystemTime;
+use crate::time::{Duration, SystemTime};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
@@ -10,6 +10,29 @@ pub struct PeerStats {
     pub last_block_received: Option<SystemTime>,
     pub last_fragment_received: Option<SystemTime>,
     pub last_gossip_received: Option<SystemTime>,
+    #[serde(default)]
+    pub traffic: PeerTraffic,
+}
+
+/// The traffic exchanged with a peer over the subscriptions, per type of item
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct PeerTraffic {
+    pub blocks: TrafficStats,
+    pub headers: TrafficStats,
+    pub fragments: TrafficStats,
+    pub gossip: TrafficStats,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct TrafficStats {
+    pub bytes_received: u64,
+    pub items_received: u64,
+    pub bytes_sent: u64,
+    pub items_sent: u64,
+    /// items received over the rate limits, which were not processed
+    pub items_dropped: u64,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -20,6 +43,8 @@ pub struct PeerRecord {
     pub last_update: SystemTime,
     pub quarantined: Option<SystemTime>,
     pub subscriptions: Vec<Subscription>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reputation: Option<PeerReputation>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -28,3 +53,33 @@ pub struct Subscription {
     pub interest: u32,
     pub topic: String,
 }
+
+/// The misbehaviours a peer can be reported for
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerReportReason {
+    InvalidBlock,
+    InvalidFragment,
+    HandshakeFailed,
+    ConnectionFailed,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerReport {
+    pub time: SystemTime,
+    pub reason: PeerReportReason,
+}
+
+/// The reputation of a peer, as scored by the node from its behaviour
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerReputation {
+    pub score: i64,
+    /// number of reports since the peer last made up for its misbehaviour
+    pub offences: u32,
+    pub quarantine_duration: Option<Duration>,
+    /// the latest reports against the peer, oldest first
+    pub reports: Vec<PeerReport>,
+}
@@ -34,6 +34,18 @@ pub struct NodeStats {
     pub tx_rejected_cnt: u64,
     pub votes_cast: u64,
     pub uptime: Option<u64>,
+    #[serde(default)]
+    pub network_bytes_received: u64,
+    #[serde(default)]
+    pub network_bytes_sent: u64,
+    #[serde(default)]
+    pub network_items_dropped: u64,
+    #[serde(default)]
+    pub branch_switch_cnt: u64,
+    #[serde(default)]
+    pub storage_size: Option<u64>,
+    #[serde(default)]
+    pub task_restart_cnt: u64,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
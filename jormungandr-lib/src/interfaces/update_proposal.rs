@@ -0,0 +1,124 @@
+use super::{BlockDate, ConfigParams, ConsensusLeaderId, FromConfigParamError};
+use crate::crypto::hash::Hash;
+use chain_impl_mockchain::{certificate::UpdateProposalId, update::UpdateProposalState};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+
+/// where an update proposal stands. The votes are counted at the start of
+/// each epoch, an accepted proposal is applied then and a pending one is
+/// dropped if it expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UpdateProposalPhase {
+    /// not enough votes yet, the proposal is dropped at the start of
+    /// `expiry_epoch` if it is still not accepted
+    Pending { expiry_epoch: u32 },
+    /// a majority of the BFT leaders voted for the proposal, its changes
+    /// take effect at the start of the next epoch
+    Accepted,
+}
+
+/// an update proposal of the ledger, with its votes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateProposalStatus {
+    pub id: Hash,
+    pub proposer: ConsensusLeaderId,
+    pub changes: ConfigParams,
+    pub proposal_date: BlockDate,
+    pub votes: Vec<ConsensusLeaderId>,
+    pub votes_needed: usize,
+    #[serde(flatten)]
+    pub phase: UpdateProposalPhase,
+}
+
+impl UpdateProposalPhase {
+    pub fn new(
+        proposal_date: BlockDate,
+        votes: usize,
+        votes_needed: usize,
+        proposal_expiration: u32,
+    ) -> Self {
+        if votes >= votes_needed {
+            UpdateProposalPhase::Accepted
+        } else {
+            // the ledger drops the proposal once the current epoch is
+            // greater than the epoch of the proposal plus the expiration
+            UpdateProposalPhase::Pending {
+                expiry_epoch: proposal_date
+                    .epoch()
+                    .saturating_add(proposal_expiration)
+                    .saturating_add(1),
+            }
+        }
+    }
+}
+
+impl UpdateProposalStatus {
+    /// `bft_leaders` and `proposal_expiration` are the current settings of
+    /// the ledger holding the proposal
+    pub fn new(
+        id: UpdateProposalId,
+        state: &UpdateProposalState,
+        bft_leaders: usize,
+        proposal_expiration: u32,
+    ) -> Result<Self, FromConfigParamError> {
+        let proposal_date = state.proposal_date.into();
+        let votes_needed = bft_leaders / 2 + 1;
+        let votes: Vec<ConsensusLeaderId> = state.votes.iter().cloned().map(Into::into).collect();
+        Ok(UpdateProposalStatus {
+            id: id.into(),
+            proposer: state.proposal.proposer_id().clone().into(),
+            changes: state.proposal.changes().clone().try_into()?,
+            proposal_date,
+            phase: UpdateProposalPhase::new(
+                proposal_date,
+                votes.len(),
+                votes_needed,
+                proposal_expiration,
+            ),
+            votes,
+            votes_needed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phase_depends_on_the_majority() {
+        let date = BlockDate::new(3, 10);
+        assert_eq!(
+            UpdateProposalPhase::new(date, 1, 2, 5),
+            UpdateProposalPhase::Pending { expiry_epoch: 9 }
+        );
+        assert_eq!(
+            UpdateProposalPhase::new(date, 2, 2, 5),
+            UpdateProposalPhase::Accepted
+        );
+    }
+
+    #[test]
+    fn expiry_follows_the_ledger_rule() {
+        let (proposal_epoch, expiration) = (3, 5);
+        let expired = |current_epoch: u32| current_epoch > proposal_epoch + expiration;
+        let expiry_epoch =
+            match UpdateProposalPhase::new(BlockDate::new(proposal_epoch, 0), 0, 1, expiration) {
+                UpdateProposalPhase::Pending { expiry_epoch } => expiry_epoch,
+                UpdateProposalPhase::Accepted => panic!("the proposal has no votes"),
+            };
+        // still pending during the last epoch of the expiration
+        assert!(!expired(expiry_epoch - 1));
+        assert!(expired(expiry_epoch));
+    }
+
+    #[test]
+    fn phase_is_serialized_flat() {
+        let json = serde_json::to_value(UpdateProposalPhase::Pending { expiry_epoch: 8 }).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "status": "pending", "expiry_epoch": 8 })
+        );
+    }
+}
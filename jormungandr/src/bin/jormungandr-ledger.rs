@@ -0,0 +1,3 @@
+fn main() {
+    jormungandr::ledger_dump::main();
+}
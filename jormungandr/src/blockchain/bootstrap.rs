@@ -5,7 +5,7 @@ use crate::{
         chain::{CheckHeaderProof, StreamInfo, StreamReporter},
         Blockchain, Ref, Tip,
     },
-    metrics::Metrics,
+    metrics::{Metrics, MetricsBackend},
 };
 use chain_core::{
     packer::Codec,
@@ -34,22 +34,23 @@ pub async fn bootstrap_from_stream<S>(
     blockchain: Blockchain,
     branch: Tip,
     stream: S,
+    block_expected: Option<u64>,
     cancellation_token: CancellationToken,
+    metrics: Metrics,
 ) -> Result<Option<Arc<Ref>>, Error>
 where
     S: Stream<Item = Result<net_data::Block, NetworkError>> + Unpin,
 {
     let block0 = *blockchain.block0();
-    let mut tip_updater = TipUpdater::new(
-        branch,
-        blockchain.clone(),
-        None,
-        None,
-        Metrics::builder().build(),
-    );
+    let mut tip_updater = TipUpdater::new(branch, blockchain.clone(), None, None, metrics.clone());
 
     let mut bootstrap_info = StreamReporter::new(report);
+    if let Some(count) = block_expected {
+        bootstrap_info.expect_blocks(count);
+    }
     let mut maybe_parent_tip = None;
+    let mut block_received = 0;
+    metrics.set_bootstrap_progress(block_received, block_expected);
 
     // This stream will either end when the block stream is exhausted or when
     // the cancellation signal arrives. Building such stream allows us to
@@ -72,6 +73,8 @@ where
                 }
 
                 bootstrap_info.append_block(&block);
+                block_received += 1;
+                metrics.set_bootstrap_progress(block_received, block_expected);
                 blockchain
                     .handle_bootstrap_block(block, CheckHeaderProof::Enabled)
                     .await
@@ -134,14 +137,20 @@ fn report(stream_info: &StreamInfo) {
         })
         .unwrap_or_else(|_| "N/A".to_string());
 
+    let progress = stream_info
+        .block_expected
+        .map(|expected| format!(" ({}/{} blocks)", stream_info.block_received, expected))
+        .unwrap_or_default();
+
     tracing::info!(
-        "receiving from network bytes={} {}/s, blockchain {}",
+        "receiving from network bytes={} {}/s, blockchain {}{}",
         bytes,
         kbs,
         stream_info
             .last_block_description
             .as_ref()
             .map(|lbd| lbd.to_string())
-            .expect("append_block should always be called before report")
+            .expect("append_block should always be called before report"),
+        progress,
     )
 }
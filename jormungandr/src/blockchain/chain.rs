@@ -227,6 +227,8 @@ pub struct LeadershipBlock {
     pub block: Block,
     pub new_ledger: Ledger,
     pub leadership: EpochLeadership,
+    /// the span of the leader event that produced the block
+    pub span: tracing::Span,
 }
 
 impl PostCheckedHeader {
@@ -571,6 +573,7 @@ impl Blockchain {
             block,
             new_ledger,
             leadership,
+            span: _,
         } = leadership_block;
 
         let header = block.header().clone();
@@ -767,6 +770,43 @@ impl Blockchain {
         Ok(Tip::new(Branch::new(last_ref)))
     }
 
+    /// replay the blocks of the storage from the block0 up to the block
+    /// `until`, as when the ledger is inspected offline. The blocks are
+    /// already in the storage, so nothing new is written to it.
+    ///
+    /// The returned `Ref` holds the state of the ledger after `until`.
+    ///
+    /// # Errors
+    ///
+    /// The resulted future may fail if
+    ///
+    /// * the block0 is not already in the storage: `Error::Block0NotAlreadyInStorage`;
+    /// * `until` is not a descendant of the block0 in the storage;
+    /// * a block cannot be applied to the ledger.
+    pub async fn replay_from_storage(&self, block0: Block, until: HeaderHash) -> Result<Arc<Ref>> {
+        let block0_id = block0.header().hash();
+        if !self.storage.block_exists(block0_id)? {
+            return Err(Error::Block0NotAlreadyInStorage);
+        }
+
+        let mut last_ref = self.apply_block0(&block0).await?.get_ref();
+        if until == block0_id {
+            return Ok(last_ref);
+        }
+
+        let mut block_stream = self
+            .storage
+            .stream_from_to(block0_id, until)
+            .map(Box::pin)?;
+
+        while let Some(block) = block_stream.next().await.transpose()? {
+            last_ref = self
+                .handle_bootstrap_block(block, CheckHeaderProof::SkipFromStorage)
+                .await?;
+        }
+        Ok(last_ref)
+    }
+
     pub fn get_checkpoints(&self, branch: &Branch) -> Checkpoints {
         Checkpoints::new_from(branch.get_ref())
     }
@@ -902,6 +942,26 @@ pub fn new_epoch_leadership_from(
     }
 }
 
+/// The ledger state a block of the given epoch is built upon. Unlike
+/// [`new_epoch_leadership_from`] the rewards report of an epoch transition
+/// is not stored and the ledger errors are returned to the caller.
+pub fn new_epoch_ledger_from(epoch: Epoch, parent: &Ref) -> Result<Arc<Ledger>, ledger::Error> {
+    let parent_ledger_state = parent.ledger();
+    if parent.block_date().epoch >= epoch {
+        return Ok(parent_ledger_state);
+    }
+
+    let ledger = parent_ledger_state.apply_protocol_changes()?;
+    match parent.epoch_leadership_schedule().stake_distribution() {
+        Some(distribution) => {
+            let (ledger, _) =
+                ledger.distribute_rewards(distribution, RewardsInfoParameters::default())?;
+            Ok(Arc::new(ledger))
+        }
+        None => Ok(Arc::new(ledger)),
+    }
+}
+
 pub struct StreamReporter<R> {
     stream_info: StreamInfo,
     report: R,
@@ -913,6 +973,8 @@ pub struct StreamInfo {
     pub last_bytes_received: u64,
     pub bytes_received: u64,
     pub block_received: u64,
+    /// number of blocks the stream is expected to yield, if known
+    pub block_expected: Option<u64>,
     pub last_block_description: Option<HeaderDesc>,
 }
 
@@ -929,12 +991,17 @@ impl<R: Fn(&StreamInfo)> StreamReporter<R> {
                 last_bytes_received: 0,
                 bytes_received: 0,
                 block_received: 0,
+                block_expected: None,
                 last_block_description: lbd,
             },
             report,
         }
     }
 
+    pub fn expect_blocks(&mut self, count: u64) {
+        self.stream_info.block_expected = Some(count);
+    }
+
     pub fn append_block(&mut self, b: &Block) {
         use chain_core::property::Serialize;
         self.stream_info.bytes_received += b.serialize_as_vec().unwrap().len() as u64; // TODO sad serialization back
@@ -29,8 +29,8 @@ pub use self::{
     bootstrap::{bootstrap_from_stream, Error as BootstrapError},
     branch::Branch,
     chain::{
-        new_epoch_leadership_from, Blockchain, CheckHeaderProof, EpochLeadership, Error,
-        LeadershipBlock, PreCheckedHeader, MAIN_BRANCH_TAG,
+        new_epoch_leadership_from, new_epoch_ledger_from, Blockchain, CheckHeaderProof,
+        EpochLeadership, Error, LeadershipBlock, PreCheckedHeader, MAIN_BRANCH_TAG,
     },
     chain_selection::{compare_against, ComparisonResult},
     checkpoints::Checkpoints,
@@ -40,3 +40,6 @@ pub use self::{
     storage::{Error as StorageError, Storage},
     tip::Tip,
 };
+
+#[cfg(test)]
+pub(crate) use self::process::network_block_error_into_reply;
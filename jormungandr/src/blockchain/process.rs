@@ -20,7 +20,10 @@ use crate::{
 };
 use chain_core::property::{Block as _, Header as _};
 use futures::prelude::*;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tracing::{span, Level};
 use tracing_futures::Instrument;
 
@@ -210,6 +213,7 @@ impl Process {
                     parent = %leadership_block.block.header().parent_id(),
                     date = %leadership_block.block.header().block_date()
                 );
+                crate::log::set_parent_from(&span, &leadership_block.span);
                 let _enter = span.enter();
                 tracing::debug!("receiving block from leadership service");
 
@@ -452,6 +456,7 @@ async fn process_network_blocks(
                     block.clone(),
                     &mut watch_msg_box,
                     &mut get_next_block_scheduler,
+                    &stats_counter,
                 )
                 .await;
                 match res {
@@ -500,6 +505,7 @@ async fn process_network_block(
     block: Block,
     watch_msg_box: &mut MessageBox<WatchMsg>,
     get_next_block_scheduler: &mut GetNextBlockScheduler,
+    stats_counter: &Metrics,
 ) -> Result<Option<Arc<Ref>>, chain::Error> {
     let header = block.header().clone();
     let span = tracing::span!(
@@ -528,7 +534,8 @@ async fn process_network_block(
                 Err(Error::MissingParentBlock(parent_hash))
             }
             PreCheckedHeader::HeaderWithCache { parent_ref, .. } => {
-                check_and_apply_block(blockchain, parent_ref, block, watch_msg_box).await
+                check_and_apply_block(blockchain, parent_ref, block, watch_msg_box, stats_counter)
+                    .await
             }
         }
     }
@@ -541,7 +548,9 @@ async fn check_and_apply_block(
     parent_ref: Arc<Ref>,
     block: Block,
     watch_msg_box: &mut MessageBox<WatchMsg>,
+    stats_counter: &Metrics,
 ) -> Result<Option<Arc<Ref>>, chain::Error> {
+    let validation_start = Instant::now();
     let post_checked = blockchain
         .post_check_header(
             block.header().clone(),
@@ -549,15 +558,20 @@ async fn check_and_apply_block(
             CheckHeaderProof::Enabled,
         )
         .await?;
+    stats_counter.observe_block_validation_time(validation_start.elapsed());
     tracing::debug!("applying block to storage");
 
     let block_for_watchers = block.clone();
 
+    let application_start = Instant::now();
     let applied_block = blockchain
         .apply_and_store_block(post_checked, block)
         .await?;
     if let AppliedBlock::New(block_ref) = applied_block {
         tracing::debug!("applied block to storage");
+        stats_counter.observe_block_application_time(application_start.elapsed());
+        // blocks arriving before the start of their slot are not delayed
+        stats_counter.observe_block_arrival_delay(block_ref.elapsed().unwrap_or_default());
 
         watch_msg_box
             .try_send(WatchMsg::NewBlock(block_for_watchers))
@@ -611,12 +625,12 @@ async fn process_chain_headers(
     }
 }
 
-fn network_block_error_into_reply(err: chain::Error) -> intercom::Error {
+pub(crate) fn network_block_error_into_reply(err: chain::Error) -> intercom::Error {
     use super::chain::Error::*;
 
     match err {
         Storage(e) => intercom::Error::failed(e),
-        Ledger(e) => intercom::Error::failed_precondition(e),
+        Ledger(e) => intercom::Error::invalid_argument(e),
         Block0(e) => intercom::Error::failed(e),
         MissingParentBlock(_) => intercom::Error::failed_precondition(err.to_string()),
         BlockHeaderVerificationFailed(_) => intercom::Error::invalid_argument(err.to_string()),
@@ -1,5 +1,5 @@
 use crate::{
-    blockcfg::{FragmentId, Header, HeaderHash},
+    blockcfg::{FragmentId, Header},
     blockchain::{
         chain_selection::{self, ComparisonResult},
         storage, Blockchain, Branch, Error, Ref, MAIN_BRANCH_TAG,
@@ -72,20 +72,19 @@ impl TipUpdater {
         }
     }
 
-    async fn switch_tip_branch(
-        &mut self,
-        candidate: Arc<Ref>,
-        tip_hash: HeaderHash,
-    ) -> Result<(), Error> {
+    async fn switch_tip_branch(&mut self, candidate: Arc<Ref>, tip_ref: &Ref) -> Result<(), Error> {
         let storage = self.blockchain.storage();
         let candidate_hash = candidate.hash();
-        let common_ancestor = storage.find_common_ancestor(candidate_hash, tip_hash)?;
+        let common_ancestor = storage.find_common_ancestor(candidate_hash, tip_ref.hash())?;
 
         let stream = storage.stream_from_to(common_ancestor, candidate_hash)?;
         tokio::pin!(stream);
 
         // there is always at least one block in the stream
         let ancestor = stream.next().await.unwrap()?;
+        // the number of blocks of the current branch rolled back
+        let depth = u32::from(tip_ref.chain_length()) - u32::from(ancestor.header().chain_length());
+        self.stats_counter.add_branch_switch(depth);
         if let Some(ref mut mbox) = self.fragment_mbox {
             mbox.try_send(TransactionMsg::BranchSwitch(ancestor.date().into()))?;
         }
@@ -162,7 +161,7 @@ impl TipUpdater {
                         tip_ref.header().description(),
                         candidate.header().description(),
                     );
-                    self.switch_tip_branch(candidate.clone(), tip_hash).await?;
+                    self.switch_tip_branch(candidate.clone(), &tip_ref).await?;
                 }
 
                 self.stats_counter.set_tip_block(&block, &candidate);
@@ -195,7 +194,11 @@ impl TipUpdater {
                 date: date.into(),
                 block: hash,
             };
-            mbox.try_send(TransactionMsg::RemoveTransactions(fragment_ids, status))?;
+            mbox.try_send(TransactionMsg::RemoveTransactions(
+                fragment_ids,
+                status,
+                tracing::Span::current(),
+            ))?;
         }
 
         Ok(())
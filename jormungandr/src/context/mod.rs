@@ -2,8 +2,8 @@ use crate::{
     blockchain::{Blockchain, Tip},
     diagnostic::Diagnostic,
     intercom::{NetworkMsg, TopologyMsg, TransactionMsg},
-    leadership::Logs as LeadershipLogs,
-    metrics::backends::SimpleCounter,
+    leadership::{DryRun as LeadershipDryRun, Logs as LeadershipLogs},
+    metrics::{backends::SimpleCounter, Metrics},
     network::GlobalStateR as NetworkStateR,
     secure::enclave::Enclave,
     utils::async_msg::MessageBox,
@@ -39,6 +39,9 @@ pub struct Context {
     blockchain: Option<Blockchain>,
     blockchain_tip: Option<Tip>,
     bootstrap_stopper: Option<CancellationToken>,
+    metrics: Option<Metrics>,
+    #[cfg(feature = "prometheus-metrics")]
+    prometheus: Option<Arc<crate::metrics::backends::Prometheus>>,
     #[cfg(feature = "evm")]
     evm_filters: crate::jrpc::EvmFilters,
 }
@@ -57,6 +60,11 @@ pub enum Error {
     BlockchainTip,
     #[error("Diagnostic data not set in REST/RPC context")]
     Diagnostic,
+    #[error("Metrics not set in REST/RPC context")]
+    Metrics,
+    #[cfg(feature = "prometheus-metrics")]
+    #[error("Prometheus metrics exporter not set in REST/RPC context")]
+    Prometheus,
 }
 
 impl warp::reject::Reject for Error {}
@@ -78,6 +86,9 @@ impl Context {
             blockchain: Default::default(),
             blockchain_tip: Default::default(),
             bootstrap_stopper: Default::default(),
+            metrics: Default::default(),
+            #[cfg(feature = "prometheus-metrics")]
+            prometheus: Default::default(),
             #[cfg(feature = "evm")]
             evm_filters: Default::default(),
         }
@@ -141,6 +152,24 @@ impl Context {
         self.blockchain_tip.as_ref().ok_or(Error::BlockchainTip)
     }
 
+    pub fn set_metrics(&mut self, metrics: Metrics) {
+        self.metrics = Some(metrics);
+    }
+
+    pub fn metrics(&self) -> Result<&Metrics, Error> {
+        self.metrics.as_ref().ok_or(Error::Metrics)
+    }
+
+    #[cfg(feature = "prometheus-metrics")]
+    pub fn set_prometheus(&mut self, prometheus: Arc<crate::metrics::backends::Prometheus>) {
+        self.prometheus = Some(prometheus);
+    }
+
+    #[cfg(feature = "prometheus-metrics")]
+    pub fn prometheus(&self) -> Result<&crate::metrics::backends::Prometheus, Error> {
+        self.prometheus.as_deref().ok_or(Error::Prometheus)
+    }
+
     #[cfg(feature = "evm")]
     pub fn evm_filters(&mut self) -> &mut crate::jrpc::EvmFilters {
         &mut self.evm_filters
@@ -167,10 +196,9 @@ pub struct FullContext {
     pub topology_task: MessageBox<TopologyMsg>,
     pub transaction_task: MessageBox<TransactionMsg>,
     pub leadership_logs: LeadershipLogs,
+    pub leadership_dry_run: LeadershipDryRun,
     pub enclave: Enclave,
     #[cfg(feature = "evm")]
     pub evm_keys: Arc<Vec<chain_evm::util::Secret>>,
     pub network_state: NetworkStateR,
-    #[cfg(feature = "prometheus-metrics")]
-    pub prometheus: Option<Arc<crate::metrics::backends::Prometheus>>,
 }
@@ -22,6 +22,14 @@ impl Logs {
         self.entries.contains(&fragment_id)
     }
 
+    pub fn is_pending(&self, fragment_id: FragmentId) -> bool {
+        let fragment_id: Hash = fragment_id.into();
+        self.entries
+            .peek(&fragment_id)
+            .map(|(log, _)| log.is_pending())
+            .unwrap_or(false)
+    }
+
     pub fn exist_all(&self, fragment_ids: impl IntoIterator<Item = FragmentId>) -> Vec<bool> {
         fragment_ids
             .into_iter()
@@ -2,10 +2,7 @@ use crate::{
     blockcfg::ApplyBlockLedger,
     blockchain::{Ref, Tip},
     fragment::{
-        selection::{
-            FragmentSelectionAlgorithm, FragmentSelectionAlgorithmParams, FragmentSelectionResult,
-            OldestFirst,
-        },
+        selection::{FragmentSelectionAlgorithmParams, FragmentSelectionResult},
         Fragment, FragmentId, Logs,
     },
     intercom::{NetworkMsg, PropagateMsg},
@@ -24,13 +21,13 @@ use jormungandr_lib::{
     },
     time::SecondsSinceUnixEpoch,
 };
-use std::mem;
+use std::{collections::HashMap, mem};
 use thiserror::Error;
 use tokio::{
     fs::File,
     io::{AsyncWriteExt, BufWriter},
 };
-use tracing::Instrument;
+use tracing::{Instrument, Span};
 
 // It's a pretty big buffer, but common cloud based storage solutions (like EBS or GlusterFS) benefits from
 // this and it's currently flushed after every request, so the possibility of losing fragments due to a crash
@@ -45,6 +42,9 @@ pub struct Pool {
     persistent_log: Option<BufWriter<File>>,
     tip: Tip,
     metrics: Metrics,
+    /// spans following the pending fragments from their submission
+    /// to their inclusion in a block
+    fragment_spans: HashMap<FragmentId, Span>,
 }
 
 #[derive(Debug, Error)]
@@ -70,6 +70,7 @@ impl Pool {
                 .map(|file| BufWriter::with_capacity(DEFAULT_BUF_SIZE, file)),
             tip,
             metrics,
+            fragment_spans: HashMap::new(),
         }
     }
 
@@ -230,6 +231,8 @@ impl Pool {
         let mut network_msg_box = self.network_msg_box.clone();
         for (fragment, id) in new_fragments {
             tracing::debug!(fragment_id=?id, "inserted fragment to the pool");
+            let fragment_span = tracing::debug_span!("pending_fragment", fragment_id = %id);
+            self.fragment_spans.insert(id, fragment_span);
             accepted.push(id);
             let fragment_msg = NetworkMsg::Propagate(Box::new(PropagateMsg::Fragment(fragment)));
             network_msg_box
@@ -249,12 +252,33 @@ impl Pool {
         Ok(FragmentsProcessingSummary { accepted, rejected })
     }
 
-    pub fn remove_added_to_block(&mut self, fragment_ids: Vec<FragmentId>, status: FragmentStatus) {
-        let date = if let FragmentStatus::InABlock { date, .. } = status {
-            date
+    /// Remove the fragments included in a block of the main branch.
+    /// `tip_update` is the span of the tip update that included them.
+    pub fn remove_added_to_block(
+        &mut self,
+        fragment_ids: Vec<FragmentId>,
+        status: FragmentStatus,
+        tip_update: &Span,
+    ) {
+        let (date, block) = if let FragmentStatus::InABlock { date, block } = status {
+            (date, block)
         } else {
             panic!("expected status to be in block, found {:?}", status);
         };
+        for id in &fragment_ids {
+            if let Some(span) = self.fragment_spans.remove(id) {
+                span.follows_from(tip_update);
+                tracing::debug!(parent: &span, %date, %block, "fragment included in a block");
+            }
+        }
+        // the fragments not received by this node have no pending log
+        for log in self.logs.logs_by_ids(fragment_ids.iter().copied()).values() {
+            if log.is_pending() {
+                let received_at: &std::time::SystemTime = log.received_at().as_ref();
+                self.metrics
+                    .observe_fragment_inclusion_time(received_at.elapsed().unwrap_or_default());
+            }
+        }
         self.pool.remove_all(fragment_ids.iter());
         self.logs.modify_all(fragment_ids, status, date);
         self.update_metrics();
@@ -272,29 +296,38 @@ impl Pool {
             contents,
             ledger,
             rejected_fragments_cnt,
-        } = match selection_alg {
-            FragmentSelectionAlgorithmParams::OldestFirst => {
-                let mut selection_alg = OldestFirst::new();
-                selection_alg
-                    .select(
-                        ledger,
-                        logs,
-                        pool,
-                        soft_deadline_future,
-                        hard_deadline_future,
-                    )
-                    .await
+        } = selection_alg
+            .select(
+                ledger,
+                logs,
+                pool,
+                soft_deadline_future,
+                hard_deadline_future,
+            )
+            .await;
+        for fragment in contents.iter() {
+            if let Some(span) = self.fragment_spans.get(&fragment.hash()) {
+                span.follows_from(&Span::current());
+                tracing::debug!(parent: span, "fragment selected for a block");
             }
-        };
+        }
         self.metrics.add_tx_rejected_cnt(rejected_fragments_cnt);
         self.update_metrics();
+        self.close_settled_fragment_spans();
         (contents, ledger)
     }
 
+    /// Copies of the oldest pending fragments, up to the given total size in
+    /// bytes. The pool is left untouched.
+    pub fn oldest_fragments(&self, max_size_bytes: usize) -> Vec<Fragment> {
+        self.pool.oldest(max_size_bytes)
+    }
+
     // Remove from logs fragments that were confirmed (or rejected) in a branch
     pub fn prune_after_ledger_branch(&mut self, branch_date: BlockDateDto) {
         self.logs.remove_logs_after_date(branch_date);
         self.update_metrics();
+        self.close_settled_fragment_spans();
     }
 
     pub async fn remove_expired_txs(&mut self) {
@@ -310,6 +343,20 @@ impl Pool {
             block_date.into(),
         );
         self.update_metrics();
+        self.close_settled_fragment_spans();
+    }
+
+    /// Close the spans of the fragments no longer pending, e.g. rejected
+    /// during the selection or expired.
+    fn close_settled_fragment_spans(&mut self) {
+        let logs = &self.logs;
+        self.fragment_spans.retain(|id, span| {
+            let pending = logs.is_pending(*id);
+            if !pending {
+                tracing::debug!(parent: &*span, "fragment is no longer pending");
+            }
+            pending
+        });
     }
 
     fn update_metrics(&self) {
@@ -513,6 +560,16 @@ pub(super) mod internal {
         fn contains(&self, key: &K) -> bool {
             self.index.contains_key(&IndexedDequeueKeyRef(key))
         }
+
+        /// Iterates over the values from the back to the front of the queue
+        fn iter_rev(&self) -> impl Iterator<Item = &V> {
+            let mut current = self.tail;
+            std::iter::from_fn(move || {
+                let entry = unsafe { current.as_ref() }?;
+                current = entry.prev;
+                Some(&entry.value)
+            })
+        }
     }
 
     unsafe impl<K: Send, V: Send> Send for IndexedDeqeue<K, V> {}
@@ -626,6 +683,20 @@ pub(super) mod internal {
             }
         }
 
+        /// Returns clones of the oldest fragments, in the order they would be
+        /// removed, as long as their total size does not exceed `max_size_bytes`
+        pub fn oldest(&self, max_size_bytes: usize) -> Vec<Fragment> {
+            let mut size_bytes = 0;
+            self.entries
+                .iter_rev()
+                .take_while(|fragment| {
+                    size_bytes += fragment.serialized_size();
+                    size_bytes <= max_size_bytes
+                })
+                .cloned()
+                .collect()
+        }
+
         fn timeout_queue_insert(&mut self, fragment: &Fragment, id: FragmentId) {
             if let Some(valid_until) = get_transaction_expiry_date(fragment) {
                 let item = TimeoutQueueItem { valid_until, id };
@@ -777,5 +848,32 @@ pub(super) mod internal {
 
             assert_eq!(pool.entries.len(), 0, "Expired fragment should be removed");
         }
+
+        #[quickcheck]
+        fn oldest_fragments_are_bounded(fragments_in: (Fragment, Fragment, Fragment)) {
+            let fragments = vec![
+                (fragments_in.0.clone(), fragments_in.0.id()),
+                (fragments_in.1.clone(), fragments_in.1.id()),
+                (fragments_in.2.clone(), fragments_in.2.id()),
+            ];
+            let mut pool = Pool::new(3);
+            let inserted = pool.insert_all(fragments);
+            let total_size_bytes = pool.total_size_bytes();
+
+            let oldest = pool.oldest(total_size_bytes);
+            let expected: Vec<_> = inserted.iter().map(|(f, _)| f.clone()).collect();
+            assert_eq!(oldest, expected);
+            assert_eq!(pool.len(), inserted.len());
+            assert_eq!(pool.total_size_bytes(), total_size_bytes);
+
+            if let Some((first, _)) = inserted.first() {
+                assert_eq!(
+                    pool.oldest(first.serialized_size()),
+                    vec![first.clone()],
+                    "only the oldest fragment fits"
+                );
+            }
+            assert!(pool.oldest(0).is_empty());
+        }
     }
 }
@@ -134,6 +134,7 @@ impl Process {
                                     // for other message we don't want to receive them through this interface, and possibly
                                     // put them in another pool.
                                     let span = debug_span!("incoming_fragments");
+                                    crate::log::set_parent_from(&span, reply_handle.span());
                                     async {
                                         let stats_counter = stats_counter.clone();
                                         let summary = pool
@@ -148,7 +149,7 @@ impl Process {
                                     .instrument(span)
                                     .await?;
                                 }
-                                TransactionMsg::RemoveTransactions(fragment_ids, status) => {
+                                TransactionMsg::RemoveTransactions(fragment_ids, status, tip_update) => {
                                     let span = debug_span!("remove_transactions_in_block");
                                     async {
                                         tracing::debug!(
@@ -156,7 +157,7 @@ impl Process {
                                             status,
                                             fragment_ids
                                         );
-                                        pool.remove_added_to_block(fragment_ids, status);
+                                        pool.remove_added_to_block(fragment_ids, status, &tip_update);
                                         pool.remove_expired_txs().await;
                                     }.instrument(span).await
                                 }
@@ -173,6 +174,10 @@ impl Process {
                                     );
                                     reply_handle.reply_ok(statuses);
                                 }
+                                TransactionMsg::GetPendingFragments(max_size_bytes, reply_handle) => {
+                                    let fragments = pool.oldest_fragments(max_size_bytes);
+                                    reply_handle.reply_ok(fragments);
+                                }
                                 TransactionMsg::BranchSwitch(fork_date) => {
                                     tracing::debug!(%fork_date, "pruning logs after branch switch");
                                     pool.prune_after_ledger_branch(fork_date);
@@ -189,6 +194,7 @@ impl Process {
                                         "fragment_selection",
                                         kind = "older_first",
                                     );
+                                    crate::log::set_parent_from(&span, reply_handle.span());
                                     async {
                                         let contents = pool
                                         .select(
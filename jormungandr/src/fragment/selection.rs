@@ -40,6 +40,58 @@ pub enum FragmentSelectionAlgorithmParams {
     OldestFirst,
 }
 
+impl FragmentSelectionAlgorithmParams {
+    pub(super) async fn select(
+        self,
+        ledger: ApplyBlockLedger,
+        logs: &mut Logs,
+        pool: &mut Pool,
+        soft_deadline_future: futures::channel::oneshot::Receiver<()>,
+        hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+    ) -> FragmentSelectionResult {
+        match self {
+            FragmentSelectionAlgorithmParams::OldestFirst => {
+                OldestFirst::new()
+                    .select(
+                        ledger,
+                        logs,
+                        pool,
+                        soft_deadline_future,
+                        hard_deadline_future,
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+/// Select the fragments of a block from copies of the pending fragments,
+/// given oldest first, as the algorithm would from the pool. The pool and
+/// the fragment logs are left untouched.
+pub async fn select_from_copies(
+    selection_alg: FragmentSelectionAlgorithmParams,
+    ledger: ApplyBlockLedger,
+    fragments: Vec<Fragment>,
+    soft_deadline_future: futures::channel::oneshot::Receiver<()>,
+    hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+) -> FragmentSelectionResult {
+    let mut pool = Pool::new(fragments.len());
+    pool.insert_all(fragments.into_iter().map(|fragment| {
+        let id = fragment.hash();
+        (fragment, id)
+    }));
+    let mut logs = Logs::new(pool.len());
+    selection_alg
+        .select(
+            ledger,
+            &mut logs,
+            &mut pool,
+            soft_deadline_future,
+            hard_deadline_future,
+        )
+        .await
+}
+
 pub struct OldestFirst;
 
 impl OldestFirst {
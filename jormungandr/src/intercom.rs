@@ -15,6 +15,7 @@ use futures::{
 };
 use jormungandr_lib::interfaces::{
     BlockDate, FragmentLog, FragmentOrigin, FragmentStatus, FragmentsProcessingSummary,
+    PeerReportReason,
 };
 use poldercast::layer::Selection;
 use std::{
@@ -22,9 +23,11 @@ use std::{
     error,
     fmt::{self, Debug, Display},
     marker::PhantomData,
+    net::SocketAddr,
     pin::Pin,
     task::{Context, Poll},
 };
+use tracing::Span;
 
 /// The error values passed via intercom messages.
 #[derive(Debug)]
@@ -153,12 +156,22 @@ impl error::Error for Error {
 
 type ReplySender<T> = oneshot::Sender<Result<T, Error>>;
 
+/// Handle for replying to a request.
+///
+/// The handle carries the span the request was made in, so that the
+/// processing on the receiving end can be attached to the requester's trace.
 #[derive(Debug)]
 pub struct ReplyHandle<T> {
     sender: ReplySender<T>,
+    span: Span,
 }
 
 impl<T> ReplyHandle<T> {
+    /// the span of the task that made the request
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
     pub fn reply(self, result: Result<T, Error>) {
         // Ignoring a send error: it means the result is no longer needed
         let _ = self.sender.send(result);
@@ -203,7 +216,11 @@ impl<T> Future for ReplyFuture<T> {
 pub fn unary_reply<T>() -> (ReplyHandle<T>, ReplyFuture<T>) {
     let (sender, receiver) = oneshot::channel();
     let future = ReplyFuture { receiver };
-    (ReplyHandle { sender }, future)
+    let handle = ReplyHandle {
+        sender,
+        span: Span::current(),
+    };
+    (handle, future)
 }
 
 #[derive(Debug)]
@@ -255,9 +272,15 @@ impl<T: 'static> error::Error for ReplyTrySendError<T> {
 pub struct ReplyStreamHandle<T> {
     lead_sender: oneshot::Sender<Result<mpsc::Receiver<Result<T, Error>>, Error>>,
     buffer_size: usize,
+    span: Span,
 }
 
 impl<T> ReplyStreamHandle<T> {
+    /// the span of the task that made the request
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
     fn reply(self, result: Result<mpsc::Receiver<Result<T, Error>>, Error>) {
         // Ignoring a send error: it means the result is no longer needed
         let _ = self.lead_sender.send(result);
@@ -416,6 +439,7 @@ pub fn stream_reply<T, E>(buffer_size: usize) -> (ReplyStreamHandle<T>, ReplyStr
     let handle = ReplyStreamHandle {
         lead_sender,
         buffer_size,
+        span: Span::current(),
     };
     let future = ReplyStreamFuture {
         lead_receiver,
@@ -508,7 +532,9 @@ pub enum TransactionMsg {
         fail_fast: bool,
         reply_handle: ReplyHandle<FragmentsProcessingSummary>,
     },
-    RemoveTransactions(Vec<FragmentId>, FragmentStatus),
+    /// remove the fragments included in a block of the main branch,
+    /// the span being the one of the tip update that included them
+    RemoveTransactions(Vec<FragmentId>, FragmentStatus, Span),
     BranchSwitch(BlockDate),
     GetLogs(ReplyHandle<Vec<FragmentLog>>),
     GetStatuses(
@@ -522,6 +548,9 @@ pub enum TransactionMsg {
         soft_deadline_future: futures::channel::oneshot::Receiver<()>,
         hard_deadline_future: futures::channel::oneshot::Receiver<()>,
     },
+    /// copies of the oldest pending fragments, up to the given total size
+    /// in bytes, leaving the pool untouched
+    GetPendingFragments(usize, ReplyHandle<Vec<Fragment>>),
 }
 
 /// Client messages, mainly requests from connected peers to our node.
@@ -607,17 +636,34 @@ pub enum NetworkMsg {
         to: HeaderHash,
     },
     PeerInfo(ReplyHandle<Vec<PeerInfo>>),
+    /// Connect to the peer at the given address, expected to have the
+    /// given id
+    Connect {
+        addr: SocketAddr,
+        id: NodeId,
+    },
+    /// Drop the connection to the peer, replies whether it was connected
+    Disconnect(NodeId, ReplyHandle<bool>),
+    /// Drop the connections to the peers banned by the operator
+    DisconnectBanned,
 }
 
 /// Messages to the topology task
 pub enum TopologyMsg {
     AcceptGossip(Gossips),
-    DemotePeer(NodeId),
+    DemotePeer(NodeId, PeerReportReason),
+    /// The peer served the given number of valid blocks
+    RewardPeer(NodeId, u64),
     PromotePeer(NodeId),
     View(Selection, ReplyHandle<View>),
     ListAvailable(ReplyHandle<Vec<TopologyPeerInfo>>),
     ListNonPublic(ReplyHandle<Vec<TopologyPeerInfo>>),
     ListQuarantined(ReplyHandle<Vec<TopologyPeerInfo>>),
+    /// Lift the quarantine of the peer, replies whether it was quarantined
+    LiftQuarantine(NodeId, ReplyHandle<bool>),
+    /// Move the peers banned by the operator out of the topology and
+    /// restore the ones that are no longer banned
+    ApplyPeerRules,
 }
 
 /// Messages to the notifier task
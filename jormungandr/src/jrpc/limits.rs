@@ -0,0 +1,83 @@
+//! Front of the JSON-RPC server enforcing the request limits
+//!
+//! The JSON-RPC server gives no way to reject a request before it is
+//! dispatched, so when limits are configured it listens on a loopback
+//! address and the requests within the limits are forwarded to it.
+use crate::{context::ContextLock, rest::limits};
+use std::{net::SocketAddr, sync::Arc};
+use warp::{
+    http::{header, HeaderMap, Method, Response, StatusCode},
+    hyper::body::Bytes,
+    Filter, Rejection,
+};
+
+/// the headers which only make sense between the client and this front, or
+/// which are set again when the body is forwarded
+static HOP_BY_HOP_HEADERS: [header::HeaderName; 6] = [
+    header::CONNECTION,
+    header::CONTENT_LENGTH,
+    header::HOST,
+    header::TE,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+fn forwarded(
+    headers: &HeaderMap,
+) -> impl Iterator<Item = (&header::HeaderName, &header::HeaderValue)> {
+    headers
+        .iter()
+        .filter(|(name, _)| !HOP_BY_HOP_HEADERS.contains(*name))
+}
+
+pub async fn serve(
+    listen: SocketAddr,
+    server: SocketAddr,
+    limiter: Arc<limits::Limiter>,
+    context: ContextLock,
+) {
+    let client = reqwest::Client::new();
+    let server = format!("http://{}", server);
+    let app = limits::filter(limiter.clone(), context.clone())
+        .and(warp::method())
+        .and(warp::header::headers_cloned())
+        .and(limits::body(limiter, context))
+        .and_then(move |method: Method, headers: HeaderMap, body: Bytes| {
+            forward(client.clone(), server.clone(), method, headers, body)
+        })
+        .recover(limits::handle_rejection);
+
+    warp::serve(app).run(listen).await
+}
+
+async fn forward(
+    client: reqwest::Client,
+    server: String,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response<Bytes>, Rejection> {
+    let mut request = client.request(method, &server).body(body);
+    for (name, value) in forwarded(&headers) {
+        request = request.header(name, value);
+    }
+
+    let reply = async {
+        let response = request.send().await?;
+        let mut reply = Response::builder().status(response.status());
+        for (name, value) in forwarded(response.headers()) {
+            reply = reply.header(name, value);
+        }
+        Ok::<_, reqwest::Error>(reply.body(response.bytes().await?))
+    };
+    let reply = match reply.await {
+        Ok(reply) => reply,
+        Err(e) => {
+            tracing::error!(reason = %e, "cannot forward the request to the JSON-RPC server");
+            Response::builder()
+                .status(StatusCode::BAD_GATEWAY)
+                .body(Bytes::new())
+        }
+    };
+    Ok(reply.expect("valid response"))
+}
@@ -12,22 +12,28 @@ mod eth_miner;
 mod eth_transaction;
 #[cfg(feature = "evm")]
 mod eth_types;
+mod limits;
 
 use crate::{
     context::ContextLock,
     intercom::{self, TransactionMsg},
+    rest::limits::Limiter,
 };
 use chain_impl_mockchain::ledger::Error as LedgerError;
 #[cfg(feature = "evm")]
 pub use eth_filter::EvmFilters;
 use futures::channel::mpsc::TrySendError;
-use jormungandr_lib::interfaces::FragmentsProcessingSummary;
+use jormungandr_lib::interfaces::{FragmentsProcessingSummary, RequestLimits};
 use jsonrpsee_http_server::{HttpServerBuilder, RpcModule};
-use std::net::SocketAddr;
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
 use thiserror::Error;
 
 pub struct Config {
     pub listen: SocketAddr,
+    pub limits: Option<RequestLimits>,
 }
 
 #[derive(Debug, Error)]
@@ -62,11 +68,26 @@ pub enum Error {
     EthereumSignatureError(String),
 }
 
-pub async fn start_jrpc_server(config: Config, _context: ContextLock) {
-    let server = HttpServerBuilder::default()
-        .build(config.listen)
-        .await
-        .unwrap();
+pub async fn start_jrpc_server(config: Config, context: ContextLock) {
+    // with limits configured, the server is only reachable through the
+    // front enforcing them
+    let (listen, front) = match config.limits {
+        Some(request_limits) => (
+            SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            Some((config.listen, request_limits)),
+        ),
+        None => (config.listen, None),
+    };
+
+    let mut builder = HttpServerBuilder::default();
+    if let Some(max) = front
+        .as_ref()
+        .and_then(|(_, request_limits)| request_limits.max_body_size)
+    {
+        builder = builder.max_request_body_size(max.min(u32::MAX as u64) as u32);
+    }
+    let server = builder.build(listen).await.unwrap();
+    let server_addr = server.local_addr().unwrap();
 
     #[allow(unused_mut)]
     let mut modules = RpcModule::new(());
@@ -74,29 +95,36 @@ pub async fn start_jrpc_server(config: Config, _context: ContextLock) {
     #[cfg(feature = "evm")]
     {
         modules
-            .merge(eth_block_info::eth_block_info_module(_context.clone()))
+            .merge(eth_block_info::eth_block_info_module(context.clone()))
             .unwrap();
 
         modules
-            .merge(eth_chain_info::eth_chain_info_module(_context.clone()))
+            .merge(eth_chain_info::eth_chain_info_module(context.clone()))
             .unwrap();
 
         modules
-            .merge(eth_transaction::eth_transaction_module(_context.clone()))
+            .merge(eth_transaction::eth_transaction_module(context.clone()))
             .unwrap();
 
         modules
-            .merge(eth_account::eth_account_module(_context.clone()))
+            .merge(eth_account::eth_account_module(context.clone()))
             .unwrap();
 
         modules
-            .merge(eth_filter::eth_filter_module(_context.clone()))
+            .merge(eth_filter::eth_filter_module(context.clone()))
             .unwrap();
 
         modules
-            .merge(eth_miner::eth_miner_module(_context))
+            .merge(eth_miner::eth_miner_module(context.clone()))
             .unwrap();
     }
 
-    server.start(modules).unwrap().await
+    let server = server.start(modules).unwrap();
+    match front {
+        Some((listen, request_limits)) => {
+            let limiter = Arc::new(Limiter::new("jrpc", &request_limits));
+            futures::join!(server, limits::serve(listen, server_addr, limiter, context));
+        }
+        None => server.await,
+    }
 }
@@ -4,15 +4,19 @@ use crate::{
         HeaderBft, HeaderBftBuilder, HeaderGenesisPraos, HeaderGenesisPraosBuilder,
         HeaderSetConsensusSignature, Leadership,
     },
-    secure::enclave::Enclave as SecureEnclave,
+    secure::{enclave::Enclave as SecureEnclave, kes::KesError},
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::SystemTime};
 use thiserror::Error;
 
-#[derive(Debug, Clone, Error)]
+#[derive(Debug, Error)]
 pub enum EnclaveError {
     #[error("Enclave does not have a leader set")]
     EmptyEnclave,
+    #[error(transparent)]
+    Kes(#[from] KesError),
+    #[error("the evolution of the KES key did not complete")]
+    KesEvolution(#[from] tokio::task::JoinError),
 }
 
 /// represent the client side of an enclave. From there we will query the
@@ -71,16 +75,31 @@ impl Enclave {
         }
     }
 
+    /// evolve the KES key to the period of the slot starting at `slot_time`
+    /// if it is not there yet, the evolved key is saved before it is used.
+    /// This runs on the blocking threads as the secret file is written.
+    pub async fn evolve_kes_key(&self, slot_time: SystemTime) -> Result<(), EnclaveError> {
+        let inner = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || inner.evolve_kes_key(slot_time)).await??;
+        Ok(())
+    }
+
     /// ask the leader associated to the `LeaderEvent` to finalize the given
-    /// block by providing the proof.
+    /// block by providing the proof. The KES key must have been evolved to
+    /// the period of the slot starting at `slot_time`, see
+    /// [`Enclave::evolve_kes_key`].
     ///
     /// TODO: for now we are querying the whole with the block builder but on the long
     ///       run we will only need the block signing data.
     pub fn query_header_genesis_praos_finalize(
         &self,
         block_builder: HeaderGenesisPraosBuilder<HeaderSetConsensusSignature>,
+        slot_time: SystemTime,
     ) -> Result<HeaderGenesisPraos, EnclaveError> {
-        if let Some(block) = self.inner.create_header_genesis_praos(block_builder) {
+        if let Some(block) = self
+            .inner
+            .create_header_genesis_praos(block_builder, slot_time)?
+        {
             Ok(block)
         } else {
             Err(EnclaveError::EmptyEnclave)
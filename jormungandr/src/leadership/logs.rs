@@ -1,5 +1,8 @@
 pub use jormungandr_lib::interfaces::LeadershipLogStatus;
-use jormungandr_lib::interfaces::{LeadershipLog, LeadershipLogId};
+use jormungandr_lib::{
+    interfaces::{BlockAssembly, LeadershipLog, LeadershipLogId},
+    time::SystemTime,
+};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -36,6 +39,18 @@ impl LeadershipLogHandle {
         self.logs.set_status(self.internal_id, status).await
     }
 
+    pub async fn set_deadlines(&self, deadline: SystemTime, hard_deadline: SystemTime) {
+        self.logs
+            .set_deadlines(self.internal_id, deadline, hard_deadline)
+            .await
+    }
+
+    pub async fn set_block_assembly(&self, block_assembly: BlockAssembly) {
+        self.logs
+            .set_block_assembly(self.internal_id, block_assembly)
+            .await
+    }
+
     /// make a leadership event as finished.
     ///
     /// This should be called when the leadership event has finished its
@@ -78,6 +93,31 @@ impl Logs {
         inner.write().await.set_status(&leadership_log_id, status);
     }
 
+    async fn set_deadlines(
+        &self,
+        leadership_log_id: LeadershipLogId,
+        deadline: SystemTime,
+        hard_deadline: SystemTime,
+    ) {
+        let inner = self.0.clone();
+        inner
+            .write()
+            .await
+            .set_deadlines(&leadership_log_id, deadline, hard_deadline);
+    }
+
+    async fn set_block_assembly(
+        &self,
+        leadership_log_id: LeadershipLogId,
+        block_assembly: BlockAssembly,
+    ) {
+        let inner = self.0.clone();
+        inner
+            .write()
+            .await
+            .set_block_assembly(&leadership_log_id, block_assembly);
+    }
+
     async fn mark_finished(&self, leadership_log_id: LeadershipLogId) {
         let inner = self.0.clone();
         inner.write().await.mark_finished(&leadership_log_id);
@@ -91,7 +131,7 @@ impl Logs {
 }
 
 pub(super) mod internal {
-    use super::{LeadershipLog, LeadershipLogId, LeadershipLogStatus};
+    use super::{BlockAssembly, LeadershipLog, LeadershipLogId, LeadershipLogStatus, SystemTime};
     use lru::LruCache;
 
     pub struct Logs {
@@ -128,6 +168,27 @@ pub(super) mod internal {
             }
         }
 
+        pub fn set_deadlines(
+            &mut self,
+            leadership_log_id: &LeadershipLogId,
+            deadline: SystemTime,
+            hard_deadline: SystemTime,
+        ) {
+            if let Some(ref mut log) = self.entries.get_mut(leadership_log_id) {
+                log.set_deadlines(deadline, hard_deadline);
+            }
+        }
+
+        pub fn set_block_assembly(
+            &mut self,
+            leadership_log_id: &LeadershipLogId,
+            block_assembly: BlockAssembly,
+        ) {
+            if let Some(ref mut log) = self.entries.get_mut(leadership_log_id) {
+                log.set_block_assembly(block_assembly);
+            }
+        }
+
         pub fn mark_finished(&mut self, leadership_log_id: &LeadershipLogId) {
             if let Some(ref mut log) = self.entries.get_mut(leadership_log_id) {
                 log.mark_finished();
@@ -62,7 +62,7 @@ mod logs;
 mod process;
 
 pub use self::{
-    enclave::{Enclave, EnclaveError, LeaderEvent},
+    enclave::{Enclave, EnclaveError, LeaderEvent, Schedule},
     logs::{LeadershipLogHandle, Logs},
-    process::{Module, ModuleConfig},
+    process::{DryRun, LeadershipError, LeadershipOutcome, Module, ModuleConfig},
 };
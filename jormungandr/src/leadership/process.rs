@@ -1,23 +1,39 @@
 use crate::{
-    blockcfg::{block_builder, ApplyBlockLedger, BlockVersion, Contents, LeaderOutput, Leadership},
-    blockchain::{new_epoch_leadership_from, EpochLeadership, LeadershipBlock, Ref, Tip},
+    blockcfg::{
+        block_builder, ApplyBlockLedger, BlockDate, BlockVersion, ChainLength, Contents, Fragment,
+        LeaderOutput, Leadership,
+    },
+    blockchain::{
+        new_epoch_leadership_from, new_epoch_ledger_from, EpochLeadership, LeadershipBlock, Ref,
+        Tip,
+    },
+    fragment::selection::{self, FragmentSelectionAlgorithmParams},
     intercom::{unary_reply, BlockMsg, Error as IntercomError, TransactionMsg},
     leadership::{
         enclave::{Enclave, EnclaveError, LeaderEvent, Schedule},
         LeadershipLogHandle, Logs,
     },
+    metrics::{Metrics, MetricsBackend},
     utils::{async_msg::MessageBox, task::TokioServiceInfo},
 };
 use chain_time::{
     era::{EpochPosition, EpochSlotOffset},
     Epoch, Slot,
 };
-use futures::{future::TryFutureExt, sink::SinkExt};
+use futures::{
+    channel::oneshot,
+    future::{Future, TryFutureExt},
+    sink::SinkExt,
+};
 use jormungandr_lib::{
-    interfaces::{LeadershipLog, LeadershipLogStatus},
+    interfaces::{BlockAssembly, BlockDryRun, LeadershipLog, LeadershipLogStatus},
     time::SystemTime,
 };
-use std::{cmp::Ordering, sync::Arc, time::Instant};
+use std::{
+    cmp::Ordering,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 use tracing::{span, Level, Span};
 use tracing_futures::Instrument;
@@ -51,6 +67,42 @@ pub enum LeadershipError {
 
     #[error("Error while performing a ledger operation")]
     LedgerError(#[from] Box<chain_impl_mockchain::ledger::Error>),
+
+    #[error("Cannot build the block")]
+    CannotBuildBlock(#[source] EnclaveError),
+
+    #[error("The block dry run task failed")]
+    DryRunTaskFailed(#[source] tokio::task::JoinError),
+}
+
+/// the outcome of a leadership event, or of a block dry run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeadershipOutcome {
+    /// a block was built and sent to the blockchain
+    Block,
+    /// the event was processed after the end of its slot
+    TooLate,
+    /// the tip was already at or after the date of the event
+    InvalidTip,
+    /// the block could not be signed
+    SigningFailed,
+    /// the event was dropped because of an error of the leadership task
+    Dropped,
+    /// a candidate block was assembled without being published
+    DryRun,
+}
+
+impl LeadershipOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LeadershipOutcome::Block => "block",
+            LeadershipOutcome::TooLate => "too_late",
+            LeadershipOutcome::InvalidTip => "invalid_tip",
+            LeadershipOutcome::SigningFailed => "signing_failed",
+            LeadershipOutcome::Dropped => "dropped",
+            LeadershipOutcome::DryRun => "dry_run",
+        }
+    }
 }
 
 struct Entry {
@@ -70,6 +122,7 @@ pub struct Module {
     rewards_report_all: bool,
     // the maximum number of slots we can allow the leader event to run for
     block_hard_deadline: u32,
+    metrics: Metrics,
 }
 
 pub struct ModuleConfig {
@@ -81,6 +134,7 @@ pub struct ModuleConfig {
     pub block_message: MessageBox<BlockMsg>,
     pub rewards_report_all: bool,
     pub block_hard_deadline: u32,
+    pub metrics: Metrics,
 }
 
 impl Module {
@@ -98,6 +152,7 @@ impl Module {
             block_message: config.block_message,
             rewards_report_all: config.rewards_report_all,
             block_hard_deadline: config.block_hard_deadline,
+            metrics: config.metrics,
         })
     }
 
@@ -304,10 +359,26 @@ impl Module {
         };
 
         let end_log = entry.log.clone();
+        let metrics = module.metrics.clone();
         entry.log.mark_wake().await;
-        let module = module.action_run_entry(entry).await?;
-        end_log.mark_finished().await;
-        Ok(module)
+        match module.action_run_entry(entry).await {
+            Ok(module) => {
+                end_log.mark_finished().await;
+                Ok(module)
+            }
+            Err(error) => {
+                // the error will stop the leadership task, do not leave the
+                // event pending
+                end_log
+                    .set_status(LeadershipLogStatus::Rejected {
+                        reason: format!("Leader event dropped: {}", error),
+                    })
+                    .await;
+                end_log.mark_finished().await;
+                metrics.add_leadership_event(LeadershipOutcome::Dropped);
+                Err(error)
+            }
+        }
     }
 
     async fn action_run_entry(self, entry: Entry) -> Result<Self, LeadershipError> {
@@ -327,6 +398,8 @@ impl Module {
         );
 
         async move {
+            entry.log.set_deadlines(event_end, event_end_hard).await;
+
             if too_late(now, event_end) {
                 // the event happened out of bounds, ignore it and move to the next one
                 tracing::error!(
@@ -339,6 +412,8 @@ impl Module {
                         reason: "Missed the deadline to compute the schedule".to_owned(),
                     })
                     .await;
+                self.metrics
+                    .add_leadership_event(LeadershipOutcome::TooLate);
 
                 Ok(self)
             } else {
@@ -373,9 +448,6 @@ impl Module {
         event_end: SystemTime,
         event_end_hard: SystemTime,
     ) -> Result<Self, LeadershipError> {
-        use futures::future::{select, Either};
-        use std::time::Duration;
-
         let now = SystemTime::now();
 
         // we can safely unwrap here as we just proved that `now <= event_end`
@@ -403,33 +475,13 @@ impl Module {
             event_remaining_time = %remaining_time.to_string()
         );
 
-        async {
-            let (soft_deadline_tx, soft_deadline_rx) = futures::channel::oneshot::channel();
-            let soft_deadline_future =
-                tokio::time::sleep_until(tokio::time::Instant::from_std(deadline));
-
-            let (hard_deadline_tx, hard_deadline_rx) = futures::channel::oneshot::channel();
-            let hard_deadline_future =
-                tokio::time::sleep_until(tokio::time::Instant::from_std(hard_deadline));
-
-            let build_block_future =
-                self.action_run_entry_build_block(entry, soft_deadline_rx, hard_deadline_rx);
-
-            match select(Box::pin(build_block_future), Box::pin(soft_deadline_future)).await {
-                Either::Left((result, _)) => result,
-                Either::Right((_, build_block_future)) => {
-                    soft_deadline_tx.send(()).unwrap();
-                    match select(Box::pin(build_block_future), Box::pin(hard_deadline_future)).await
-                    {
-                        Either::Left((result, _)) => result,
-                        Either::Right((_, build_block_future)) => {
-                            hard_deadline_tx.send(()).unwrap();
-                            build_block_future.await
-                        }
-                    }
-                }
-            }
-        }
+        run_with_deadlines(
+            deadline,
+            hard_deadline,
+            |soft_deadline_rx, hard_deadline_rx| {
+                self.action_run_entry_build_block(entry, soft_deadline_rx, hard_deadline_rx)
+            },
+        )
         .instrument(span)
         .await?;
 
@@ -442,8 +494,10 @@ impl Module {
         soft_deadline_future: futures::channel::oneshot::Receiver<()>,
         hard_deadline_future: futures::channel::oneshot::Receiver<()>,
     ) -> Result<(), LeadershipError> {
+        let started = Instant::now();
         let event = entry.event;
         let event_logs = entry.log;
+        let slot_time = *self.event_slot_time(&event).as_ref();
 
         let enclave = self.enclave.clone();
         let mut sender = self.block_message.clone();
@@ -470,23 +524,28 @@ impl Module {
                         reason: "Not computing this schedule because of invalid state against the network blockchain".to_owned()
                     }
                 ).await;
+            self.metrics
+                .add_leadership_event(LeadershipOutcome::InvalidTip);
 
             return Ok(());
         };
 
-        let leadership = new_epoch_leadership_from(
-            event.date.epoch,
+        let AssembledContents {
+            leadership,
+            contents,
+            ledger,
+            fragment_selection_time,
+        } = assemble_contents(
             Arc::clone(&self.tip_ref),
+            event.date,
+            chain_length,
             self.rewards_report_all,
-        );
-        let ledger = leadership.state.clone();
-
-        let ledger = ledger
-            .begin_block(chain_length, event.date)
-            .map_err(Box::new)?;
-
-        let (contents, ledger) =
-            prepare_block(pool, ledger, soft_deadline_future, hard_deadline_future).await?;
+            pool,
+            soft_deadline_future,
+            hard_deadline_future,
+        )
+        .await?;
+        let (fragment_count, content_size) = contents_stats(&contents);
 
         let event_logs_error = event_logs.clone();
         let signing = {
@@ -530,23 +589,30 @@ impl Module {
                                     reason: format!("Cannot sign the block: {}", e),
                                 })
                                 .await;
+                            self.metrics
+                                .add_leadership_event(LeadershipOutcome::SigningFailed);
                             Ok(None)
                         }
                     }
                 }
                 LeaderOutput::GenesisPraos(node_id, vrfproof) => {
-                    let block = block_builder(ver, contents, |hdr_builder| {
-                        let final_builder = hdr_builder
-                            .set_parent(&parent_id, chain_length)
-                            .set_date(date)
-                            .into_genesis_praos_builder()
-                            .expect("Valid Header Builder")
-                            .set_consensus_data(&node_id, &vrfproof.into());
-
-                        enclave
-                            .query_header_genesis_praos_finalize(final_builder)
-                            .map(|h| h.generalize())
-                    });
+                    // the first slots of a KES period can come before the key
+                    // evolution task wakes up for the period
+                    let block = match enclave.evolve_kes_key(slot_time).await {
+                        Ok(()) => block_builder(ver, contents, |hdr_builder| {
+                            let final_builder = hdr_builder
+                                .set_parent(&parent_id, chain_length)
+                                .set_date(date)
+                                .into_genesis_praos_builder()
+                                .expect("Valid Header Builder")
+                                .set_consensus_data(&node_id, &vrfproof.into());
+
+                            enclave
+                                .query_header_genesis_praos_finalize(final_builder, slot_time)
+                                .map(|h| h.generalize())
+                        }),
+                        Err(e) => Err(e),
+                    };
 
                     match block {
                         Ok(block) => Ok(Some(block)),
@@ -556,6 +622,8 @@ impl Module {
                                     reason: format!("Cannot sign the block: {}", e),
                                 })
                                 .await;
+                            self.metrics
+                                .add_leadership_event(LeadershipOutcome::SigningFailed);
                             Ok(None)
                         }
                     }
@@ -563,6 +631,15 @@ impl Module {
             }
         };
 
+        let assembly = BlockAssembly {
+            total_time: started.elapsed().into(),
+            fragment_selection_time: fragment_selection_time.into(),
+            fragment_count,
+            content_size,
+        };
+        self.metrics.set_block_assembly(&assembly);
+        event_logs.set_block_assembly(assembly).await;
+
         match signing {
             Ok(maybe_block) => {
                 if let Some(block) = maybe_block {
@@ -574,6 +651,7 @@ impl Module {
                         block,
                         new_ledger: ledger,
                         leadership,
+                        span: Span::current(),
                     };
                     sender
                         .send(BlockMsg::LeadershipBlock(Box::new(leadership_block)))
@@ -586,6 +664,7 @@ impl Module {
                             chain_length,
                         })
                         .await;
+                    self.metrics.add_leadership_event(LeadershipOutcome::Block);
                 };
                 Ok(())
             }
@@ -688,19 +767,213 @@ impl Entry {
     }
 }
 
+/// assemble candidate blocks on the tip, outside of the leadership schedule,
+/// to measure how long the block assembly takes
+#[derive(Clone)]
+pub struct DryRun {
+    pool: MessageBox<TransactionMsg>,
+    block_hard_deadline: u32,
+    metrics: Metrics,
+}
+
+impl DryRun {
+    pub fn new(
+        pool: MessageBox<TransactionMsg>,
+        block_hard_deadline: u32,
+        metrics: Metrics,
+    ) -> Self {
+        Self {
+            pool,
+            block_hard_deadline,
+            metrics,
+        }
+    }
+
+    /// assemble a block for the current slot, or the slot following the
+    /// tip if it is already at the current slot, with the same deadlines as
+    /// a leadership event. The fragments are selected by the algorithm of
+    /// the leadership events from copies of the oldest pending fragments of
+    /// the mempool, applied to a ledger of our own outside of the fragment
+    /// task, and the block is neither signed nor published.
+    pub async fn run(&self, tip_ref: Arc<Ref>) -> Result<BlockDryRun, LeadershipError> {
+        let started = Instant::now();
+        let era = tip_ref.epoch_leadership_schedule().era();
+        let now = SystemTime::now();
+        let current_slot = tip_ref
+            .time_frame()
+            .slot_at(now.as_ref())
+            .ok_or(LeadershipError::TooEarlyForTimeFrame { time: now })?;
+        let date = era
+            .from_slot_to_era(current_slot)
+            .map(|position| BlockDate {
+                epoch: position.epoch.0,
+                slot_id: position.slot.0,
+            })
+            .filter(|date| tip_ref.block_date() < *date)
+            .unwrap_or_else(|| tip_ref.block_date().next(era));
+        let chain_length = tip_ref.chain_length().increase();
+        let parent = tip_ref.hash();
+
+        let slot_duration = Duration::from_secs(tip_ref.time_frame().slot_duration());
+        let deadline = started + slot_duration;
+        let hard_deadline = started + slot_duration * self.block_hard_deadline;
+
+        let ledger = tokio::task::spawn_blocking(move || {
+            new_epoch_ledger_from(date.epoch, &tip_ref)
+                .and_then(|ledger| ledger.begin_block(chain_length, date))
+                .map_err(Box::new)
+        })
+        .await
+        .map_err(LeadershipError::DryRunTaskFailed)??;
+
+        let selection_started = Instant::now();
+        let max_size_bytes = ledger.settings().block_content_max_size as usize;
+        let fragments = pending_fragments(self.pool.clone(), max_size_bytes).await?;
+        let contents =
+            run_with_deadlines(deadline, hard_deadline, |soft_deadline, hard_deadline| {
+                selection::select_from_copies(
+                    FRAGMENT_SELECTION,
+                    ledger,
+                    fragments,
+                    soft_deadline,
+                    hard_deadline,
+                )
+            })
+            .await
+            .contents;
+        let fragment_selection_time = selection_started.elapsed();
+        let (fragment_count, content_size) = contents_stats(&contents);
+
+        block_builder(BlockVersion::Genesis, contents, |hdr_builder| {
+            Ok(hdr_builder
+                .set_parent(&parent, chain_length)
+                .set_date(date)
+                .into_unsigned_header()
+                .expect("Valid Header Builder")
+                .generalize())
+        })
+        .map_err(LeadershipError::CannotBuildBlock)?;
+
+        let assembly = BlockAssembly {
+            total_time: started.elapsed().into(),
+            fragment_selection_time: fragment_selection_time.into(),
+            fragment_count,
+            content_size,
+        };
+        self.metrics.add_leadership_event(LeadershipOutcome::DryRun);
+
+        Ok(BlockDryRun {
+            date: date.into(),
+            parent: parent.into(),
+            chain_length: chain_length.into(),
+            assembly,
+        })
+    }
+}
+
+async fn pending_fragments(
+    mut fragment_pool: MessageBox<TransactionMsg>,
+    max_size_bytes: usize,
+) -> Result<Vec<Fragment>, LeadershipError> {
+    let (reply_handle, reply_future) = unary_reply();
+
+    let msg = TransactionMsg::GetPendingFragments(max_size_bytes, reply_handle);
+
+    if fragment_pool.try_send(msg).is_err() {
+        tracing::error!("cannot send query to the fragment pool for the pending fragments");
+        Err(LeadershipError::CannotConnectToFragmentPool)
+    } else {
+        reply_future.await.map_err(Into::into)
+    }
+}
+
+struct AssembledContents {
+    leadership: EpochLeadership,
+    contents: Contents,
+    ledger: ApplyBlockLedger,
+    fragment_selection_time: Duration,
+}
+
+async fn assemble_contents(
+    tip_ref: Arc<Ref>,
+    date: BlockDate,
+    chain_length: ChainLength,
+    rewards_report_all: bool,
+    pool: MessageBox<TransactionMsg>,
+    soft_deadline_future: oneshot::Receiver<()>,
+    hard_deadline_future: oneshot::Receiver<()>,
+) -> Result<AssembledContents, LeadershipError> {
+    let leadership = new_epoch_leadership_from(date.epoch, tip_ref, rewards_report_all);
+    let ledger = leadership.state.clone();
+
+    let ledger = ledger.begin_block(chain_length, date).map_err(Box::new)?;
+
+    let selection_started = Instant::now();
+    let (contents, ledger) =
+        prepare_block(pool, ledger, soft_deadline_future, hard_deadline_future).await?;
+
+    Ok(AssembledContents {
+        leadership,
+        contents,
+        ledger,
+        fragment_selection_time: selection_started.elapsed(),
+    })
+}
+
+/// the number of fragments and the size of the contents of a block
+fn contents_stats(contents: &Contents) -> (usize, u32) {
+    let (_, content_size) = contents.compute_hash_size();
+    (contents.iter().count(), content_size)
+}
+
+/// run the block building future, notifying it through the given channels
+/// when the soft then the hard deadlines are reached
+async fn run_with_deadlines<F, Fut, T>(deadline: Instant, hard_deadline: Instant, build: F) -> T
+where
+    F: FnOnce(oneshot::Receiver<()>, oneshot::Receiver<()>) -> Fut,
+    Fut: Future<Output = T>,
+{
+    use futures::future::{select, Either};
+
+    let (soft_deadline_tx, soft_deadline_rx) = oneshot::channel();
+    let soft_deadline_future = tokio::time::sleep_until(tokio::time::Instant::from_std(deadline));
+
+    let (hard_deadline_tx, hard_deadline_rx) = oneshot::channel();
+    let hard_deadline_future =
+        tokio::time::sleep_until(tokio::time::Instant::from_std(hard_deadline));
+
+    let build_block_future = build(soft_deadline_rx, hard_deadline_rx);
+
+    match select(Box::pin(build_block_future), Box::pin(soft_deadline_future)).await {
+        Either::Left((result, _)) => result,
+        Either::Right((_, build_block_future)) => {
+            soft_deadline_tx.send(()).unwrap();
+            match select(build_block_future, Box::pin(hard_deadline_future)).await {
+                Either::Left((result, _)) => result,
+                Either::Right((_, build_block_future)) => {
+                    hard_deadline_tx.send(()).unwrap();
+                    build_block_future.await
+                }
+            }
+        }
+    }
+}
+
+/// the algorithm selecting the fragments of the blocks, in the dry runs as well
+const FRAGMENT_SELECTION: FragmentSelectionAlgorithmParams =
+    FragmentSelectionAlgorithmParams::OldestFirst;
+
 async fn prepare_block(
     mut fragment_pool: MessageBox<TransactionMsg>,
     ledger: ApplyBlockLedger,
-    soft_deadline_future: futures::channel::oneshot::Receiver<()>,
-    hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+    soft_deadline_future: oneshot::Receiver<()>,
+    hard_deadline_future: oneshot::Receiver<()>,
 ) -> Result<(Contents, ApplyBlockLedger), LeadershipError> {
-    use crate::fragment::selection::FragmentSelectionAlgorithmParams;
-
     let (reply_handle, reply_future) = unary_reply();
 
     let msg = TransactionMsg::SelectTransactions {
         ledger,
-        selection_alg: FragmentSelectionAlgorithmParams::OldestFirst,
+        selection_alg: FRAGMENT_SELECTION,
         reply_handle,
         soft_deadline_future,
         hard_deadline_future,
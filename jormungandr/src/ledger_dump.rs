@@ -0,0 +1,530 @@
+//! Offline inspection of the ledger kept in a node storage directory
+//!
+//! The blocks of the storage are replayed from the block0 up to the chosen
+//! block, with the same rules as when the node starts, and the state of the
+//! ledger after that block is written as JSON. The storage directory is
+//! copied to a temporary directory and only the copy is opened, so nothing is
+//! ever written to the storage. A node may be using the storage meanwhile, but
+//! the copy is only consistent if the node is stopped.
+
+use crate::{
+    blockcfg::{HeaderHash, Ledger},
+    blockchain::{
+        Blockchain, Error as BlockchainError, Ref, Storage, StorageError, MAIN_BRANCH_TAG,
+    },
+};
+use chain_core::property::FromStr;
+use chain_crypto::{PublicKey, PublicKeyFromStrError};
+use chain_impl_mockchain::{
+    account::{AccountAlg, Identifier},
+    certificate::PoolId,
+};
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{
+        AccountState, Address, BlockDate, Rewards as StakePoolRewards, StakePoolStats,
+        TaxTypeSerde, UTxOInfo, Value, VotePlanStatus,
+    },
+};
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use structopt::StructOpt;
+use thiserror::Error;
+
+/// number of block references kept in memory while replaying the storage
+const CACHE_CAPACITY: usize = 102_400;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("the storage directory '{}' does not exist", .0.display())]
+    NoStorage(PathBuf),
+    #[error("cannot copy the storage to a temporary directory")]
+    Snapshot(#[source] io::Error),
+    #[error("cannot read the storage")]
+    Storage(#[from] StorageError),
+    #[error("the storage holds no block0")]
+    NoBlock0,
+    #[error("the storage has no main branch")]
+    NoMainBranch,
+    #[error("the block {0} is not in the storage")]
+    BlockNotFound(HeaderHash),
+    #[error("the main branch is only {0} blocks long")]
+    ChainTooShort(u32),
+    #[error("cannot replay the blocks of the storage")]
+    Replay(#[from] BlockchainError),
+    #[error("cannot start the runtime")]
+    Runtime(#[source] io::Error),
+    #[error("cannot write the ledger dump")]
+    Output(#[source] io::Error),
+    #[error("cannot serialize the ledger dump")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// Dump the ledger of a node storage as JSON, at the tip of its main branch
+/// or at any of its blocks
+#[derive(StructOpt)]
+#[structopt(name = "jormungandr-ledger", rename_all = "kebab-case")]
+pub struct Command {
+    /// the storage directory of the node. It is copied whole to a temporary
+    /// directory, created under TMPDIR, and only the copy is read: this takes
+    /// as much free disk space as the storage itself. Stop the node first,
+    /// the copy of a storage in use may be inconsistent
+    #[structopt(long, parse(from_os_str))]
+    storage: PathBuf,
+
+    /// replay up to this block, the tip of the main branch if not given
+    #[structopt(long, conflicts_with = "chain-length")]
+    block: Option<HeaderHash>,
+
+    /// replay up to the block of the main branch at this chain length
+    #[structopt(long)]
+    chain_length: Option<u32>,
+
+    /// the parts of the ledger to dump, all of them if not given
+    #[structopt(
+        long,
+        use_delimiter = true,
+        possible_values = &["pots", "accounts", "utxos", "stake-pools", "vote-plans"]
+    )]
+    only: Vec<String>,
+
+    /// only dump these accounts, given by their public key in hexadecimal
+    #[structopt(long = "account", parse(try_from_str = parse_account_id))]
+    accounts: Vec<Identifier>,
+
+    /// only dump the UTxOs of these addresses
+    #[structopt(long = "address")]
+    addresses: Vec<Address>,
+
+    /// only dump these stake pools and the accounts delegating to them
+    #[structopt(long = "stake-pool")]
+    stake_pools: Vec<PoolId>,
+
+    /// only dump these vote plans
+    #[structopt(long = "vote-plan")]
+    vote_plans: Vec<Hash>,
+
+    /// only dump the accounts and UTxOs holding at least this value
+    #[structopt(long)]
+    min_value: Option<u64>,
+
+    /// write the dump to this file rather than to the standard output
+    #[structopt(long, parse(from_os_str))]
+    output: Option<PathBuf>,
+
+    /// indent the JSON output
+    #[structopt(long)]
+    pretty: bool,
+}
+
+/// what to dump out of the ledger
+#[derive(Default)]
+pub struct Filters {
+    pub sections: Vec<String>,
+    pub accounts: Vec<Identifier>,
+    pub addresses: Vec<Address>,
+    pub stake_pools: Vec<PoolId>,
+    pub vote_plans: Vec<Hash>,
+    pub min_value: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockInfo {
+    pub hash: String,
+    pub chain_length: u32,
+    pub date: BlockDate,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Pots {
+    pub treasury: Value,
+    pub rewards: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Account {
+    pub id: String,
+    #[serde(flatten)]
+    pub state: AccountState,
+    #[cfg(feature = "evm")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evm: Option<EvmAccount>,
+}
+
+#[cfg(feature = "evm")]
+#[derive(Debug, Serialize)]
+pub struct EvmAccount {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub nonce: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakePool {
+    pub id: String,
+    #[serde(flatten)]
+    pub stats: StakePoolStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LedgerDump {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pots: Option<Pots>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounts: Option<Vec<Account>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utxos: Option<Vec<UTxOInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stake_pools: Option<Vec<StakePool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vote_plans: Option<Vec<VotePlanStatus>>,
+}
+
+impl Filters {
+    fn section(&self, section: &str) -> bool {
+        self.sections.is_empty() || self.sections.iter().any(|s| s == section)
+    }
+
+    fn value(&self, value: u64) -> bool {
+        self.min_value.map_or(true, |min| value >= min)
+    }
+}
+
+pub fn dump(ledger: &Ledger, filters: &Filters) -> LedgerDump {
+    let pots = filters.section("pots").then(|| Pots {
+        treasury: ledger.treasury_value().into(),
+        rewards: ledger.remaining_rewards().into(),
+    });
+
+    let accounts = filters.section("accounts").then(|| {
+        ledger
+            .accounts()
+            .iter()
+            .filter(|(id, _)| filters.accounts.is_empty() || filters.accounts.contains(id))
+            .map(|(id, state)| (id, AccountState::from(state), state))
+            .filter(|(_, state, _)| filters.value((*state.value()).into()))
+            .filter(|(_, state, _)| {
+                filters.stake_pools.is_empty()
+                    || state.delegation().pools().iter().any(|(pool, _)| {
+                        filters
+                            .stake_pools
+                            .iter()
+                            .any(|id| Hash::from(id.clone()) == *pool)
+                    })
+            })
+            .map(|(id, state, _raw)| Account {
+                id: PublicKey::<AccountAlg>::from(id.clone()).to_string(),
+                state,
+                #[cfg(feature = "evm")]
+                evm: evm_account(ledger, id, _raw),
+            })
+            .collect()
+    });
+
+    let utxos = filters.section("utxos").then(|| {
+        ledger
+            .utxos()
+            .map(UTxOInfo::from)
+            .filter(|utxo| {
+                filters.addresses.is_empty()
+                    || filters
+                        .addresses
+                        .iter()
+                        .any(|address| address.1 == utxo.address().1)
+            })
+            .filter(|utxo| filters.value((*utxo.associated_fund()).into()))
+            .collect()
+    });
+
+    let stake_pools = filters.section("stake-pools").then(|| {
+        let distribution = ledger.get_stake_distribution();
+        ledger
+            .delegation()
+            .stake_pool_ids()
+            .filter(|id| filters.stake_pools.is_empty() || filters.stake_pools.contains(id))
+            .filter_map(|id| {
+                let pool = ledger.delegation().lookup(&id)?;
+                let total_stake = distribution
+                    .to_pools
+                    .get(&id)
+                    .map(|pool| pool.stake.total.into())
+                    .unwrap_or(0);
+                Some(StakePool {
+                    id: id.to_string(),
+                    stats: StakePoolStats {
+                        kes_public_key: pool.registration.keys.kes_public_key.to_bech32_str(),
+                        vrf_public_key: pool.registration.keys.vrf_public_key.to_bech32_str(),
+                        total_stake,
+                        rewards: StakePoolRewards {
+                            epoch: pool.last_rewards.epoch,
+                            value_taxed: pool.last_rewards.value_taxed,
+                            value_for_stakers: pool.last_rewards.value_for_stakers,
+                        },
+                        tax: TaxTypeSerde(pool.registration.rewards),
+                    },
+                })
+            })
+            .collect()
+    });
+
+    let vote_plans = filters.section("vote-plans").then(|| {
+        ledger
+            .active_vote_plans()
+            .into_iter()
+            .map(VotePlanStatus::from)
+            .filter(|vote_plan| {
+                filters.vote_plans.is_empty() || filters.vote_plans.contains(&vote_plan.id)
+            })
+            .collect()
+    });
+
+    LedgerDump {
+        block: None,
+        pots,
+        accounts,
+        utxos,
+        stake_pools,
+        vote_plans,
+    }
+}
+
+fn parse_account_id(id_hex: &str) -> Result<Identifier, PublicKeyFromStrError> {
+    PublicKey::<AccountAlg>::from_str(id_hex).map(Into::into)
+}
+
+#[cfg(feature = "evm")]
+fn evm_account(
+    ledger: &Ledger,
+    id: &Identifier,
+    state: &chain_impl_mockchain::accounting::account::AccountState<()>,
+) -> Option<EvmAccount> {
+    let address = ledger.get_evm_mapped_address(id).map(|a| a.to_string());
+    if address.is_none() && state.evm_state.code.is_empty() {
+        return None;
+    }
+    Some(EvmAccount {
+        address,
+        nonce: state.evm_state.nonce.to_string(),
+        code: hex::encode(&state.evm_state.code),
+    })
+}
+
+fn target_block(storage: &Storage, command: &Command) -> Result<HeaderHash, Error> {
+    if let Some(block) = command.block {
+        return if storage.block_exists(block)? {
+            Ok(block)
+        } else {
+            Err(Error::BlockNotFound(block))
+        };
+    }
+    let tip = storage
+        .get_tag(MAIN_BRANCH_TAG)?
+        .ok_or(Error::NoMainBranch)?;
+    let chain_length = match command.chain_length {
+        Some(chain_length) => chain_length,
+        None => return Ok(tip),
+    };
+    let tip_length = storage
+        .get_chain_length(tip)
+        .ok_or(Error::BlockNotFound(tip))?;
+    if chain_length > tip_length {
+        return Err(Error::ChainTooShort(tip_length));
+    }
+    let block = storage
+        .get_nth_ancestor(tip, tip_length - chain_length)?
+        .ok_or(Error::BlockNotFound(tip))?;
+    Ok(block.header().hash())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+async fn replay(command: &Command) -> Result<Arc<Ref>, Error> {
+    if !command.storage.is_dir() {
+        return Err(Error::NoStorage(command.storage.clone()));
+    }
+    // The storage backend has no read-only mode, so the blocks are read from
+    // a copy which is removed once the ledger is replayed.
+    let snapshot = tempfile::tempdir().map_err(Error::Snapshot)?;
+    copy_dir(&command.storage, snapshot.path()).map_err(Error::Snapshot)?;
+    let storage = Storage::file(snapshot.path(), tracing::Span::none())?;
+    let block0 = storage
+        .get_blocks_by_chain_length(0)?
+        .pop()
+        .ok_or(Error::NoBlock0)?;
+    let until = target_block(&storage, command)?;
+
+    let blockchain = Blockchain::new(block0.header().hash(), storage, CACHE_CAPACITY, false);
+    Ok(blockchain.replay_from_storage(block0, until).await?)
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    let runtime = tokio::runtime::Runtime::new().map_err(Error::Runtime)?;
+    let block_ref = runtime.block_on(replay(&command))?;
+
+    let filters = Filters {
+        sections: command.only.clone(),
+        accounts: command.accounts.clone(),
+        addresses: command.addresses.clone(),
+        stake_pools: command.stake_pools.clone(),
+        vote_plans: command.vote_plans.clone(),
+        min_value: command.min_value,
+    };
+    let mut ledger_dump = dump(&block_ref.ledger(), &filters);
+    ledger_dump.block = Some(BlockInfo {
+        hash: block_ref.hash().to_string(),
+        chain_length: block_ref.chain_length().into(),
+        date: block_ref.block_date().into(),
+    });
+
+    let mut output: Box<dyn Write> = match &command.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path).map_err(Error::Output)?)),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    if command.pretty {
+        serde_json::to_writer_pretty(&mut output, &ledger_dump)?;
+    } else {
+        serde_json::to_writer(&mut output, &ledger_dump)?;
+    }
+    writeln!(output)
+        .and_then(|()| output.flush())
+        .map_err(Error::Output)
+}
+
+pub fn main() {
+    use std::error::Error as _;
+
+    if let Err(error) = run(Command::from_args()) {
+        eprintln!("{}", error);
+        let mut source = error.source();
+        while let Some(err) = source {
+            eprintln!(" |-> {}", err);
+            source = err.source();
+        }
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockcfg::Block;
+    use chain_core::property::Block as _;
+    use jormungandr_lib::interfaces::block0_configuration_documented_example_block;
+
+    fn ledger() -> Ledger {
+        let block0 = block0_configuration_documented_example_block();
+        Ledger::new(block0.id(), block0.fragments()).unwrap()
+    }
+
+    /// a storage directory holding only the block0, as the main branch
+    fn storage_dir(block0: &Block) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::file(dir.path(), tracing::Span::none()).unwrap();
+        storage.put_block(block0).unwrap();
+        storage.put_tag(MAIN_BRANCH_TAG, block0.id()).unwrap();
+        dir
+    }
+
+    fn command(storage: &Path, args: &[&str]) -> Command {
+        let storage = storage.to_str().unwrap();
+        Command::from_iter(
+            ["jormungandr-ledger", "--storage", storage]
+                .iter()
+                .chain(args),
+        )
+    }
+
+    fn dir_contents(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut contents = Vec::new();
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                contents.extend(dir_contents(&path));
+            } else {
+                let bytes = fs::read(&path).unwrap();
+                contents.push((path, bytes));
+            }
+        }
+        contents.sort();
+        contents
+    }
+
+    #[tokio::test]
+    async fn replay_from_storage() {
+        let block0 = block0_configuration_documented_example_block();
+        let dir = storage_dir(&block0);
+        let before = dir_contents(dir.path());
+
+        let block_ref = replay(&command(dir.path(), &[])).await.unwrap();
+        assert_eq!(block_ref.hash(), block0.id());
+        assert_eq!(u32::from(block_ref.chain_length()), 0);
+        let accounts = dump(&block_ref.ledger(), &Filters::default()).accounts;
+        assert_eq!(accounts.unwrap().len(), 2);
+
+        // the storage is left untouched
+        assert_eq!(dir_contents(dir.path()), before);
+
+        match replay(&command(dir.path(), &["--chain-length", "1"])).await {
+            Err(Error::ChainTooShort(0)) => {}
+            other => panic!("unexpected replay result: {:?}", other.map(|r| r.hash())),
+        }
+    }
+
+    #[test]
+    fn dump_everything() {
+        let dump = dump(&ledger(), &Filters::default());
+        let pots = dump.pots.unwrap();
+        assert_eq!(u64::from(pots.treasury), 1_000_000_000_000);
+        assert_eq!(u64::from(pots.rewards), 100_000_000_000_000);
+        assert_eq!(dump.accounts.unwrap().len(), 2);
+        assert!(dump.utxos.unwrap().is_empty());
+        assert!(dump.stake_pools.unwrap().is_empty());
+        assert!(dump.vote_plans.unwrap().is_empty());
+    }
+
+    #[test]
+    fn sections_and_filters() {
+        let ledger = ledger();
+        let filters = Filters {
+            sections: vec!["accounts".to_owned()],
+            ..Filters::default()
+        };
+        let all = dump(&ledger, &filters);
+        assert!(all.pots.is_none() && all.utxos.is_none());
+        let accounts = all.accounts.unwrap();
+
+        let filters = Filters {
+            accounts: vec![parse_account_id(&accounts[0].id).unwrap()],
+            ..Filters::default()
+        };
+        let one = dump(&ledger, &filters).accounts.unwrap();
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].id, accounts[0].id);
+
+        let filters = Filters {
+            min_value: Some(10_001),
+            ..Filters::default()
+        };
+        assert!(dump(&ledger, &filters).accounts.unwrap().is_empty());
+    }
+}
@@ -8,7 +8,7 @@ extern crate lazy_static;
 extern crate serde_derive;
 
 use crate::{
-    blockcfg::{HeaderHash, Leader},
+    blockcfg::HeaderHash,
     blockchain::Blockchain,
     diagnostic::Diagnostic,
     metrics::MetricsBackend,
@@ -19,11 +19,12 @@ use crate::{
 use chain_impl_mockchain::leadership::LeadershipConsensus;
 use futures::{executor::block_on, prelude::*};
 use jormungandr_lib::interfaces::NodeState;
-use settings::{start::RawSettings, CommandLine};
+use settings::{logging::LogGuards, start::RawSettings, CommandLine};
 use std::{sync::Arc, time::Duration};
 use tokio::signal;
 use tokio_util::sync::CancellationToken;
 use tracing::{span, Level, Span};
+use zeroize::Zeroizing;
 
 pub mod blockcfg;
 pub mod blockchain;
@@ -33,6 +34,7 @@ pub mod diagnostic;
 pub mod fragment;
 pub mod intercom;
 pub mod jrpc;
+pub mod ledger_dump;
 pub mod leadership;
 pub mod log;
 pub mod metrics;
@@ -47,11 +49,10 @@ pub mod topology;
 pub mod utils;
 pub mod watch_client;
 
-use tracing_appender::non_blocking::WorkerGuard;
 use tracing_futures::Instrument;
 
-fn start() -> Result<(), start_up::Error> {
-    let initialized_node = initialize_node()?;
+fn start(env_passphrase: Option<Zeroizing<String>>) -> Result<(), start_up::Error> {
+    let initialized_node = initialize_node(env_passphrase)?;
 
     let bootstrapped_node = bootstrap(initialized_node)?;
 
@@ -63,10 +64,16 @@ pub struct BootstrappedNode {
     blockchain: Blockchain,
     blockchain_tip: blockchain::Tip,
     block0_hash: HeaderHash,
+    /// only needed by a genesis praos leader, the block0 of the other nodes
+    /// does not have to hold the KES settings
+    kes_schedule: Result<secure::kes::KesSchedule, blockcfg::Block0Error>,
     context: Option<context::ContextLock>,
     services: Services,
     initial_peers: Vec<topology::Peer>,
-    _logger_guards: Vec<WorkerGuard>,
+    known_peers: topology::KnownPeers,
+    stats_counter: metrics::Metrics,
+    simple_metrics_counter: Arc<metrics::backends::SimpleCounter>,
+    _logger_guards: LogGuards,
 }
 
 const BLOCK_TASK_QUEUE_LEN: usize = 32;
@@ -100,23 +107,7 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
     let leadership_logs =
         leadership::Logs::new(bootstrapped_node.settings.leadership.logs_capacity);
 
-    let metrics_builder = crate::metrics::Metrics::builder();
-
-    let simple_metrics_counter = Arc::new(crate::metrics::backends::SimpleCounter::new());
-    let metrics_builder = metrics_builder.add_backend(simple_metrics_counter.clone());
-
-    #[cfg(feature = "prometheus-metrics")]
-    let (prometheus_metric, metrics_builder) = if bootstrapped_node.settings.prometheus {
-        let prometheus = Arc::new(crate::metrics::backends::Prometheus::new());
-        (
-            Some(prometheus.clone()),
-            metrics_builder.add_backend(prometheus),
-        )
-    } else {
-        (None, metrics_builder)
-    };
-
-    let stats_counter = metrics_builder.build();
+    let stats_counter = bootstrapped_node.stats_counter;
 
     {
         let block_ref = services.block_on_task("get_tip_block", |_| blockchain_tip.get_ref());
@@ -173,10 +164,15 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
         });
     }
 
+    let peer_rules = Arc::new(network::p2p::rules::PeerRules::new(
+        bootstrapped_node.settings.storage.as_deref(),
+    ));
+
     // FIXME: reduce state sharing across services
     let network_state = Arc::new(network::GlobalState::new(
         bootstrapped_node.block0_hash,
         bootstrapped_node.settings.network.clone(),
+        peer_rules.clone(),
         stats_counter.clone(),
         span!(Level::TRACE, "task", kind = "network"),
     ));
@@ -210,6 +206,9 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
             initial_peers: bootstrapped_node.initial_peers,
             topology_queue,
             stats_counter: stats_counter.clone(),
+            storage: bootstrapped_node.settings.storage.clone(),
+            rules: peer_rules,
+            known_peers: bootstrapped_node.known_peers,
         };
 
         services.spawn_future("topology", move |_| topology::start(task_data));
@@ -232,18 +231,35 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
             None
         }
     });
+    let secret_passphrase = bootstrapped_node.settings.secret_passphrase;
     let node_secret = bootstrapped_node
         .settings
         .secret
         .map::<Result<_, start_up::Error>, _>(|secret_path| {
-            let secret = secure::NodeSecret::load_from_file(secret_path.as_path())?;
+            let secret =
+                secure::NodeSecret::load_from_file(secret_path.as_path(), &secret_passphrase)?;
             Ok(secret)
         })
         .transpose()?;
+    let (node_secret, secret_file) = match node_secret {
+        Some((secret, file)) => (Some(secret), Some(file)),
+        None => (None, None),
+    };
 
-    let leader_secret = node_secret.as_ref().map(|secret| {
-        if let (Some(leaders), Some(leader)) = (&bft_leaders, secret.bft()) {
-            let public_key = &leader.sig_key.to_public();
+    let kes_period = node_secret
+        .as_ref()
+        .and_then(secure::NodeSecret::kes_period);
+    #[cfg(feature = "evm")]
+    let evm_keys = Arc::new(
+        node_secret
+            .as_ref()
+            .map(|secret| secret.evm_keys())
+            .unwrap_or_default(),
+    );
+    let leader_secret = node_secret.map(|secret| {
+        let leader = secret.into_leader();
+        if let (Some(leaders), Some(bft_leader)) = (&bft_leaders, &leader.bft_leader) {
+            let public_key = &bft_leader.sig_key.to_public();
             if !leaders.contains(public_key) {
                 tracing::warn!(
                     "node was started with a BFT secret key but the corresponding \
@@ -252,21 +268,42 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
                 );
             }
         };
-        Leader {
-            bft_leader: secret.bft(),
-            genesis_leader: secret.genesis(),
-        }
+        leader
     });
-    let enclave = Enclave::new(leader_secret);
+    let kes_state = kes_period
+        .zip(secret_file)
+        .map::<Result<_, start_up::Error>, _>(|(period, secret_file)| {
+            let kes_schedule = bootstrapped_node.kes_schedule?;
+            let kes_state = secure::kes::KesState::new(kes_schedule, period, secret_file);
+            let current_period = kes_state.schedule().period_at(std::time::SystemTime::now());
+            if period > current_period {
+                tracing::warn!(
+                    "the KES key was evolved to period {} but the current period is {}, \
+                        the node will not sign blocks until then",
+                    period,
+                    current_period
+                );
+            }
+            Ok(kes_state)
+        })
+        .transpose()?;
+    let has_kes_key = kes_state.is_some();
+    let enclave = Enclave::new(leader_secret, kes_state);
 
-    #[cfg(feature = "evm")]
-    let evm_keys = Arc::new(
-        node_secret
-            .map(|secret| secret.evm_keys())
-            .unwrap_or_default(),
-    );
+    if has_kes_key {
+        let enclave = enclave.clone();
+        let expiry_warning = bootstrapped_node
+            .settings
+            .leadership
+            .kes_expiry_warning
+            .map_or(secure::kes::DEFAULT_EXPIRY_WARNING, Into::into);
+        let metrics = stats_counter.clone();
+        services.spawn_future("kes", move |_| {
+            secure::kes::evolve_kes_key(enclave, expiry_warning, metrics)
+        });
+    }
 
-    {
+    let leadership_dry_run = {
         let logs = leadership_logs.clone();
         let block_message = block_msgbox;
         let tip = blockchain_tip.clone();
@@ -274,6 +311,8 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
         let pool = fragment_msgbox.clone();
         let rewards_report_all = bootstrapped_node.settings.rewards_report_all;
         let block_hard_deadline = bootstrapped_node.settings.block_hard_deadline;
+        let metrics = stats_counter.clone();
+        let dry_run = leadership::DryRun::new(pool.clone(), block_hard_deadline, metrics.clone());
 
         services.spawn_try_future("leadership", move |service_info| {
             leadership::Module::new(leadership::ModuleConfig {
@@ -285,10 +324,19 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
                 block_message,
                 rewards_report_all,
                 block_hard_deadline,
+                metrics,
             })
             .and_then(|module| module.run())
         });
-    }
+
+        dry_run
+    };
+
+    let fragment_log_dir = bootstrapped_node
+        .settings
+        .mempool
+        .persistent_log
+        .map(|s| s.dir);
 
     {
         let blockchain_tip = blockchain_tip.clone();
@@ -297,11 +345,8 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
             bootstrapped_node.settings.mempool.log_max_entries.into(),
             network_msgbox.clone(),
         );
-        let fragment_log_dir = bootstrapped_node
-            .settings
-            .mempool
-            .persistent_log
-            .map(|s| s.dir);
+        let fragment_log_dir = fragment_log_dir.clone();
+        let stats_counter = stats_counter.clone();
 
         services.spawn_try_future("fragment", move |info| {
             process.start(
@@ -316,17 +361,16 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
 
     if let Some(context) = bootstrapped_node.context {
         let full_context = context::FullContext {
-            stats_counter: simple_metrics_counter,
+            stats_counter: bootstrapped_node.simple_metrics_counter,
             network_task: network_msgbox,
             transaction_task: fragment_msgbox,
             topology_task: topology_msgbox,
             leadership_logs,
+            leadership_dry_run,
             #[cfg(feature = "evm")]
             evm_keys,
             enclave,
             network_state,
-            #[cfg(feature = "prometheus-metrics")]
-            prometheus: prometheus_metric,
         };
         block_on(async {
             let mut context = context.write().await;
@@ -335,6 +379,22 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
         })
     };
 
+    {
+        let storage_dirs: Vec<_> = bootstrapped_node
+            .settings
+            .storage
+            .map(|dir| (metrics::StorageKind::Blocks, dir))
+            .into_iter()
+            .chain(fragment_log_dir.map(|dir| (metrics::StorageKind::FragmentLogs, dir)))
+            .collect();
+        if !storage_dirs.is_empty() {
+            let stats_counter = stats_counter.clone();
+            services.spawn_future("storage_size", move |_| {
+                metrics::storage::report_storage_size(storage_dirs, stats_counter)
+            });
+        }
+    }
+
     {
         let blockchain_tip = blockchain_tip;
         let no_blockchain_updates_warning_interval = bootstrapped_node
@@ -385,6 +445,31 @@ fn bootstrap(initialized_node: InitializedNode) -> Result<BootstrappedNode, star
         _logger_guards,
     } = initialized_node;
 
+    let kes_schedule = secure::kes::KesSchedule::from_block0(&block0);
+
+    let metrics_builder = metrics::Metrics::builder();
+
+    let simple_metrics_counter = Arc::new(metrics::backends::SimpleCounter::new());
+    let metrics_builder = metrics_builder.add_backend(simple_metrics_counter.clone());
+
+    #[cfg(feature = "prometheus-metrics")]
+    let metrics_builder = if settings.prometheus {
+        let prometheus = Arc::new(metrics::backends::Prometheus::new());
+        // served from the start of the bootstrap, to report its progress
+        if let Some(context) = &context {
+            block_on(async { context.write().await.set_prometheus(prometheus.clone()) });
+        }
+        metrics_builder.add_backend(prometheus)
+    } else {
+        metrics_builder
+    };
+
+    let stats_counter = metrics_builder.build();
+    services.set_metrics(stats_counter.clone());
+    if let Some(context) = &context {
+        block_on(async { context.write().await.set_metrics(stats_counter.clone()) });
+    }
+
     let BootstrapData {
         blockchain,
         blockchain_tip,
@@ -392,6 +477,7 @@ fn bootstrap(initialized_node: InitializedNode) -> Result<BootstrappedNode, star
         context,
         settings,
         initial_peers,
+        known_peers,
     } = services.block_on_task("bootstrap", |info| {
         bootstrap_internal(
             context,
@@ -400,6 +486,7 @@ fn bootstrap(initialized_node: InitializedNode) -> Result<BootstrappedNode, star
             storage,
             settings,
             cancellation_token,
+            stats_counter.clone(),
         )
     })?;
 
@@ -408,9 +495,13 @@ fn bootstrap(initialized_node: InitializedNode) -> Result<BootstrappedNode, star
         blockchain,
         blockchain_tip,
         block0_hash,
+        kes_schedule,
         context,
         services,
         initial_peers,
+        known_peers,
+        stats_counter,
+        simple_metrics_counter,
         _logger_guards,
     })
 }
@@ -422,6 +513,7 @@ struct BootstrapData {
     context: Option<context::ContextLock>,
     settings: Settings,
     initial_peers: Vec<topology::Peer>,
+    known_peers: topology::KnownPeers,
 }
 
 async fn bootstrap_internal(
@@ -431,6 +523,7 @@ async fn bootstrap_internal(
     storage: blockchain::Storage,
     settings: Settings,
     cancellation_token: CancellationToken,
+    stats_counter: metrics::Metrics,
 ) -> Result<BootstrapData, start_up::Error> {
     use futures::future::FutureExt;
 
@@ -458,6 +551,8 @@ async fn bootstrap_internal(
         context.set_bootstrap_stopper(cancellation_token.clone());
     };
 
+    let known_peers = topology::KnownPeers::new(settings.storage.as_deref());
+
     let mut bootstrap_attempt: usize = 0;
 
     let network_res = loop {
@@ -475,9 +570,11 @@ async fn bootstrap_internal(
         // Will return true if we successfully bootstrap or skip_bootstrap is set.
         let res = network::bootstrap(
             &settings.network,
+            known_peers.peers(),
             blockchain.clone(),
             blockchain_tip.clone(),
             cancellation_token.clone(),
+            &stats_counter,
             &span,
         )
         .await?;
@@ -510,7 +607,8 @@ async fn bootstrap_internal(
         settings,
         initial_peers: network_res
             .map(|res| res.initial_peers)
-            .unwrap_or_else(Vec::new),
+            .unwrap_or_else(|| known_peers.peers()),
+        known_peers,
     })
 }
 
@@ -521,7 +619,7 @@ pub struct InitializedNode {
     pub context: Option<context::ContextLock>,
     pub services: Services,
     pub cancellation_token: CancellationToken,
-    pub _logger_guards: Vec<WorkerGuard>,
+    pub _logger_guards: LogGuards,
 }
 
 #[cfg(unix)]
@@ -575,7 +673,9 @@ fn init_os_signal_watchers(services: &mut Services, token: CancellationToken) {
     });
 }
 
-fn initialize_node() -> Result<InitializedNode, start_up::Error> {
+fn initialize_node(
+    env_passphrase: Option<Zeroizing<String>>,
+) -> Result<InitializedNode, start_up::Error> {
     let command_line = CommandLine::load();
     let exit_after_storage_setup = command_line.storage_check;
 
@@ -608,7 +708,7 @@ fn initialize_node() -> Result<InitializedNode, start_up::Error> {
     let diagnostic = Diagnostic::new()?;
     tracing::debug!("system settings are: {}", diagnostic);
 
-    let settings = raw_settings.try_into_settings()?;
+    let settings = raw_settings.try_into_settings(env_passphrase)?;
 
     let storage = start_up::prepare_storage(&settings)?;
     if exit_after_storage_setup {
@@ -641,10 +741,29 @@ fn initialize_node() -> Result<InitializedNode, start_up::Error> {
         Some(rest_config) => {
             let context = init_context(diagnostic.clone());
 
+            let health = rest_config.health.unwrap_or_default();
+            let auth = rest_config
+                .auth
+                .as_ref()
+                .map(rest::auth::Config::load)
+                .transpose()?;
             let rest_config = rest::Config {
                 listen: rest_config.listen,
                 tls: rest_config.tls,
                 cors: rest_config.cors,
+                health: rest::health::Config {
+                    max_tip_age: health
+                        .max_tip_age
+                        .map(Into::into)
+                        .unwrap_or(settings.no_blockchain_updates_warning_interval),
+                    min_connected_peers: health
+                        .min_connected_peers
+                        .unwrap_or(rest::health::DEFAULT_MIN_CONNECTED_PEERS),
+                    storage: settings.storage.clone(),
+                },
+                auth,
+                limits: rest_config.limits,
+                max_fragments_per_batch: rest_config.max_fragments_per_batch,
                 #[cfg(feature = "prometheus-metrics")]
                 enable_prometheus: settings.prometheus,
             };
@@ -667,6 +786,7 @@ fn initialize_node() -> Result<InitializedNode, start_up::Error> {
 
             let jrpc_config = jrpc::Config {
                 listen: jrpc_config.listen,
+                limits: jrpc_config.limits,
             };
             let server_handler = jrpc::start_jrpc_server(jrpc_config, context.clone());
             let service_context = context.clone();
@@ -733,7 +853,10 @@ fn initialize_node() -> Result<InitializedNode, start_up::Error> {
 pub fn main() {
     use std::error::Error;
 
-    if let Err(error) = start() {
+    // before any other thread is started
+    let env_passphrase = secure::take_env_passphrase();
+
+    if let Err(error) = start(env_passphrase) {
         eprintln!("{}", error);
         let mut source = error.source();
         while let Some(err) = source {
@@ -1,3 +1,63 @@
+use opentelemetry::{
+    propagation::Extractor,
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+    Context,
+};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use warp::http::HeaderMap;
+
 pub const KEY_TASK: &str = "task";
 pub const KEY_SUB_TASK: &str = "sub_task";
 pub const KEY_SCOPE: &str = "scope";
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Attach the span to the trace of `origin`, the span of the task the work
+/// is done on behalf of, e.g. the sender of an intercom message.
+///
+/// Unlike an explicit parent this does not change the span's place in
+/// the logs, only in the exported traces.
+pub fn set_parent_from(span: &Span, origin: &Span) {
+    if !origin.is_none() {
+        span.set_parent(origin.context());
+    }
+}
+
+/// Attach the span to the trace of the remote caller, as given by the W3C
+/// `traceparent` header or, failing that, by the Zipkin B3 headers.
+///
+/// This has no effect unless the spans are exported to a trace collector.
+pub fn set_remote_parent(span: &Span, headers: &HeaderMap) {
+    let context = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(headers))
+    });
+    if context.span().span_context().is_valid() {
+        span.set_parent(context);
+        return;
+    }
+
+    if let Some(trace_context) = http_zipkin::get_trace_context(headers) {
+        let trace_id = TraceId::from_hex(&trace_context.trace_id().to_string());
+        let span_id = SpanId::from_hex(&trace_context.span_id().to_string());
+        if let (Ok(trace_id), Ok(span_id)) = (trace_id, span_id) {
+            let trace_flags = match trace_context.sampled() {
+                Some(false) => TraceFlags::default(),
+                _ => TraceFlags::SAMPLED,
+            };
+            let remote =
+                SpanContext::new(trace_id, span_id, trace_flags, true, TraceState::default());
+            span.set_parent(Context::new().with_remote_span_context(remote));
+        }
+    }
+}
@@ -1,4 +1,9 @@
-use crate::metrics::MetricsBackend;
+use crate::{
+    leadership::LeadershipOutcome,
+    metrics::{MetricsBackend, StorageKind},
+    network::p2p::traffic::{TrafficDirection, TrafficKind},
+    rest::limits::RequestRejection,
+};
 use arc_swap::ArcSwapOption;
 use chain_impl_mockchain::{
     block::BlockContentHash,
@@ -6,13 +11,29 @@ use chain_impl_mockchain::{
     transaction::Transaction,
     value::{Value, ValueError},
 };
+use jormungandr_lib::interfaces::BlockAssembly;
 use prometheus::{
-    core::{AtomicU64, GenericGauge},
-    Encoder, Gauge, IntCounter, Registry, TextEncoder,
+    core::{AtomicU64, GenericGauge, GenericGaugeVec},
+    Encoder, Gauge, Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts, Registry,
+    TextEncoder,
+};
+use std::{
+    convert::TryInto,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
-use std::{convert::TryInto, sync::Arc, time::SystemTime};
 
 type UIntGauge = GenericGauge<AtomicU64>;
+type UIntGaugeVec = GenericGaugeVec<AtomicU64>;
+
+// the delays are counted in seconds, from the slot start time of the
+// blocks or from the reception of the fragments
+const BLOCK_ARRIVAL_DELAY_BUCKETS: &[f64] = &[0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0];
+const FRAGMENT_INCLUSION_BUCKETS: &[f64] = &[
+    1.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0,
+];
+// the number of blocks rolled back by a branch switch
+const BRANCH_SWITCH_DEPTH_BUCKETS: &[f64] = &[1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 50.0, 100.0];
 
 pub struct Prometheus {
     registry: Registry,
@@ -27,6 +48,12 @@ pub struct Prometheus {
     peer_quarantined_cnt: UIntGauge,
     peer_available_cnt: UIntGauge,
     peer_total_cnt: UIntGauge,
+    peer_traffic_bytes: IntCounterVec,
+    peer_traffic_items: IntCounterVec,
+    peer_traffic_dropped: IntCounterVec,
+    leadership_events: IntCounterVec,
+    block_assembly_time: UIntGauge,
+    block_fragment_selection_time: UIntGauge,
     slot_start_time: UIntGauge,
     block_tx_count: UIntGauge,
     block_input_sum: UIntGauge,
@@ -37,6 +64,19 @@ pub struct Prometheus {
     block_chain_length: UIntGauge,
     block_time: UIntGauge,
     block_hash: Vec<UIntGauge>,
+    block_validation_time: Histogram,
+    block_application_time: Histogram,
+    block_arrival_delay: Histogram,
+    fragment_inclusion_time: Histogram,
+    branch_switch_cnt: IntCounter,
+    branch_switch_depth: Histogram,
+    bootstrap_blocks_received: UIntGauge,
+    bootstrap_blocks_expected: UIntGauge,
+    storage_size_bytes: UIntGaugeVec,
+    task_restarts: IntCounterVec,
+    kes_key_period: UIntGauge,
+    kes_key_remaining_seconds: UIntGauge,
+    requests_rejected: IntCounterVec,
 
     block_hash_value: ArcSwapOption<BlockContentHash>,
 }
@@ -112,6 +152,51 @@ impl Default for Prometheus {
             .unwrap();
         let peer_total_cnt = UIntGauge::new("peerTotalCnt", "peerTotalCnt").unwrap();
         registry.register(Box::new(peer_total_cnt.clone())).unwrap();
+        let peer_traffic_bytes = IntCounterVec::new(
+            Opts::new("peerTrafficBytes", "peerTrafficBytes"),
+            &["direction", "kind"],
+        )
+        .unwrap();
+        registry
+            .register(Box::new(peer_traffic_bytes.clone()))
+            .unwrap();
+        let peer_traffic_items = IntCounterVec::new(
+            Opts::new("peerTrafficItems", "peerTrafficItems"),
+            &["direction", "kind"],
+        )
+        .unwrap();
+        registry
+            .register(Box::new(peer_traffic_items.clone()))
+            .unwrap();
+        let peer_traffic_dropped = IntCounterVec::new(
+            Opts::new("peerTrafficDropped", "peerTrafficDropped"),
+            &["kind"],
+        )
+        .unwrap();
+        registry
+            .register(Box::new(peer_traffic_dropped.clone()))
+            .unwrap();
+        let leadership_events = IntCounterVec::new(
+            Opts::new("leadershipEvents", "leadershipEvents"),
+            &["outcome"],
+        )
+        .unwrap();
+        registry
+            .register(Box::new(leadership_events.clone()))
+            .unwrap();
+        let block_assembly_time =
+            UIntGauge::new("lastBlockAssemblyMillis", "lastBlockAssemblyMillis").unwrap();
+        registry
+            .register(Box::new(block_assembly_time.clone()))
+            .unwrap();
+        let block_fragment_selection_time = UIntGauge::new(
+            "lastBlockFragmentSelectionMillis",
+            "lastBlockFragmentSelectionMillis",
+        )
+        .unwrap();
+        registry
+            .register(Box::new(block_fragment_selection_time.clone()))
+            .unwrap();
         let slot_start_time =
             UIntGauge::new("lastReceivedBlockTime", "lastReceivedBlockTime").unwrap();
         registry
@@ -152,6 +237,88 @@ impl Default for Prometheus {
             pcs
         };
 
+        let block_validation_time = Histogram::with_opts(HistogramOpts::new(
+            "blockValidationSeconds",
+            "blockValidationSeconds",
+        ))
+        .unwrap();
+        registry
+            .register(Box::new(block_validation_time.clone()))
+            .unwrap();
+        let block_application_time = Histogram::with_opts(HistogramOpts::new(
+            "blockApplicationSeconds",
+            "blockApplicationSeconds",
+        ))
+        .unwrap();
+        registry
+            .register(Box::new(block_application_time.clone()))
+            .unwrap();
+        let block_arrival_delay = Histogram::with_opts(
+            HistogramOpts::new("blockArrivalDelaySeconds", "blockArrivalDelaySeconds")
+                .buckets(BLOCK_ARRIVAL_DELAY_BUCKETS.to_vec()),
+        )
+        .unwrap();
+        registry
+            .register(Box::new(block_arrival_delay.clone()))
+            .unwrap();
+        let fragment_inclusion_time = Histogram::with_opts(
+            HistogramOpts::new("fragmentInclusionSeconds", "fragmentInclusionSeconds")
+                .buckets(FRAGMENT_INCLUSION_BUCKETS.to_vec()),
+        )
+        .unwrap();
+        registry
+            .register(Box::new(fragment_inclusion_time.clone()))
+            .unwrap();
+        let branch_switch_cnt = IntCounter::new("branchSwitchCnt", "branchSwitchCnt").unwrap();
+        registry
+            .register(Box::new(branch_switch_cnt.clone()))
+            .unwrap();
+        let branch_switch_depth = Histogram::with_opts(
+            HistogramOpts::new("branchSwitchDepth", "branchSwitchDepth")
+                .buckets(BRANCH_SWITCH_DEPTH_BUCKETS.to_vec()),
+        )
+        .unwrap();
+        registry
+            .register(Box::new(branch_switch_depth.clone()))
+            .unwrap();
+        let bootstrap_blocks_received =
+            UIntGauge::new("bootstrapBlocksReceived", "bootstrapBlocksReceived").unwrap();
+        registry
+            .register(Box::new(bootstrap_blocks_received.clone()))
+            .unwrap();
+        let bootstrap_blocks_expected =
+            UIntGauge::new("bootstrapBlocksExpected", "bootstrapBlocksExpected").unwrap();
+        registry
+            .register(Box::new(bootstrap_blocks_expected.clone()))
+            .unwrap();
+        let storage_size_bytes =
+            UIntGaugeVec::new(Opts::new("storageSizeBytes", "storageSizeBytes"), &["kind"])
+                .unwrap();
+        registry
+            .register(Box::new(storage_size_bytes.clone()))
+            .unwrap();
+        let task_restarts = IntCounterVec::new(
+            Opts::new("taskRestarts", "taskRestarts"),
+            &["service", "task"],
+        )
+        .unwrap();
+        registry.register(Box::new(task_restarts.clone())).unwrap();
+        let kes_key_period = UIntGauge::new("kesKeyPeriod", "kesKeyPeriod").unwrap();
+        registry.register(Box::new(kes_key_period.clone())).unwrap();
+        let kes_key_remaining_seconds =
+            UIntGauge::new("kesKeyRemainingSeconds", "kesKeyRemainingSeconds").unwrap();
+        registry
+            .register(Box::new(kes_key_remaining_seconds.clone()))
+            .unwrap();
+        let requests_rejected = IntCounterVec::new(
+            Opts::new("requestsRejected", "requestsRejected"),
+            &["server", "reason"],
+        )
+        .unwrap();
+        registry
+            .register(Box::new(requests_rejected.clone()))
+            .unwrap();
+
         Self {
             registry,
             tx_recv_cnt,
@@ -164,6 +331,12 @@ impl Default for Prometheus {
             peer_quarantined_cnt,
             peer_available_cnt,
             peer_total_cnt,
+            peer_traffic_bytes,
+            peer_traffic_items,
+            peer_traffic_dropped,
+            leadership_events,
+            block_assembly_time,
+            block_fragment_selection_time,
             slot_start_time,
             block_tx_count,
             block_input_sum,
@@ -174,6 +347,19 @@ impl Default for Prometheus {
             block_chain_length,
             block_time,
             block_hash,
+            block_validation_time,
+            block_application_time,
+            block_arrival_delay,
+            fragment_inclusion_time,
+            branch_switch_cnt,
+            branch_switch_depth,
+            bootstrap_blocks_received,
+            bootstrap_blocks_expected,
+            storage_size_bytes,
+            task_restarts,
+            kes_key_period,
+            kes_key_remaining_seconds,
+            requests_rejected,
             block_hash_value: Default::default(),
         }
     }
@@ -303,4 +489,131 @@ impl MetricsBackend for Prometheus {
         let block_hash = block.header().hash();
         self.block_hash_value.store(Some(Arc::new(block_hash)));
     }
+
+    fn add_peer_traffic(&self, direction: TrafficDirection, kind: TrafficKind, bytes: u64) {
+        let labels = [direction.as_str(), kind.as_str()];
+        self.peer_traffic_bytes
+            .with_label_values(&labels)
+            .inc_by(bytes);
+        self.peer_traffic_items.with_label_values(&labels).inc();
+    }
+
+    fn add_peer_traffic_dropped(&self, kind: TrafficKind, count: usize) {
+        let count = count.try_into().unwrap_or(u64::MAX);
+        self.peer_traffic_dropped
+            .with_label_values(&[kind.as_str()])
+            .inc_by(count);
+    }
+
+    fn add_leadership_event(&self, outcome: LeadershipOutcome) {
+        self.leadership_events
+            .with_label_values(&[outcome.as_str()])
+            .inc();
+    }
+
+    fn set_block_assembly(&self, assembly: &BlockAssembly) {
+        let total_time = assembly.total_time.as_millis().try_into().unwrap();
+        self.block_assembly_time.set(total_time);
+        let fragment_selection_time = assembly
+            .fragment_selection_time
+            .as_millis()
+            .try_into()
+            .unwrap();
+        self.block_fragment_selection_time
+            .set(fragment_selection_time);
+    }
+
+    fn observe_block_validation_time(&self, time: Duration) {
+        self.block_validation_time.observe(time.as_secs_f64());
+    }
+
+    fn observe_block_application_time(&self, time: Duration) {
+        self.block_application_time.observe(time.as_secs_f64());
+    }
+
+    fn observe_block_arrival_delay(&self, delay: Duration) {
+        self.block_arrival_delay.observe(delay.as_secs_f64());
+    }
+
+    fn observe_fragment_inclusion_time(&self, time: Duration) {
+        self.fragment_inclusion_time.observe(time.as_secs_f64());
+    }
+
+    fn add_branch_switch(&self, depth: u32) {
+        self.branch_switch_cnt.inc();
+        self.branch_switch_depth.observe(depth.into());
+    }
+
+    fn set_bootstrap_progress(&self, block_received: u64, block_expected: Option<u64>) {
+        self.bootstrap_blocks_received.set(block_received);
+        self.bootstrap_blocks_expected
+            .set(block_expected.unwrap_or_default());
+    }
+
+    fn set_storage_size(&self, kind: StorageKind, bytes: u64) {
+        self.storage_size_bytes
+            .with_label_values(&[kind.as_str()])
+            .set(bytes);
+    }
+
+    fn add_task_restart(&self, service: &'static str, task: &'static str) {
+        self.task_restarts.with_label_values(&[service, task]).inc();
+    }
+
+    fn set_kes_key(&self, period: u32, remaining_lifetime: Duration) {
+        self.kes_key_period.set(period.into());
+        self.kes_key_remaining_seconds
+            .set(remaining_lifetime.as_secs());
+    }
+
+    fn add_request_rejected(&self, server: &'static str, reason: RequestRejection) {
+        self.requests_rejected
+            .with_label_values(&[server, reason.as_str()])
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latencies_are_observed() {
+        let prometheus = Prometheus::new();
+        prometheus.observe_block_validation_time(Duration::from_millis(20));
+        prometheus.observe_block_application_time(Duration::from_millis(30));
+        prometheus.observe_block_arrival_delay(Duration::from_secs(3));
+        prometheus.observe_fragment_inclusion_time(Duration::from_secs(40));
+        prometheus.observe_fragment_inclusion_time(Duration::from_secs(50));
+
+        assert_eq!(prometheus.block_validation_time.get_sample_count(), 1);
+        assert_eq!(prometheus.block_application_time.get_sample_count(), 1);
+        assert_eq!(prometheus.block_arrival_delay.get_sample_count(), 1);
+        assert!((prometheus.block_arrival_delay.get_sample_sum() - 3.0).abs() < f64::EPSILON);
+        assert_eq!(prometheus.fragment_inclusion_time.get_sample_count(), 2);
+        assert!((prometheus.fragment_inclusion_time.get_sample_sum() - 90.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn events_are_counted() {
+        let prometheus = Prometheus::new();
+        prometheus.add_branch_switch(2);
+        prometheus.add_branch_switch(5);
+        prometheus.set_bootstrap_progress(10, Some(100));
+        prometheus.set_storage_size(StorageKind::Blocks, 4096);
+        prometheus.add_task_restart("block", "collect stale branches");
+
+        assert_eq!(prometheus.branch_switch_cnt.get(), 2);
+        assert_eq!(prometheus.branch_switch_depth.get_sample_count(), 2);
+        assert_eq!(prometheus.bootstrap_blocks_received.get(), 10);
+        assert_eq!(prometheus.bootstrap_blocks_expected.get(), 100);
+        let storage = prometheus
+            .storage_size_bytes
+            .with_label_values(&[StorageKind::Blocks.as_str()]);
+        assert_eq!(storage.get(), 4096);
+        let restarts = prometheus
+            .task_restarts
+            .with_label_values(&["block", "collect stale branches"]);
+        assert_eq!(restarts.get(), 1);
+    }
 }
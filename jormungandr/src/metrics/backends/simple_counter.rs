@@ -1,4 +1,10 @@
-use crate::{blockchain::Ref, metrics::MetricsBackend};
+use crate::{
+    blockchain::Ref,
+    leadership::LeadershipOutcome,
+    metrics::{MetricsBackend, StorageKind},
+    network::p2p::traffic::{TrafficDirection, TrafficKind},
+    rest::limits::RequestRejection,
+};
 use arc_swap::ArcSwapOption;
 use chain_impl_mockchain::{
     block::Block,
@@ -7,7 +13,7 @@ use chain_impl_mockchain::{
     value::{Value, ValueError},
 };
 use jormungandr_lib::{
-    interfaces::NodeStats,
+    interfaces::{BlockAssembly, NodeStats},
     time::{SecondsSinceUnixEpoch, SystemTime},
 };
 use std::{
@@ -16,7 +22,7 @@ use std::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc, RwLock,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 const EXP_MOVING_AVERAGE_COEFF: f64 = 0.5;
@@ -35,6 +41,13 @@ pub struct SimpleCounter {
     peers_connected_cnt: AtomicUsize,
     peers_quarantined_cnt: AtomicUsize,
     peers_available_cnt: AtomicUsize,
+    network_bytes_received: AtomicU64,
+    network_bytes_sent: AtomicU64,
+    network_items_dropped: AtomicU64,
+    branch_switch_cnt: AtomicU64,
+    // zero until the size of the blocks storage is first computed
+    storage_size: AtomicU64,
+    task_restart_cnt: AtomicU64,
     tip_block: ArcSwapOption<BlockCounters>,
     start_time: Instant,
 }
@@ -100,6 +113,12 @@ impl SimpleCounter {
                 .unwrap(),
             votes_cast: self.votes_cast.load(Ordering::Relaxed),
             uptime: Some(self.start_time.elapsed().as_secs()),
+            network_bytes_received: self.network_bytes_received.load(Ordering::Relaxed),
+            network_bytes_sent: self.network_bytes_sent.load(Ordering::Relaxed),
+            network_items_dropped: self.network_items_dropped.load(Ordering::Relaxed),
+            branch_switch_cnt: self.branch_switch_cnt.load(Ordering::Relaxed),
+            storage_size: Some(self.storage_size.load(Ordering::Relaxed)).filter(|size| *size > 0),
+            task_restart_cnt: self.task_restart_cnt.load(Ordering::Relaxed),
         }
     }
 }
@@ -117,6 +136,12 @@ impl Default for SimpleCounter {
             peers_connected_cnt: Default::default(),
             peers_quarantined_cnt: Default::default(),
             peers_available_cnt: Default::default(),
+            network_bytes_received: Default::default(),
+            network_bytes_sent: Default::default(),
+            network_items_dropped: Default::default(),
+            branch_switch_cnt: Default::default(),
+            storage_size: Default::default(),
+            task_restart_cnt: Default::default(),
             tip_block: Default::default(),
             start_time: Instant::now(),
         }
@@ -242,4 +267,78 @@ impl MetricsBackend for SimpleCounter {
         self.votes_cast.fetch_add(votes_cast, Ordering::Relaxed);
         self.tip_block.store(Some(Arc::new(block_data)));
     }
+
+    fn add_peer_traffic(&self, direction: TrafficDirection, _kind: TrafficKind, bytes: u64) {
+        let counter = match direction {
+            TrafficDirection::Inbound => &self.network_bytes_received,
+            TrafficDirection::Outbound => &self.network_bytes_sent,
+        };
+        counter.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn add_peer_traffic_dropped(&self, _kind: TrafficKind, count: usize) {
+        self.network_items_dropped
+            .fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    // the outcome of every leadership event is available in the leadership logs
+    fn add_leadership_event(&self, _outcome: LeadershipOutcome) {}
+
+    fn set_block_assembly(&self, _assembly: &BlockAssembly) {}
+
+    // the latency distributions are only exported to Prometheus
+    fn observe_block_validation_time(&self, _time: Duration) {}
+
+    fn observe_block_application_time(&self, _time: Duration) {}
+
+    fn observe_block_arrival_delay(&self, _delay: Duration) {}
+
+    fn observe_fragment_inclusion_time(&self, _time: Duration) {}
+
+    fn add_branch_switch(&self, _depth: u32) {
+        self.branch_switch_cnt.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // the node stats are only served once the bootstrap is over
+    fn set_bootstrap_progress(&self, _block_received: u64, _block_expected: Option<u64>) {}
+
+    fn set_storage_size(&self, kind: StorageKind, bytes: u64) {
+        if kind == StorageKind::Blocks {
+            self.storage_size.store(bytes, Ordering::Relaxed);
+        }
+    }
+
+    fn add_task_restart(&self, _service: &'static str, _task: &'static str) {
+        self.task_restart_cnt.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // served by the dedicated leaders endpoint
+    fn set_kes_key(&self, _period: u32, _remaining_lifetime: Duration) {}
+
+    fn add_request_rejected(&self, _server: &'static str, _reason: RequestRejection) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_stats_are_counted() {
+        let counter = SimpleCounter::new();
+        assert_eq!(counter.get_stats().storage_size, None);
+
+        counter.add_branch_switch(3);
+        counter.set_storage_size(StorageKind::FragmentLogs, 10);
+        counter.add_task_restart("block", "collect stale branches");
+        counter.add_task_restart("block", "collect stale branches");
+
+        let stats = counter.get_stats();
+        assert_eq!(stats.branch_switch_cnt, 1);
+        // only the size of the blocks storage is shown
+        assert_eq!(stats.storage_size, None);
+        assert_eq!(stats.task_restart_cnt, 2);
+
+        counter.set_storage_size(StorageKind::Blocks, 4096);
+        assert_eq!(counter.get_stats().storage_size, Some(4096));
+    }
 }
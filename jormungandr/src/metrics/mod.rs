@@ -1,9 +1,17 @@
-use crate::blockchain::Ref;
+use crate::{
+    blockchain::Ref,
+    leadership::LeadershipOutcome,
+    network::p2p::traffic::{TrafficDirection, TrafficKind},
+    rest::limits::RequestRejection,
+};
 use chain_impl_mockchain::block::Block;
-use jormungandr_lib::time::SecondsSinceUnixEpoch;
-use std::sync::Arc;
+use jormungandr_lib::{interfaces::BlockAssembly, time::SecondsSinceUnixEpoch};
+use std::{sync::Arc, time::Duration};
 
 pub mod backends;
+pub mod storage;
+
+pub use storage::StorageKind;
 
 pub trait MetricsBackend {
     fn add_tx_recv_cnt(&self, count: usize);
@@ -18,6 +26,20 @@ pub trait MetricsBackend {
     fn set_peer_available_cnt(&self, count: usize);
     fn set_slot_start_time(&self, time: SecondsSinceUnixEpoch);
     fn set_tip_block(&self, block: &Block, block_ref: &Ref);
+    fn add_peer_traffic(&self, direction: TrafficDirection, kind: TrafficKind, bytes: u64);
+    fn add_peer_traffic_dropped(&self, kind: TrafficKind, count: usize);
+    fn add_leadership_event(&self, outcome: LeadershipOutcome);
+    fn set_block_assembly(&self, assembly: &BlockAssembly);
+    fn observe_block_validation_time(&self, time: Duration);
+    fn observe_block_application_time(&self, time: Duration);
+    fn observe_block_arrival_delay(&self, delay: Duration);
+    fn observe_fragment_inclusion_time(&self, time: Duration);
+    fn add_branch_switch(&self, depth: u32);
+    fn set_bootstrap_progress(&self, block_received: u64, block_expected: Option<u64>);
+    fn set_storage_size(&self, kind: StorageKind, bytes: u64);
+    fn add_task_restart(&self, service: &'static str, task: &'static str);
+    fn set_kes_key(&self, period: u32, remaining_lifetime: Duration);
+    fn add_request_rejected(&self, server: &'static str, reason: RequestRejection);
 }
 
 #[derive(Clone)]
@@ -83,4 +105,60 @@ impl MetricsBackend for Metrics {
             backend.set_tip_block(block, block_ref);
         }
     }
+
+    fn add_peer_traffic(&self, direction: TrafficDirection, kind: TrafficKind, bytes: u64) {
+        for backend in &self.backends {
+            backend.add_peer_traffic(direction, kind, bytes);
+        }
+    }
+
+    fn add_peer_traffic_dropped(&self, kind: TrafficKind, count: usize) {
+        for backend in &self.backends {
+            backend.add_peer_traffic_dropped(kind, count);
+        }
+    }
+
+    metrics_method!(add_leadership_event, LeadershipOutcome);
+
+    fn set_block_assembly(&self, assembly: &BlockAssembly) {
+        for backend in &self.backends {
+            backend.set_block_assembly(assembly);
+        }
+    }
+
+    metrics_method!(observe_block_validation_time, Duration);
+    metrics_method!(observe_block_application_time, Duration);
+    metrics_method!(observe_block_arrival_delay, Duration);
+    metrics_method!(observe_fragment_inclusion_time, Duration);
+    metrics_method!(add_branch_switch, u32);
+
+    fn set_bootstrap_progress(&self, block_received: u64, block_expected: Option<u64>) {
+        for backend in &self.backends {
+            backend.set_bootstrap_progress(block_received, block_expected);
+        }
+    }
+
+    fn set_storage_size(&self, kind: StorageKind, bytes: u64) {
+        for backend in &self.backends {
+            backend.set_storage_size(kind, bytes);
+        }
+    }
+
+    fn add_task_restart(&self, service: &'static str, task: &'static str) {
+        for backend in &self.backends {
+            backend.add_task_restart(service, task);
+        }
+    }
+
+    fn set_kes_key(&self, period: u32, remaining_lifetime: Duration) {
+        for backend in &self.backends {
+            backend.set_kes_key(period, remaining_lifetime);
+        }
+    }
+
+    fn add_request_rejected(&self, server: &'static str, reason: RequestRejection) {
+        for backend in &self.backends {
+            backend.add_request_rejected(server, reason);
+        }
+    }
 }
@@ -0,0 +1,63 @@
+use crate::metrics::{Metrics, MetricsBackend};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::time::interval;
+
+const STORAGE_SIZE_CHECK_PERIOD: Duration = Duration::from_secs(60);
+
+/// the on-disk data of the node whose size is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    /// the blocks storage and the files kept next to it
+    Blocks,
+    /// the persistent fragment logs
+    FragmentLogs,
+}
+
+impl StorageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageKind::Blocks => "blocks",
+            StorageKind::FragmentLogs => "fragment_logs",
+        }
+    }
+}
+
+/// Periodically report the size of the given directories. The directories
+/// are walked on the blocking threads, as they can hold many files.
+pub async fn report_storage_size(dirs: Vec<(StorageKind, PathBuf)>, metrics: Metrics) {
+    let mut interval = interval(STORAGE_SIZE_CHECK_PERIOD);
+
+    loop {
+        interval.tick().await;
+        for (kind, dir) in &dirs {
+            let dir = dir.clone();
+            match tokio::task::spawn_blocking(move || dir_size(&dir)).await {
+                Ok(Ok(bytes)) => metrics.set_storage_size(*kind, bytes),
+                Ok(Err(e)) => tracing::warn!(
+                    reason = %e,
+                    "cannot compute the size of the {} storage",
+                    kind.as_str()
+                ),
+                Err(e) => tracing::error!(reason = %e, "storage size computation failed"),
+            }
+        }
+    }
+}
+
+fn dir_size(dir: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
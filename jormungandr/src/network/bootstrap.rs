@@ -1,14 +1,23 @@
 use super::grpc;
 use crate::{
+    blockcfg::HeaderHash,
     blockchain::{self, Blockchain, BootstrapError, Error as BlockchainError, Tip},
-    network::convert::Decode,
+    metrics::Metrics,
+    network::convert::{Decode, Encode},
     settings::start::network::Peer,
     topology,
 };
 use chain_core::property::ReadError;
 use chain_network::{data as net_data, error::Error as NetworkError};
-use futures::prelude::*;
-use std::fmt::Debug;
+use futures::{channel::mpsc, prelude::*, stream::FuturesUnordered};
+use jormungandr_lib::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Debug,
+    net::SocketAddr,
+    time::Instant,
+};
 use tokio_util::sync::CancellationToken;
 
 #[derive(thiserror::Error, Debug)]
@@ -33,6 +42,18 @@ pub enum Error {
     GcFailed(#[source] Box<BlockchainError>),
     #[error("bootstrap pull stream failed")]
     PullStreamFailed(#[source] NetworkError),
+    #[error("bootstrap headers request failed")]
+    PullHeadersFailed(#[source] NetworkError),
+    #[error("the headers received from the peer are not chained")]
+    HeadersNotChained,
+    #[error("bootstrap blocks request failed")]
+    GetBlocksFailed(#[source] NetworkError),
+    #[error("the peer did not send the requested blocks")]
+    UnexpectedBlocks,
+    #[error("the peer did not send the requested blocks in time")]
+    GetBlocksTimeout,
+    #[error("all the bootstrap peers failed")]
+    AllPeersFailed,
     #[error("Trusted peers cannot be empty. To avoid bootstrap use `skip_bootstrap: true`")]
     EmptyTrustedPeers,
     #[error("the bootstrap process was interrupted")]
@@ -40,6 +61,53 @@ pub enum Error {
 }
 
 const MAX_BOOTSTRAP_PEERS: u32 = 32;
+/// number of failed requests after which a peer is not used anymore
+/// during a parallel bootstrap
+const MAX_PEER_FAILURES: u32 = 3;
+/// number of blocks buffered between the download and their application
+const BLOCKS_BUFFER: usize = 1024;
+
+/// Settings of the parallel bootstrap, which fetches the headers up to the
+/// tip from one peer, then downloads the blocks by chunks from several peers
+/// at once and applies them in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParallelBootstrapConfig {
+    /// number of peers to download the blocks from at once
+    #[serde(default = "ParallelBootstrapConfig::default_peers")]
+    pub peers: usize,
+    /// number of blocks requested at once from a peer
+    #[serde(default = "ParallelBootstrapConfig::default_chunk_size")]
+    pub chunk_size: usize,
+    /// time after which a request for a chunk of blocks is given up and the
+    /// chunk is requested from another peer
+    #[serde(default = "ParallelBootstrapConfig::default_chunk_timeout")]
+    pub chunk_timeout: Duration,
+}
+
+impl ParallelBootstrapConfig {
+    fn default_peers() -> usize {
+        4
+    }
+
+    fn default_chunk_size() -> usize {
+        500
+    }
+
+    fn default_chunk_timeout() -> Duration {
+        std::time::Duration::from_secs(60).into()
+    }
+}
+
+impl Default for ParallelBootstrapConfig {
+    fn default() -> Self {
+        ParallelBootstrapConfig {
+            peers: Self::default_peers(),
+            chunk_size: Self::default_chunk_size(),
+            chunk_timeout: Self::default_chunk_timeout(),
+        }
+    }
+}
 
 pub async fn peers_from_trusted_peer(peer: &Peer) -> Result<Vec<topology::Peer>, Error> {
     tracing::info!("getting peers from bootstrap peer {}", peer.connection);
@@ -61,26 +129,27 @@ pub async fn peers_from_trusted_peer(peer: &Peer) -> Result<Vec<topology::Peer>,
     Ok(peers)
 }
 
+async fn with_cancellation_token<T>(
+    future: impl Future<Output = T> + Unpin,
+    token: &CancellationToken,
+) -> Result<T, Error> {
+    use futures::future::{select, Either};
+
+    match select(future, token.cancelled().boxed()).await {
+        Either::Left((result, _)) => Ok(result),
+        Either::Right(((), _)) => Err(Error::Interrupted),
+    }
+}
+
 pub async fn bootstrap_from_peer(
     peer: &Peer,
     blockchain: Blockchain,
     tip: Tip,
     cancellation_token: CancellationToken,
+    metrics: &Metrics,
 ) -> Result<(), Error> {
     use chain_network::data::BlockId;
 
-    async fn with_cancellation_token<T>(
-        future: impl Future<Output = T> + Unpin,
-        token: &CancellationToken,
-    ) -> Result<T, Error> {
-        use futures::future::{select, Either};
-
-        match select(future, token.cancelled().boxed()).await {
-            Either::Left((result, _)) => Ok(result),
-            Either::Right(((), _)) => Err(Error::Interrupted),
-        }
-    }
-
     tracing::debug!("connecting to bootstrap peer {}", peer.connection);
 
     let mut client = with_cancellation_token(grpc::connect(peer).boxed(), &cancellation_token)
@@ -91,13 +160,20 @@ pub async fn bootstrap_from_peer(
         let remote_tip = with_cancellation_token(client.tip().boxed(), &cancellation_token)
             .await?
             .and_then(|header| header.decode())
-            .map_err(Error::TipFailed)?
-            .id();
+            .map_err(Error::TipFailed)?;
+        let local_tip = tip.get_ref().await;
 
-        if remote_tip == tip.get_ref().await.hash() {
+        if remote_tip.id() == local_tip.hash() {
             break Ok(());
         }
 
+        // only an estimate if the peer is on another branch, as the blocks
+        // are then streamed from the common ancestor
+        let block_expected = u32::from(remote_tip.chain_length())
+            .checked_sub(u32::from(local_tip.chain_length()))
+            .map(u64::from);
+        let remote_tip = remote_tip.id();
+
         let checkpoints = blockchain.get_checkpoints(&tip.branch().await);
         let checkpoints = net_data::block::try_ids_from_iter(checkpoints).unwrap();
 
@@ -120,9 +196,462 @@ pub async fn bootstrap_from_peer(
             blockchain.clone(),
             tip.clone(),
             stream,
+            block_expected,
             cancellation_token.clone(),
+            metrics.clone(),
         )
         .await
         .map_err(Box::new)?;
     }
 }
+
+/// A peer blocks are downloaded from during a parallel bootstrap
+struct DownloadPeer {
+    addr: SocketAddr,
+    client: grpc::Client,
+    failures: u32,
+    blocks: u64,
+    bytes: u64,
+    busy: std::time::Duration,
+}
+
+/// A peer chunks of blocks are requested from during a parallel bootstrap
+trait ChunkSource {
+    fn addr(&self) -> SocketAddr;
+
+    /// record a failed request, returning the number of failures so far
+    fn add_failure(&mut self) -> u32;
+
+    fn report_throughput(&self);
+}
+
+impl ChunkSource for DownloadPeer {
+    fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    fn add_failure(&mut self) -> u32 {
+        self.failures += 1;
+        self.failures
+    }
+
+    fn report_throughput(&self) {
+        let secs = self.busy.as_secs_f64();
+        let kbs = if secs > 0.0 {
+            self.bytes as f64 / 1024.0 / secs
+        } else {
+            0.0
+        };
+        tracing::info!(
+            peer = %self.addr,
+            blocks = self.blocks,
+            "downloaded {} bytes at {:.2}kb/s",
+            self.bytes,
+            kbs
+        );
+    }
+}
+
+/// Bootstrap from several peers at once, see [`ParallelBootstrapConfig`].
+///
+/// The headers are fetched from the first peer able to provide them, the
+/// peers being tried in order. Peers failing or timing out repeatedly are
+/// dropped, and their chunks requested from the other peers.
+pub async fn bootstrap_from_peers(
+    peers: &[Peer],
+    config: &ParallelBootstrapConfig,
+    blockchain: Blockchain,
+    tip: Tip,
+    cancellation_token: CancellationToken,
+    metrics: &Metrics,
+) -> Result<(), Error> {
+    let mut download_peers = Vec::with_capacity(config.peers);
+    let mut connect_error = None;
+    for peer in peers {
+        if download_peers.len() == config.peers.max(1) {
+            break;
+        }
+        match with_cancellation_token(grpc::connect(peer).boxed(), &cancellation_token).await? {
+            Ok(client) => download_peers.push(DownloadPeer {
+                addr: peer.connection,
+                client,
+                failures: 0,
+                blocks: 0,
+                bytes: 0,
+                busy: std::time::Duration::ZERO,
+            }),
+            Err(e) => {
+                tracing::warn!(peer = %peer.connection, reason = %e, "unable to reach peer for parallel bootstrap");
+                connect_error = Some(e);
+            }
+        }
+    }
+    if download_peers.is_empty() {
+        return Err(connect_error.map_or(Error::AllPeersFailed, Error::Connect));
+    }
+    tracing::info!(
+        peers = download_peers.len(),
+        "bootstrapping from several peers at once"
+    );
+
+    loop {
+        let ids =
+            fetch_headers_from_any(&mut download_peers, &blockchain, &tip, &cancellation_token)
+                .await?;
+        if ids.is_empty() {
+            break;
+        }
+        tracing::info!(
+            blocks = ids.len(),
+            "fetched the headers, downloading the blocks"
+        );
+
+        let (sink, stream) = mpsc::channel(BLOCKS_BUFFER);
+        let timeout: std::time::Duration = config.chunk_timeout.into();
+        let download = download_in_order(
+            download_peers,
+            &ids,
+            config.chunk_size,
+            |peer, index, ids| fetch_chunk(peer, index, ids, timeout),
+            sink,
+        );
+        let apply = blockchain::bootstrap_from_stream(
+            blockchain.clone(),
+            tip.clone(),
+            stream,
+            Some(ids.len() as u64),
+            cancellation_token.clone(),
+            metrics.clone(),
+        );
+        let (download_result, apply_result) = future::join(
+            with_cancellation_token(download.boxed(), &cancellation_token),
+            apply,
+        )
+        .await;
+        apply_result.map_err(Box::new)?;
+        let (peers_left, result) = download_result?;
+        for peer in &peers_left {
+            peer.report_throughput();
+        }
+        result?;
+        download_peers = peers_left;
+    }
+
+    Ok(())
+}
+
+/// Fetch the headers from the first of the peers able to provide them. The
+/// peers are dropped after too many failures, like during the download.
+async fn fetch_headers_from_any(
+    peers: &mut Vec<DownloadPeer>,
+    blockchain: &Blockchain,
+    tip: &Tip,
+    cancellation_token: &CancellationToken,
+) -> Result<Vec<HeaderHash>, Error> {
+    let mut last_error = Error::AllPeersFailed;
+    let mut i = 0;
+    while i < peers.len() {
+        match fetch_headers(&mut peers[i].client, blockchain, tip, cancellation_token).await {
+            Ok(ids) => return Ok(ids),
+            Err(Error::Interrupted) => return Err(Error::Interrupted),
+            Err(e) => {
+                tracing::warn!(peer = %peers[i].addr, reason = %e, "failed to fetch the headers");
+                if peers[i].add_failure() < MAX_PEER_FAILURES {
+                    i += 1;
+                } else {
+                    tracing::warn!(peer = %peers[i].addr, "too many failures, not downloading from this peer anymore");
+                    peers.remove(i).report_throughput();
+                }
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+async fn fetch_headers(
+    client: &mut grpc::Client,
+    blockchain: &Blockchain,
+    tip: &Tip,
+    cancellation_token: &CancellationToken,
+) -> Result<Vec<HeaderHash>, Error> {
+    let remote_tip = with_cancellation_token(client.tip().boxed(), cancellation_token)
+        .await?
+        .and_then(|header| header.decode())
+        .map_err(Error::TipFailed)?
+        .id();
+
+    if remote_tip == tip.get_ref().await.hash() {
+        return Ok(Vec::new());
+    }
+
+    let checkpoints = blockchain.get_checkpoints(&tip.branch().await);
+    let checkpoints = net_data::block::try_ids_from_iter(checkpoints).unwrap();
+
+    let stream = with_cancellation_token(
+        client
+            .pull_headers(checkpoints, remote_tip.encode())
+            .boxed(),
+        cancellation_token,
+    )
+    .await?
+    .map_err(Error::PullHeadersFailed)?;
+
+    // only the ids are kept, the headers are checked again with the blocks
+    let ids = stream.map_err(Error::PullHeadersFailed).try_fold(
+        Vec::new(),
+        |mut ids: Vec<HeaderHash>, header| async move {
+            let header = header.decode().map_err(Error::PullHeadersFailed)?;
+            if let Some(parent) = ids.last() {
+                if header.block_parent_hash() != *parent {
+                    return Err(Error::HeadersNotChained);
+                }
+            }
+            ids.push(header.id());
+            Ok(ids)
+        },
+    );
+    with_cancellation_token(ids.boxed(), cancellation_token).await?
+}
+
+/// Download the blocks by chunks from the peers with `fetch` and send them
+/// to `sink` in order. The requests in flight are polled while the blocks
+/// wait for room in `sink`, so that their timeouts only run while they are
+/// making progress. Returns the peers still in use.
+async fn download_in_order<P, B, F, Fut>(
+    peers: Vec<P>,
+    ids: &[HeaderHash],
+    chunk_size: usize,
+    mut fetch: F,
+    mut sink: mpsc::Sender<Result<B, NetworkError>>,
+) -> (Vec<P>, Result<(), Error>)
+where
+    P: ChunkSource,
+    F: FnMut(P, usize, Vec<HeaderHash>) -> Fut,
+    Fut: Future<Output = (P, usize, Result<Vec<B>, Error>)>,
+{
+    let chunks: Vec<&[HeaderHash]> = ids.chunks(chunk_size.max(1)).collect();
+    // do not download too far ahead of the next chunk to apply, to bound
+    // the memory used by the chunks waiting to be applied
+    let window = peers.len() * 2;
+
+    let mut idle: VecDeque<P> = peers.into();
+    let mut queue: VecDeque<usize> = (0..chunks.len()).collect();
+    let mut downloaded = BTreeMap::new();
+    let mut next = 0;
+    let mut to_send: VecDeque<B> = VecDeque::new();
+    let mut in_flight = FuturesUnordered::new();
+
+    let result = loop {
+        while let Some(&index) = queue.front() {
+            if index >= next + window {
+                break;
+            }
+            let peer = match idle.pop_front() {
+                Some(peer) => peer,
+                None => break,
+            };
+            queue.pop_front();
+            in_flight.push(fetch(peer, index, chunks[index].to_vec()));
+        }
+
+        if to_send.is_empty() {
+            if let Some(blocks) = downloaded.remove(&next) {
+                to_send.extend(blocks);
+                next += 1;
+                continue;
+            }
+            if in_flight.is_empty() {
+                if next == chunks.len() {
+                    break Ok(());
+                } else {
+                    break Err(Error::AllPeersFailed);
+                }
+            }
+        }
+
+        tokio::select! {
+            Some((mut peer, index, result)) = in_flight.next(), if !in_flight.is_empty() => {
+                match result {
+                    Ok(blocks) => {
+                        downloaded.insert(index, blocks);
+                        idle.push_back(peer);
+                    }
+                    Err(e) => {
+                        tracing::warn!(peer = %peer.addr(), chunk = index, reason = %e, "failed to download blocks");
+                        queue.push_front(index);
+                        if peer.add_failure() < MAX_PEER_FAILURES {
+                            // let the other peers take the chunk first
+                            idle.push_back(peer);
+                        } else {
+                            tracing::warn!(peer = %peer.addr(), "too many failures, not downloading from this peer anymore");
+                            peer.report_throughput();
+                        }
+                    }
+                }
+            }
+            ready = future::poll_fn(|cx| sink.poll_ready(cx)), if !to_send.is_empty() => {
+                let block = to_send.pop_front().unwrap();
+                // the blocks are not applied anymore, the error is reported
+                // by the application side
+                if ready.and_then(|()| sink.start_send(Ok(block))).is_err() {
+                    break Ok(());
+                }
+            }
+        }
+    };
+
+    // wait for the requests in flight to get the peers back
+    while let Some((peer, _, _)) = in_flight.next().await {
+        idle.push_back(peer);
+    }
+    (idle.into(), result)
+}
+
+/// Request a chunk of blocks from the peer. The timeout starts when the
+/// returned future is first polled.
+async fn fetch_chunk(
+    mut peer: DownloadPeer,
+    index: usize,
+    ids: Vec<HeaderHash>,
+    timeout: std::time::Duration,
+) -> (DownloadPeer, usize, Result<Vec<net_data::Block>, Error>) {
+    let start = Instant::now();
+    let request = async {
+        let stream = peer
+            .client
+            .get_blocks(ids.encode())
+            .await
+            .map_err(Error::GetBlocksFailed)?;
+        let blocks: Vec<net_data::Block> =
+            stream.try_collect().await.map_err(Error::GetBlocksFailed)?;
+        if blocks.len() != ids.len() {
+            return Err(Error::UnexpectedBlocks);
+        }
+        for (block, id) in blocks.iter().zip(&ids) {
+            let block = block.clone().decode().map_err(Error::GetBlocksFailed)?;
+            if block.header().id() != *id {
+                return Err(Error::UnexpectedBlocks);
+            }
+        }
+        Ok(blocks)
+    };
+    let result = tokio::time::timeout(timeout, request)
+        .await
+        .unwrap_or(Err(Error::GetBlocksTimeout));
+    if let Ok(blocks) = &result {
+        peer.busy += start.elapsed();
+        peer.blocks += blocks.len() as u64;
+        peer.bytes += blocks
+            .iter()
+            .map(|block| block.as_bytes().len() as u64)
+            .sum::<u64>();
+        tracing::debug!(peer = %peer.addr, chunk = index, "downloaded blocks");
+    }
+    (peer, index, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockPeer {
+        addr: SocketAddr,
+        failing: bool,
+        failures: u32,
+    }
+
+    impl MockPeer {
+        fn new(port: u16, failing: bool) -> Self {
+            MockPeer {
+                addr: SocketAddr::from(([127, 0, 0, 1], port)),
+                failing,
+                failures: 0,
+            }
+        }
+    }
+
+    impl ChunkSource for MockPeer {
+        fn addr(&self) -> SocketAddr {
+            self.addr
+        }
+
+        fn add_failure(&mut self) -> u32 {
+            self.failures += 1;
+            self.failures
+        }
+
+        fn report_throughput(&self) {}
+    }
+
+    /// the blocks are mocked by their ids, the lower chunks taking longer to
+    /// download so that they complete out of order
+    async fn fetch_mock(
+        peer: MockPeer,
+        index: usize,
+        ids: Vec<HeaderHash>,
+    ) -> (MockPeer, usize, Result<Vec<HeaderHash>, Error>) {
+        if peer.failing {
+            return (peer, index, Err(Error::UnexpectedBlocks));
+        }
+        let delay = 100u64.saturating_sub(index as u64 * 10);
+        tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+        (peer, index, Ok(ids))
+    }
+
+    fn ids(count: u8) -> Vec<HeaderHash> {
+        (0..count).map(|i| HeaderHash::hash_bytes(&[i])).collect()
+    }
+
+    async fn download(
+        peers: Vec<MockPeer>,
+        ids: &[HeaderHash],
+        buffer: usize,
+    ) -> (Vec<MockPeer>, Result<(), Error>, Vec<HeaderHash>) {
+        let (sink, stream) = mpsc::channel(buffer);
+        let ((peers, result), received) = future::join(
+            download_in_order(peers, ids, 3, fetch_mock, sink),
+            stream.map(Result::unwrap).collect::<Vec<_>>(),
+        )
+        .await;
+        (peers, result, received)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn chunks_are_sent_in_order() {
+        let ids = ids(20);
+        let peers = (0..4).map(|port| MockPeer::new(port, false)).collect();
+
+        // a buffer smaller than a chunk keeps the downloads waiting on the sink
+        let (peers, result, received) = download(peers, &ids, 1).await;
+
+        result.unwrap();
+        assert_eq!(received, ids);
+        assert_eq!(peers.len(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn chunks_of_a_failing_peer_are_downloaded_from_the_others() {
+        let ids = ids(20);
+        let peers = vec![MockPeer::new(1, true), MockPeer::new(2, false)];
+
+        let (peers, result, received) = download(peers, &ids, BLOCKS_BUFFER).await;
+
+        result.unwrap();
+        assert_eq!(received, ids);
+        assert_eq!(peers.len(), 1, "the failing peer should be dropped");
+        assert_eq!(peers[0].addr(), SocketAddr::from(([127, 0, 0, 1], 2)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn download_fails_when_all_peers_fail() {
+        let ids = ids(20);
+        let peers = vec![MockPeer::new(1, true), MockPeer::new(2, true)];
+
+        let (peers, result, received) = download(peers, &ids, BLOCKS_BUFFER).await;
+
+        assert!(matches!(result, Err(Error::AllPeersFailed)));
+        assert!(received.is_empty());
+        assert!(peers.is_empty());
+    }
+}
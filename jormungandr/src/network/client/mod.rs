@@ -8,20 +8,25 @@ use super::{
         self,
         client::{BlockSubscription, FragmentSubscription, GossipSubscription},
     },
-    p2p::comm::{OutboundSubscription, PeerComms},
+    p2p::{
+        comm::{OutboundSubscription, PeerComms},
+        traffic::TrafficKind,
+    },
     subscription::{BlockAnnouncementProcessor, Direction, FragmentProcessor, GossipProcessor},
     Channels, GlobalStateR,
 };
 use crate::{
-    intercom::{self, BlockMsg, ClientMsg},
+    intercom::{self, BlockMsg, ClientMsg, TopologyMsg},
     topology::NodeId,
     utils::async_msg::MessageBox,
 };
 use chain_network::{
     data as net_data,
     data::block::{BlockEvent, BlockIds, ChainPullRequest},
+    error as net_error,
 };
 use futures::{prelude::*, ready};
+use jormungandr_lib::interfaces::PeerReportReason;
 use std::{
     pin::Pin,
     task::{Context, Poll},
@@ -40,6 +45,7 @@ pub struct Client {
     fragment_sink: FragmentProcessor,
     gossip_sink: GossipProcessor,
     client_box: MessageBox<ClientMsg>,
+    topology_box: MessageBox<TopologyMsg>,
     incoming_block_announcement: Option<net_data::Header>,
     incoming_solicitation: Option<ClientMsg>,
     shutting_down: bool,
@@ -74,11 +80,12 @@ impl Client {
         );
         let fragment_sink = FragmentProcessor::new(
             builder.channels.transaction_box,
+            builder.channels.topology_box.clone(),
             inbound.peer_id,
             global_state.clone(),
         );
         let gossip_sink = GossipProcessor::new(
-            builder.channels.topology_box,
+            builder.channels.topology_box.clone(),
             inbound.peer_id,
             global_state.clone(),
             Direction::Client,
@@ -94,6 +101,7 @@ impl Client {
             fragment_sink,
             gossip_sink,
             client_box: builder.channels.client_box,
+            topology_box: builder.channels.topology_box,
             incoming_block_announcement: None,
             incoming_solicitation: None,
             shutting_down: false,
@@ -347,7 +355,7 @@ impl Client {
     fn pull_headers(&mut self, req: ChainPullRequest) {
         let mut block_box = self.block_sink.message_box();
 
-        let (handle, sink, _) = intercom::stream_request(buffer_sizes::inbound::HEADERS);
+        let (handle, sink, reply) = intercom::stream_request(buffer_sizes::inbound::HEADERS);
         // TODO: make sure that back pressure on the number of requests
         // in flight prevents unlimited spawning of these tasks.
         // https://github.com/input-output-hk/jormungandr/issues/1034
@@ -364,26 +372,43 @@ impl Client {
             .in_current_span(),
         );
         let mut client = self.inner.clone();
+        let topology_box = self.topology_box.clone();
+        let node_id = self.inbound.peer_id;
+        let global_state = self.global_state.clone();
         self.global_state.spawn(
             async move {
-                match client.pull_headers(req.from, req.to).await {
+                let report = match client.pull_headers(req.from, req.to).await {
                     Err(e) => {
                         tracing::info!(
                             reason = %e,
                             "request failed"
                         );
+                        report_request_error(node_id, &e)
                     }
                     Ok(stream) => {
-                        let stream = stream.and_then(|item| async { item.decode() });
+                        let stream = stream
+                            .inspect_ok(|header| {
+                                global_state.peers.traffic().record_inbound(
+                                    node_id,
+                                    TrafficKind::Headers,
+                                    header.as_ref().len(),
+                                )
+                            })
+                            .and_then(|item| async { item.decode() });
                         let res = stream.forward(sink.sink_err_into()).await;
-                        if let Err(e) = res {
+                        if let Err(e) = &res {
                             tracing::info!(
                                 reason = %e,
                                 "response stream failed"
                             );
                         }
+                        let processed = reply.await;
+                        res.err()
+                            .and_then(|e| report_request_error(node_id, &e))
+                            .or_else(|| report_processing(node_id, processed, 0))
                     }
-                }
+                };
+                send_report(topology_box, report).await;
             }
             .in_current_span(),
         );
@@ -392,7 +417,7 @@ impl Client {
     #[instrument(skip_all, level = "debug")]
     fn solicit_blocks(&mut self, block_ids: BlockIds) {
         let mut block_box = self.block_sink.message_box();
-        let (handle, sink, _) = intercom::stream_request(buffer_sizes::inbound::BLOCKS);
+        let (handle, sink, reply) = intercom::stream_request(buffer_sizes::inbound::BLOCKS);
         // TODO: make sure that back pressure on the number of requests
         // in flight prevents unlimited spawning of these tasks.
         // https://github.com/input-output-hk/jormungandr/issues/1034
@@ -409,26 +434,45 @@ impl Client {
             .in_current_span(),
         );
         let mut client = self.inner.clone();
+        let topology_box = self.topology_box.clone();
+        let node_id = self.inbound.peer_id;
+        let global_state = self.global_state.clone();
         self.global_state.spawn(
             async move {
-                match client.get_blocks(block_ids).await {
+                let report = match client.get_blocks(block_ids).await {
                     Err(e) => {
                         tracing::info!(
                             reason = %e,
                             "request failed"
                         );
+                        report_request_error(node_id, &e)
                     }
                     Ok(stream) => {
-                        let stream = stream.and_then(|item| async { item.decode() });
+                        let mut blocks = 0;
+                        let stream = stream
+                            .inspect_ok(|block| {
+                                global_state.peers.traffic().record_inbound(
+                                    node_id,
+                                    TrafficKind::Blocks,
+                                    block.as_ref().len(),
+                                )
+                            })
+                            .and_then(|item| async { item.decode() })
+                            .inspect_ok(|_| blocks += 1);
                         let res = stream.forward(sink.sink_err_into()).await;
-                        if let Err(e) = res {
+                        if let Err(e) = &res {
                             tracing::info!(
                                 reason = %e,
                                 "response stream failed"
                             );
                         }
+                        let processed = reply.await;
+                        res.err()
+                            .and_then(|e| report_request_error(node_id, &e))
+                            .or_else(|| report_processing(node_id, processed, blocks))
                     }
-                }
+                };
+                send_report(topology_box, report).await;
             }
             .in_current_span(),
         );
@@ -575,3 +619,93 @@ impl Future for Client {
         }
     }
 }
+
+/// A request to the peer that timed out counts against its reputation
+fn report_request_error(node_id: NodeId, e: &net_error::Error) -> Option<TopologyMsg> {
+    match e.code() {
+        net_error::Code::DeadlineExceeded => {
+            Some(TopologyMsg::DemotePeer(node_id, PeerReportReason::Timeout))
+        }
+        _ => None,
+    }
+}
+
+/// Invalid blocks or headers sent by the peer count against its reputation,
+/// while valid blocks count in its favour.
+fn report_processing(
+    node_id: NodeId,
+    res: Result<(), intercom::Error>,
+    blocks: u64,
+) -> Option<TopologyMsg> {
+    match res {
+        Ok(()) if blocks > 0 => Some(TopologyMsg::RewardPeer(node_id, blocks)),
+        Ok(()) => None,
+        Err(e) if matches!(e.code(), net_error::Code::InvalidArgument) => Some(
+            TopologyMsg::DemotePeer(node_id, PeerReportReason::InvalidBlock),
+        ),
+        Err(_) => None,
+    }
+}
+
+async fn send_report(mut topology_box: MessageBox<TopologyMsg>, report: Option<TopologyMsg>) {
+    if let Some(msg) = report {
+        topology_box
+            .send(msg)
+            .await
+            .unwrap_or_else(|e| tracing::error!("Error sending message to topology task: {}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blockchain,
+        topology::{Peer, ReportRecords},
+    };
+    use chain_impl_mockchain::{block::BlockDate, ledger};
+    use rand::SeedableRng;
+    use std::{net::SocketAddr, time::Duration};
+
+    fn key(seed: u8) -> keynesis::key::ed25519::SecretKey {
+        keynesis::key::ed25519::SecretKey::new(rand_chacha::ChaChaRng::from_seed([seed; 32]))
+    }
+
+    fn ledger_invalid_block() -> intercom::Error {
+        let date = |epoch| BlockDate { epoch, slot_id: 0 };
+        blockchain::network_block_error_into_reply(blockchain::Error::Ledger(
+            ledger::Error::NonMonotonicDate {
+                block_date: date(0),
+                chain_date: date(1),
+            },
+        ))
+    }
+
+    #[test]
+    fn ledger_invalid_blocks_escalate_the_quarantine() {
+        let mut topology = poldercast::Topology::new(([127, 0, 0, 1], 0).into(), &key(0));
+        let addr: SocketAddr = ([10, 0, 0, 1], 3000).into();
+        let peer = Peer::from(poldercast::Gossip::new(
+            addr,
+            &key(1),
+            poldercast::Subscriptions::new().as_slice(),
+        ));
+        topology.add_peer(poldercast::Profile::from_gossip(peer.clone().into()));
+        let mut records = ReportRecords::default();
+
+        let mut durations = Vec::new();
+        for _ in 0..2 {
+            let reason = match report_processing(peer.id(), Err(ledger_invalid_block()), 0) {
+                Some(TopologyMsg::DemotePeer(id, reason)) if id == peer.id() => reason,
+                _ => panic!("the peer is not reported for the invalid block"),
+            };
+            records.report_node(&mut topology, peer.clone(), reason);
+            let reported = records.reported_nodes().pop().unwrap();
+            assert!(reported.quarantined.is_some());
+            let reputation = reported.reputation.unwrap();
+            durations.push(Duration::from(reputation.quarantine_duration.unwrap()));
+            assert!(records.lift_report(&peer.id()).is_some());
+        }
+        assert_eq!(durations[1], durations[0] * 2);
+    }
+}
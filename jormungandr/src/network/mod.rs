@@ -69,7 +69,10 @@ mod security_params {
 }
 
 pub use self::bootstrap::Error as BootstrapError;
-use self::{client::ConnectError, p2p::comm::Peers};
+use self::{
+    client::ConnectError,
+    p2p::{comm::Peers, rules::PeerRules},
+};
 use crate::{
     blockcfg::{Block, HeaderHash},
     blockchain::{Blockchain as NewBlockchain, Tip},
@@ -80,6 +83,7 @@ use crate::{
     utils::async_msg::{MessageBox, MessageQueue},
 };
 use chain_network::data::NodeKeyPair;
+use jormungandr_lib::interfaces::PeerReportReason;
 use rand::seq::SliceRandom;
 use std::{
     collections::HashSet,
@@ -152,6 +156,7 @@ pub struct GlobalState {
     block0_hash: HeaderHash,
     config: Configuration,
     peers: Peers,
+    rules: Arc<PeerRules>,
     keypair: NodeKeyPair,
     span: Span,
 
@@ -165,10 +170,16 @@ impl GlobalState {
     pub fn new(
         block0_hash: HeaderHash,
         config: Configuration,
+        rules: Arc<PeerRules>,
         stats_counter: Metrics,
         span: Span,
     ) -> Self {
-        let peers = Peers::new(config.max_connections, stats_counter);
+        let peers = Peers::new(
+            config.max_connections,
+            config.rate_limits.clone(),
+            rules.clone(),
+            stats_counter,
+        );
 
         //TODO: move this to a secure enclave
         let keypair =
@@ -178,6 +189,7 @@ impl GlobalState {
             block0_hash,
             config,
             peers,
+            rules,
             keypair,
             span,
             connected_count: AtomicUsize::new(0),
@@ -192,6 +204,10 @@ impl GlobalState {
         self.config.public_address
     }
 
+    pub fn peer_rules(&self) -> &PeerRules {
+        &self.rules
+    }
+
     pub fn spawn<F>(&self, f: F)
     where
         F: Future<Output = ()> + Send + 'static,
@@ -333,6 +349,23 @@ async fn handle_network_input(
             NetworkMsg::PeerInfo(reply) => {
                 state.peers.infos().map(|infos| reply.reply_ok(infos)).await;
             }
+            NetworkMsg::Connect { addr, id } => {
+                connect_and_propagate(
+                    addr,
+                    id,
+                    state.clone(),
+                    channels.clone(),
+                    Default::default(),
+                );
+            }
+            NetworkMsg::Disconnect(id, reply) => {
+                let disconnected = state.peers.remove_peer(&id).await.is_some();
+                reply.reply_ok(disconnected);
+            }
+            NetworkMsg::DisconnectBanned => {
+                let count = state.peers.remove_banned().await;
+                tracing::debug!(count, "disconnected banned peers");
+            }
         };
         tracing::trace!("item handling finished");
     }
@@ -460,6 +493,10 @@ fn connect_and_propagate(
     mut options: p2p::comm::ConnectOptions,
 ) {
     let _enter = state.span.enter();
+    if state.rules.is_banned(&id, addr.ip()) {
+        tracing::debug!(peer = %addr, %id, "peer is banned, not connecting");
+        return;
+    }
     options.evict_clients = state.num_clients_to_bump();
     if let Some(self_addr) = state.node_address() {
         if addr == self_addr {
@@ -478,28 +515,28 @@ fn connect_and_propagate(
         state.peers.add_connecting(id, addr, handle, options).await;
         match connecting.await {
             Err(e) => {
-                let benign = match e {
+                let report = match e {
                     ConnectError::Transport(e) => {
                         tracing::info!(reason = %e, "gRPC connection to peer failed");
-                        false
+                        Some(PeerReportReason::ConnectionFailed)
                     }
                     ConnectError::Handshake(e) => {
                         tracing::info!(reason = %e, "protocol handshake with peer failed");
-                        false
+                        Some(PeerReportReason::HandshakeFailed)
                     }
                     ConnectError::Canceled => {
                         tracing::debug!("connection to peer has been canceled");
-                        true
+                        None
                     }
                     _ => {
                         tracing::info!(error = ?e, "connection to peer failed");
-                        false
+                        Some(PeerReportReason::HandshakeFailed)
                     }
                 };
-                if !benign {
+                if let Some(reason) = report {
                     channels
                         .topology_box
-                        .send(TopologyMsg::DemotePeer(id))
+                        .send(TopologyMsg::DemotePeer(id, reason))
                         .await
                         .unwrap_or_else(|e| {
                             tracing::error!("Error sending message to topology task: {}", e)
@@ -603,11 +640,16 @@ pub struct NetworkBootstrapResult {
     pub bootstrapped: bool,
 }
 
+/// Bootstrap from the peers obtained from the trusted peers, then from the
+/// known peers saved by a previous run of the node, which are also injected
+/// in the topology.
 pub async fn bootstrap(
     config: &Configuration,
+    known_peers: Vec<topology::Peer>,
     blockchain: NewBlockchain,
     branch: Tip,
     cancellation_token: CancellationToken,
+    metrics: &Metrics,
     span: &Span,
 ) -> Result<NetworkBootstrapResult, bootstrap::Error> {
     use futures::future::{select, Either, FutureExt};
@@ -618,7 +660,7 @@ pub async fn bootstrap(
 
     if config.skip_bootstrap {
         return Ok(NetworkBootstrapResult {
-            initial_peers: Vec::new(),
+            initial_peers: known_peers,
             bootstrapped: true,
         });
     }
@@ -641,12 +683,68 @@ pub async fn bootstrap(
 
     let BootstrapPeers {
         mut bootstrap_peers,
-        topology_peers,
+        mut topology_peers,
     } = netboot_peers;
     let mut rng = rand::thread_rng();
     bootstrap_peers.shuffle(&mut rng);
 
-    for peer in &bootstrap_peers {
+    // the known peers are tried after the fresh ones, best first
+    let netboot_ids: HashSet<_> = bootstrap_peers
+        .iter()
+        .chain(topology_peers.iter())
+        .map(|peer| peer.id())
+        .collect();
+    let known_peers = known_peers
+        .into_iter()
+        .filter(|peer| !netboot_ids.contains(&peer.id()));
+    if config.bootstrap_from_trusted_peers {
+        topology_peers.extend(known_peers);
+    } else {
+        bootstrap_peers.extend(known_peers);
+    }
+
+    if let Some(parallel_config) = &config.parallel_bootstrap {
+        let span = span!(parent: span, Level::DEBUG, "parallel_bootstrap");
+        let peers: Vec<_> = bootstrap_peers
+            .iter()
+            .map(|peer| Peer::new(peer.address()))
+            .collect();
+        let res = bootstrap::bootstrap_from_peers(
+            &peers,
+            parallel_config,
+            blockchain.clone(),
+            branch.clone(),
+            cancellation_token.clone(),
+            metrics,
+        )
+        .instrument(span.clone())
+        .await;
+
+        match res {
+            Err(bootstrap::Error::Interrupted) => {
+                span.in_scope(|| tracing::warn!("the bootstrap process was interrupted"));
+                return Err(bootstrap::Error::Interrupted);
+            }
+            Err(e) => span.in_scope(|| {
+                tracing::warn!(
+                    error = ?e,
+                    "parallel bootstrap failed, bootstrapping from one peer at a time"
+                )
+            }),
+            Ok(()) => {
+                span.in_scope(|| tracing::info!("initial bootstrap completed"));
+                bootstrapped = true;
+            }
+        }
+    }
+
+    // one peer at a time, unless already bootstrapped from several at once
+    let sequential_peers = if bootstrapped {
+        &[][..]
+    } else {
+        &bootstrap_peers[..]
+    };
+    for peer in sequential_peers {
         let span =
             span!(parent: span, Level::DEBUG, "bootstrap", peer_addr = %peer.address().to_string());
         let res = bootstrap::bootstrap_from_peer(
@@ -654,6 +752,7 @@ pub async fn bootstrap(
             blockchain.clone(),
             branch.clone(),
             cancellation_token.clone(),
+            metrics,
         )
         .instrument(span.clone())
         .await;
@@ -1,5 +1,9 @@
 mod peer_map;
-use super::Address;
+use super::{
+    rules::PeerRules,
+    traffic::{Traffic, TrafficKind},
+    Address,
+};
 use crate::{
     metrics::Metrics,
     network::{client::ConnectHandle, security_params::NONCE_LEN},
@@ -18,6 +22,7 @@ use futures::{
     prelude::*,
     stream,
 };
+use jormungandr_lib::interfaces::{PeerTraffic, RateLimitsConfig};
 use peer_map::{CommStatus, PeerMap};
 use std::{
     fmt,
@@ -25,6 +30,7 @@ use std::{
     mem,
     net::SocketAddr,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::SystemTime,
 };
@@ -463,6 +469,7 @@ pub struct PeerInfo {
     pub id: NodeId,
     pub addr: Option<SocketAddr>,
     pub stats: PeerStats,
+    pub traffic: PeerTraffic,
 }
 
 /// The collection of currently connected peer nodes.
@@ -471,15 +478,26 @@ pub struct PeerInfo {
 /// all network connection tasks.
 pub struct Peers {
     mutex: Mutex<PeerMap>,
+    traffic: Traffic,
 }
 
 impl Peers {
-    pub fn new(capacity: usize, stats_counter: Metrics) -> Self {
+    pub fn new(
+        capacity: usize,
+        rate_limits: RateLimitsConfig,
+        rules: Arc<PeerRules>,
+        stats_counter: Metrics,
+    ) -> Self {
         Peers {
-            mutex: Mutex::new(PeerMap::new(capacity, stats_counter)),
+            mutex: Mutex::new(PeerMap::new(capacity, rules, stats_counter.clone())),
+            traffic: Traffic::new(capacity, rate_limits, stats_counter),
         }
     }
 
+    pub fn traffic(&self) -> &Traffic {
+        &self.traffic
+    }
+
     fn inner(&self) -> MutexLockFuture<PeerMap> {
         self.mutex.lock()
     }
@@ -519,6 +537,12 @@ impl Peers {
         }
     }
 
+    /// Disconnect the peers banned by the rules
+    pub async fn remove_banned(&self) -> usize {
+        let mut map = self.inner().await;
+        map.remove_banned()
+    }
+
     pub async fn remove_peer(&self, peer: &NodeId) -> Option<PeerComms> {
         let mut map = self.inner().await;
         map.remove_peer(peer)
@@ -596,6 +620,7 @@ impl Peers {
 
     pub async fn propagate_block(&self, peer: NodeId, header: Header) -> Result<(), NodeId> {
         tracing::debug!("sending block");
+        let bytes = header.as_ref().len();
         self.propagate_with(peer, move |status| match status {
             CommStatus::Established(comms) => comms.try_send_block_announcement(header.clone()),
             CommStatus::Connecting(comms) => {
@@ -603,11 +628,15 @@ impl Peers {
                 Ok(())
             }
         })
-        .await
+        .await?;
+        self.traffic
+            .record_outbound(peer, TrafficKind::Headers, bytes);
+        Ok(())
     }
 
     pub async fn propagate_fragment(&self, peer: NodeId, fragment: Fragment) -> Result<(), NodeId> {
         tracing::debug!("sending fragment");
+        let bytes = fragment.as_bytes().len();
         self.propagate_with(peer, move |status| match status {
             CommStatus::Established(comms) => comms.try_send_fragment(fragment.clone()),
             CommStatus::Connecting(comms) => {
@@ -615,11 +644,15 @@ impl Peers {
                 Ok(())
             }
         })
-        .await
+        .await?;
+        self.traffic
+            .record_outbound(peer, TrafficKind::Fragments, bytes);
+        Ok(())
     }
 
     pub async fn propagate_gossip_to(&self, peer: NodeId, gossip: Gossip) -> Result<(), Gossip> {
         tracing::debug!("sending gossip");
+        let bytes = gossip.nodes.iter().map(|node| node.as_bytes().len()).sum();
         let mut map = self.inner().await;
         if let Some(mut entry) = map.entry(peer) {
             let res = match entry.update_comm_status() {
@@ -636,7 +669,11 @@ impl Peers {
                 );
                 entry.remove();
                 e.into_item()
-            })
+            })?;
+            drop(map);
+            self.traffic
+                .record_outbound(peer, TrafficKind::Gossip, bytes);
+            Ok(())
         } else {
             Err(gossip)
         }
@@ -762,6 +799,11 @@ impl Peers {
 
     pub async fn infos(&self) -> Vec<PeerInfo> {
         let map = self.inner().await;
-        map.infos()
+        let mut infos = map.infos();
+        drop(map);
+        for info in &mut infos {
+            info.traffic = self.traffic.peer_traffic(&info.id);
+        }
+        infos
     }
 }
@@ -2,7 +2,10 @@ use crate::{
     metrics::{Metrics, MetricsBackend},
     network::{
         client::ConnectHandle,
-        p2p::comm::{Address, PeerComms, PeerInfo, PeerStats},
+        p2p::{
+            comm::{Address, PeerComms, PeerInfo, PeerStats},
+            rules::PeerRules,
+        },
         security_params::NONCE_LEN,
     },
     topology::NodeId,
@@ -11,6 +14,7 @@ use chain_network::error::{Code as ErrorCode, Error as NetworkError};
 use linked_hash_map::LinkedHashMap;
 use lru::LruCache;
 use rand::Rng;
+use std::sync::Arc;
 
 /// Peer authentication is checked during the handshake. For client connections, we simply
 /// do not add a peer to the map if the authentication fails.
@@ -80,6 +84,7 @@ pub struct PeerMap {
     map: LinkedHashMap<NodeId, PeerData>,
     client_auth: ClientAuth,
     capacity: usize,
+    rules: Arc<PeerRules>,
     stats_counter: Metrics,
 }
 
@@ -142,11 +147,12 @@ impl<'a> CommStatus<'a> {
 }
 
 impl PeerMap {
-    pub fn new(capacity: usize, stats_counter: Metrics) -> Self {
+    pub fn new(capacity: usize, rules: Arc<PeerRules>, stats_counter: Metrics) -> Self {
         PeerMap {
             map: LinkedHashMap::new(),
             client_auth: ClientAuth::default(),
             capacity,
+            rules,
             stats_counter,
         }
     }
@@ -269,26 +275,50 @@ impl PeerMap {
                 id,
                 addr: None,
                 stats: data.stats.clone(),
+                traffic: Default::default(),
             })
             .collect()
     }
 
     pub fn evict_clients(&mut self, num: usize) {
+        let rules = &self.rules;
         for entry in self
             .map
             .entries()
-            .filter(|entry| entry.get().comms.has_client_subscriptions())
+            .filter(|entry| {
+                entry.get().comms.has_client_subscriptions() && !rules.is_pinned(entry.key())
+            })
             .take(num)
         {
             entry.remove();
         }
     }
 
+    /// Remove the peers banned by the rules, returns how many were removed
+    pub fn remove_banned(&mut self) -> usize {
+        let banned: Vec<NodeId> = self
+            .map
+            .iter()
+            .filter(|(id, data)| self.rules.is_banned(id, data.comms.remote_addr().ip()))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &banned {
+            self.remove_peer(id);
+        }
+        banned.len()
+    }
+
+    // The least recently active peer which is not pinned is evicted. If all
+    // the peers are pinned, the capacity is exceeded.
     fn evict_if_full(&mut self) {
         if self.map.len() >= self.capacity {
-            if let Some((_, v)) = self.map.pop_front() {
-                self.stats_counter.sub_peer_connected_cnt(1);
-                self.client_auth.remove(v.comms.remote_addr());
+            let evicted = self
+                .map
+                .keys()
+                .find(|id| !self.rules.is_pinned(id))
+                .copied();
+            if let Some(id) = evicted {
+                self.remove_peer(&id);
             }
         }
     }
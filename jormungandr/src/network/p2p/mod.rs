@@ -2,6 +2,8 @@
 /// It takes care of managing connections with said peers and sending messages to them.
 /// The topology task is instead responsible for the discovery of active peers.
 pub mod comm;
+pub mod rules;
+pub mod traffic;
 
 /// At the logical level, every peer is identified by its public key, and this is the only
 /// info exposed in the external interface.
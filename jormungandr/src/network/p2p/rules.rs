@@ -0,0 +1,317 @@
+//! Rules set by the node operator on the peers of the node.
+//!
+//! Banned peers are kept out of the topology and their connections are
+//! refused. Pinned peers are never evicted from the connections to make
+//! room for others, and are never quarantined.
+use crate::{
+    topology::NodeId,
+    utils::fs::{load_optional, save_atomically},
+};
+use jormungandr_lib::interfaces::{self, BanRequest, PeerBan, PeerTarget};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    io,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::RwLock,
+    time::{Duration, SystemTime},
+};
+use thiserror::Error;
+
+/// name of the file, in the storage directory, where the rules are kept
+/// across restarts
+const PEER_RULES_FILE: &str = "peer_rules.json";
+/// longest ban with an expiry, a longer ban is better made permanent
+const MAX_BAN_DURATION: Duration = Duration::from_secs(100 * 365 * 24 * 3600);
+
+#[derive(Debug, Error)]
+pub enum BanError {
+    #[error("the ban duration cannot exceed 100 years, omit it for a permanent ban")]
+    DurationTooLong,
+    #[error("could not save the peer rules")]
+    Save(#[from] io::Error),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Id(NodeId),
+    Ip(IpAddr),
+}
+
+impl From<&PeerTarget> for Target {
+    fn from(target: &PeerTarget) -> Self {
+        match target {
+            PeerTarget::Id(id) => Target::Id(id.clone().into()),
+            PeerTarget::Ip(ip) => Target::Ip(*ip),
+        }
+    }
+}
+
+struct Ban {
+    target: Target,
+    until: Option<SystemTime>,
+    info: PeerBan,
+}
+
+impl Ban {
+    fn new(info: PeerBan) -> Self {
+        Ban {
+            target: Target::from(&info.target),
+            until: info.until.map(Into::into),
+            info,
+        }
+    }
+
+    fn is_active(&self, now: SystemTime) -> bool {
+        self.until.map_or(true, |until| now < until)
+    }
+
+    fn matches(&self, id: Option<&NodeId>, ip: IpAddr) -> bool {
+        match &self.target {
+            Target::Id(banned) => Some(banned) == id,
+            Target::Ip(banned) => *banned == ip,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Rules {
+    bans: Vec<Ban>,
+    pinned: HashSet<NodeId>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PersistedRules {
+    bans: Vec<PeerBan>,
+    pinned: Vec<interfaces::NodeId>,
+}
+
+pub struct PeerRules {
+    path: Option<PathBuf>,
+    rules: RwLock<Rules>,
+}
+
+impl PeerRules {
+    /// Create the rules, kept in the given storage directory if any. The
+    /// rules saved by a previous run of the node are loaded.
+    pub fn new(storage: Option<&Path>) -> Self {
+        let rules = PeerRules {
+            path: storage.map(|storage| storage.join(PEER_RULES_FILE)),
+            rules: RwLock::new(Rules::default()),
+        };
+        if let Err(e) = rules.load() {
+            tracing::warn!(reason = %e, "could not load the peer rules");
+        }
+        rules
+    }
+
+    fn load(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let bytes = match load_optional(path)? {
+            Some(bytes) => bytes,
+            None => return Ok(()),
+        };
+        let persisted: PersistedRules = serde_json::from_slice(&bytes)?;
+        let now = SystemTime::now();
+        let mut rules = self.rules.write().unwrap();
+        rules.bans = persisted
+            .bans
+            .into_iter()
+            .map(Ban::new)
+            .filter(|ban| ban.is_active(now))
+            .collect();
+        rules.pinned = persisted.pinned.into_iter().map(Into::into).collect();
+        Ok(())
+    }
+
+    fn save(&self, rules: &Rules) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let persisted = PersistedRules {
+            bans: rules.bans.iter().map(|ban| ban.info.clone()).collect(),
+            pinned: rules.pinned.iter().map(|id| (*id).into()).collect(),
+        };
+        save_atomically(path, &serde_json::to_vec(&persisted)?)
+    }
+
+    /// Ban a peer, replacing any previous ban of the same target
+    pub fn ban(&self, request: BanRequest) -> Result<PeerBan, BanError> {
+        let since = SystemTime::now();
+        let until = match request.duration {
+            Some(duration) => Some(
+                Some(Duration::from(duration))
+                    .filter(|duration| *duration <= MAX_BAN_DURATION)
+                    .and_then(|duration| since.checked_add(duration))
+                    .ok_or(BanError::DurationTooLong)?,
+            ),
+            None => None,
+        };
+        let info = PeerBan {
+            target: request.target,
+            since: since.into(),
+            until: until.map(Into::into),
+            reason: request.reason,
+        };
+        let ban = Ban::new(info.clone());
+        let mut rules = self.rules.write().unwrap();
+        rules
+            .bans
+            .retain(|existing| existing.target != ban.target && existing.is_active(since));
+        rules.bans.push(ban);
+        self.save(&rules)?;
+        Ok(info)
+    }
+
+    /// Returns whether the target was banned
+    pub fn unban(&self, target: &PeerTarget) -> io::Result<bool> {
+        let target = Target::from(target);
+        let now = SystemTime::now();
+        let mut rules = self.rules.write().unwrap();
+        let banned = rules
+            .bans
+            .iter()
+            .any(|ban| ban.target == target && ban.is_active(now));
+        rules
+            .bans
+            .retain(|ban| ban.target != target && ban.is_active(now));
+        self.save(&rules)?;
+        Ok(banned)
+    }
+
+    pub fn bans(&self) -> Vec<PeerBan> {
+        let now = SystemTime::now();
+        let rules = self.rules.read().unwrap();
+        rules
+            .bans
+            .iter()
+            .filter(|ban| ban.is_active(now))
+            .map(|ban| ban.info.clone())
+            .collect()
+    }
+
+    /// Whether the connections from the address are refused, before the
+    /// peer is identified
+    pub fn is_ip_banned(&self, ip: IpAddr) -> bool {
+        self.is_banned_inner(None, ip)
+    }
+
+    pub fn is_banned(&self, id: &NodeId, ip: IpAddr) -> bool {
+        self.is_banned_inner(Some(id), ip)
+    }
+
+    fn is_banned_inner(&self, id: Option<&NodeId>, ip: IpAddr) -> bool {
+        let now = SystemTime::now();
+        let rules = self.rules.read().unwrap();
+        rules
+            .bans
+            .iter()
+            .any(|ban| ban.is_active(now) && ban.matches(id, ip))
+    }
+
+    /// Returns whether the peer was not pinned already
+    pub fn pin(&self, id: NodeId) -> io::Result<bool> {
+        let mut rules = self.rules.write().unwrap();
+        let pinned = rules.pinned.insert(id);
+        self.save(&rules)?;
+        Ok(pinned)
+    }
+
+    /// Returns whether the peer was pinned
+    pub fn unpin(&self, id: &NodeId) -> io::Result<bool> {
+        let mut rules = self.rules.write().unwrap();
+        let unpinned = rules.pinned.remove(id);
+        self.save(&rules)?;
+        Ok(unpinned)
+    }
+
+    pub fn is_pinned(&self, id: &NodeId) -> bool {
+        self.rules.read().unwrap().pinned.contains(id)
+    }
+
+    pub fn pinned(&self) -> Vec<NodeId> {
+        self.rules.read().unwrap().pinned.iter().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn node_id(byte: u8) -> NodeId {
+        NodeId::try_from(&[byte; 32][..]).unwrap()
+    }
+
+    #[test]
+    fn bans_match_ids_and_addresses() {
+        let rules = PeerRules::new(None);
+        let ip: IpAddr = [10, 0, 0, 1].into();
+        let other_ip: IpAddr = [10, 0, 0, 2].into();
+        rules
+            .ban(BanRequest {
+                target: PeerTarget::Id(node_id(1).into()),
+                duration: None,
+                reason: None,
+            })
+            .unwrap();
+        rules
+            .ban(BanRequest {
+                target: PeerTarget::Ip(ip),
+                duration: None,
+                reason: Some("spam".to_owned()),
+            })
+            .unwrap();
+        assert!(rules.is_banned(&node_id(1), other_ip));
+        assert!(rules.is_banned(&node_id(2), ip));
+        assert!(rules.is_ip_banned(ip));
+        assert!(!rules.is_banned(&node_id(2), other_ip));
+        assert_eq!(rules.bans().len(), 2);
+
+        assert!(rules.unban(&PeerTarget::Ip(ip)).unwrap());
+        assert!(!rules.unban(&PeerTarget::Ip(ip)).unwrap());
+        assert!(!rules.is_ip_banned(ip));
+    }
+
+    #[test]
+    fn bans_expire() {
+        let rules = PeerRules::new(None);
+        let ip: IpAddr = [10, 0, 0, 1].into();
+        rules
+            .ban(BanRequest {
+                target: PeerTarget::Ip(ip),
+                duration: Some(Duration::from_secs(0).into()),
+                reason: None,
+            })
+            .unwrap();
+        assert!(!rules.is_ip_banned(ip));
+        assert!(rules.bans().is_empty());
+    }
+
+    #[test]
+    fn too_long_bans_are_refused() {
+        let rules = PeerRules::new(None);
+        let ip: IpAddr = [10, 0, 0, 1].into();
+        let ban = |duration: Duration| {
+            rules.ban(BanRequest {
+                target: PeerTarget::Ip(ip),
+                duration: Some(duration.into()),
+                reason: None,
+            })
+        };
+        assert!(matches!(ban(Duration::MAX), Err(BanError::DurationTooLong)));
+        assert!(matches!(
+            ban(MAX_BAN_DURATION + Duration::from_secs(1)),
+            Err(BanError::DurationTooLong)
+        ));
+        assert!(!rules.is_ip_banned(ip));
+        assert!(ban(MAX_BAN_DURATION).unwrap().until.is_some());
+        assert!(rules.is_ip_banned(ip));
+    }
+}
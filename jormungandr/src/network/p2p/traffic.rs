@@ -0,0 +1,280 @@
+//! Accounting of the traffic exchanged with every peer over the
+//! subscriptions, and rate limiting of what the peers send us so that
+//! a single noisy peer cannot saturate the node.
+use crate::{
+    metrics::{Metrics, MetricsBackend},
+    topology::NodeId,
+    utils::token_bucket::TokenBucket,
+};
+use jormungandr_lib::interfaces::{
+    PeerTraffic, RateLimit, RateLimits, RateLimitsConfig, TrafficStats,
+};
+use lru::LruCache;
+use std::{sync::Mutex, time::Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficKind {
+    Blocks,
+    Headers,
+    Fragments,
+    Gossip,
+}
+
+impl TrafficKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrafficKind::Blocks => "blocks",
+            TrafficKind::Headers => "headers",
+            TrafficKind::Fragments => "fragments",
+            TrafficKind::Gossip => "gossip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficDirection {
+    Inbound,
+    Outbound,
+}
+
+impl TrafficDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrafficDirection::Inbound => "inbound",
+            TrafficDirection::Outbound => "outbound",
+        }
+    }
+}
+
+struct Limiter {
+    items: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl Limiter {
+    fn new(limit: &RateLimit) -> Self {
+        Limiter {
+            items: limit
+                .items_per_second
+                .map(|rate| TokenBucket::new(rate.into())),
+            bytes: limit
+                .bytes_per_second
+                .map(|rate| TokenBucket::new(rate as f64)),
+        }
+    }
+
+    fn allows(&mut self, bytes: u64, now: Instant) -> bool {
+        let items_allowed = self.items.as_mut().map_or(true, |bucket| {
+            bucket.refill(now);
+            bucket.allows(1.0)
+        });
+        let bytes_allowed = self.bytes.as_mut().map_or(true, |bucket| {
+            bucket.refill(now);
+            bucket.allows(bytes as f64)
+        });
+        items_allowed && bytes_allowed
+    }
+
+    fn take(&mut self, bytes: u64) {
+        if let Some(bucket) = &mut self.items {
+            bucket.take(1.0);
+        }
+        if let Some(bucket) = &mut self.bytes {
+            bucket.take(bytes as f64);
+        }
+    }
+}
+
+struct Limiters {
+    fragments: Limiter,
+    block_announcements: Limiter,
+}
+
+impl Limiters {
+    fn new(limits: &RateLimits) -> Self {
+        Limiters {
+            fragments: Limiter::new(&limits.fragments),
+            block_announcements: Limiter::new(&limits.block_announcements),
+        }
+    }
+
+    fn get_mut(&mut self, kind: TrafficKind) -> Option<&mut Limiter> {
+        match kind {
+            TrafficKind::Fragments => Some(&mut self.fragments),
+            TrafficKind::Headers => Some(&mut self.block_announcements),
+            TrafficKind::Blocks | TrafficKind::Gossip => None,
+        }
+    }
+}
+
+struct PeerEntry {
+    traffic: PeerTraffic,
+    limiters: Limiters,
+}
+
+struct Inner {
+    peers: LruCache<NodeId, PeerEntry>,
+    total: Limiters,
+}
+
+fn peer_entry<'a>(
+    peers: &'a mut LruCache<NodeId, PeerEntry>,
+    peer: NodeId,
+    limits: &RateLimits,
+) -> &'a mut PeerEntry {
+    if !peers.contains(&peer) {
+        peers.put(
+            peer,
+            PeerEntry {
+                traffic: PeerTraffic::default(),
+                limiters: Limiters::new(limits),
+            },
+        );
+    }
+    peers.get_mut(&peer).unwrap()
+}
+
+fn stats_mut(traffic: &mut PeerTraffic, kind: TrafficKind) -> &mut TrafficStats {
+    match kind {
+        TrafficKind::Blocks => &mut traffic.blocks,
+        TrafficKind::Headers => &mut traffic.headers,
+        TrafficKind::Fragments => &mut traffic.fragments,
+        TrafficKind::Gossip => &mut traffic.gossip,
+    }
+}
+
+/// The traffic of the peers. The least recently active peers are forgotten
+/// first.
+pub struct Traffic {
+    config: RateLimitsConfig,
+    inner: Mutex<Inner>,
+    stats_counter: Metrics,
+}
+
+impl Traffic {
+    pub fn new(capacity: usize, config: RateLimitsConfig, stats_counter: Metrics) -> Self {
+        let total = Limiters::new(&config.total);
+        Traffic {
+            config,
+            inner: Mutex::new(Inner {
+                peers: LruCache::new(capacity),
+                total,
+            }),
+            stats_counter,
+        }
+    }
+
+    /// Account for an item the peer sent us of its own initiative, subject
+    /// to the rate limits. Returns `false` if the item is over the limits,
+    /// in which case it is to be dropped.
+    pub fn admit_inbound(&self, peer: NodeId, kind: TrafficKind, bytes: usize) -> bool {
+        let bytes = bytes as u64;
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+        let Inner { peers, total } = &mut *inner;
+        let entry = peer_entry(peers, peer, &self.config.per_peer);
+        let mut total_limiter = total.get_mut(kind);
+        let mut peer_limiter = entry.limiters.get_mut(kind);
+
+        let allowed = total_limiter
+            .as_mut()
+            .map_or(true, |limiter| limiter.allows(bytes, now))
+            && peer_limiter
+                .as_mut()
+                .map_or(true, |limiter| limiter.allows(bytes, now));
+
+        let stats = stats_mut(&mut entry.traffic, kind);
+        if allowed {
+            stats.items_received += 1;
+            stats.bytes_received += bytes;
+            if let Some(limiter) = total_limiter {
+                limiter.take(bytes);
+            }
+            if let Some(limiter) = peer_limiter {
+                limiter.take(bytes);
+            }
+        } else {
+            stats.items_dropped += 1;
+        }
+        drop(inner);
+
+        if allowed {
+            self.stats_counter
+                .add_peer_traffic(TrafficDirection::Inbound, kind, bytes);
+        } else {
+            self.stats_counter.add_peer_traffic_dropped(kind, 1);
+        }
+        allowed
+    }
+
+    /// Account for an item received from the peer as the response to one
+    /// of our requests
+    pub fn record_inbound(&self, peer: NodeId, kind: TrafficKind, bytes: usize) {
+        self.record(peer, TrafficDirection::Inbound, kind, bytes as u64)
+    }
+
+    pub fn record_outbound(&self, peer: NodeId, kind: TrafficKind, bytes: usize) {
+        self.record(peer, TrafficDirection::Outbound, kind, bytes as u64)
+    }
+
+    fn record(&self, peer: NodeId, direction: TrafficDirection, kind: TrafficKind, bytes: u64) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            let entry = peer_entry(&mut inner.peers, peer, &self.config.per_peer);
+            let stats = stats_mut(&mut entry.traffic, kind);
+            match direction {
+                TrafficDirection::Inbound => {
+                    stats.items_received += 1;
+                    stats.bytes_received += bytes;
+                }
+                TrafficDirection::Outbound => {
+                    stats.items_sent += 1;
+                    stats.bytes_sent += bytes;
+                }
+            }
+        }
+        self.stats_counter.add_peer_traffic(direction, kind, bytes);
+    }
+
+    pub fn peer_traffic(&self, peer: &NodeId) -> PeerTraffic {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .peers
+            .peek(peer)
+            .map(|entry| entry.traffic.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_limits_bursts() {
+        let now = Instant::now();
+        let mut limiter = Limiter::new(&RateLimit {
+            items_per_second: Some(2),
+            bytes_per_second: None,
+        });
+        for _ in 0..2 {
+            assert!(limiter.allows(100, now));
+            limiter.take(100);
+        }
+        assert!(!limiter.allows(100, now));
+        assert!(limiter.allows(100, now + std::time::Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn large_items_are_paid_back() {
+        let now = Instant::now();
+        let mut limiter = Limiter::new(&RateLimit {
+            items_per_second: None,
+            bytes_per_second: Some(1000),
+        });
+        assert!(limiter.allows(3000, now));
+        limiter.take(3000);
+        assert!(!limiter.allows(1, now + std::time::Duration::from_secs(1)));
+        assert!(limiter.allows(1, now + std::time::Duration::from_secs(3)));
+    }
+}
@@ -57,6 +57,13 @@ impl Node for NodeService {
     type GossipService = Self;
 
     async fn handshake(&self, peer: Peer, nonce: &[u8]) -> Result<HandshakeResponse, Error> {
+        if self
+            .global_state
+            .peer_rules()
+            .is_ip_banned(peer.addr().ip())
+        {
+            return Err(Error::new(ErrorCode::PermissionDenied, "peer is banned"));
+        }
         let block0_id = BlockId::try_from(self.global_state.block0_hash.as_bytes()).unwrap();
         let keypair = &self.global_state.keypair;
         let auth = keypair.sign(nonce);
@@ -74,6 +81,9 @@ impl Node for NodeService {
     async fn client_auth(&self, peer: Peer, auth: AuthenticatedNodeId) -> Result<(), Error> {
         let addr = peer.addr();
         let id = auth.id().clone().decode()?;
+        if self.global_state.peer_rules().is_banned(&id, addr.ip()) {
+            return Err(Error::new(ErrorCode::PermissionDenied, "peer is banned"));
+        }
         self.global_state
             .peers
             .server_complete_handshake(addr, id, |nonce| auth.verify(&nonce[..]))
@@ -279,6 +289,7 @@ impl FragmentService for NodeService {
             subscription::process_fragments(
                 stream,
                 self.channels.transaction_box.clone(),
+                self.channels.topology_box.clone(),
                 peer_id,
                 self.global_state.clone(),
             )
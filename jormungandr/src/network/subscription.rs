@@ -1,4 +1,4 @@
-use super::{buffer_sizes, convert::Decode, GlobalStateR};
+use super::{buffer_sizes, convert::Decode, p2p::traffic::TrafficKind, GlobalStateR};
 use crate::{
     blockcfg::Fragment,
     intercom::{self, BlockMsg, TopologyMsg, TransactionMsg},
@@ -11,7 +11,9 @@ use chain_network::{
     error::{Code, Error},
 };
 use futures::{future::BoxFuture, prelude::*, ready};
-use jormungandr_lib::interfaces::FragmentOrigin;
+use jormungandr_lib::interfaces::{
+    FragmentOrigin, FragmentRejectionReason, FragmentsProcessingSummary, PeerReportReason,
+};
 use std::{
     error::Error as _,
     mem,
@@ -78,12 +80,13 @@ pub async fn process_gossip<S>(
 pub async fn process_fragments<S>(
     stream: S,
     mbox: MessageBox<TransactionMsg>,
+    topology_box: MessageBox<TopologyMsg>,
     node_id: NodeId,
     global_state: GlobalStateR,
 ) where
     S: TryStream<Ok = net_data::Fragment, Error = Error>,
 {
-    let sink = FragmentProcessor::new(mbox, node_id, global_state);
+    let sink = FragmentProcessor::new(mbox, topology_box, node_id, global_state);
     stream
         .into_stream()
         .forward(sink)
@@ -144,6 +147,7 @@ impl BlockAnnouncementProcessor {
 #[must_use = "sinks do nothing unless polled"]
 pub struct FragmentProcessor {
     mbox: MessageBox<TransactionMsg>,
+    topology_box: MessageBox<TopologyMsg>,
     node_id: NodeId,
     global_state: GlobalStateR,
     buffered_fragments: Vec<Fragment>,
@@ -153,11 +157,13 @@ pub struct FragmentProcessor {
 impl FragmentProcessor {
     pub(super) fn new(
         mbox: MessageBox<TransactionMsg>,
+        topology_box: MessageBox<TopologyMsg>,
         node_id: NodeId,
         global_state: GlobalStateR,
     ) -> Self {
         FragmentProcessor {
             mbox,
+            topology_box,
             node_id,
             global_state,
             buffered_fragments: Vec::with_capacity(buffer_sizes::inbound::FRAGMENTS),
@@ -179,6 +185,38 @@ impl FragmentProcessor {
         // timestamp matters.
         self.pending_processing.start(fut);
     }
+
+    /// Report the peer once the fragments it sent are processed, if some of
+    /// them are invalid
+    fn report_invalid_fragments(
+        &self,
+        reply_future: intercom::ReplyFuture<FragmentsProcessingSummary>,
+    ) {
+        let mut topology_box = self.topology_box.clone();
+        let node_id = self.node_id;
+        let fut = async move {
+            let invalid = match reply_future.await {
+                Ok(summary) => summary
+                    .rejected
+                    .iter()
+                    .any(|info| info.reason == FragmentRejectionReason::FragmentInvalid),
+                Err(_) => false,
+            };
+            if invalid {
+                topology_box
+                    .send(TopologyMsg::DemotePeer(
+                        node_id,
+                        PeerReportReason::InvalidFragment,
+                    ))
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Error sending message to topology task: {}", e)
+                    });
+            }
+        }
+        .in_current_span();
+        self.global_state.spawn(fut);
+    }
 }
 
 pub enum Direction {
@@ -235,6 +273,16 @@ impl Sink<net_data::Header> for BlockAnnouncementProcessor {
     }
 
     fn start_send(mut self: Pin<&mut Self>, raw_header: net_data::Header) -> Result<(), Error> {
+        let node_id = self.node_id;
+        let admitted = self.global_state.peers.traffic().admit_inbound(
+            node_id,
+            TrafficKind::Headers,
+            raw_header.as_ref().len(),
+        );
+        if !admitted {
+            tracing::debug!("block announcement over the rate limit, dropping");
+            return Ok(());
+        }
         let header = raw_header.decode().map_err(|e| {
             tracing::info!(
                 reason = %e.source().unwrap(),
@@ -242,7 +290,6 @@ impl Sink<net_data::Header> for BlockAnnouncementProcessor {
             );
             e
         })?;
-        let node_id = self.node_id;
         self.mbox
             .start_send(BlockMsg::AnnouncedBlock(Box::new(header), node_id))
             .map_err(handle_mbox_error)?;
@@ -293,6 +340,15 @@ impl Sink<net_data::Fragment> for FragmentProcessor {
             self.buffered_fragments.len() < buffer_sizes::inbound::FRAGMENTS,
             "should call `poll_ready` which returns `Poll::Ready(Ok(()))` before `start_send`",
         );
+        let admitted = self.global_state.peers.traffic().admit_inbound(
+            self.node_id,
+            TrafficKind::Fragments,
+            raw_fragment.as_bytes().len(),
+        );
+        if !admitted {
+            tracing::debug!("fragment over the rate limit, dropping");
+            return Ok(());
+        }
         let fragment = raw_fragment.decode().map_err(|e| {
             tracing::info!(
                 reason = %e.source().unwrap(),
@@ -349,7 +405,7 @@ impl FragmentProcessor {
             &mut self.buffered_fragments,
             Vec::with_capacity(buffer_sizes::inbound::FRAGMENTS),
         );
-        let (reply_handle, _reply_future) = intercom::unary_reply();
+        let (reply_handle, reply_future) = intercom::unary_reply();
         self.mbox
             .start_send(TransactionMsg::SendTransactions {
                 origin: FragmentOrigin::Network,
@@ -364,6 +420,7 @@ impl FragmentProcessor {
                 );
                 Error::new(Code::Internal, e)
             })?;
+        self.report_invalid_fragments(reply_future);
         self.refresh_stat();
         Poll::Ready(Ok(()))
     }
@@ -392,6 +449,11 @@ impl Sink<net_data::Gossip> for GossipProcessor {
     }
 
     fn start_send(mut self: Pin<&mut Self>, gossip: net_data::Gossip) -> Result<(), Error> {
+        self.global_state.peers.traffic().record_inbound(
+            self.node_id,
+            TrafficKind::Gossip,
+            gossip.nodes.iter().map(|node| node.as_bytes().len()).sum(),
+        );
         let nodes = gossip.nodes.decode().map_err(|e| {
             tracing::info!(
                 reason = %e.source().unwrap(),
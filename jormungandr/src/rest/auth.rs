@@ -0,0 +1,154 @@
+//! Authentication of the REST API clients with static bearer tokens or API
+//! keys, and authorization of the route groups by role.
+use jormungandr_lib::interfaces::{RestAuth, RestCredential, RestRole};
+use std::{collections::HashMap, path::Path, sync::Arc};
+use thiserror::Error;
+use warp::{http::StatusCode, reject::Reject, Filter, Rejection, Reply};
+
+const API_KEY_HEADER: &str = "x-api-key";
+const BEARER_PREFIX: &str = "Bearer ";
+
+#[derive(Debug, Error)]
+pub enum CredentialsFromFileError {
+    #[error("Cannot read the REST API credentials: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid REST API credentials file: {0}")]
+    Format(#[from] serde_yaml::Error),
+}
+
+#[derive(Clone)]
+pub struct Config {
+    credentials: HashMap<String, RestRole>,
+    anonymous_role: Option<RestRole>,
+}
+
+impl Config {
+    pub fn load(auth: &RestAuth) -> Result<Self, CredentialsFromFileError> {
+        Ok(Config {
+            credentials: load_credentials(&auth.credentials_file)?,
+            anonymous_role: auth.anonymous_role,
+        })
+    }
+
+    fn role(&self, token: Option<&str>) -> Result<Option<RestRole>, AuthError> {
+        match token {
+            Some(token) => self
+                .credentials
+                .get(token)
+                .copied()
+                .map(Some)
+                .ok_or(AuthError::Unauthenticated),
+            None => Ok(self.anonymous_role),
+        }
+    }
+}
+
+fn load_credentials<P: AsRef<Path>>(
+    path: P,
+) -> Result<HashMap<String, RestRole>, CredentialsFromFileError> {
+    let file = std::fs::File::open(path)?;
+    let credentials: Vec<RestCredential> = serde_yaml::from_reader(file)?;
+    Ok(credentials
+        .into_iter()
+        .map(|credential| (credential.token, credential.role))
+        .collect())
+}
+
+#[derive(Debug, Error)]
+enum AuthError {
+    #[error("missing or invalid credentials")]
+    Unauthenticated,
+    #[error("the credentials do not grant access to this endpoint")]
+    Forbidden,
+}
+
+impl Reject for AuthError {}
+
+/// Rejects the requests whose credentials do not grant at least the `required`
+/// role. Everything is let through if the authentication is not configured.
+pub fn require(
+    config: Option<Arc<Config>>,
+    required: RestRole,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(warp::header::optional::<String>(API_KEY_HEADER))
+        .and_then(
+            move |authorization: Option<String>, api_key: Option<String>| {
+                let config = config.clone();
+                async move {
+                    let config = match config {
+                        Some(config) => config,
+                        None => return Ok(()),
+                    };
+                    let token = authorization
+                        .as_deref()
+                        .map(|value| value.strip_prefix(BEARER_PREFIX).unwrap_or(value))
+                        .or_else(|| api_key.as_deref());
+                    match config.role(token).map_err(warp::reject::custom)? {
+                        Some(role) if role >= required => Ok(()),
+                        Some(_) => Err(warp::reject::custom(AuthError::Forbidden)),
+                        None => Err(warp::reject::custom(AuthError::Unauthenticated)),
+                    }
+                }
+            },
+        )
+        .untuple_one()
+}
+
+/// Convert the authentication rejections to actual HTTP errors
+pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
+    if let Some(err) = err.find::<AuthError>() {
+        let code = match err {
+            AuthError::Unauthenticated => StatusCode::UNAUTHORIZED,
+            AuthError::Forbidden => StatusCode::FORBIDDEN,
+        };
+        return Ok(warp::reply::with_status(err.to_string(), code));
+    }
+
+    Err(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(anonymous_role: Option<RestRole>) -> Config {
+        Config {
+            credentials: vec![
+                ("reader".to_owned(), RestRole::ReadOnly),
+                ("admin".to_owned(), RestRole::Admin),
+            ]
+            .into_iter()
+            .collect(),
+            anonymous_role,
+        }
+    }
+
+    #[test]
+    fn role_of_known_token() {
+        let config = config(None);
+        assert_eq!(
+            config.role(Some("reader")).unwrap(),
+            Some(RestRole::ReadOnly)
+        );
+        assert_eq!(config.role(Some("admin")).unwrap(), Some(RestRole::Admin));
+    }
+
+    #[test]
+    fn unknown_token_is_rejected_even_with_anonymous_role() {
+        let config = config(Some(RestRole::ReadOnly));
+        assert!(matches!(
+            config.role(Some("unknown")),
+            Err(AuthError::Unauthenticated)
+        ));
+    }
+
+    #[test]
+    fn missing_token_gets_anonymous_role() {
+        assert_eq!(config(None).role(None).unwrap(), None);
+        assert_eq!(
+            config(Some(RestRole::ReadOnly)).role(None).unwrap(),
+            Some(RestRole::ReadOnly)
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Liveness and readiness probes, for the orchestrators restarting the node
+//! or routing requests to it.
+use crate::rest::ContextLock;
+use jormungandr_lib::interfaces::{HealthCheck, HealthCheckName, HealthReport, NodeState};
+use std::{io, path::PathBuf, sync::Arc, time::Duration};
+use warp::{http::StatusCode, Filter, Rejection, Reply};
+
+pub const DEFAULT_MIN_CONNECTED_PEERS: usize = 1;
+
+/// the tip is read with a timeout, a node that cannot read it is considered stuck
+const TIP_READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// prefix of the probe files, each probe writes a file of its own so that
+/// concurrent probes do not remove each other's file
+const STORAGE_PROBE_PREFIX: &str = ".health_probe";
+
+#[derive(Clone)]
+pub struct Config {
+    pub max_tip_age: Duration,
+    pub min_connected_peers: usize,
+    pub storage: Option<PathBuf>,
+}
+
+pub fn filter(
+    context: ContextLock,
+    config: Config,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let with_context = warp::any().map(move || context.clone());
+    let config = Arc::new(config);
+    let with_config = warp::any().map(move || config.clone());
+    let root = warp::path!("health" / ..);
+
+    let live = warp::path!("live")
+        .and(warp::get())
+        .and(with_context.clone())
+        .and_then(|context: ContextLock| async move {
+            let report = HealthReport::new(vec![check_tip(&context).await]);
+            Ok::<_, Rejection>(reply(report))
+        });
+
+    let ready = warp::path!("ready")
+        .and(warp::get())
+        .and(with_context)
+        .and(with_config)
+        .and_then(|context: ContextLock, config: Arc<Config>| async move {
+            let report = HealthReport::new(vec![
+                check_bootstrap(&context).await,
+                check_tip_freshness(&context, config.max_tip_age).await,
+                check_peers(&context, config.min_connected_peers).await,
+                check_storage(config.storage.clone()).await,
+            ]);
+            Ok::<_, Rejection>(reply(report))
+        });
+
+    root.and(live.or(ready))
+}
+
+fn reply(report: HealthReport) -> impl Reply {
+    let status = if report.healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    warp::reply::with_status(warp::reply::json(&report), status)
+}
+
+async fn check_tip(context: &ContextLock) -> HealthCheck {
+    let name = HealthCheckName::Tip;
+    let tip = match context.read().await.blockchain_tip() {
+        Ok(tip) => tip.clone(),
+        // the blockchain is still being loaded from the storage
+        Err(_) => return HealthCheck::passed(name, "the blockchain is not loaded yet"),
+    };
+    match tokio::time::timeout(TIP_READ_TIMEOUT, tip.get_ref()).await {
+        Ok(tip) => HealthCheck::passed(name, format!("the tip is {}", tip.hash())),
+        Err(_) => HealthCheck::failed(
+            name,
+            format!("the tip could not be read in {:?}", TIP_READ_TIMEOUT),
+        ),
+    }
+}
+
+async fn check_bootstrap(context: &ContextLock) -> HealthCheck {
+    let name = HealthCheckName::Bootstrap;
+    match context.read().await.node_state() {
+        NodeState::Running => HealthCheck::passed(name, "the node is running"),
+        state => HealthCheck::failed(name, format!("the node state is {:?}", state)),
+    }
+}
+
+async fn check_tip_freshness(context: &ContextLock, max_tip_age: Duration) -> HealthCheck {
+    let name = HealthCheckName::TipFreshness;
+    let tip = match context.read().await.blockchain_tip() {
+        Ok(tip) => tip.clone(),
+        Err(_) => return HealthCheck::failed(name, "the blockchain is not loaded yet"),
+    };
+    let tip = tip.get_ref().await;
+    // a tip set in the future is as fresh as can be
+    let age = tip.elapsed().unwrap_or_default();
+    if age > max_tip_age {
+        HealthCheck::failed(
+            name,
+            format!(
+                "the slot of the tip {} started {}s ago, more than the {}s allowed",
+                tip.header().description(),
+                age.as_secs(),
+                max_tip_age.as_secs()
+            ),
+        )
+    } else {
+        HealthCheck::passed(
+            name,
+            format!("the slot of the tip started {}s ago", age.as_secs()),
+        )
+    }
+}
+
+async fn check_peers(context: &ContextLock, min_connected_peers: usize) -> HealthCheck {
+    let name = HealthCheckName::Peers;
+    let connected = match context.read().await.try_full() {
+        Ok(full_context) => full_context.stats_counter.get_stats().peer_connected_cnt,
+        Err(_) => return HealthCheck::failed(name, "the network is not started yet"),
+    };
+    let message = format!(
+        "{} peers connected, {} required",
+        connected, min_connected_peers
+    );
+    if connected < min_connected_peers {
+        HealthCheck::failed(name, message)
+    } else {
+        HealthCheck::passed(name, message)
+    }
+}
+
+async fn check_storage(storage: Option<PathBuf>) -> HealthCheck {
+    let name = HealthCheckName::Storage;
+    let storage = match storage {
+        Some(storage) => storage,
+        None => return HealthCheck::passed(name, "the blocks are kept in memory"),
+    };
+    let dir = storage.clone();
+    let res = tokio::task::spawn_blocking(move || {
+        tempfile::Builder::new()
+            .prefix(STORAGE_PROBE_PREFIX)
+            .tempfile_in(&dir)?
+            .close()
+    })
+    .await
+    .unwrap_or_else(|e| Err(io::Error::new(io::ErrorKind::Other, e)));
+    match res {
+        Ok(()) => HealthCheck::passed(
+            name,
+            format!("the storage directory {} is writable", storage.display()),
+        ),
+        Err(e) => HealthCheck::failed(
+            name,
+            format!(
+                "cannot write in the storage directory {}: {}",
+                storage.display(),
+                e
+            ),
+        ),
+    }
+}
@@ -0,0 +1,360 @@
+//! Rate and size limits on the requests served by the REST API and the
+//! JSON-RPC server, so that a single client cannot overload the node.
+use crate::{context::ContextLock, metrics::MetricsBackend, utils::token_bucket::TokenBucket};
+use futures::prelude::*;
+use jormungandr_lib::interfaces::RequestLimits;
+use lru::LruCache;
+use serde::de::DeserializeOwned;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use thiserror::Error;
+use warp::{
+    http::StatusCode,
+    hyper::body::Bytes,
+    reject::{self, Reject},
+    Buf, Filter, Rejection, Reply,
+};
+
+/// number of client addresses whose rate is tracked, the least recently
+/// active ones are forgotten first
+const TRACKED_CLIENTS: usize = 4096;
+
+/// the largest request body read when no maximum size is configured
+pub const DEFAULT_MAX_BODY_SIZE: u64 = 10 * 1024 * 1024;
+
+/// why a request was rejected, as reported in the metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestRejection {
+    /// the client or all the clients together sent too many requests
+    RateLimited,
+    /// the request body is too large, or its size is not announced
+    BodyTooLarge,
+    /// the fragments batch holds too many fragments
+    BatchTooLarge,
+}
+
+impl RequestRejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestRejection::RateLimited => "rate_limited",
+            RequestRejection::BodyTooLarge => "body_too_large",
+            RequestRejection::BatchTooLarge => "batch_too_large",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum LimitError {
+    #[error("too many requests, retry later")]
+    TooManyRequests,
+    #[error("the request body is larger than {0} bytes")]
+    BodyTooLarge(u64),
+    #[error("the request body length must be announced")]
+    LengthRequired,
+    #[error("the batch holds more than {0} fragments")]
+    BatchTooLarge(usize),
+}
+
+impl Reject for LimitError {}
+
+#[derive(Debug, Error)]
+enum BodyError {
+    #[error("cannot read the request body")]
+    Read(#[source] warp::Error),
+    #[error("cannot deserialize the request body")]
+    Json(#[source] serde_json::Error),
+}
+
+impl Reject for BodyError {}
+
+struct Buckets {
+    per_ip: LruCache<IpAddr, TokenBucket>,
+    total: Option<TokenBucket>,
+}
+
+/// the limits of a server, shared by all its connections
+pub struct Limiter {
+    server: &'static str,
+    per_ip_rate: Option<u32>,
+    max_body_size: Option<u64>,
+    buckets: Mutex<Buckets>,
+}
+
+impl Limiter {
+    /// `server` names the server in the metrics
+    pub fn new(server: &'static str, limits: &RequestLimits) -> Self {
+        Limiter {
+            server,
+            per_ip_rate: limits.per_ip_requests_per_second,
+            max_body_size: limits.max_body_size,
+            buckets: Mutex::new(Buckets {
+                per_ip: LruCache::new(TRACKED_CLIENTS),
+                total: limits
+                    .total_requests_per_second
+                    .map(|rate| TokenBucket::new(rate.into())),
+            }),
+        }
+    }
+
+    /// account for a request of the client, returns `false` if it is over
+    /// the rate limits
+    fn admit(&self, client: Option<IpAddr>, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap();
+        let Buckets { per_ip, total } = &mut *buckets;
+
+        let mut client_bucket = match (client, self.per_ip_rate) {
+            (Some(ip), Some(rate)) => {
+                if !per_ip.contains(&ip) {
+                    per_ip.put(ip, TokenBucket::new(rate.into()));
+                }
+                per_ip.get_mut(&ip)
+            }
+            _ => None,
+        };
+
+        let allowed = total.as_mut().map_or(true, |bucket| {
+            bucket.refill(now);
+            bucket.allows(1.0)
+        }) && client_bucket.as_mut().map_or(true, |bucket| {
+            bucket.refill(now);
+            bucket.allows(1.0)
+        });
+        if allowed {
+            if let Some(bucket) = total {
+                bucket.take(1.0);
+            }
+            if let Some(bucket) = client_bucket {
+                bucket.take(1.0);
+            }
+        }
+        allowed
+    }
+
+    /// the largest body read, whether or not a maximum is configured
+    fn body_limit(&self) -> u64 {
+        self.max_body_size.unwrap_or(DEFAULT_MAX_BODY_SIZE)
+    }
+}
+
+async fn count_rejection(context: &ContextLock, server: &'static str, reason: RequestRejection) {
+    if let Ok(metrics) = context.read().await.metrics() {
+        metrics.add_request_rejected(server, reason);
+    }
+}
+
+/// Rejects the requests over the rate limits
+pub fn filter(
+    limiter: Arc<Limiter>,
+    context: ContextLock,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |client: Option<SocketAddr>| {
+            let limiter = limiter.clone();
+            let context = context.clone();
+            async move {
+                if limiter.admit(client.map(|addr| addr.ip()), Instant::now()) {
+                    Ok(())
+                } else {
+                    count_rejection(&context, limiter.server, RequestRejection::RateLimited).await;
+                    Err(reject::custom(LimitError::TooManyRequests))
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Reads the body until the end or until it is larger than `limit`, so that
+/// a body whose length is not announced cannot exceed the limit either.
+async fn read_bounded<S, B>(body: S, limit: u64) -> Result<Bytes, Rejection>
+where
+    S: Stream<Item = Result<B, warp::Error>>,
+    B: Buf,
+{
+    futures::pin_mut!(body);
+    let mut bytes = Vec::new();
+    while let Some(mut chunk) = body
+        .try_next()
+        .await
+        .map_err(|e| reject::custom(BodyError::Read(e)))?
+    {
+        if (bytes.len() + chunk.remaining()) as u64 > limit {
+            return Err(reject::custom(LimitError::BodyTooLarge(limit)));
+        }
+        while chunk.has_remaining() {
+            let part = chunk.chunk();
+            let len = part.len();
+            bytes.extend_from_slice(part);
+            chunk.advance(len);
+        }
+    }
+    Ok(bytes.into())
+}
+
+/// Reads the request body, rejecting it if it is too large. When a maximum
+/// body size is configured, the body length must also be announced.
+pub fn body(
+    limiter: Arc<Limiter>,
+    context: ContextLock,
+) -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    let announced = match limiter.max_body_size {
+        Some(max) => warp::body::content_length_limit(max).boxed(),
+        None => warp::any().boxed(),
+    };
+    let limit = limiter.body_limit();
+    announced
+        .and(warp::body::stream())
+        .and_then(move |body| read_bounded(body, limit))
+        .or_else(move |err: Rejection| {
+            let limiter = limiter.clone();
+            let context = context.clone();
+            async move {
+                if err.find::<LimitError>().is_some()
+                    || err.find::<reject::PayloadTooLarge>().is_some()
+                    || err.find::<reject::LengthRequired>().is_some()
+                {
+                    count_rejection(&context, limiter.server, RequestRejection::BodyTooLarge).await;
+                }
+                Err::<(Bytes,), _>(err)
+            }
+        })
+}
+
+/// Deserializes the request body as JSON, with the limits of [`body`]
+pub fn json<T>(
+    limiter: Arc<Limiter>,
+    context: ContextLock,
+) -> impl Filter<Extract = (T,), Error = Rejection> + Clone
+where
+    T: DeserializeOwned + Send,
+{
+    body(limiter, context).and_then(|bytes: Bytes| async move {
+        serde_json::from_slice(&bytes).map_err(|e| reject::custom(BodyError::Json(e)))
+    })
+}
+
+/// Rejects the fragments batches holding more than `max` fragments
+pub async fn check_batch_size(
+    fragments: usize,
+    max: Option<usize>,
+    context: &ContextLock,
+) -> Result<(), Rejection> {
+    match max {
+        Some(max) if fragments > max => {
+            count_rejection(context, "rest", RequestRejection::BatchTooLarge).await;
+            Err(warp::reject::custom(LimitError::BatchTooLarge(max)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Convert the limits rejections to actual HTTP errors
+pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
+    if let Some(err) = err.find::<LimitError>() {
+        let code = match err {
+            LimitError::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            LimitError::BodyTooLarge(_) | LimitError::BatchTooLarge(_) => {
+                StatusCode::PAYLOAD_TOO_LARGE
+            }
+            LimitError::LengthRequired => StatusCode::LENGTH_REQUIRED,
+        };
+        return Ok(warp::reply::with_status(err.to_string(), code));
+    }
+    if let Some(err) = err.find::<BodyError>() {
+        return Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Err(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Context;
+    use std::time::Duration;
+    use tokio::sync::RwLock;
+
+    fn limiter(per_ip: Option<u32>, total: Option<u32>) -> Limiter {
+        body_limiter(per_ip, total, Some(100))
+    }
+
+    fn body_limiter(per_ip: Option<u32>, total: Option<u32>, max_body: Option<u64>) -> Limiter {
+        Limiter::new(
+            "test",
+            &RequestLimits {
+                per_ip_requests_per_second: per_ip,
+                total_requests_per_second: total,
+                max_body_size: max_body,
+            },
+        )
+    }
+
+    fn chunks(sizes: &[usize]) -> impl Stream<Item = Result<Bytes, warp::Error>> {
+        let chunks: Vec<_> = sizes.iter().map(|size| Ok(vec![0; *size].into())).collect();
+        stream::iter(chunks)
+    }
+
+    #[test]
+    fn clients_are_limited_separately() {
+        let limiter = limiter(Some(2), None);
+        let now = Instant::now();
+        let (a, b) = ("10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap());
+        assert!(limiter.admit(Some(a), now));
+        assert!(limiter.admit(Some(a), now));
+        assert!(!limiter.admit(Some(a), now));
+        assert!(limiter.admit(Some(b), now));
+        assert!(limiter.admit(Some(a), now + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn total_rate_covers_all_clients() {
+        let limiter = limiter(None, Some(1));
+        let now = Instant::now();
+        assert!(limiter.admit(Some("10.0.0.1".parse().unwrap()), now));
+        assert!(!limiter.admit(Some("10.0.0.2".parse().unwrap()), now));
+        assert!(!limiter.admit(None, now));
+    }
+
+    #[tokio::test]
+    async fn body_read_is_bounded() {
+        let body = read_bounded(chunks(&[60, 40]), 100).await.unwrap();
+        assert_eq!(body.len(), 100);
+        let err = read_bounded(chunks(&[60, 41]), 100).await.unwrap_err();
+        assert!(matches!(
+            err.find::<LimitError>(),
+            Some(LimitError::BodyTooLarge(100))
+        ));
+    }
+
+    #[tokio::test]
+    async fn body_size_is_checked() {
+        let context = Arc::new(RwLock::new(Context::default()));
+        let filter = body(Arc::new(limiter(None, None)), context.clone());
+        let accepted = warp::test::request()
+            .method("POST")
+            .body(vec![0; 100])
+            .filter(&filter)
+            .await;
+        assert_eq!(accepted.unwrap().len(), 100);
+        let rejected = warp::test::request()
+            .method("POST")
+            .body(vec![0; 101])
+            .filter(&filter)
+            .await;
+        assert!(rejected.is_err());
+
+        // without a configured maximum, the default one applies
+        let filter = body(Arc::new(body_limiter(None, None, None)), context);
+        let rejected = warp::test::request()
+            .method("POST")
+            .body(vec![0; DEFAULT_MAX_BODY_SIZE as usize + 1])
+            .filter(&filter)
+            .await;
+        assert!(rejected.is_err());
+    }
+}
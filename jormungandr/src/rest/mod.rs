@@ -1,4 +1,7 @@
 //! REST API of the node
+pub mod auth;
+pub mod health;
+pub mod limits;
 #[cfg(feature = "prometheus-metrics")]
 mod prometheus;
 pub mod v0;
@@ -6,14 +9,18 @@ mod v1;
 
 use crate::context::{Context, ContextLock, ServerStopper};
 use futures::{channel::mpsc, prelude::*};
-use jormungandr_lib::interfaces::{Cors, Tls};
-use std::{error::Error, net::SocketAddr, time::Duration};
+use jormungandr_lib::interfaces::{Cors, RequestLimits, Tls};
+use std::{error::Error, net::SocketAddr, sync::Arc, time::Duration};
 use warp::Filter;
 
 pub struct Config {
     pub listen: SocketAddr,
     pub tls: Option<Tls>,
     pub cors: Option<Cors>,
+    pub health: health::Config,
+    pub auth: Option<auth::Config>,
+    pub limits: Option<RequestLimits>,
+    pub max_fragments_per_batch: Option<usize>,
     #[cfg(feature = "prometheus-metrics")]
     pub enable_prometheus: bool,
 }
@@ -25,10 +32,24 @@ pub async fn start_rest_server(config: Config, context: ContextLock) {
         .write()
         .await
         .set_rest_server_stopper(ServerStopper::new(stopper_tx));
-    let api = v0::filter(context.clone()).or(v1::filter(context.clone()));
+    let auth = config.auth.clone().map(Arc::new);
+    let limiter = Arc::new(limits::Limiter::new(
+        "rest",
+        &config.limits.clone().unwrap_or_default(),
+    ));
+    let api = v0::filter(context.clone(), auth.clone(), limiter.clone())
+        .or(v1::filter(
+            context.clone(),
+            auth,
+            limiter.clone(),
+            config.max_fragments_per_batch,
+        ))
+        .recover(auth::handle_rejection);
 
     let api = warp::path!("api" / ..)
+        .and(limits::filter(limiter, context.clone()))
         .and(api)
+        .recover(limits::handle_rejection)
         .with(warp::filters::trace::trace(|info| {
             use http_zipkin::get_trace_context;
             use tracing::field::Empty;
@@ -53,8 +74,10 @@ pub async fn start_rest_server(config: Config, context: ContextLock) {
                     span.record("parent_span_id", parent_span_id.to_string().as_str());
                 }
             }
+            crate::log::set_remote_parent(&span, info.request_headers());
             span
-        }));
+        }))
+        .or(health::filter(context.clone(), config.health.clone()));
 
     setup_prometheus(api, config, context, stopper_rx).await;
 }
@@ -142,11 +165,15 @@ async fn run_server_with_app<App>(
 {
     let server = warp::serve(app);
     if let Some(tls_config) = tls_config {
-        let (_, server_fut) = server
+        let server = server
             .tls()
             .cert_path(tls_config.cert_file)
-            .key_path(tls_config.priv_key_file)
-            .bind_with_graceful_shutdown(listen_addr, shutdown_signal);
+            .key_path(tls_config.priv_key_file);
+        let server = match tls_config.client_ca_file {
+            Some(client_ca_file) => server.client_auth_required_path(client_ca_file),
+            None => server,
+        };
+        let (_, server_fut) = server.bind_with_graceful_shutdown(listen_addr, shutdown_signal);
         server_fut.await;
     } else {
         let (_, server_fut) = server.bind_with_graceful_shutdown(listen_addr, shutdown_signal);
@@ -9,11 +9,9 @@ pub fn filter(
         .and(warp::any().map(move || context.clone()))
         .and_then(|context: ContextLock| async move {
             let context = context.read().await;
-            let full_context = context.try_full().map_err(warp::reject::custom)?;
-            full_context
-                .prometheus
-                .as_ref()
-                .expect("Prometheus metrics exporter not set in API context!")
+            context
+                .prometheus()
+                .map_err(warp::reject::custom)?
                 .http_response()
         })
 }
@@ -120,6 +120,31 @@ pub async fn get_leaders_logs(context: ContextLock) -> Result<impl Reply, Reject
         .map_err(warp::reject::custom)
 }
 
+pub async fn get_leaders_kes(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_leaders_kes(&context)
+        .await
+        .map_err(warp::reject::custom)?
+        .map(|r| warp::reply::json(&r))
+        .ok_or_else(warp::reject::not_found)
+}
+
+pub async fn get_leaders_schedule(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_leaders_schedule(&context)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn post_leaders_dry_run(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::post_leaders_dry_run(&context)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
 pub async fn get_stake_pools(context: ContextLock) -> Result<impl Reply, Rejection> {
     let context = context.read().await;
     logic::get_stake_pools(&context)
@@ -259,6 +284,34 @@ pub async fn get_active_vote_plans(context: ContextLock) -> Result<impl Reply, R
         .map_err(warp::reject::custom)
 }
 
+pub async fn get_update_proposals(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_update_proposals(&context)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn get_update_proposal(
+    proposal_id_hex: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_update_proposal(&context, &proposal_id_hex)
+        .await
+        .map_err(warp::reject::custom)?
+        .map(|r| warp::reply::json(&r))
+        .ok_or_else(warp::reject::not_found)
+}
+
+pub async fn get_tokens(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_tokens(&context)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
 #[cfg(feature = "evm")]
 pub async fn get_jor_address(
     evm_id_hex: String,
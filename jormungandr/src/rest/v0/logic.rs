@@ -6,9 +6,11 @@
 // - All errors should be processed on the framework  integration side. Usually
 //   they are 400 or 500.
 use crate::{
-    blockchain::StorageError,
+    blockcfg::{ConsensusVersion, Leadership},
+    blockchain::{Ref, StorageError},
     diagnostic::Diagnostic,
     intercom::{self, NetworkMsg, TopologyMsg, TransactionMsg},
+    leadership::{Enclave, EnclaveError, LeadershipError, Schedule},
     rest::Context,
     topology::PeerInfo,
     utils::async_msg::MessageBox,
@@ -26,22 +28,29 @@ use chain_impl_mockchain::{
     fragment::{Fragment, FragmentId},
     key::Hash,
     leadership::LeadershipConsensus,
+    ledger::Ledger,
     value::ValueError,
 };
+use chain_time::{
+    era::{EpochPosition, EpochSlotOffset},
+    Epoch, TimeFrame,
+};
 use futures::{
     channel::mpsc::{SendError, TrySendError},
     prelude::*,
 };
 use jormungandr_lib::{
     interfaces::{
-        AccountState, EpochRewardsInfo, FragmentLog, FragmentOrigin, FragmentsProcessingSummary,
-        LeadershipLog, NodeStatsDto, PeerStats, Rewards as StakePoolRewards, SettingsDto,
-        StakeDistribution, StakeDistributionDto, StakePoolStats, TaxTypeSerde, TransactionOutput,
-        Value, VotePlanStatus,
+        AccountState, BlockDate, BlockDryRun, EpochRewardsInfo, FragmentLog, FragmentOrigin,
+        FragmentsProcessingSummary, FromConfigParamError, KesKeyStatus, LeadershipLog,
+        LeadershipSchedule, LeadershipScheduleSlot, NodeStatsDto, PeerStats,
+        Rewards as StakePoolRewards, SettingsDto, StakeDistribution, StakeDistributionDto,
+        StakePoolStats, TaxTypeSerde, TokenIdentifier, TokenSupply, TransactionOutput,
+        UpdateProposalStatus, Value, VotePlanStatus,
     },
     time::SystemTime,
 };
-use std::{net::SocketAddr, sync::Arc};
+use std::{collections::BTreeMap, net::SocketAddr, sync::Arc};
 use tracing::{span, Level};
 use tracing_futures::Instrument;
 
@@ -78,6 +87,14 @@ pub enum Error {
     Hex(#[from] hex::FromHexError),
     #[error("Could not process fragment")]
     Fragment(FragmentsProcessingSummary),
+    #[error(transparent)]
+    Enclave(#[from] EnclaveError),
+    #[error("Could not compute the leadership schedule")]
+    ScheduleTask(#[from] tokio::task::JoinError),
+    #[error(transparent)]
+    Leadership(#[from] LeadershipError),
+    #[error("Invalid changes in update proposal")]
+    UpdateProposal(#[from] FromConfigParamError),
     #[cfg(feature = "evm")]
     #[error("Can not parse address: {0}")]
     AddressParseError(String),
@@ -327,6 +344,88 @@ pub async fn get_leaders_logs(context: &Context) -> Result<Vec<LeadershipLog>, E
     Ok(context.try_full()?.leadership_logs.logs().await)
 }
 
+/// the evolution state of the KES key of the node's genesis praos leader,
+/// if any
+pub async fn get_leaders_kes(context: &Context) -> Result<Option<KesKeyStatus>, Error> {
+    Ok(context
+        .try_full()?
+        .enclave
+        .kes_key_status(std::time::SystemTime::now()))
+}
+
+pub async fn get_leaders_schedule(context: &Context) -> Result<Vec<LeadershipSchedule>, Error> {
+    let enclave = Enclave::new(context.try_full()?.enclave.clone());
+    let tip = context.blockchain_tip()?.get_ref().await;
+
+    let mut leaderships = vec![Arc::clone(tip.epoch_leadership_schedule())];
+    leaderships.extend(next_epoch_leadership(&tip));
+
+    let mut schedules = Vec::with_capacity(leaderships.len());
+    for leadership in leaderships {
+        let nb_slots = leadership.era().slots_per_epoch();
+        let schedule = enclave
+            .query_schedules(Arc::clone(&leadership), 0, nb_slots)
+            .await?;
+        let time_frame = Arc::clone(tip.time_frame());
+        // evaluating the leadership of every slot of the epoch is CPU bound,
+        // keep it away from the REST server's executor
+        let schedule = tokio::task::spawn_blocking(move || {
+            collect_leadership_schedule(schedule, &leadership, &time_frame)
+        })
+        .await?;
+        schedules.push(schedule);
+    }
+    Ok(schedules)
+}
+
+/// the leadership of the epoch following the tip, if its stake distribution
+/// is already fixed.
+///
+/// This mirrors the choice of the ledger state made by
+/// `blockchain::new_epoch_leadership_from` when the node actually enters the
+/// next epoch: with Genesis Praos the stake distribution is the one at the
+/// end of the epoch preceding the tip's, which is not known in memory for the
+/// first epochs. With BFT it is the tip's ledger with the accepted update
+/// proposals applied, as they take effect when entering the next epoch.
+fn next_epoch_leadership(tip: &Ref) -> Option<Arc<Leadership>> {
+    let next_epoch = tip.block_date().epoch + 1;
+    let ledger = tip.ledger().apply_protocol_changes().ok()?;
+    let epoch_state = match ledger.consensus_version() {
+        ConsensusVersion::GenesisPraos => tip.last_ref_previous_epoch()?.ledger(),
+        ConsensusVersion::Bft => Arc::new(ledger),
+    };
+    Some(Arc::new(Leadership::new(next_epoch, &epoch_state)))
+}
+
+fn collect_leadership_schedule(
+    mut schedule: Schedule,
+    leadership: &Leadership,
+    time_frame: &TimeFrame,
+) -> LeadershipSchedule {
+    let era = leadership.era();
+    let mut slots = Vec::new();
+    while let Some(event) = schedule.next_event() {
+        let position = EpochPosition {
+            epoch: Epoch(event.date.epoch),
+            slot: EpochSlotOffset(event.date.slot_id),
+        };
+        if let Some(time) = time_frame.slot_to_systemtime(era.from_era_to_slot(position)) {
+            slots.push(LeadershipScheduleSlot::new(
+                BlockDate::from(event.date),
+                time.into(),
+            ));
+        }
+    }
+    LeadershipSchedule::new(leadership.epoch(), slots)
+}
+
+pub async fn post_leaders_dry_run(context: &Context) -> Result<BlockDryRun, Error> {
+    let dry_run = context.try_full()?.leadership_dry_run.clone();
+    let tip = context.blockchain_tip()?.get_ref().await;
+    let span = span!(parent: context.span()?, Level::TRACE, "request", request = "leaders_dry_run");
+    dry_run.run(tip).instrument(span).await.map_err(Into::into)
+}
+
 pub async fn get_stake_pools(context: &Context) -> Result<Vec<String>, Error> {
     Ok(context
         .blockchain_tip()?
@@ -361,6 +460,7 @@ pub async fn get_network_stats(context: &Context) -> Result<Vec<PeerStats>, Erro
                 last_block_received: info.stats.last_block_received().map(SystemTime::from),
                 last_fragment_received: info.stats.last_fragment_received().map(SystemTime::from),
                 last_gossip_received: info.stats.last_gossip_received().map(SystemTime::from),
+                traffic: info.traffic,
             })
             .collect())
     }
@@ -595,6 +695,61 @@ pub async fn get_active_vote_plans(context: &Context) -> Result<Vec<VotePlanStat
     Ok(vp)
 }
 
+/// the update proposals pending in the ledger, with their votes
+pub async fn get_update_proposals(context: &Context) -> Result<Vec<UpdateProposalStatus>, Error> {
+    let ledger = context.blockchain_tip()?.get_ref().await.ledger();
+    let settings = ledger.settings();
+    ledger
+        .updates()
+        .proposals
+        .iter()
+        .map(|(id, state)| {
+            UpdateProposalStatus::new(
+                *id,
+                state,
+                settings.bft_leaders.len(),
+                settings.proposal_expiration,
+            )
+            .map_err(Into::into)
+        })
+        .collect()
+}
+
+pub async fn get_update_proposal(
+    context: &Context,
+    proposal_id_hex: &str,
+) -> Result<Option<UpdateProposalStatus>, Error> {
+    let proposal_id = parse_fragment_id(proposal_id_hex)?.into();
+    Ok(get_update_proposals(context)
+        .await?
+        .into_iter()
+        .find(|proposal| proposal.id == proposal_id))
+}
+
+pub async fn get_tokens(context: &Context) -> Result<Vec<TokenSupply>, Error> {
+    let ledger = context.blockchain_tip()?.get_ref().await.ledger();
+    Ok(token_supplies(&ledger))
+}
+
+fn token_supplies(ledger: &Ledger) -> Vec<TokenSupply> {
+    let mut supplies = BTreeMap::<TokenIdentifier, (u64, usize)>::new();
+    for (_, account) in ledger.accounts().iter() {
+        for (token_id, value) in account.tokens.iter() {
+            let (supply, holders) = supplies.entry(token_id.clone().into()).or_default();
+            *supply = supply.saturating_add(value.0);
+            *holders += 1;
+        }
+    }
+    supplies
+        .into_iter()
+        .map(|(token_id, (supply, holders))| TokenSupply {
+            token_id,
+            supply: supply.into(),
+            holders,
+        })
+        .collect()
+}
+
 #[cfg(feature = "evm")]
 pub async fn get_jor_address(context: &Context, evm_id_hex: &str) -> Result<String, Error> {
     Ok(context
@@ -623,3 +778,36 @@ pub async fn get_evm_address(context: &Context, jor_id_hex: &str) -> Result<Opti
         )
         .map(|val| val.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jormungandr_lib::interfaces::block0_configuration_documented_example_block;
+
+    #[test]
+    fn token_supplies_sum_the_account_balances() {
+        let block0 = block0_configuration_documented_example_block();
+        let ledger = Ledger::new(block0.id(), block0.fragments()).unwrap();
+
+        let supplies: Vec<_> = token_supplies(&ledger)
+            .into_iter()
+            .map(|supply| {
+                (
+                    supply.token_id.clone(),
+                    u64::from(supply.supply),
+                    supply.holders,
+                )
+            })
+            .collect();
+        let token_id = |name: &str| -> TokenIdentifier {
+            serde_yaml::from_str(&format!(
+                "00000000000000000000000000000000000000000000000000000000.{}",
+                name
+            ))
+            .unwrap()
+        };
+        assert_eq!(supplies.len(), 2);
+        assert!(supplies.contains(&(token_id("6c1e8abc"), 88, 2)));
+        assert!(supplies.contains(&(token_id("7e5d6abc"), 405, 2)));
+    }
+}
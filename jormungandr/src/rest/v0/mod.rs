@@ -1,13 +1,21 @@
 mod handlers;
 pub mod logic;
 
-use crate::rest::{display_internal_server_error, ContextLock};
+use crate::rest::{auth, display_internal_server_error, limits, ContextLock};
+use jormungandr_lib::interfaces::RestRole;
+use std::sync::Arc;
 use warp::{http::StatusCode, Filter, Rejection, Reply};
 
 pub fn filter(
     context: ContextLock,
+    auth: Option<Arc<auth::Config>>,
+    limiter: Arc<limits::Limiter>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let body = limits::body(limiter, context.clone());
     let with_context = warp::any().map(move || context.clone());
+    let read_only = auth::require(auth.clone(), RestRole::ReadOnly);
+    let submit = auth::require(auth.clone(), RestRole::Submit);
+    let admin = auth::require(auth, RestRole::Admin);
     let root = warp::path!("v0" / ..);
 
     #[cfg(feature = "evm")]
@@ -30,6 +38,7 @@ pub fn filter(
     };
 
     let shutdown = warp::path!("shutdown")
+        .and(admin.clone())
         .and(warp::get().or(warp::post()))
         .and(with_context.clone())
         .and_then(|_, context| handlers::shutdown(context))
@@ -81,7 +90,26 @@ pub fn filter(
             .and_then(handlers::get_leaders_logs)
             .boxed();
 
-        root.and(logs).boxed()
+        let schedule = warp::path!("schedule")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_leaders_schedule)
+            .boxed();
+
+        let kes = warp::path!("kes")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_leaders_kes)
+            .boxed();
+
+        let dry_run = warp::path!("dry_run")
+            .and(warp::post())
+            .and(admin.clone())
+            .and(with_context.clone())
+            .and_then(handlers::post_leaders_dry_run)
+            .boxed();
+
+        root.and(logs.or(schedule).or(kes).or(dry_run)).boxed()
     };
 
     let p2p = {
@@ -123,7 +151,8 @@ pub fn filter(
             root.and(view.or(view_topic)).boxed()
         };
 
-        root.and(quarantined.or(non_public).or(available).or(view))
+        root.and(admin.clone())
+            .and(quarantined.or(non_public).or(available).or(view))
             .boxed()
     };
 
@@ -177,7 +206,8 @@ pub fn filter(
 
     let message = warp::path!("message")
         .and(warp::post())
-        .and(warp::body::bytes())
+        .and(submit)
+        .and(body)
         .and(with_context.clone())
         .and_then(handlers::post_message)
         .boxed();
@@ -226,6 +256,7 @@ pub fn filter(
 
     let diagnostic = warp::path!("diagnostic")
         .and(warp::get())
+        .and(admin)
         .and(with_context.clone())
         .and_then(handlers::get_diagnostic)
         .boxed();
@@ -240,12 +271,34 @@ pub fn filter(
 
         let vote_plans = warp::path!("plans")
             .and(warp::get())
-            .and(with_context)
+            .and(with_context.clone())
             .and_then(handlers::get_active_vote_plans)
             .boxed();
         root.and(committees.or(vote_plans)).boxed()
     };
 
+    let governance = {
+        let root = warp::path!("governance" / "proposals" / ..);
+        let proposals = warp::path::end()
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_update_proposals)
+            .boxed();
+
+        let proposal = warp::path!(String)
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_update_proposal)
+            .boxed();
+        root.and(proposals.or(proposal)).boxed()
+    };
+
+    let tokens = warp::path!("tokens")
+        .and(warp::get())
+        .and(with_context)
+        .and_then(handlers::get_tokens)
+        .boxed();
+
     let routes = shutdown
         .or(account)
         .or(block)
@@ -262,12 +315,17 @@ pub fn filter(
         .or(rewards)
         .or(utxo)
         .or(diagnostic)
-        .or(votes);
+        .or(votes)
+        .or(governance)
+        .or(tokens);
 
     #[cfg(feature = "evm")]
     let routes = routes.or(address_mapping);
 
-    root.and(routes.boxed()).recover(handle_rejection).boxed()
+    root.and(read_only)
+        .and(routes.boxed())
+        .recover(handle_rejection)
+        .boxed()
 }
 
 /// Convert rejections to actual HTTP errors
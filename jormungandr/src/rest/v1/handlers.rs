@@ -1,5 +1,5 @@
-use crate::rest::{v1::logic, ContextLock};
-use jormungandr_lib::interfaces::{FragmentsBatch, VotePlanId};
+use crate::rest::{limits, v1::logic, ContextLock};
+use jormungandr_lib::interfaces::{BanRequest, ConnectRequest, FragmentsBatch, VotePlanId};
 use warp::{reject::Reject, Rejection, Reply};
 
 impl Reject for logic::Error {}
@@ -7,7 +7,9 @@ impl Reject for logic::Error {}
 pub async fn post_fragments(
     fragments: FragmentsBatch,
     context: ContextLock,
+    max_fragments: Option<usize>,
 ) -> Result<impl Reply, Rejection> {
+    limits::check_batch_size(fragments.fragments.len(), max_fragments, &context).await?;
     let context = context.read().await;
     logic::post_fragments(&context, fragments)
         .await
@@ -72,3 +74,100 @@ pub async fn get_accounts_votes_all(context: ContextLock) -> Result<impl Reply,
         .map_err(warp::reject::custom)
         .map(|r| warp::reply::json(&r))
 }
+
+pub async fn get_peer_bans(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_peer_bans(&context)
+        .await
+        .map_err(warp::reject::custom)
+        .map(|r| warp::reply::json(&r))
+}
+
+pub async fn post_peer_ban(
+    request: BanRequest,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::post_peer_ban(&context, request)
+        .await
+        .map_err(warp::reject::custom)
+        .map(|r| warp::reply::json(&r))
+}
+
+pub async fn delete_peer_ban(
+    target: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::delete_peer_ban(&context, target)
+        .await
+        .map_err(warp::reject::custom)?
+        .ok_or_else(warp::reject::not_found)
+        .map(|_| warp::reply())
+}
+
+pub async fn delete_quarantined_peer(
+    node_id_hex: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::delete_quarantined_peer(&context, node_id_hex)
+        .await
+        .map_err(warp::reject::custom)?
+        .ok_or_else(warp::reject::not_found)
+        .map(|_| warp::reply())
+}
+
+pub async fn post_peer_connection(
+    request: ConnectRequest,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::post_peer_connection(&context, request)
+        .await
+        .map_err(warp::reject::custom)
+        .map(|_| warp::reply())
+}
+
+pub async fn delete_peer_connection(
+    node_id_hex: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::delete_peer_connection(&context, node_id_hex)
+        .await
+        .map_err(warp::reject::custom)?
+        .ok_or_else(warp::reject::not_found)
+        .map(|_| warp::reply())
+}
+
+pub async fn get_pinned_peers(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_pinned_peers(&context)
+        .await
+        .map_err(warp::reject::custom)
+        .map(|r| warp::reply::json(&r))
+}
+
+pub async fn post_pinned_peer(
+    node_id_hex: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::post_pinned_peer(&context, node_id_hex)
+        .await
+        .map_err(warp::reject::custom)
+        .map(|_| warp::reply())
+}
+
+pub async fn delete_pinned_peer(
+    node_id_hex: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::delete_pinned_peer(&context, node_id_hex)
+        .await
+        .map_err(warp::reject::custom)?
+        .ok_or_else(warp::reject::not_found)
+        .map(|_| warp::reply())
+}
@@ -1,7 +1,10 @@
 use crate::{
     blockchain::StorageError,
-    intercom::{self, TransactionMsg},
+    intercom::{self, NetworkMsg, TopologyMsg, TransactionMsg},
+    network::p2p::rules::BanError,
     rest::Context,
+    settings::start::network::PeerResolveError,
+    topology::NodeId,
 };
 use chain_crypto::{
     digest::Error as DigestError, hash::Error as HashError, PublicKey, PublicKeyFromStrError,
@@ -18,10 +21,12 @@ use futures::{
 };
 use hex::ToHex;
 use jormungandr_lib::interfaces::{
-    AccountVotes, FragmentLog, FragmentOrigin, FragmentStatus, FragmentsBatch,
-    FragmentsProcessingSummary, VotePlanId,
+    self, AccountVotes, BanRequest, ConnectRequest, FragmentLog, FragmentOrigin, FragmentStatus,
+    FragmentsBatch, FragmentsProcessingSummary, PeerBan, PeerTarget, PeerTargetFromStrError,
+    VotePlanId,
 };
-use std::{collections::HashMap, convert::TryInto, str::FromStr};
+use jormungandr_lib::multiaddr;
+use std::{collections::HashMap, convert::TryInto, io, str::FromStr};
 use tracing::{span, Level};
 use tracing_futures::Instrument;
 
@@ -50,6 +55,18 @@ pub enum Error {
     Hex(#[from] hex::FromHexError),
     #[error("Could not process all fragments")]
     Fragments(FragmentsProcessingSummary),
+    #[error(transparent)]
+    PeerTarget(#[from] PeerTargetFromStrError),
+    #[error("invalid peer address")]
+    PeerAddress(#[from] PeerResolveError),
+    #[error("could not save the peer rules")]
+    PeerRules(#[source] io::Error),
+    #[error(transparent)]
+    PeerBan(#[from] BanError),
+}
+
+fn parse_node_id(id_hex: &str) -> Result<NodeId, Error> {
+    Ok(interfaces::NodeId::from_hex(id_hex)?.into())
 }
 
 fn parse_account_id(id_hex: &str) -> Result<Identifier, Error> {
@@ -252,3 +269,160 @@ pub async fn get_accounts_votes_all(
     .instrument(span)
     .await
 }
+
+pub async fn get_peer_bans(context: &Context) -> Result<Vec<PeerBan>, Error> {
+    Ok(context.try_full()?.network_state.peer_rules().bans())
+}
+
+pub async fn post_peer_ban(context: &Context, request: BanRequest) -> Result<PeerBan, Error> {
+    let span = span!(parent: context.span()?, Level::INFO, "peer_ban", target = %request.target);
+    async move {
+        let full_context = context.try_full()?;
+        let ban = full_context.network_state.peer_rules().ban(request)?;
+        tracing::info!(until = ?ban.until, reason = ?ban.reason, "banning peer");
+        full_context
+            .network_task
+            .clone()
+            .send(NetworkMsg::DisconnectBanned)
+            .await?;
+        full_context
+            .topology_task
+            .clone()
+            .send(TopologyMsg::ApplyPeerRules)
+            .await?;
+        Ok(ban)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Returns `None` if the target was not banned
+pub async fn delete_peer_ban(context: &Context, target: String) -> Result<Option<()>, Error> {
+    let target = PeerTarget::from_str(&target)?;
+    let span = span!(parent: context.span()?, Level::INFO, "peer_unban", %target);
+    async move {
+        let full_context = context.try_full()?;
+        let unbanned = full_context
+            .network_state
+            .peer_rules()
+            .unban(&target)
+            .map_err(Error::PeerRules)?;
+        if !unbanned {
+            return Ok(None);
+        }
+        tracing::info!("lifting peer ban");
+        full_context
+            .topology_task
+            .clone()
+            .send(TopologyMsg::ApplyPeerRules)
+            .await?;
+        Ok(Some(()))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Returns `None` if the peer was not quarantined
+pub async fn delete_quarantined_peer(
+    context: &Context,
+    node_id_hex: String,
+) -> Result<Option<()>, Error> {
+    let node_id = parse_node_id(&node_id_hex)?;
+    let span = span!(parent: context.span()?, Level::INFO, "lift_quarantine", %node_id);
+    async move {
+        let (reply_handle, reply_future) = intercom::unary_reply();
+        let mut mbox = context.try_full()?.topology_task.clone();
+        mbox.send(TopologyMsg::LiftQuarantine(node_id, reply_handle))
+            .await?;
+        let lifted = reply_future.await?;
+        if lifted {
+            tracing::info!("lifted peer quarantine");
+        }
+        Ok(if lifted { Some(()) } else { None })
+    }
+    .instrument(span)
+    .await
+}
+
+pub async fn post_peer_connection(context: &Context, request: ConnectRequest) -> Result<(), Error> {
+    let id = parse_node_id(&request.id)?;
+    let addr = multiaddr::resolve_dns(&request.address)
+        .map_err(PeerResolveError::from)
+        .and_then(|address| {
+            multiaddr::to_tcp_socket_addr(&address).ok_or(PeerResolveError::InvalidAddress)
+        })?;
+    let span = span!(parent: context.span()?, Level::INFO, "peer_connect", %addr, %id);
+    async move {
+        tracing::info!("connecting to peer");
+        let mut mbox = context.try_full()?.network_task.clone();
+        mbox.send(NetworkMsg::Connect { addr, id }).await?;
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+/// Returns `None` if the peer was not connected
+pub async fn delete_peer_connection(
+    context: &Context,
+    node_id_hex: String,
+) -> Result<Option<()>, Error> {
+    let node_id = parse_node_id(&node_id_hex)?;
+    let span = span!(parent: context.span()?, Level::INFO, "peer_disconnect", %node_id);
+    async move {
+        let (reply_handle, reply_future) = intercom::unary_reply();
+        let mut mbox = context.try_full()?.network_task.clone();
+        mbox.send(NetworkMsg::Disconnect(node_id, reply_handle))
+            .await?;
+        let disconnected = reply_future.await?;
+        if disconnected {
+            tracing::info!("disconnected peer");
+        }
+        Ok(if disconnected { Some(()) } else { None })
+    }
+    .instrument(span)
+    .await
+}
+
+pub async fn get_pinned_peers(context: &Context) -> Result<Vec<String>, Error> {
+    Ok(context
+        .try_full()?
+        .network_state
+        .peer_rules()
+        .pinned()
+        .into_iter()
+        .map(|id| id.to_string())
+        .collect())
+}
+
+pub async fn post_pinned_peer(context: &Context, node_id_hex: String) -> Result<(), Error> {
+    let node_id = parse_node_id(&node_id_hex)?;
+    let pinned = context
+        .try_full()?
+        .network_state
+        .peer_rules()
+        .pin(node_id)
+        .map_err(Error::PeerRules)?;
+    if pinned {
+        tracing::info!(%node_id, "pinned peer");
+    }
+    Ok(())
+}
+
+/// Returns `None` if the peer was not pinned
+pub async fn delete_pinned_peer(
+    context: &Context,
+    node_id_hex: String,
+) -> Result<Option<()>, Error> {
+    let node_id = parse_node_id(&node_id_hex)?;
+    let unpinned = context
+        .try_full()?
+        .network_state
+        .peer_rules()
+        .unpin(&node_id)
+        .map_err(Error::PeerRules)?;
+    if unpinned {
+        tracing::info!(%node_id, "unpinned peer");
+    }
+    Ok(if unpinned { Some(()) } else { None })
+}
@@ -1,14 +1,25 @@
 mod handlers;
 mod logic;
 
-use crate::rest::{display_internal_server_error, ContextLock};
-use jormungandr_lib::interfaces::VotePlanId;
+use crate::{
+    network::p2p::rules::BanError,
+    rest::{auth, display_internal_server_error, limits, ContextLock},
+};
+use jormungandr_lib::interfaces::{FragmentsBatch, RestRole, VotePlanId};
+use std::sync::Arc;
 use warp::{http::StatusCode, Filter, Rejection, Reply};
 
 pub fn filter(
     context: ContextLock,
+    auth: Option<Arc<auth::Config>>,
+    limiter: Arc<limits::Limiter>,
+    max_fragments_per_batch: Option<usize>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let limits_context = context.clone();
     let with_context = warp::any().map(move || context.clone());
+    let read_only = auth::require(auth.clone(), RestRole::ReadOnly);
+    let submit = auth::require(auth.clone(), RestRole::Submit);
+    let admin = auth::require(auth, RestRole::Admin);
     let root = warp::path!("v1" / ..);
 
     let fragments = {
@@ -16,9 +27,12 @@ pub fn filter(
 
         let post = warp::path::end()
             .and(warp::post())
-            .and(warp::body::json())
+            .and(submit)
+            .and(limits::json(limiter.clone(), limits_context.clone()))
             .and(with_context.clone())
-            .and_then(handlers::post_fragments)
+            .and_then(move |batch: FragmentsBatch, context: ContextLock| {
+                handlers::post_fragments(batch, context, max_fragments_per_batch)
+            })
             .boxed();
 
         let status = warp::path!("statuses")
@@ -49,19 +63,105 @@ pub fn filter(
 
     let votes_count = warp::path!("votes" / "plan" / "accounts-votes-all")
         .and(warp::get())
-        .and(with_context)
+        .and(with_context.clone())
         .and_then(handlers::get_accounts_votes_all);
 
-    let routes = fragments.or(votes_with_plan).or(votes).or(votes_count);
+    let p2p = {
+        let root = warp::path!("network" / "p2p" / ..);
+
+        let get_bans = warp::path!("bans")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_peer_bans)
+            .boxed();
+
+        let post_ban = warp::path!("bans")
+            .and(warp::post())
+            .and(limits::json(limiter.clone(), limits_context.clone()))
+            .and(with_context.clone())
+            .and_then(handlers::post_peer_ban)
+            .boxed();
+
+        let delete_ban = warp::path!("bans" / String)
+            .and(warp::delete())
+            .and(with_context.clone())
+            .and_then(handlers::delete_peer_ban)
+            .boxed();
+
+        let delete_quarantined = warp::path!("quarantined" / String)
+            .and(warp::delete())
+            .and(with_context.clone())
+            .and_then(handlers::delete_quarantined_peer)
+            .boxed();
+
+        let post_connection = warp::path!("connections")
+            .and(warp::post())
+            .and(limits::json(limiter.clone(), limits_context.clone()))
+            .and(with_context.clone())
+            .and_then(handlers::post_peer_connection)
+            .boxed();
+
+        let delete_connection = warp::path!("connections" / String)
+            .and(warp::delete())
+            .and(with_context.clone())
+            .and_then(handlers::delete_peer_connection)
+            .boxed();
+
+        let get_pinned = warp::path!("pinned")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_pinned_peers)
+            .boxed();
+
+        let post_pinned = warp::path!("pinned" / String)
+            .and(warp::post())
+            .and(with_context.clone())
+            .and_then(handlers::post_pinned_peer)
+            .boxed();
+
+        let delete_pinned = warp::path!("pinned" / String)
+            .and(warp::delete())
+            .and(with_context)
+            .and_then(handlers::delete_pinned_peer)
+            .boxed();
+
+        root.and(admin)
+            .and(
+                get_bans
+                    .or(post_ban)
+                    .or(delete_ban)
+                    .or(delete_quarantined)
+                    .or(post_connection)
+                    .or(delete_connection)
+                    .or(get_pinned)
+                    .or(post_pinned)
+                    .or(delete_pinned),
+            )
+            .boxed()
+    };
+
+    let routes = fragments
+        .or(votes_with_plan)
+        .or(votes)
+        .or(votes_count)
+        .or(p2p);
 
-    root.and(routes).recover(handle_rejection).boxed()
+    root.and(read_only)
+        .and(routes)
+        .recover(handle_rejection)
+        .boxed()
 }
 
 /// Convert rejections to actual HTTP errors
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
     if let Some(err) = err.find::<logic::Error>() {
         let (body, code) = match err {
-            logic::Error::PublicKey(_) | logic::Error::Hash(_) | logic::Error::Hex(_) => {
+            logic::Error::PublicKey(_)
+            | logic::Error::Hash(_)
+            | logic::Error::Hex(_)
+            | logic::Error::PeerTarget(_)
+            | logic::Error::PeerAddress(_)
+            | logic::Error::PeerBan(BanError::DurationTooLong) => {
                 (err.to_string(), StatusCode::BAD_REQUEST)
             }
             logic::Error::Fragments(summary) => (
@@ -1,14 +1,25 @@
-use crate::blockcfg::{
-    BlockDate, HeaderBft, HeaderBftBuilder, HeaderGenesisPraos, HeaderGenesisPraosBuilder,
-    HeaderSetConsensusSignature,
+use crate::{
+    blockcfg::{
+        BlockDate, HeaderBft, HeaderBftBuilder, HeaderGenesisPraos, HeaderGenesisPraosBuilder,
+        HeaderSetConsensusSignature,
+    },
+    secure::kes::{evolve_key, KesError, KesState, MAX_PERIODS},
 };
 use chain_impl_mockchain::leadership::{Leader, LeaderOutput, Leadership};
 use chain_time::Epoch;
-use std::sync::Arc;
+use jormungandr_lib::interfaces::KesKeyStatus;
+use std::{
+    sync::{Arc, Mutex, RwLock},
+    time::SystemTime,
+};
 
 #[derive(Clone)]
 pub struct Enclave {
-    leader_data: Arc<Option<Leader>>,
+    leader_data: Arc<RwLock<Option<Leader>>>,
+    kes: Arc<Mutex<Option<KesState>>>,
+    /// held while the KES key is evolved and saved, so the key is not
+    /// evolved twice at once and the file is not written out of order
+    kes_evolution: Arc<Mutex<()>>,
 }
 
 pub struct LeaderEvent {
@@ -25,36 +36,111 @@ pub struct Schedule {
 }
 
 impl Enclave {
-    pub fn new(leader_data: Option<Leader>) -> Self {
+    /// the KES state is expected along a leader holding a genesis praos
+    /// setting, the KES key cannot sign without it
+    pub fn new(leader_data: Option<Leader>, kes: Option<KesState>) -> Self {
         Enclave {
-            leader_data: Arc::new(leader_data),
+            leader_data: Arc::new(RwLock::new(leader_data)),
+            kes: Arc::new(Mutex::new(kes)),
+            kes_evolution: Arc::new(Mutex::new(())),
         }
     }
 
+    /// sign the header of a block of the slot starting at `slot_time` with
+    /// the KES key, which must have been evolved to the period of the slot
+    /// already, see [`Enclave::evolve_kes_key`]
     pub fn create_header_genesis_praos(
         &self,
         header_builder: HeaderGenesisPraosBuilder<HeaderSetConsensusSignature>,
-    ) -> Option<HeaderGenesisPraos> {
-        let leader = self
-            .leader_data
+        slot_time: SystemTime,
+    ) -> Result<Option<HeaderGenesisPraos>, KesError> {
+        let kes = self.kes.lock().unwrap();
+        let kes = match kes.as_ref() {
+            Some(kes) => kes,
+            None => return Ok(None),
+        };
+        let period = kes.target_period(slot_time)?;
+        if period > kes.period() {
+            return Err(KesError::NotEvolved {
+                current: kes.period(),
+                requested: period,
+            });
+        }
+
+        let leader_data = self.leader_data.read().unwrap();
+        let leader = match leader_data
             .as_ref()
-            .as_ref()?
-            .genesis_leader
-            .as_ref()?;
+            .and_then(|leader| leader.genesis_leader.as_ref())
+        {
+            Some(leader) => leader,
+            None => return Ok(None),
+        };
+
         let data = header_builder.get_authenticated_data();
         let signature = leader.sig_key.sign_slice(data);
-        Some(header_builder.set_signature(signature.into()))
+        Ok(Some(header_builder.set_signature(signature.into())))
     }
 
     pub fn create_header_bft(
         &self,
         header_builder: HeaderBftBuilder<HeaderSetConsensusSignature>,
     ) -> Option<HeaderBft> {
-        let leader = self.leader_data.as_ref().as_ref()?.bft_leader.as_ref()?;
+        let leader_data = self.leader_data.read().unwrap();
+        let leader = leader_data.as_ref()?.bft_leader.as_ref()?;
         let data = header_builder.get_authenticated_data();
         let signature = leader.sig_key.sign_slice(data);
         Some(header_builder.set_signature(signature.into()))
     }
+
+    /// evolve the KES key to the period of the given time, if it is not there
+    /// already. The key is evolved and saved without holding the locks used
+    /// for signing, and only replaced once the secret file holds the evolved
+    /// one.
+    pub fn evolve_kes_key(&self, now: SystemTime) -> Result<(), KesError> {
+        let _evolution = self.kes_evolution.lock().unwrap();
+        let (key, current, target, secret_file) = {
+            let kes = self.kes.lock().unwrap();
+            let kes = match kes.as_ref() {
+                Some(kes) => kes,
+                None => return Ok(()),
+            };
+            // an expired key is evolved to its last period and left there
+            let target = kes.schedule().period_at(now).min(MAX_PERIODS - 1);
+            if target <= kes.period() {
+                return Ok(());
+            }
+            let leader_data = self.leader_data.read().unwrap();
+            let key = leader_data
+                .as_ref()
+                .and_then(|leader| leader.genesis_leader.as_ref())
+                .ok_or(KesError::NoGenesisLeader)?
+                .sig_key
+                .clone();
+            (key, kes.period(), target, kes.secret_file())
+        };
+
+        let evolved = evolve_key(&key, current, target)?;
+        secret_file.update_kes_key(&evolved, target)?;
+
+        let mut kes = self.kes.lock().unwrap();
+        let mut leader_data = self.leader_data.write().unwrap();
+        if let (Some(kes), Some(leader)) = (
+            kes.as_mut(),
+            leader_data
+                .as_mut()
+                .and_then(|leader| leader.genesis_leader.as_mut()),
+        ) {
+            leader.sig_key = evolved;
+            kes.set_period(target);
+        }
+        Ok(())
+    }
+
+    /// the evolution state of the KES key, if the enclave holds a genesis
+    /// praos leader
+    pub fn kes_key_status(&self, now: SystemTime) -> Option<KesKeyStatus> {
+        self.kes.lock().unwrap().as_ref().map(|kes| kes.status(now))
+    }
 }
 
 impl Schedule {
@@ -76,7 +162,9 @@ impl Schedule {
     }
 
     fn fill(&mut self) {
-        let leader = if let Some(leader) = self.enclave.leader_data.as_ref() {
+        let enclave = Arc::clone(&self.enclave);
+        let leader_data = enclave.leader_data.read().unwrap();
+        let leader = if let Some(leader) = leader_data.as_ref() {
             leader
         } else {
             return;
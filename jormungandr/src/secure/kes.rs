@@ -0,0 +1,304 @@
+//! Evolution of the KES signing key of the Genesis Praos leader
+//!
+//! The `SumEd25519_12` key starts at period 0 with the blockchain, evolves
+//! every `kes_update_speed` of the block0 settings and can only sign for
+//! [`MAX_PERIODS`] periods. Evolving erases the secrets of the past periods,
+//! so the evolved key is written back to the secret file: restarting the
+//! node must not bring back a key able to sign for a period already passed.
+
+use crate::{
+    blockcfg::{Block, Block0DataSource as _, Block0Error},
+    metrics::{Metrics, MetricsBackend},
+    secure::{enclave::Enclave, NodeSecretToFileError, SecretFile},
+};
+use chain_crypto::{SecretKey, SumEd25519_12};
+use jormungandr_lib::interfaces::KesKeyStatus;
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use thiserror::Error;
+
+/// number of periods a `SumEd25519_12` key can sign for
+pub const MAX_PERIODS: u32 = 1 << 12;
+
+/// how long before the expiry of the key warnings are logged, if not configured
+pub const DEFAULT_EXPIRY_WARNING: Duration = Duration::from_secs(7 * 24 * 3600);
+
+const KES_CHECK_PERIOD: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Error)]
+pub enum KesError {
+    #[error("the KES key is at period {current}, it cannot sign for the past period {requested}")]
+    PeriodPassed { current: u32, requested: u32 },
+    #[error("the KES key has expired, it cannot sign for period {0}")]
+    Expired(u32),
+    #[error("the KES key is at period {current}, not evolved to period {requested} yet")]
+    NotEvolved { current: u32, requested: u32 },
+    #[error("the KES key cannot be evolved past period {0}")]
+    CannotEvolve(u32),
+    #[error("the enclave does not hold a genesis praos leader")]
+    NoGenesisLeader,
+    #[error("cannot save the evolved KES key, not using it before it is saved")]
+    Persist(#[from] NodeSecretToFileError),
+}
+
+/// the pace of the KES key evolution, from the block0 settings
+#[derive(Debug, Clone, Copy)]
+pub struct KesSchedule {
+    start_time: SystemTime,
+    update_speed: Duration,
+}
+
+/// the KES key evolution state of the enclave
+pub struct KesState {
+    schedule: KesSchedule,
+    period: u32,
+    secret_file: Arc<SecretFile>,
+}
+
+impl KesSchedule {
+    pub fn from_block0(block0: &Block) -> Result<Self, Block0Error> {
+        Ok(KesSchedule {
+            start_time: block0.start_time()?,
+            update_speed: block0.kes_update_speed()?,
+        })
+    }
+
+    /// the period a key must be evolved to for signing at the given time
+    pub fn period_at(&self, time: SystemTime) -> u32 {
+        let elapsed = time
+            .duration_since(self.start_time)
+            .unwrap_or(Duration::ZERO);
+        let period = elapsed.as_secs() / self.update_speed.as_secs().max(1);
+        period.min(u32::MAX as u64) as u32
+    }
+
+    /// the end of the given period, when the key evolves to the next one
+    pub fn period_end(&self, period: u32) -> SystemTime {
+        self.start_time + self.update_speed * (period + 1)
+    }
+
+    /// the end of the last period a key can sign for
+    pub fn expiry(&self) -> SystemTime {
+        self.period_end(MAX_PERIODS - 1)
+    }
+}
+
+impl KesState {
+    pub fn new(schedule: KesSchedule, period: u32, secret_file: SecretFile) -> Self {
+        KesState {
+            schedule,
+            period,
+            secret_file: Arc::new(secret_file),
+        }
+    }
+
+    pub fn schedule(&self) -> &KesSchedule {
+        &self.schedule
+    }
+
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+
+    /// check the key can sign at the given time, returning the period it
+    /// needs to be evolved to
+    pub fn target_period(&self, time: SystemTime) -> Result<u32, KesError> {
+        let requested = self.schedule.period_at(time);
+        if requested >= MAX_PERIODS {
+            Err(KesError::Expired(requested))
+        } else if requested < self.period {
+            Err(KesError::PeriodPassed {
+                current: self.period,
+                requested,
+            })
+        } else {
+            Ok(requested)
+        }
+    }
+
+    /// the file the evolved key is saved to before being used
+    pub fn secret_file(&self) -> Arc<SecretFile> {
+        Arc::clone(&self.secret_file)
+    }
+
+    /// record the period of the key, once it is evolved and saved
+    pub fn set_period(&mut self, period: u32) {
+        self.period = period;
+    }
+
+    pub fn status(&self, now: SystemTime) -> KesKeyStatus {
+        let expiry = self.schedule.expiry();
+        KesKeyStatus::new(
+            self.period,
+            MAX_PERIODS,
+            self.schedule.period_end(self.period).into(),
+            expiry.into(),
+            expiry.duration_since(now).unwrap_or(Duration::ZERO).into(),
+        )
+    }
+}
+
+/// evolve a copy of `key` from the `current` period to the `target` one
+pub fn evolve_key(
+    key: &SecretKey<SumEd25519_12>,
+    current: u32,
+    target: u32,
+) -> Result<SecretKey<SumEd25519_12>, KesError> {
+    let mut evolved = key.clone();
+    for period in current..target {
+        if !evolved.update() {
+            return Err(KesError::CannotEvolve(period));
+        }
+    }
+    Ok(evolved)
+}
+
+/// Evolve the KES key of the enclave at the start of each period, report
+/// its remaining lifetime and warn once per period when it is about to
+/// expire. The leadership task evolves the key as well before signing a
+/// block, for the slots at the start of a period this task has not woken up
+/// for yet.
+pub async fn evolve_kes_key(enclave: Enclave, expiry_warning: Duration, metrics: Metrics) {
+    let mut warned_period = None;
+    let mut wait = Duration::ZERO;
+
+    loop {
+        tokio::time::sleep(wait).await;
+        wait = KES_CHECK_PERIOD;
+        let now = SystemTime::now();
+        let enclave = enclave.clone();
+        let status = tokio::task::spawn_blocking(move || {
+            enclave.evolve_kes_key(now)?;
+            Ok::<_, KesError>(enclave.kes_key_status(now))
+        })
+        .await;
+        let status = match status {
+            Ok(Ok(Some(status))) => status,
+            Ok(Ok(None)) => break,
+            Ok(Err(e)) => {
+                tracing::error!(reason = %e, "cannot evolve the KES key");
+                continue;
+            }
+            Err(e) => {
+                tracing::error!(reason = %e, "KES key evolution failed");
+                continue;
+            }
+        };
+
+        let remaining = *status.remaining_lifetime().as_ref();
+        metrics.set_kes_key(status.period(), remaining);
+        // wake up at the start of the next period to evolve the key in time
+        // for the first slot of the period
+        if let Ok(until_period_end) = status.period_end().as_ref().duration_since(now) {
+            wait = wait.min(until_period_end);
+        }
+        if warned_period == Some(status.period()) {
+            continue;
+        }
+        if remaining == Duration::ZERO {
+            tracing::error!(
+                "the KES key has expired, the node cannot produce blocks anymore, \
+                    a new stake pool key must be registered"
+            );
+        } else if remaining <= expiry_warning {
+            tracing::warn!(
+                "the KES key expires in {} seconds (period {} of {}), \
+                    a new stake pool key must be registered before then",
+                remaining.as_secs(),
+                status.period(),
+                status.max_periods()
+            );
+        } else {
+            continue;
+        }
+        warned_period = Some(status.period());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jormungandr_lib::crypto::key::SigningKey;
+    use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+    use std::path::PathBuf;
+
+    fn schedule() -> KesSchedule {
+        KesSchedule {
+            start_time: SystemTime::UNIX_EPOCH + Duration::from_secs(1000),
+            update_speed: Duration::from_secs(100),
+        }
+    }
+
+    #[test]
+    fn period_at_time() {
+        let schedule = schedule();
+        let at = |secs| schedule.period_at(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(at(0), 0);
+        assert_eq!(at(1000), 0);
+        assert_eq!(at(1099), 0);
+        assert_eq!(at(1100), 1);
+        assert_eq!(at(1000 + 100 * 4096), MAX_PERIODS);
+    }
+
+    #[test]
+    fn period_end_and_expiry() {
+        let schedule = schedule();
+        assert_eq!(
+            schedule.period_end(0),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1100)
+        );
+        assert_eq!(
+            schedule.expiry(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1000 + 100 * 4096)
+        );
+    }
+
+    fn kes_state(period: u32) -> KesState {
+        let secret_file = SecretFile {
+            path: PathBuf::from("node_secret.yaml"),
+            encryption: None,
+        };
+        KesState::new(schedule(), period, secret_file)
+    }
+
+    fn bech32(key: &SecretKey<SumEd25519_12>) -> String {
+        SigningKey::from(key.clone()).to_bech32_str()
+    }
+
+    #[test]
+    fn target_period_of_a_slot() {
+        let state = kes_state(5);
+        let at = |period: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(1000 + 100 * period);
+        assert!(matches!(
+            state.target_period(at(3)),
+            Err(KesError::PeriodPassed {
+                current: 5,
+                requested: 3
+            })
+        ));
+        assert_eq!(state.target_period(at(5)).unwrap(), 5);
+        assert_eq!(state.target_period(at(7)).unwrap(), 7);
+        assert!(matches!(
+            state.target_period(at(MAX_PERIODS as u64)),
+            Err(KesError::Expired(requested)) if requested == MAX_PERIODS
+        ));
+    }
+
+    #[test]
+    fn evolve_key_updates_once_per_period() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let key = SecretKey::<SumEd25519_12>::generate(&mut rng);
+
+        let evolved = evolve_key(&key, 2, 5).unwrap();
+
+        let mut expected = key.clone();
+        for _ in 2..5 {
+            assert!(expected.update());
+        }
+        assert_eq!(bech32(&evolved), bech32(&expected));
+        assert_ne!(bech32(&evolved), bech32(&key));
+        assert_eq!(bech32(&evolve_key(&key, 3, 3).unwrap()), bech32(&key));
+    }
+}
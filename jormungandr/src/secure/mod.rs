@@ -1,14 +1,25 @@
-use chain_crypto::{Blake2b256, Ed25519, PublicKey, RistrettoGroup2HashDh, SumEd25519_12};
-use chain_impl_mockchain::leadership::{BftLeader, GenesisLeader};
+use crate::utils::fs::save_atomically;
+use chain_crypto::{
+    Blake2b256, Ed25519, PublicKey, RistrettoGroup2HashDh, SecretKey, SumEd25519_12,
+};
+use chain_impl_mockchain::leadership::{BftLeader, GenesisLeader, Leader};
 use jormungandr_lib::crypto::{
+    encrypted::{EncryptedSecret, EncryptedSecretError, PASSPHRASE_ENV_VAR},
     hash::Hash,
     key::{Identifier, SigningKey},
 };
+use rand::rngs::OsRng;
 use serde::Deserialize;
-use std::path::Path;
+use std::{
+    fmt,
+    io::Read,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
 
 pub mod enclave;
+pub mod kes;
 
 /// hold the node's bft secret setting
 #[derive(Clone, Deserialize)]
@@ -23,12 +34,22 @@ pub struct GenesisPraos {
     node_id: Hash,
     sig_key: SigningKey<SumEd25519_12>,
     vrf_key: SigningKey<RistrettoGroup2HashDh>,
+    #[serde(default)]
+    kes_period: u32,
 }
 
 #[derive(Clone, Deserialize)]
 pub struct OwnerKey(Identifier<Ed25519>);
 
 /// Node Secret(s)
+///
+/// The keys are `chain_crypto` secret keys, which give no mutable access to
+/// their bytes and are not wiped when dropped, and neither are the leader
+/// types of the ledger they end up in. They cannot be zeroed from here, so
+/// [`NodeSecret::into_leader`] moves them into the leader instead of copying
+/// them, keeping a single instance of each key in memory. The buffers owned
+/// by the node (the decrypted file, the passphrase and the documents written
+/// back on KES updates) are zeroed when dropped.
 #[derive(Clone, Deserialize)]
 pub struct NodeSecret {
     bft: Option<Bft>,
@@ -43,32 +64,165 @@ pub struct NodePublic {
     pub block_publickey: PublicKey<Ed25519>,
 }
 
+/// where to read the passphrase of an encrypted secret file from
+#[derive(Clone)]
+pub enum PassphraseSource {
+    /// an interactive prompt
+    Prompt,
+    /// the `JORMUNGANDR_SECRET_PASSPHRASE` environment variable, taken out
+    /// of the environment at startup, see [`take_env_passphrase`]
+    Environment(Zeroizing<String>),
+    /// the content of a keyfile, without its trailing newline
+    Keyfile(PathBuf),
+    /// the content of a file descriptor inherited from the parent process,
+    /// without its trailing newline
+    #[cfg(unix)]
+    FileDescriptor(i32),
+}
+
 #[derive(Debug, Error)]
 pub enum NodeSecretFromFileError {
     #[error("Cannot read node's secrets: {0}")]
     Io(#[from] std::io::Error),
     #[error("Invalid Node secret file: {0}")]
     Format(#[from] serde_yaml::Error),
+    #[error("Cannot read the passphrase of the encrypted node's secrets: {0}")]
+    Passphrase(#[source] std::io::Error),
+    #[error("Cannot decrypt node's secrets: {0}")]
+    Decryption(#[from] EncryptedSecretError),
+}
+
+#[derive(Debug, Error)]
+pub enum NodeSecretToFileError {
+    #[error("Cannot write node's secrets: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Cannot update node's secrets: {0}")]
+    Format(#[from] serde_yaml::Error),
+    #[error("Cannot decrypt or encrypt node's secrets: {0}")]
+    Encryption(#[from] EncryptedSecretError),
+    #[error("The node's secrets do not hold a genesis praos setting anymore")]
+    NoGenesis,
+}
+
+/// the file the node's secrets were loaded from, kept to write back the
+/// evolved KES key
+pub struct SecretFile {
+    path: PathBuf,
+    /// the passphrase and the number of key derivation iterations, if the
+    /// file is encrypted
+    encryption: Option<(Zeroizing<String>, u32)>,
+}
+
+/// take the passphrase out of the `JORMUNGANDR_SECRET_PASSPHRASE`
+/// environment variable, so it is neither inherited by the child processes
+/// nor left readable in the environment of the node
+///
+/// The environment cannot be modified safely once other threads are running,
+/// this is to be called before the runtime or the logger are started.
+pub fn take_env_passphrase() -> Option<Zeroizing<String>> {
+    let passphrase = std::env::var(PASSPHRASE_ENV_VAR).ok().map(Zeroizing::new);
+    std::env::remove_var(PASSPHRASE_ENV_VAR);
+    passphrase
+}
+
+impl fmt::Debug for PassphraseSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassphraseSource::Prompt => f.write_str("Prompt"),
+            PassphraseSource::Environment(_) => f.write_str("Environment"),
+            PassphraseSource::Keyfile(path) => f.debug_tuple("Keyfile").field(path).finish(),
+            #[cfg(unix)]
+            PassphraseSource::FileDescriptor(fd) => {
+                f.debug_tuple("FileDescriptor").field(fd).finish()
+            }
+        }
+    }
+}
+
+impl PassphraseSource {
+    fn read(&self) -> Result<Zeroizing<String>, std::io::Error> {
+        let passphrase = match self {
+            PassphraseSource::Prompt => Zeroizing::new(rpassword::prompt_password(
+                "Introduce the passphrase of the node's secrets:\n",
+            )?),
+            PassphraseSource::Environment(passphrase) => return Ok(passphrase.clone()),
+            PassphraseSource::Keyfile(path) => Zeroizing::new(std::fs::read_to_string(path)?),
+            #[cfg(unix)]
+            PassphraseSource::FileDescriptor(fd) => {
+                use nix::fcntl::{fcntl, FcntlArg};
+                use std::os::unix::io::FromRawFd;
+                if *fd < 3 {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "the passphrase cannot be read from the standard streams",
+                    ));
+                }
+                // only take the ownership of a descriptor which is open
+                fcntl(*fd, FcntlArg::F_GETFD)
+                    .map_err(|errno| std::io::Error::from_raw_os_error(errno as i32))?;
+                // the descriptor is handed over by the caller for this single read,
+                // it is closed once the passphrase is read
+                let mut file = unsafe { std::fs::File::from_raw_fd(*fd) };
+                let mut passphrase = Zeroizing::new(String::new());
+                file.read_to_string(&mut passphrase)?;
+                passphrase
+            }
+        };
+        Ok(Zeroizing::new(
+            passphrase.trim_end_matches(&['\r', '\n'][..]).to_owned(),
+        ))
+    }
 }
 
 impl NodeSecret {
-    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<NodeSecret, NodeSecretFromFileError> {
-        let file = std::fs::File::open(path)?;
-        Ok(serde_yaml::from_reader(file)?)
+    /// load the secrets from a plain YAML file or from an encrypted container,
+    /// asking for its passphrase from the given source
+    pub fn load_from_file<P: AsRef<Path>>(
+        path: P,
+        passphrase: &PassphraseSource,
+    ) -> Result<(NodeSecret, SecretFile), NodeSecretFromFileError> {
+        let path = path.as_ref();
+        let mut content = Zeroizing::new(Vec::new());
+        std::fs::File::open(path)?.read_to_end(&mut content)?;
+        match serde_yaml::from_slice::<EncryptedSecret>(&content) {
+            Ok(encrypted) => {
+                let passphrase = passphrase
+                    .read()
+                    .map_err(NodeSecretFromFileError::Passphrase)?;
+                let decrypted = encrypted.decrypt(passphrase.as_bytes())?;
+                let file = SecretFile {
+                    path: path.to_owned(),
+                    encryption: Some((passphrase, encrypted.iterations())),
+                };
+                Ok((serde_yaml::from_slice(&decrypted)?, file))
+            }
+            Err(_) => {
+                let file = SecretFile {
+                    path: path.to_owned(),
+                    encryption: None,
+                };
+                Ok((serde_yaml::from_slice(&content)?, file))
+            }
+        }
     }
 
-    pub fn bft(&self) -> Option<BftLeader> {
-        self.bft.clone().map(|bft| BftLeader {
-            sig_key: bft.signing_key.into_secret_key(),
-        })
+    /// the period the genesis praos signing key has been evolved to
+    pub fn kes_period(&self) -> Option<u32> {
+        self.genesis.as_ref().map(|genesis| genesis.kes_period)
     }
 
-    pub fn genesis(&self) -> Option<GenesisLeader> {
-        self.genesis.clone().map(|genesis| GenesisLeader {
-            node_id: Blake2b256::from(genesis.node_id).into(),
-            sig_key: genesis.sig_key.into_secret_key(),
-            vrf_key: genesis.vrf_key.into_secret_key(),
-        })
+    /// the leader keys, moved out of the secrets
+    pub fn into_leader(self) -> Leader {
+        Leader {
+            bft_leader: self.bft.map(|bft| BftLeader {
+                sig_key: bft.signing_key.into_secret_key(),
+            }),
+            genesis_leader: self.genesis.map(|genesis| GenesisLeader {
+                node_id: Blake2b256::from(genesis.node_id).into(),
+                sig_key: genesis.sig_key.into_secret_key(),
+                vrf_key: genesis.vrf_key.into_secret_key(),
+            }),
+        }
     }
 
     #[cfg(feature = "evm")]
@@ -83,3 +237,218 @@ impl NodeSecret {
             .unwrap_or_default()
     }
 }
+
+impl SecretFile {
+    /// replace the genesis praos signing key and its period in the file,
+    /// leaving the other secrets untouched
+    ///
+    /// The updated secrets are written to a temporary file next to the
+    /// original one and moved over it, so the file always holds either the
+    /// previous or the new key, even if the node is stopped in between.
+    pub fn update_kes_key(
+        &self,
+        sig_key: &SecretKey<SumEd25519_12>,
+        kes_period: u32,
+    ) -> Result<(), NodeSecretToFileError> {
+        let mut content = Zeroizing::new(Vec::new());
+        std::fs::File::open(&self.path)?.read_to_end(&mut content)?;
+        let plain = match &self.encryption {
+            Some((passphrase, _)) => serde_yaml::from_slice::<EncryptedSecret>(&content)?
+                .decrypt(passphrase.as_bytes())?,
+            None => content,
+        };
+
+        let mut secret = YamlSecret(serde_yaml::from_slice(&plain)?);
+        let genesis = secret
+            .0
+            .get_mut("genesis")
+            .and_then(serde_yaml::Value::as_mapping_mut)
+            .ok_or(NodeSecretToFileError::NoGenesis)?;
+        let sig_key_bech32 = Zeroizing::new(SigningKey::from(sig_key.clone()).to_bech32_str());
+        genesis.insert("sig_key".into(), sig_key_bech32.as_str().into());
+        genesis.insert(
+            "kes_period".into(),
+            serde_yaml::Value::Number(kes_period.into()),
+        );
+
+        let plain = Zeroizing::new(serde_yaml::to_vec(&secret.0)?);
+        let content = match &self.encryption {
+            Some((passphrase, iterations)) => serde_yaml::to_vec(&EncryptedSecret::encrypt(
+                &mut OsRng,
+                passphrase.as_bytes(),
+                *iterations,
+                &plain,
+            )?)?,
+            None => plain.to_vec(),
+        };
+        let content = Zeroizing::new(content);
+
+        save_atomically(&self.path, &content)?;
+        Ok(())
+    }
+}
+
+/// a YAML document holding secrets, its strings are zeroed when dropped
+struct YamlSecret(serde_yaml::Value);
+
+impl Drop for YamlSecret {
+    fn drop(&mut self) {
+        fn zeroize_strings(value: &mut serde_yaml::Value) {
+            match value {
+                serde_yaml::Value::String(s) => s.zeroize(),
+                serde_yaml::Value::Sequence(values) => values.iter_mut().for_each(zeroize_strings),
+                serde_yaml::Value::Mapping(mapping) => mapping
+                    .iter_mut()
+                    .for_each(|(_, value)| zeroize_strings(value)),
+                _ => {}
+            }
+        }
+        zeroize_strings(&mut self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jormungandr_lib::crypto::encrypted::{EncryptedSecret, MIN_ITERATIONS};
+    use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+
+    fn genesis_secret(rng: &mut ChaChaRng) -> String {
+        let sig_key = SigningKey::<SumEd25519_12>::generate(&mut *rng);
+        let vrf_key = SigningKey::<RistrettoGroup2HashDh>::generate(&mut *rng);
+        format!(
+            "genesis:\n  node_id: {}\n  sig_key: {}\n  vrf_key: {}\n",
+            Hash::from(Blake2b256::new(b"node id")),
+            sig_key.to_bech32_str(),
+            vrf_key.to_bech32_str(),
+        )
+    }
+
+    fn write_encrypted(dir: &Path, rng: &mut ChaChaRng, secret: &str) -> (PathBuf, PathBuf) {
+        let encrypted =
+            EncryptedSecret::encrypt(rng, b"passphrase", MIN_ITERATIONS, secret.as_bytes())
+                .unwrap();
+        let secret_path = dir.join("secret.yaml");
+        std::fs::write(&secret_path, serde_yaml::to_vec(&encrypted).unwrap()).unwrap();
+        let keyfile = dir.join("passphrase");
+        std::fs::write(&keyfile, "passphrase\n").unwrap();
+        (secret_path, keyfile)
+    }
+
+    #[test]
+    fn load_encrypted_secret_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let secret = genesis_secret(&mut rng);
+        let (secret_path, keyfile) = write_encrypted(dir.path(), &mut rng, &secret);
+
+        let (node_secret, file) =
+            NodeSecret::load_from_file(&secret_path, &PassphraseSource::Keyfile(keyfile)).unwrap();
+
+        let expected: NodeSecret = serde_yaml::from_str(&secret).unwrap();
+        assert_eq!(
+            node_secret.genesis.as_ref().unwrap().node_id,
+            expected.genesis.as_ref().unwrap().node_id
+        );
+        assert_eq!(node_secret.kes_period(), Some(0));
+        let (passphrase, iterations) = file.encryption.unwrap();
+        assert_eq!(passphrase.as_str(), "passphrase");
+        assert_eq!(iterations, MIN_ITERATIONS);
+    }
+
+    #[test]
+    fn load_encrypted_secret_file_with_wrong_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let secret = genesis_secret(&mut rng);
+        let (secret_path, keyfile) = write_encrypted(dir.path(), &mut rng, &secret);
+        std::fs::write(&keyfile, "wrong passphrase\n").unwrap();
+
+        let result = NodeSecret::load_from_file(&secret_path, &PassphraseSource::Keyfile(keyfile));
+
+        assert!(matches!(
+            result,
+            Err(NodeSecretFromFileError::Decryption(
+                EncryptedSecretError::Decryption
+            ))
+        ));
+    }
+
+    fn update_kes_key_roundtrip(encrypted: bool) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let secret = genesis_secret(&mut rng);
+        let (secret_path, passphrase) = if encrypted {
+            let (secret_path, keyfile) = write_encrypted(dir.path(), &mut rng, &secret);
+            (secret_path, PassphraseSource::Keyfile(keyfile))
+        } else {
+            let secret_path = dir.path().join("secret.yaml");
+            std::fs::write(&secret_path, &secret).unwrap();
+            (secret_path, PassphraseSource::Prompt)
+        };
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&secret_path, std::fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        let (node_secret, file) = NodeSecret::load_from_file(&secret_path, &passphrase).unwrap();
+        let mut sig_key = node_secret
+            .clone()
+            .into_leader()
+            .genesis_leader
+            .unwrap()
+            .sig_key;
+        assert!(sig_key.update());
+        assert!(sig_key.update());
+        file.update_kes_key(&sig_key, 2).unwrap();
+
+        let content = std::fs::read(&secret_path).unwrap();
+        assert_eq!(
+            serde_yaml::from_slice::<EncryptedSecret>(&content).is_ok(),
+            encrypted
+        );
+        let (updated, _) = NodeSecret::load_from_file(&secret_path, &passphrase).unwrap();
+        assert_eq!(updated.kes_period(), Some(2));
+        let (genesis, updated_genesis) = (
+            node_secret.genesis.unwrap(),
+            updated.genesis.as_ref().unwrap(),
+        );
+        assert_eq!(
+            updated_genesis.sig_key.to_bech32_str(),
+            SigningKey::from(sig_key).to_bech32_str()
+        );
+        assert_eq!(
+            updated_genesis.vrf_key.to_bech32_str(),
+            genesis.vrf_key.to_bech32_str()
+        );
+        assert_eq!(updated_genesis.node_id, genesis.node_id);
+
+        assert!(!dir.path().join("secret.yaml.tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let permissions = std::fs::metadata(&secret_path).unwrap().permissions();
+            assert_eq!(permissions.mode() & 0o777, 0o640);
+        }
+    }
+
+    #[test]
+    fn update_kes_key_of_a_plain_secret_file() {
+        update_kes_key_roundtrip(false)
+    }
+
+    #[test]
+    fn update_kes_key_of_an_encrypted_secret_file() {
+        update_kes_key_roundtrip(true)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn standard_streams_are_not_taken_as_passphrase_descriptors() {
+        for fd in 0..3 {
+            let error = PassphraseSource::FileDescriptor(fd).read().unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        }
+    }
+}
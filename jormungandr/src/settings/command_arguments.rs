@@ -29,6 +29,17 @@ pub struct StartArguments {
     #[structopt(long = "secret", parse(from_os_str))]
     pub secret: Option<PathBuf>,
 
+    /// Read the passphrase of an encrypted secret file from the given keyfile.
+    /// If no passphrase source is given, it is read from the
+    /// `JORMUNGANDR_SECRET_PASSPHRASE` environment variable or prompted for.
+    #[structopt(long = "secret-passphrase-file", parse(from_os_str))]
+    pub secret_passphrase_file: Option<PathBuf>,
+
+    /// Read the passphrase of an encrypted secret file from the given file descriptor.
+    #[cfg(unix)]
+    #[structopt(long = "secret-passphrase-fd", conflicts_with = "secret-passphrase-file")]
+    pub secret_passphrase_fd: Option<i32>,
+
     /// Path to the genesis block (the block0) of the blockchain
     #[structopt(long = "genesis-block", parse(try_from_str))]
     pub block_0_path: Option<PathBuf>,
@@ -105,6 +116,11 @@ pub struct CommandLine {
     #[structopt(long = "log-output", parse(try_from_str))]
     pub log_output: Option<LogOutput>,
 
+    /// Export the node's spans to the trace collector listening at the given
+    /// OTLP/HTTP endpoint (e.g. "http://127.0.0.1:4318/v1/traces").
+    #[structopt(long = "log-trace-collector-endpoint")]
+    pub log_trace_collector_endpoint: Option<String>,
+
     /// report all the rewards in the reward distribution history
     ///
     /// NOTE: this will slowdown the epoch transition computation and will add
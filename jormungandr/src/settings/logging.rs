@@ -26,6 +26,8 @@ pub struct LogSettingsEntry {
     pub level: LevelFilter,
     pub format: LogFormat,
     pub output: LogOutput,
+    /// OTLP/HTTP endpoint of a trace collector the spans are exported to
+    pub trace_collector_endpoint: Option<String>,
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -97,13 +99,40 @@ impl FromStr for LogOutput {
     }
 }
 
+/// Guards of the log outputs that need to be held on to for as long as
+/// the node is logging.
+pub struct LogGuards {
+    _workers: Vec<WorkerGuard>,
+    _trace_exporter: Option<TraceExporterGuard>,
+}
+
+/// Flushes the spans not yet sent to the trace collector when dropped.
+struct TraceExporterGuard;
+
+impl Drop for TraceExporterGuard {
+    fn drop(&mut self) {
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+}
+
 impl LogSettings {
-    pub fn init_log(self) -> Result<(Vec<WorkerGuard>, LogInfoMsg), Error> {
+    pub fn init_log(self) -> Result<(LogGuards, LogInfoMsg), Error> {
         use tracing_subscriber::prelude::*;
 
         // Worker guards that need to be held on to.
         let mut guards = Vec::new();
 
+        let (trace_layer, trace_exporter) = match &self.config.trace_collector_endpoint {
+            Some(endpoint) => {
+                let tracer = init_trace_exporter(endpoint)?;
+                (
+                    Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+                    Some(TraceExporterGuard),
+                )
+            }
+            None => (None, None),
+        };
+
         // configure the registry subscriber as the global default,
         // panics if something goes wrong.
         match self.config.output {
@@ -116,6 +145,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
+                            .with(trace_layer)
                             .with(self.config.level)
                             .with(layer)
                             .init();
@@ -127,6 +157,7 @@ impl LogSettings {
                             .with_timer(tracing_subscriber::fmt::time::UtcTime::rfc_3339())
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
+                            .with(trace_layer)
                             .with(self.config.level)
                             .with(layer)
                             .init();
@@ -142,6 +173,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
+                            .with(trace_layer)
                             .with(self.config.level)
                             .with(layer)
                             .init();
@@ -153,6 +185,7 @@ impl LogSettings {
                             .with_timer(tracing_subscriber::fmt::time::UtcTime::rfc_3339())
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
+                            .with(trace_layer)
                             .with(self.config.level)
                             .with(layer)
                             .init();
@@ -178,6 +211,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
+                            .with(trace_layer)
                             .with(self.config.level)
                             .with(layer)
                             .init();
@@ -189,6 +223,7 @@ impl LogSettings {
                             .with_timer(tracing_subscriber::fmt::time::UtcTime::rfc_3339())
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
+                            .with(trace_layer)
                             .with(self.config.level)
                             .with(layer)
                             .init();
@@ -200,6 +235,7 @@ impl LogSettings {
                 self.config.format.require_default()?;
                 let layer = tracing_journald::layer().map_err(Error::Journald)?;
                 tracing_subscriber::registry()
+                    .with(trace_layer)
                     .with(self.config.level)
                     .with(layer)
                     .init();
@@ -211,16 +247,48 @@ impl LogSettings {
                     .map_err(Error::Gelf)?;
                 tokio::spawn(task);
                 tracing_subscriber::registry()
+                    .with(trace_layer)
                     .with(self.config.level)
                     .with(layer)
                     .init();
             }
         }
 
+        let guards = LogGuards {
+            _workers: guards,
+            _trace_exporter: trace_exporter,
+        };
         Ok((guards, self.msgs))
     }
 }
 
+/// Install the OTLP exporter as the global tracer provider.
+///
+/// The exporter runs its own single threaded runtime as the logs are
+/// initialized before any of the node's services is started.
+fn init_trace_exporter(endpoint: &str) -> Result<opentelemetry::sdk::trace::Tracer, Error> {
+    use opentelemetry::{
+        sdk::{propagation::TraceContextPropagator, trace, Resource},
+        KeyValue,
+    };
+    use opentelemetry_otlp::WithExportConfig;
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(trace::config().with_resource(Resource::new(vec![
+            KeyValue::new("service.name", env!("CARGO_PKG_NAME")),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ])))
+        .install_batch(opentelemetry::runtime::TokioCurrentThread)
+        .map_err(Error::TraceExporter)
+}
+
 impl LogFormat {
     #[allow(dead_code)]
     fn require_default(&self) -> Result<(), Error> {
@@ -247,6 +315,8 @@ pub enum Error {
     #[cfg(feature = "gelf")]
     #[error("GELF connection failed")]
     Gelf(tracing_gelf::BuilderError),
+    #[error("cannot set up the trace exporter")]
+    TraceExporter(#[source] opentelemetry::trace::TraceError),
     #[error("failed to set global subscriber")]
     SetGlobalSubscriberError(#[source] SetGlobalDefaultError),
 }
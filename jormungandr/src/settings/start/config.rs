@@ -1,5 +1,5 @@
 use crate::{
-    network::p2p::Address,
+    network::{bootstrap::ParallelBootstrapConfig, p2p::Address},
     settings::{
         logging::{LogFormat, LogOutput},
         LOG_FILTER_LEVEL_POSSIBLE_VALUES,
@@ -7,7 +7,10 @@ use crate::{
     topology::QuarantineConfig,
 };
 pub use jormungandr_lib::interfaces::{Cors, JRpc, LayersConfig, Rest, Tls, TrustedPeer};
-use jormungandr_lib::{interfaces::Mempool, time::Duration};
+use jormungandr_lib::{
+    interfaces::{Mempool, RateLimitsConfig},
+    time::Duration,
+};
 use multiaddr::Multiaddr;
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use std::path::PathBuf;
@@ -60,6 +63,7 @@ pub struct ConfigLogSettings {
     pub level: Option<LevelFilter>,
     pub format: Option<LogFormat>,
     pub output: Option<LogOutput>,
+    pub trace_collector_endpoint: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -132,6 +136,20 @@ pub struct P2pConfig {
     /// gossip with the trusted peers if any are defined.
     #[serde(default)]
     pub max_bootstrap_attempts: Option<usize>,
+
+    /// limits on the rate of fragments and block announcements the peers
+    /// may send to the node, the items over the limits are dropped.
+    ///
+    /// There are no limits by default.
+    #[serde(default)]
+    pub rate_limits: RateLimitsConfig,
+
+    /// download the blocks from several peers at once during bootstrap,
+    /// after fetching the headers from one of them.
+    ///
+    /// The blocks are downloaded from one peer at a time if not set.
+    #[serde(default)]
+    pub parallel_bootstrap: Option<ParallelBootstrapConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -141,6 +159,10 @@ pub struct Leadership {
     /// the least recently used log will be erased from the logs for a new one
     /// to be inserted.
     pub logs_capacity: usize,
+    /// how long before the expiry of the KES key warnings are logged,
+    /// a week if not set
+    #[serde(default)]
+    pub kes_expiry_warning: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -153,6 +175,7 @@ impl Default for Leadership {
     fn default() -> Self {
         Leadership {
             logs_capacity: 1_024,
+            kes_expiry_warning: None,
         }
     }
 }
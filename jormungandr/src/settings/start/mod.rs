@@ -6,6 +6,7 @@ use self::{
     network::{Protocol, TrustedPeer},
 };
 use crate::{
+    secure::PassphraseSource,
     settings::{
         command_arguments::*,
         logging::{LogFormat, LogInfoMsg, LogOutput, LogSettings, LogSettingsEntry},
@@ -19,6 +20,7 @@ use jormungandr_lib::{crypto::key::SigningKey, multiaddr};
 use std::{convert::TryFrom, fs::File, path::PathBuf};
 use thiserror::Error;
 use tracing::level_filters::LevelFilter;
+use zeroize::Zeroizing;
 
 const DEFAULT_FILTER_LEVEL: LevelFilter = LevelFilter::TRACE;
 const DEFAULT_LOG_FORMAT: LogFormat = LogFormat::Default;
@@ -29,6 +31,7 @@ const DEFAULT_LOG_SETTINGS_ENTRY: LogSettingsEntry = LogSettingsEntry {
     level: DEFAULT_FILTER_LEVEL,
     format: DEFAULT_LOG_FORMAT,
     output: DEFAULT_LOG_OUTPUT,
+    trace_collector_endpoint: None,
 };
 
 #[derive(Debug, Error)]
@@ -53,6 +56,7 @@ pub struct Settings {
     pub storage: Option<PathBuf>,
     pub block_0: Block0Info,
     pub secret: Option<PathBuf>,
+    pub secret_passphrase: PassphraseSource,
     pub rest: Option<Rest>,
     pub jrpc: Option<JRpc>,
     pub mempool: Mempool,
@@ -98,6 +102,9 @@ impl RawSettings {
             if let Some(output) = &cfg.output {
                 log_config.output = output.clone();
             }
+            if let Some(endpoint) = &cfg.trace_collector_endpoint {
+                log_config.trace_collector_endpoint = Some(endpoint.clone());
+            }
         }
 
         // If the command line specifies log arguments, they override everything
@@ -129,6 +136,15 @@ impl RawSettings {
             }
             log_config.format = format;
         }
+        if let Some(endpoint) = &self.command_line.log_trace_collector_endpoint {
+            if log_config.trace_collector_endpoint.as_ref() != Some(endpoint) {
+                info_msgs.push(format!(
+                    "trace collector endpoint overriden from command line: {:?} replaced with {:?}",
+                    log_config.trace_collector_endpoint, endpoint
+                ));
+            }
+            log_config.trace_collector_endpoint = Some(endpoint.clone());
+        }
 
         let log_info_msg: LogInfoMsg = if info_msgs.is_empty() {
             None
@@ -154,6 +170,10 @@ impl RawSettings {
                 listen: cmd_listen,
                 tls: None,
                 cors: None,
+                health: None,
+                auth: None,
+                limits: None,
+                max_fragments_per_batch: None,
             }),
             (None, None) => None,
         }
@@ -163,9 +183,15 @@ impl RawSettings {
         let cmd_listen_opt = self.command_line.jrpc_arguments.listen;
         let config_rpc_opt = self.config.as_ref().and_then(|cfg| cfg.jrpc.clone());
         match (config_rpc_opt, cmd_listen_opt) {
-            (Some(_), Some(cmd_listen)) => Some(JRpc { listen: cmd_listen }),
+            (Some(config_rpc), Some(cmd_listen)) => Some(JRpc {
+                listen: cmd_listen,
+                ..config_rpc
+            }),
             (Some(config_rpc), None) => Some(config_rpc),
-            (None, Some(cmd_listen)) => Some(JRpc { listen: cmd_listen }),
+            (None, Some(cmd_listen)) => Some(JRpc {
+                listen: cmd_listen,
+                limits: None,
+            }),
             (None, None) => None,
         }
     }
@@ -175,7 +201,13 @@ impl RawSettings {
     /// - from the config
     ///
     /// This function will print&exit if anything is not as it should be.
-    pub fn try_into_settings(self) -> Result<Settings, Error> {
+    ///
+    /// `env_passphrase` is the passphrase taken out of the environment at
+    /// startup, used if no other source is given on the command line.
+    pub fn try_into_settings(
+        self,
+        env_passphrase: Option<Zeroizing<String>>,
+    ) -> Result<Settings, Error> {
         let rest = self.rest_config();
         let jrpc = self.jrpc_config();
         let RawSettings {
@@ -204,6 +236,16 @@ impl RawSettings {
             );
         };
 
+        let secret_passphrase = match &command_arguments.secret_passphrase_file {
+            Some(path) => PassphraseSource::Keyfile(path.clone()),
+            None => env_passphrase.map_or(PassphraseSource::Prompt, PassphraseSource::Environment),
+        };
+        #[cfg(unix)]
+        let secret_passphrase = match command_arguments.secret_passphrase_fd {
+            Some(fd) => PassphraseSource::FileDescriptor(fd),
+            None => secret_passphrase,
+        };
+
         let block_0 = match (
             &command_arguments.block_0_path,
             &command_arguments.block_0_hash,
@@ -227,6 +269,7 @@ impl RawSettings {
             block_0,
             network,
             secret,
+            secret_passphrase,
             rewards_report_all: command_line.rewards_report_all,
             rest,
             jrpc,
@@ -371,6 +414,8 @@ fn generate_network(
             .map(Into::into)
             .unwrap_or(crate::topology::DEFAULT_NETWORK_STUCK_INTERVAL),
         max_bootstrap_attempts: p2p.max_bootstrap_attempts,
+        rate_limits: p2p.rate_limits.clone(),
+        parallel_bootstrap: p2p.parallel_bootstrap.clone(),
         http_fetch_block0_service,
         bootstrap_from_trusted_peers,
         skip_bootstrap,
@@ -1,10 +1,10 @@
 use super::config;
 use crate::{
-    network::p2p::Address,
+    network::{bootstrap::ParallelBootstrapConfig, p2p::Address},
     topology::{layers::LayersConfig, NodeId, QuarantineConfig},
 };
 use chain_crypto::Ed25519;
-use jormungandr_lib::{crypto::key::SigningKey, multiaddr};
+use jormungandr_lib::{crypto::key::SigningKey, interfaces::RateLimitsConfig, multiaddr};
 use std::{net::SocketAddr, str, time::Duration};
 
 /// Protocol to use for a connection.
@@ -91,6 +91,12 @@ pub struct Configuration {
 
     pub max_bootstrap_attempts: Option<usize>,
 
+    /// limits on the rate of items the peers send to the node
+    pub rate_limits: RateLimitsConfig,
+
+    /// download the blocks from several peers at once during bootstrap
+    pub parallel_bootstrap: Option<ParallelBootstrapConfig>,
+
     /// Whether to limit bootstrap to trusted peers (which increase their load / reduce their connectivities)
     pub bootstrap_from_trusted_peers: bool,
 
@@ -2,7 +2,7 @@ use crate::{
     blockcfg, blockchain,
     blockchain::StorageError,
     diagnostic::DiagnosticError,
-    network, secure,
+    network, rest, secure,
     settings::{self, logging},
 };
 use chain_core::property::ReadError;
@@ -52,6 +52,8 @@ pub enum Error {
     NetworkBootstrapError(#[source] network::BootstrapError),
     #[error("Error while loading the node's secrets.")]
     NodeSecrets(#[from] secure::NodeSecretFromFileError),
+    #[error("Error while loading the REST API credentials.")]
+    RestCredentials(#[from] rest::auth::CredentialsFromFileError),
     #[error("Block 0 is set to start in the future")]
     Block0InFuture,
     #[error("A service has terminated with an error")]
@@ -90,6 +92,7 @@ impl Error {
             Error::NetworkBootstrapError { .. } => 10,
             Error::ServiceTerminatedWithError { .. } => 12,
             Error::DiagnosticError { .. } => 13,
+            Error::RestCredentials { .. } => 14,
         }
     }
 }
@@ -0,0 +1,200 @@
+//! Peers the node recently found in good standing, kept across restarts.
+//!
+//! They seed the topology and are used to bootstrap alongside the trusted
+//! peers, so the node can rejoin the network quickly after a restart even if
+//! the trusted peers are slow or unreachable.
+use super::{NodeId, Peer};
+use crate::utils::fs::{load_optional, save_atomically};
+use chain_core::{
+    packer::Codec,
+    property::{Deserialize as _, Serialize as _},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// name of the file, in the storage directory, where the known peers are
+/// kept across restarts
+const KNOWN_PEERS_FILE: &str = "known_peers.json";
+/// maximum number of peers kept, the ones with the best reputation first
+const MAX_KNOWN_PEERS: usize = 128;
+/// peers not seen in good standing for this long are forgotten
+const MAX_AGE: Duration = Duration::from_secs(7 * 24 * 3600);
+
+struct KnownPeer {
+    peer: Peer,
+    last_seen: SystemTime,
+    score: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct PersistedPeer {
+    /// hex-encoded gossip of the peer, as signed by the peer itself
+    gossip: String,
+    last_seen: jormungandr_lib::time::SystemTime,
+    score: i64,
+}
+
+pub struct KnownPeers {
+    path: Option<PathBuf>,
+    peers: HashMap<NodeId, KnownPeer>,
+}
+
+impl KnownPeers {
+    /// Create the list, kept in the given storage directory if any. The
+    /// peers saved by a previous run of the node are loaded.
+    pub fn new(storage: Option<&Path>) -> Self {
+        let mut known_peers = KnownPeers {
+            path: storage.map(|storage| storage.join(KNOWN_PEERS_FILE)),
+            peers: HashMap::new(),
+        };
+        if let Err(e) = known_peers.load() {
+            tracing::warn!(reason = %e, "could not load the known peers");
+        }
+        known_peers
+    }
+
+    fn load(&mut self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let bytes = match load_optional(path)? {
+            Some(bytes) => bytes,
+            None => return Ok(()),
+        };
+        let persisted: Vec<PersistedPeer> = serde_json::from_slice(&bytes)?;
+        for record in persisted {
+            let gossip = hex::decode(&record.gossip)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let peer = Peer::deserialize(&mut Codec::new(gossip.as_slice()))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.peers.insert(
+                peer.id(),
+                KnownPeer {
+                    peer,
+                    last_seen: record.last_seen.into(),
+                    score: record.score,
+                },
+            );
+        }
+        self.forget_stale(SystemTime::now());
+        tracing::debug!(count = self.peers.len(), "loaded known peers");
+        Ok(())
+    }
+
+    /// Save the list, dropping the stale peers and keeping only the best ones
+    pub fn save(&mut self) -> io::Result<()> {
+        self.forget_stale(SystemTime::now());
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let persisted = self
+            .sorted()
+            .into_iter()
+            .map(|known| {
+                Ok(PersistedPeer {
+                    gossip: hex::encode(known.peer.serialize_as_vec()?),
+                    last_seen: known.last_seen.into(),
+                    score: known.score,
+                })
+            })
+            .collect::<Result<Vec<_>, chain_core::property::WriteError>>()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        save_atomically(path, &serde_json::to_vec(&persisted)?)
+    }
+
+    /// Record that the peer is available and in good standing. Peers with a
+    /// negative score are forgotten instead.
+    pub fn update(&mut self, peer: Peer, score: i64) {
+        let id = peer.id();
+        if score < 0 {
+            self.peers.remove(&id);
+            return;
+        }
+        self.peers.insert(
+            id,
+            KnownPeer {
+                peer,
+                last_seen: SystemTime::now(),
+                score,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, id: &NodeId) {
+        self.peers.remove(id);
+    }
+
+    /// The known peers, the ones with the best reputation first
+    pub fn peers(&self) -> Vec<Peer> {
+        self.sorted()
+            .into_iter()
+            .map(|known| known.peer.clone())
+            .collect()
+    }
+
+    fn sorted(&self) -> Vec<&KnownPeer> {
+        let mut peers: Vec<_> = self.peers.values().collect();
+        peers.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.last_seen.cmp(&a.last_seen))
+        });
+        peers.truncate(MAX_KNOWN_PEERS);
+        peers
+    }
+
+    fn forget_stale(&mut self, now: SystemTime) {
+        self.peers.retain(|_, known| {
+            now.duration_since(known.last_seen)
+                .map_or(true, |age| age < MAX_AGE)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use std::net::SocketAddr;
+
+    fn peer(seed: u8) -> Peer {
+        let addr: SocketAddr = ([10, 0, 0, seed], 3000).into();
+        Peer::from(poldercast::Gossip::new(
+            addr,
+            &keynesis::key::ed25519::SecretKey::new(rand_chacha::ChaChaRng::from_seed([seed; 32])),
+            poldercast::Subscriptions::new().as_slice(),
+        ))
+    }
+
+    #[test]
+    fn best_peers_first() {
+        let mut known_peers = KnownPeers::new(None);
+        known_peers.update(peer(1), 10);
+        known_peers.update(peer(2), 100);
+        known_peers.update(peer(3), 0);
+        let ids: Vec<_> = known_peers.peers().iter().map(Peer::id).collect();
+        assert_eq!(ids, vec![peer(2).id(), peer(1).id(), peer(3).id()]);
+
+        known_peers.update(peer(2), -1);
+        known_peers.remove(&peer(3).id());
+        let ids: Vec<_> = known_peers.peers().iter().map(Peer::id).collect();
+        assert_eq!(ids, vec![peer(1).id()]);
+    }
+
+    #[test]
+    fn stale_peers_are_forgotten() {
+        let mut known_peers = KnownPeers::new(None);
+        known_peers.update(peer(1), 0);
+        known_peers.peers.get_mut(&peer(1).id()).unwrap().last_seen = SystemTime::now() - MAX_AGE;
+        known_peers.save().unwrap();
+        assert!(known_peers.peers().is_empty());
+    }
+}
@@ -2,7 +2,10 @@
 //! selecting the subset to which we propagate info
 //!
 use crate::network::p2p::Address;
-use jormungandr_lib::{interfaces::Subscription, time::SystemTime};
+use jormungandr_lib::{
+    interfaces::{PeerReputation, Subscription},
+    time::SystemTime,
+};
 use serde::{Serialize, Serializer};
 use std::{
     convert::{TryFrom, TryInto},
@@ -11,14 +14,17 @@ use std::{
 };
 
 mod gossip;
+mod known_peers;
 pub mod layers;
 mod process;
 mod quarantine;
+mod reputation;
 #[allow(clippy::module_inception)]
 mod topology;
 
 pub use self::{
     gossip::{Gossip, Gossips},
+    known_peers::KnownPeers,
     process::{start, TaskData, DEFAULT_NETWORK_STUCK_INTERVAL},
     topology::{P2pTopology, View},
 };
@@ -114,6 +120,8 @@ pub struct PeerInfo {
     pub last_update: SystemTime,
     pub quarantined: Option<SystemTime>,
     pub subscriptions: Vec<Subscription>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reputation: Option<PeerReputation>,
 }
 
 impl PartialEq for PeerInfo {
@@ -137,6 +145,7 @@ impl From<Peer> for PeerInfo {
             address: other.address(),
             last_update: other.time().to_system_time().into(),
             quarantined: None,
+            reputation: None,
             subscriptions: other
                 .subscriptions()
                 .iter()
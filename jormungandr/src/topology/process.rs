@@ -1,17 +1,22 @@
-use super::{Gossip, Gossips, P2pTopology, Peer};
+use super::{Gossip, Gossips, KnownPeers, P2pTopology, Peer};
 use crate::{
     intercom::{NetworkMsg, PropagateMsg, TopologyMsg},
     metrics::Metrics,
+    network::p2p::rules::PeerRules,
     settings::start::network::Configuration,
     utils::async_msg::{MessageBox, MessageQueue},
 };
-use std::time::Duration;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 use tokio::time::{Instant, Interval, MissedTickBehavior};
 use tokio_stream::StreamExt;
 
 pub const DEFAULT_NETWORK_STUCK_INTERVAL: Duration = Duration::from_secs(60 * 5); // 5 min
 const QUARANTINE_CHECK: Duration = Duration::from_secs(60);
 const MAX_GOSSIP_SIZE: usize = 10;
+const KNOWN_PEERS_SAVE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// name of the file, in the storage directory, where the reputation of the
+/// peers is kept across restarts
+const REPUTATION_FILE: &str = "reputation.json";
 
 struct Process {
     input: MessageQueue<TopologyMsg>,
@@ -19,6 +24,8 @@ struct Process {
     gossip_interval: Interval,
     network_stuck_check: Duration,
     topology: P2pTopology,
+    reputation_path: Option<PathBuf>,
+    known_peers: KnownPeers,
 }
 
 pub struct TaskData {
@@ -27,6 +34,9 @@ pub struct TaskData {
     pub initial_peers: Vec<Peer>,
     pub config: Configuration,
     pub stats_counter: Metrics,
+    pub storage: Option<PathBuf>,
+    pub rules: Arc<PeerRules>,
+    pub known_peers: KnownPeers,
 }
 
 pub async fn start(task_data: TaskData) {
@@ -36,9 +46,19 @@ pub async fn start(task_data: TaskData) {
         initial_peers,
         config,
         stats_counter,
+        storage,
+        rules,
+        mut known_peers,
     } = task_data;
 
-    let mut topology = P2pTopology::new(&config, stats_counter);
+    let mut topology = P2pTopology::new(&config, rules, stats_counter);
+
+    let reputation_path = storage.map(|storage| storage.join(REPUTATION_FILE));
+    if let Some(path) = &reputation_path {
+        if let Err(e) = topology.load_reputations(path) {
+            tracing::warn!(reason = %e, path = %path.display(), "could not load the reputation of the peers");
+        }
+    }
 
     topology.accept_gossips(Gossips::from(
         initial_peers
@@ -50,12 +70,20 @@ pub async fn start(task_data: TaskData) {
     let mut gossip_interval = tokio::time::interval(config.gossip_interval);
     gossip_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
+    // saved right away, so that the stale peers are dropped from the file
+    // even if the node does not run until the next save
+    if let Err(e) = known_peers.save() {
+        tracing::warn!(reason = %e, "could not save the known peers");
+    }
+
     let mut process = Process {
         input: topology_queue,
         gossip_interval,
         network_stuck_check: config.network_stuck_check,
         network_msgbox,
         topology,
+        reputation_path,
+        known_peers,
     };
     process.handle_input().await;
 }
@@ -64,6 +92,10 @@ impl Process {
     async fn handle_input(&mut self) {
         let mut last_update = Instant::now();
         let mut quarantine_check = tokio::time::interval(QUARANTINE_CHECK);
+        let mut known_peers_save = tokio::time::interval_at(
+            Instant::now() + KNOWN_PEERS_SAVE_INTERVAL,
+            KNOWN_PEERS_SAVE_INTERVAL,
+        );
 
         loop {
             tokio::select! {
@@ -74,7 +106,8 @@ impl Process {
                             self.topology.accept_gossips(gossip);
                             last_update = Instant::now();
                         },
-                        TopologyMsg::DemotePeer(id) => self.topology.report_node(&id),
+                        TopologyMsg::DemotePeer(id, reason) => self.topology.report_node(&id, reason),
+                        TopologyMsg::RewardPeer(id, blocks) => self.topology.reward_node(id, blocks),
                         TopologyMsg::PromotePeer(id) => self.topology.promote_node(&id),
                         TopologyMsg::View(selection, handle) => {
                             handle.reply_ok(self.topology.view(selection))
@@ -88,6 +121,12 @@ impl Process {
                         TopologyMsg::ListQuarantined(handle) => {
                             handle.reply_ok(self.topology.list_quarantined())
                         }
+                        TopologyMsg::LiftQuarantine(id, handle) => {
+                            let lifted = self.topology.lift_quarantine(&id);
+                            handle.reply_ok(lifted.is_some());
+                            self.send_gossip_messages(lifted.into_iter().collect());
+                        }
+                        TopologyMsg::ApplyPeerRules => self.topology.apply_rules(),
                     }
                     tracing::trace!("item handling finished");
                 },
@@ -121,7 +160,24 @@ impl Process {
                     }
 
                     self.send_gossip_messages(nodes_to_contact);
+                    self.save_reputations();
                 }
+                _ = known_peers_save.tick() => self.save_known_peers(),
+            }
+        }
+    }
+
+    fn save_known_peers(&mut self) {
+        self.topology.update_known_peers(&mut self.known_peers);
+        if let Err(e) = self.known_peers.save() {
+            tracing::warn!(reason = %e, "could not save the known peers");
+        }
+    }
+
+    fn save_reputations(&mut self) {
+        if let Some(path) = &self.reputation_path {
+            if let Err(e) = self.topology.save_reputations(path) {
+                tracing::warn!(reason = %e, path = %path.display(), "could not save the reputation of the peers");
             }
         }
     }
@@ -143,3 +199,12 @@ impl Process {
         }
     }
 }
+
+/// The topology task is dropped when the services of the node are shut
+/// down, the peers are saved one last time then.
+impl Drop for Process {
+    fn drop(&mut self) {
+        self.save_known_peers();
+        self.save_reputations();
+    }
+}
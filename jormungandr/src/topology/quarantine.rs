@@ -5,14 +5,20 @@
 /// by a call to 'promote_peer', not to ban a node forever from the topology.
 ///
 /// It is also responsible for determining wheter a report is to be accounted for
-/// according to the node configs.
+/// according to the node configs, and for how long the node is quarantined according
+/// to its reputation.
 use crate::network::p2p::Address;
-use crate::topology::{NodeId, Peer, PeerInfo};
-use jormungandr_lib::time::Duration;
+use crate::topology::{
+    reputation::{Reputation, Reputations},
+    NodeId, Peer, PeerInfo,
+};
+use jormungandr_lib::{interfaces::PeerReportReason, time::Duration};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
+    io,
+    path::Path,
     time::{Duration as StdDuration, Instant, SystemTime},
 };
 
@@ -25,10 +31,15 @@ const DEFAULT_MAX_QUARANTINE_DURATION: StdDuration = StdDuration::from_secs(2 *
 /// default number of records is 24_000
 const DEFAULT_MAX_NUM_QUARANTINE_RECORDS: usize = 24_000;
 
+/// the number of times a peer has to be demoted to go from the trusted
+/// pool to the dirty pool
+pub(super) const MAX_DEMOTIONS: usize = 2;
+
 #[derive(Debug, Clone)]
 struct ReportRecord {
     peer_info: PeerInfo,
-    report_time: Instant,
+    expires: Instant,
+    duration: StdDuration,
 }
 
 pub enum ReportNodeStatus {
@@ -39,8 +50,11 @@ pub enum ReportNodeStatus {
 
 /// Forgive nodes we demoted after some time
 pub struct ReportRecords {
-    /// A report will be lifted after 'report_duration'
+    /// A first report will be lifted after 'report_duration', the following
+    /// ones after twice as long as the previous one
     report_duration: StdDuration,
+    /// The longest a report can last
+    max_report_duration: StdDuration,
     report_whitelist: HashSet<Address>,
     /// To avoid cycling down nodes back and and forth(and as such prevent them
     /// from being evicted from the lru cache), do not report again nodes that were recently
@@ -50,6 +64,12 @@ pub struct ReportRecords {
     /// from that list after we receive a new gossip about it.
     report_grace: LruCache<NodeId, ()>,
     report_records: LruCache<NodeId, ReportRecord>,
+    /// The reported nodes by expiry of their report, so that lifting the
+    /// expired reports does not go through all the records. Entries of
+    /// reports that were lifted early, evicted or superseded are dropped
+    /// once they expire.
+    report_expiries: BTreeMap<Instant, Vec<NodeId>>,
+    reputations: Reputations,
 }
 
 impl ReportRecords {
@@ -57,8 +77,14 @@ impl ReportRecords {
         let max_num_quarantine_records = config
             .max_num_quarantine_records
             .unwrap_or(DEFAULT_MAX_NUM_QUARANTINE_RECORDS);
+        let report_duration = StdDuration::from(config.quarantine_duration);
         Self {
-            report_duration: StdDuration::from(config.quarantine_duration),
+            report_duration,
+            max_report_duration: config
+                .max_quarantine
+                .map(StdDuration::from)
+                .unwrap_or(DEFAULT_MAX_QUARANTINE_DURATION)
+                .max(report_duration),
             report_whitelist: config
                 .quarantine_whitelist
                 .into_iter()
@@ -66,6 +92,8 @@ impl ReportRecords {
                 .collect(),
             report_grace: LruCache::new(max_num_quarantine_records),
             report_records: LruCache::new(max_num_quarantine_records),
+            report_expiries: BTreeMap::new(),
+            reputations: Reputations::new(max_num_quarantine_records),
         }
     }
 
@@ -74,6 +102,7 @@ impl ReportRecords {
         &mut self,
         topology: &mut poldercast::Topology,
         node: Peer,
+        reason: PeerReportReason,
     ) -> ReportNodeStatus {
         if self.report_whitelist.contains(&node.address()) {
             tracing::debug!(
@@ -82,15 +111,34 @@ impl ReportRecords {
                 "quarantine whitelists prevents this node from being reported",
             );
             ReportNodeStatus::Ok
-        } else if self.report_grace.contains(&node.id()) {
-            tracing::trace!(node = %node.address(), id=%node.id(), "not reporting node in grace list");
-            ReportNodeStatus::Ok
         } else {
+            // Reports for misbehaviour are never forgiven by the grace list,
+            // it only avoids cycling nodes with connectivity issues. A
+            // forgiven report does not count against the reputation either.
+            let severe = match self.reputations.get(&node.id()) {
+                Some(reputation) => reputation.is_severe(reason),
+                None => Reputation::default().is_severe(reason),
+            };
+            if !severe && self.report_grace.contains(&node.id()) {
+                tracing::trace!(node = %node.address(), id=%node.id(), "not reporting node in grace list");
+                return ReportNodeStatus::Ok;
+            }
+            let reputation = self.reputations.penalize(node.id(), reason);
+            let severe = reputation.is_severe(reason);
+            let duration =
+                reputation.quarantine_duration(self.report_duration, self.max_report_duration);
+
             let mut peer_info = PeerInfo::from(node);
-            tracing::debug!(node = %peer_info.address, id=%peer_info.id, ?self.report_duration, "reporting node");
-            // If we'll handle report reasons other that a connectivity issue in the future, we may want to
-            // demote a peer all the way down to dirty in case of a serious violation.
-            topology.remove_peer(peer_info.id.as_ref());
+            tracing::debug!(node = %peer_info.address, id=%peer_info.id, ?reason, ?duration, "reporting node");
+            // A serious violation demotes the peer all the way down to dirty,
+            // other reports demote it once.
+            let demotions = if severe { MAX_DEMOTIONS } else { 1 };
+            for _ in 0..demotions {
+                if topology.peers().dirty().contains(peer_info.id.as_ref()) {
+                    break;
+                }
+                topology.remove_peer(peer_info.id.as_ref());
+            }
 
             let mut result = ReportNodeStatus::SoftReport;
 
@@ -108,11 +156,17 @@ impl ReportRecords {
                 result = ReportNodeStatus::Quarantine;
             }
 
+            let expires = Instant::now() + duration;
+            self.report_expiries
+                .entry(expires)
+                .or_default()
+                .push(peer_info.id);
             self.report_records.put(
                 peer_info.id,
                 ReportRecord {
                     peer_info,
-                    report_time: Instant::now(),
+                    expires,
+                    duration,
                 },
             );
 
@@ -120,32 +174,77 @@ impl ReportRecords {
         }
     }
 
+    /// Improve the reputation of a node for the blocks it served us
+    pub fn reward_node(&mut self, node: NodeId, blocks: u64) {
+        self.reputations
+            .reward(node, blocks.try_into().unwrap_or(i64::MAX));
+    }
+
     pub fn reported_nodes(&self) -> Vec<PeerInfo> {
         self.report_records
             .iter()
-            .map(|(_, v)| v.peer_info.clone())
+            .map(|(id, record)| {
+                let mut peer_info = record.peer_info.clone();
+                peer_info.reputation = self
+                    .reputations
+                    .get(id)
+                    .map(|reputation| reputation.to_interface(Some(record.duration)));
+                peer_info
+            })
             .collect()
     }
 
+    /// The score of the node, neutral if nothing is known about it
+    pub fn score(&self, node: &NodeId) -> i64 {
+        self.reputations.get(node).map_or(0, Reputation::score)
+    }
+
+    pub fn load_reputations(&mut self, path: &Path) -> io::Result<()> {
+        self.reputations.load(path)
+    }
+
+    pub fn save_reputations(&mut self, path: &Path) -> io::Result<()> {
+        self.reputations.save(path)
+    }
+
     pub fn record_new_gossip(&mut self, node: &NodeId) {
         self.report_grace.pop(node);
     }
 
+    pub fn is_reported(&self, node: &NodeId) -> bool {
+        self.report_records.contains(node)
+    }
+
+    /// Lift the report against the node before it expires
+    pub fn lift_report(&mut self, node: &NodeId) -> Option<PeerInfo> {
+        let record = self.report_records.pop(node)?;
+        self.report_grace.put(*node, ());
+        Some(record.peer_info)
+    }
+
     pub fn lift_reports(&mut self) -> Vec<PeerInfo> {
-        let mut res = Vec::new();
-        // This is basically a FIFO queue, a lru cache is being used just to
-        // avoid keeping another data structure to know if an address was already quarantined
-        while let Some((_id, record)) = self.report_records.peek_lru() {
-            if record.report_time.elapsed() < self.report_duration {
-                break;
-            }
+        // Reports last more or less depending on the reputation of the node,
+        // so they do not expire in the order they were made.
+        let pending = self.report_expiries.split_off(&Instant::now());
+        let expired = std::mem::replace(&mut self.report_expiries, pending);
 
-            let (id, record) = self.report_records.pop_lru().unwrap();
-            self.report_grace.put(id, ());
-            res.push(record.peer_info);
+        let mut lifted = Vec::new();
+        for (expires, nodes) in expired {
+            for id in nodes {
+                let current = self
+                    .report_records
+                    .peek(&id)
+                    .map_or(false, |record| record.expires == expires);
+                if !current {
+                    continue;
+                }
+                if let Some(record) = self.report_records.pop(&id) {
+                    self.report_grace.put(id, ());
+                    lifted.push(record.peer_info);
+                }
+            }
         }
-
-        res
+        lifted
     }
 }
 
@@ -153,9 +252,12 @@ impl Default for ReportRecords {
     fn default() -> Self {
         Self {
             report_duration: DEFAULT_QUARANTINE_DURATION,
+            max_report_duration: DEFAULT_MAX_QUARANTINE_DURATION,
             report_whitelist: HashSet::new(),
             report_grace: LruCache::new(DEFAULT_MAX_NUM_QUARANTINE_RECORDS),
             report_records: LruCache::new(DEFAULT_MAX_NUM_QUARANTINE_RECORDS),
+            report_expiries: BTreeMap::new(),
+            reputations: Reputations::new(DEFAULT_MAX_NUM_QUARANTINE_RECORDS),
         }
     }
 }
@@ -0,0 +1,241 @@
+//! Scoring of the behaviour of the peers of the node.
+//!
+//! Every report against a peer lowers its score and counts as an offence,
+//! every block it serves raises the score back. The offences are forgiven
+//! once the score is positive again. The number of offences determines how
+//! long the peer is quarantined for.
+use super::NodeId;
+use crate::utils::fs::{load_optional, save_atomically};
+use jormungandr_lib::interfaces::{self, PeerReport, PeerReportReason, PeerReputation};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+const MAX_SCORE: i64 = 1_000;
+const MIN_SCORE: i64 = -1_000;
+/// a peer with a score this low is put in quarantine on the next report,
+/// whatever the reason
+const SEVERE_SCORE: i64 = -300;
+/// number of reports kept per peer to explain its score
+const MAX_REPORTS: usize = 10;
+
+fn penalty(reason: PeerReportReason) -> i64 {
+    match reason {
+        PeerReportReason::InvalidBlock => 200,
+        PeerReportReason::HandshakeFailed => 50,
+        PeerReportReason::InvalidFragment => 20,
+        PeerReportReason::Timeout => 10,
+        PeerReportReason::ConnectionFailed => 10,
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reputation {
+    score: i64,
+    offences: u32,
+    reports: VecDeque<PeerReport>,
+}
+
+impl Reputation {
+    fn penalize(&mut self, reason: PeerReportReason) {
+        self.score = self.score.saturating_sub(penalty(reason)).max(MIN_SCORE);
+        self.offences = self.offences.saturating_add(1);
+        if self.reports.len() == MAX_REPORTS {
+            self.reports.pop_front();
+        }
+        self.reports.push_back(PeerReport {
+            time: SystemTime::now().into(),
+            reason,
+        });
+    }
+
+    fn reward(&mut self, amount: i64) {
+        self.score = self.score.saturating_add(amount).min(MAX_SCORE);
+        if self.score >= 0 {
+            self.offences = 0;
+        }
+    }
+
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// whether the peer should be quarantined right away instead of being
+    /// demoted one step at a time
+    pub fn is_severe(&self, reason: PeerReportReason) -> bool {
+        reason == PeerReportReason::InvalidBlock || self.score <= SEVERE_SCORE
+    }
+
+    /// `base` doubled for every offence after the first one, up to `max`
+    pub fn quarantine_duration(&self, base: Duration, max: Duration) -> Duration {
+        let doublings = self.offences.saturating_sub(1);
+        2u32.checked_pow(doublings)
+            .and_then(|factor| base.checked_mul(factor))
+            .map_or(max, |duration| duration.min(max))
+    }
+
+    pub fn to_interface(&self, quarantine_duration: Option<Duration>) -> PeerReputation {
+        PeerReputation {
+            score: self.score,
+            offences: self.offences,
+            quarantine_duration: quarantine_duration.map(Into::into),
+            reports: self.reports.iter().cloned().collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PersistedReputation {
+    id: interfaces::NodeId,
+    score: i64,
+    offences: u32,
+    reports: Vec<PeerReport>,
+}
+
+/// The reputation of the peers the node has heard of, the least recently
+/// updated ones are forgotten first.
+pub struct Reputations {
+    records: LruCache<NodeId, Reputation>,
+    changed: bool,
+}
+
+impl Reputations {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: LruCache::new(capacity),
+            changed: false,
+        }
+    }
+
+    pub fn get(&self, node: &NodeId) -> Option<&Reputation> {
+        self.records.peek(node)
+    }
+
+    pub fn penalize(&mut self, node: NodeId, reason: PeerReportReason) -> &Reputation {
+        let reputation = self.entry(node);
+        reputation.penalize(reason);
+        reputation
+    }
+
+    pub fn reward(&mut self, node: NodeId, amount: i64) {
+        self.entry(node).reward(amount);
+    }
+
+    fn entry(&mut self, node: NodeId) -> &mut Reputation {
+        self.changed = true;
+        if !self.records.contains(&node) {
+            self.records.put(node, Reputation::default());
+        }
+        self.records.get_mut(&node).unwrap()
+    }
+
+    /// Load the reputations saved by a previous run of the node. A missing
+    /// file is not an error.
+    pub fn load(&mut self, path: &Path) -> io::Result<()> {
+        let bytes = match load_optional(path)? {
+            Some(bytes) => bytes,
+            None => return Ok(()),
+        };
+        let persisted: Vec<PersistedReputation> = serde_json::from_slice(&bytes)?;
+        for record in persisted {
+            self.records.put(
+                record.id.into(),
+                Reputation {
+                    score: record.score.clamp(MIN_SCORE, MAX_SCORE),
+                    offences: record.offences,
+                    reports: record
+                        .reports
+                        .into_iter()
+                        .rev()
+                        .take(MAX_REPORTS)
+                        .rev()
+                        .collect(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Save the reputations if they changed since the last time
+    pub fn save(&mut self, path: &Path) -> io::Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        // least recently used first, so that the order of the cache is
+        // restored on load
+        let persisted: Vec<_> = self
+            .records
+            .iter()
+            .rev()
+            .map(|(id, reputation)| PersistedReputation {
+                id: (*id).into(),
+                score: reputation.score,
+                offences: reputation.offences,
+                reports: reputation.reports.iter().cloned().collect(),
+            })
+            .collect();
+        save_atomically(path, &serde_json::to_vec(&persisted)?)?;
+        self.changed = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(10 * 60);
+    const MAX: Duration = Duration::from_secs(2 * 24 * 3600);
+
+    #[test]
+    fn quarantine_escalates_with_offences() {
+        let mut reputation = Reputation::default();
+        reputation.penalize(PeerReportReason::ConnectionFailed);
+        assert_eq!(reputation.quarantine_duration(BASE, MAX), BASE);
+        reputation.penalize(PeerReportReason::ConnectionFailed);
+        assert_eq!(reputation.quarantine_duration(BASE, MAX), BASE * 2);
+        reputation.penalize(PeerReportReason::Timeout);
+        assert_eq!(reputation.quarantine_duration(BASE, MAX), BASE * 4);
+    }
+
+    #[test]
+    fn quarantine_is_capped() {
+        let mut reputation = Reputation::default();
+        for _ in 0..100 {
+            reputation.penalize(PeerReportReason::Timeout);
+        }
+        assert_eq!(reputation.quarantine_duration(BASE, MAX), MAX);
+        assert_eq!(reputation.reports.len(), MAX_REPORTS);
+        assert_eq!(reputation.score, MIN_SCORE);
+    }
+
+    #[test]
+    fn served_blocks_forgive_offences() {
+        let mut reputation = Reputation::default();
+        reputation.penalize(PeerReportReason::Timeout);
+        reputation.penalize(PeerReportReason::Timeout);
+        reputation.reward(5);
+        assert_eq!(reputation.offences, 2);
+        reputation.reward(15);
+        assert_eq!(reputation.score, 0);
+        assert_eq!(reputation.offences, 0);
+        assert_eq!(reputation.quarantine_duration(BASE, MAX), BASE);
+    }
+
+    #[test]
+    fn invalid_blocks_are_severe() {
+        let mut reputation = Reputation::default();
+        assert!(reputation.is_severe(PeerReportReason::InvalidBlock));
+        assert!(!reputation.is_severe(PeerReportReason::Timeout));
+        while reputation.score > SEVERE_SCORE {
+            reputation.penalize(PeerReportReason::HandshakeFailed);
+        }
+        assert!(reputation.is_severe(PeerReportReason::Timeout));
+    }
+}
@@ -2,15 +2,16 @@
 //!
 use super::{
     layers::{self, LayersConfig},
-    quarantine::ReportNodeStatus,
-    topic, Gossips, NodeId, Peer, PeerInfo, ReportRecords,
+    quarantine::{ReportNodeStatus, MAX_DEMOTIONS},
+    topic, Gossips, KnownPeers, NodeId, Peer, PeerInfo, ReportRecords,
 };
 use crate::{
     metrics::{Metrics, MetricsBackend},
+    network::p2p::rules::PeerRules,
     settings::start::network::Configuration,
 };
 use chain_crypto::Ed25519;
-use jormungandr_lib::crypto::key::SigningKey;
+use jormungandr_lib::{crypto::key::SigningKey, interfaces::PeerReportReason};
 use poldercast::{
     layer::{self as poldercast_layer, Layer, LayerBuilder},
     Profile, Topology,
@@ -18,8 +19,12 @@ use poldercast::{
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
 use std::{
+    collections::HashSet,
     convert::TryInto,
+    io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::Path,
+    sync::Arc,
 };
 use tracing::instrument;
 
@@ -41,6 +46,9 @@ pub struct View {
 pub struct P2pTopology {
     topology: Topology,
     quarantine: ReportRecords,
+    rules: Arc<PeerRules>,
+    /// peers moved out of the topology because they are banned
+    banned: HashSet<NodeId>,
     key: keynesis::key::ed25519::SecretKey,
     stats_counter: Metrics,
 }
@@ -105,7 +113,7 @@ impl LayerBuilder for CustomLayerBuilder {
 }
 
 impl P2pTopology {
-    pub fn new(config: &Configuration, stats_counter: Metrics) -> Self {
+    pub fn new(config: &Configuration, rules: Arc<PeerRules>, stats_counter: Metrics) -> Self {
         let addr = config.public_address.unwrap_or(*LOCAL_ADDR);
         let key = secret_key_into_keynesis(config.node_key.clone());
 
@@ -117,6 +125,8 @@ impl P2pTopology {
         P2pTopology {
             topology,
             quarantine,
+            rules,
+            banned: HashSet::new(),
             key,
             stats_counter,
         }
@@ -156,6 +166,10 @@ impl P2pTopology {
             let peer = Profile::from_gossip(gossip);
             let peer_id = NodeId(peer.id());
             tracing::trace!(addr = %peer.address(), %peer_id, "received peer from incoming gossip");
+            if self.rules.is_banned(&peer_id, peer.address().ip()) {
+                tracing::trace!(%peer_id, "ignoring gossip about banned peer");
+                continue;
+            }
             if self.topology.add_peer(peer) {
                 self.quarantine.record_new_gossip(&peer_id);
                 self.stats_counter
@@ -212,12 +226,18 @@ impl P2pTopology {
     }
 
     /// register a strike against the given peer
-    #[instrument(skip_all, level = "debug", fields(%node_id))]
-    pub fn report_node(&mut self, node_id: &NodeId) {
+    #[instrument(skip_all, level = "debug", fields(%node_id, ?reason))]
+    pub fn report_node(&mut self, node_id: &NodeId, reason: PeerReportReason) {
+        if self.rules.is_pinned(node_id) {
+            tracing::debug!("not reporting pinned node");
+            return;
+        }
         if let Some(node) = self.topology.get(node_id.as_ref()).cloned() {
-            let result = self
-                .quarantine
-                .report_node(&mut self.topology, Peer::from(node.gossip().clone()));
+            let result = self.quarantine.report_node(
+                &mut self.topology,
+                Peer::from(node.gossip().clone()),
+                reason,
+            );
             if let ReportNodeStatus::Quarantine | ReportNodeStatus::SoftReport = result {
                 self.stats_counter
                     .set_peer_available_cnt(self.peer_available_cnt());
@@ -228,6 +248,31 @@ impl P2pTopology {
         }
     }
 
+    /// register that the given peer served us valid blocks
+    pub fn reward_node(&mut self, node_id: NodeId, blocks: u64) {
+        self.quarantine.reward_node(node_id, blocks);
+    }
+
+    /// Record the available peers in good standing as known peers, and
+    /// forget the reported ones
+    pub fn update_known_peers(&self, known_peers: &mut KnownPeers) {
+        for peer in self.list_available() {
+            let score = self.quarantine.score(&peer.id());
+            known_peers.update(peer, score);
+        }
+        for info in self.quarantine.reported_nodes() {
+            known_peers.remove(&info.id);
+        }
+    }
+
+    pub fn load_reputations(&mut self, path: &Path) -> io::Result<()> {
+        self.quarantine.load_reputations(path)
+    }
+
+    pub fn save_reputations(&mut self, path: &Path) -> io::Result<()> {
+        self.quarantine.save_reputations(path)
+    }
+
     /// update our gossip so that other nodes can see that we are updating
     /// it and are alive
     pub fn update_gossip(&mut self) {
@@ -238,22 +283,91 @@ impl P2pTopology {
         self.quarantine
             .lift_reports()
             .into_iter()
-            .filter_map(|node| {
-                let node = self.topology.peers().dirty().peek(node.id.as_ref()).cloned();
-                // It may happen that a node is evicted from the dirty pool
-                // in poldercast and then re-enters the topology in the 'pool'
-                // pool, all while we hold the node in quarantine.
-                // If that happens we should not promote it anymore.
-                if let Some(node) = &node {
-                    tracing::debug!(node = %node.address(), id=?node.id(), "lifting node from quarantine");
-                    self.topology.promote_peer(&node.id());
-                    self.stats_counter.sub_peer_quarantined_cnt(1);
-                    self.stats_counter
-                    .set_peer_available_cnt(self.peer_available_cnt());
+            .filter_map(|node| self.restore_node(node))
+            .collect()
+    }
+
+    /// Lift the quarantine of the node before it expires, returns the node
+    /// if it was quarantined
+    pub fn lift_quarantine(&mut self, node_id: &NodeId) -> Option<Peer> {
+        self.quarantine
+            .lift_report(node_id)
+            .and_then(|node| self.restore_node(node))
+    }
+
+    fn restore_node(&mut self, lifted: PeerInfo) -> Option<Peer> {
+        // the node leaves the quarantine whether or not it is promoted below
+        if lifted.quarantined.is_some() {
+            self.stats_counter.sub_peer_quarantined_cnt(1);
+        }
+        let node_id = &lifted.id;
+        let node = self
+            .topology
+            .peers()
+            .dirty()
+            .peek(node_id.as_ref())
+            .cloned();
+        // It may happen that a node is evicted from the dirty pool
+        // in poldercast and then re-enters the topology in the 'pool'
+        // pool, all while we hold the node in quarantine.
+        // If that happens we should not promote it anymore.
+        let node = node?;
+        if self.rules.is_banned(node_id, node.address().ip()) {
+            tracing::debug!(node = %node.address(), id=?node.id(), "not lifting banned node from quarantine");
+            self.banned.insert(*node_id);
+            return None;
+        }
+        tracing::debug!(node = %node.address(), id=?node.id(), "lifting node from quarantine");
+        self.topology.promote_peer(&node.id());
+        self.stats_counter
+            .set_peer_available_cnt(self.peer_available_cnt());
+        Some(Peer::from(node.gossip().clone()))
+    }
+
+    /// Move the banned peers out of the topology, and restore the ones which
+    /// are no longer banned unless they are quarantined
+    pub fn apply_rules(&mut self) {
+        let rules = &self.rules;
+        let newly_banned: Vec<NodeId> = self
+            .list_available()
+            .filter(|peer| rules.is_banned(&peer.id(), peer.address().ip()))
+            .map(|peer| peer.id())
+            .collect();
+        for id in newly_banned {
+            tracing::debug!(%id, "removing banned node from the topology");
+            for _ in 0..MAX_DEMOTIONS {
+                if self.topology.peers().dirty().contains(id.as_ref()) {
+                    break;
                 }
-                node.map(|node| Peer::from(node.gossip().clone()))
+                self.topology.remove_peer(id.as_ref());
+            }
+            self.banned.insert(id);
+        }
+
+        let unbanned: Vec<NodeId> = self
+            .banned
+            .iter()
+            .filter(|id| {
+                self.topology
+                    .peers()
+                    .dirty()
+                    .peek(id.as_ref())
+                    .map_or(true, |node| !self.rules.is_banned(id, node.address().ip()))
             })
-            .collect()
+            .copied()
+            .collect();
+        for id in unbanned {
+            self.banned.remove(&id);
+            if !self.quarantine.is_reported(&id)
+                && self.topology.peers().dirty().contains(id.as_ref())
+            {
+                tracing::debug!(%id, "restoring unbanned node in the topology");
+                self.topology.promote_peer(id.as_ref());
+            }
+        }
+
+        self.stats_counter
+            .set_peer_available_cnt(self.peer_available_cnt());
     }
 
     fn peer_available_cnt(&self) -> usize {
@@ -262,3 +376,93 @@ impl P2pTopology {
         self.list_available().size_hint().0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jormungandr_lib::interfaces::{BanRequest, PeerTarget};
+
+    fn key(seed: u8) -> keynesis::key::ed25519::SecretKey {
+        keynesis::key::ed25519::SecretKey::new(ChaChaRng::from_seed([seed; 32]))
+    }
+
+    fn topology(rules: Arc<PeerRules>) -> P2pTopology {
+        let key = key(0);
+        P2pTopology {
+            topology: Topology::new(*LOCAL_ADDR, &key),
+            quarantine: ReportRecords::default(),
+            rules,
+            banned: HashSet::new(),
+            key,
+            stats_counter: Metrics::builder().build(),
+        }
+    }
+
+    fn peer(seed: u8) -> Peer {
+        let gossip = poldercast::Gossip::new(
+            ([10, 0, 0, seed], 3000).into(),
+            &key(seed),
+            poldercast::Subscriptions::new().as_slice(),
+        );
+        Peer::from(gossip)
+    }
+
+    fn is_available(topology: &P2pTopology, peer: &Peer) -> bool {
+        topology
+            .list_available()
+            .any(|available| available.id() == peer.id())
+    }
+
+    fn ban(rules: &PeerRules, peer: &Peer) -> PeerTarget {
+        let target = PeerTarget::Id(peer.id().into());
+        rules
+            .ban(BanRequest {
+                target: target.clone(),
+                duration: None,
+                reason: None,
+            })
+            .unwrap();
+        target
+    }
+
+    #[test]
+    fn banned_peers_leave_the_topology_until_the_ban_is_lifted() {
+        let rules = Arc::new(PeerRules::new(None));
+        let mut topology = topology(Arc::clone(&rules));
+        let (banned, other) = (peer(1), peer(2));
+        topology.accept_gossips(Gossips(vec![banned.clone(), other.clone()]));
+        assert!(is_available(&topology, &banned));
+
+        let target = ban(&rules, &banned);
+        topology.apply_rules();
+        assert!(!is_available(&topology, &banned));
+        assert!(is_available(&topology, &other));
+
+        // gossips about a banned peer do not bring it back
+        topology.accept_gossips(Gossips(vec![banned.clone()]));
+        assert!(!is_available(&topology, &banned));
+
+        assert!(rules.unban(&target).unwrap());
+        topology.apply_rules();
+        assert!(is_available(&topology, &banned));
+        assert!(is_available(&topology, &other));
+    }
+
+    #[test]
+    fn banned_peers_are_not_restored_from_quarantine() {
+        let rules = Arc::new(PeerRules::new(None));
+        let mut topology = topology(Arc::clone(&rules));
+        let banned = peer(1);
+        topology.accept_gossips(Gossips(vec![banned.clone()]));
+        topology.report_node(&banned.id(), PeerReportReason::InvalidBlock);
+        assert!(!is_available(&topology, &banned));
+
+        let target = ban(&rules, &banned);
+        assert!(topology.lift_quarantine(&banned.id()).is_none());
+        assert!(!is_available(&topology, &banned));
+
+        assert!(rules.unban(&target).unwrap());
+        topology.apply_rules();
+        assert!(is_available(&topology, &banned));
+    }
+}
@@ -0,0 +1,94 @@
+//! Reading and writing the state files the node keeps in its storage
+//! directory.
+use std::{
+    fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Read the content of the file, `None` if it does not exist
+pub fn load_optional(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Replace the content of the file, so that a crash leaves either the old
+/// or the new content.
+///
+/// The content is written to a temporary file next to it, only readable by
+/// the node until it is complete, then renamed over the file. The
+/// permissions of an existing file are kept.
+pub fn save_atomically(path: &Path, content: &[u8]) -> io::Result<()> {
+    let permissions = match fs::metadata(path) {
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(content)?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions)?;
+        }
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)?;
+    // the rename is only durable once the directory is synced as well
+    if let Some(parent) = path.parent().filter(|_| cfg!(unix)) {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_files_load_as_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load_optional(&dir.path().join("missing")).unwrap(), None);
+    }
+
+    #[test]
+    fn saved_content_replaces_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        save_atomically(&path, b"first").unwrap();
+        assert_eq!(load_optional(&path).unwrap().unwrap(), b"first");
+        save_atomically(&path, b"second").unwrap();
+        assert_eq!(load_optional(&path).unwrap().unwrap(), b"second");
+        assert!(!dir.path().join("state.json.tmp").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn permissions_are_kept() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        save_atomically(&path, b"first").unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        save_atomically(&path, b"second").unwrap();
+        assert_eq!(mode(&path), 0o640);
+    }
+}
@@ -1,3 +1,5 @@
 pub mod async_msg;
 pub mod fire_forget_scheduler;
+pub mod fs;
 pub mod task;
+pub mod token_bucket;
@@ -5,6 +5,7 @@
 //! modules utilized in jormungandr.
 //!
 
+use crate::metrics::{Metrics, MetricsBackend};
 use futures::{prelude::*, stream::FuturesUnordered};
 use std::{
     error,
@@ -26,6 +27,7 @@ pub struct Services {
     services: Vec<Service>,
     finish_listener: FuturesUnordered<JoinHandle<Result<(), Box<dyn error::Error + Send + Sync>>>>,
     runtime: Runtime,
+    metrics: Metrics,
 }
 
 #[derive(Debug, Error)]
@@ -68,6 +70,7 @@ pub struct TokioServiceInfo {
     up_time: Instant,
     span: tracing::Span,
     handle: Handle,
+    metrics: Metrics,
 }
 
 pub struct TaskMessageBox<Msg>(Sender<Msg>);
@@ -91,9 +94,16 @@ impl Services {
             services: Vec::new(),
             finish_listener: FuturesUnordered::new(),
             runtime: Runtime::new().unwrap(),
+            metrics: Metrics::builder().build(),
         }
     }
 
+    /// set the metrics the restarts of the tasks of the services spawned
+    /// from now on are reported to
+    pub fn set_metrics(&mut self, metrics: Metrics) {
+        self.metrics = metrics;
+    }
+
     /// Spawn the given Future in a new dedicated runtime
     pub fn spawn_future<F, T>(&mut self, name: &'static str, f: F)
     where
@@ -109,6 +119,7 @@ impl Services {
             up_time: now,
             span: tracing_span,
             handle,
+            metrics: self.metrics.clone(),
         };
         let span_parent = future_service_info.span.clone();
         let handle = self.runtime.spawn(
@@ -147,6 +158,7 @@ impl Services {
             up_time: now,
             span: tracing_span,
             handle,
+            metrics: self.metrics.clone(),
         };
         let parent_span = future_service_info.span.clone();
         let handle = self.runtime.spawn(
@@ -211,6 +223,7 @@ impl Services {
             up_time: now,
             span: parent_span.clone(),
             handle,
+            metrics: self.metrics.clone(),
         };
         parent_span.in_scope(|| {
             self.runtime
@@ -254,14 +267,21 @@ impl TokioServiceInfo {
         &self.span
     }
 
+    /// the span of a task spawned by this service, linked to the span the
+    /// task is spawned from so that it can be traced back to its cause
+    fn task_span(&self, name: &'static str) -> Span {
+        let span = span!(parent: &self.span, Level::TRACE, "task", kind = name);
+        span.follows_from(&Span::current());
+        span
+    }
+
     /// spawn a std::future within the service's tokio handle
     pub fn spawn<F>(&self, name: &'static str, future: F)
     where
         F: Future<Output = ()> + Send + 'static,
     {
         tracing::trace!("service `{}` spawning task `{}`", self.name, name);
-        self.handle
-            .spawn(future.instrument(span!(parent: &self.span, Level::TRACE, "task", kind = name)));
+        self.handle.spawn(future.instrument(self.task_span(name)));
     }
 
     /// just like spawn but instead log an error on Result::Err
@@ -281,7 +301,7 @@ impl TokioServiceInfo {
                     }
                 }
             }
-            .instrument(span!(parent: &self.span, Level::TRACE, "task", kind = name)),
+            .instrument(self.task_span(name)),
         );
     }
 
@@ -297,7 +317,7 @@ impl TokioServiceInfo {
                     tracing::error!("task {} timed out", name)
                 }
             }
-            .instrument(span!(parent: &self.span, Level::TRACE, "task", kind = name)),
+            .instrument(self.task_span(name)),
         );
     }
 
@@ -317,7 +337,7 @@ impl TokioServiceInfo {
                     Ok(Ok(())) => {}
                 };
             }
-            .instrument(span!(parent: &self.span, Level::TRACE, "task", kind = name)),
+            .instrument(self.task_span(name)),
         );
     }
 
@@ -353,13 +373,14 @@ impl TokioServiceInfo {
                     );
                 }
             }
-            .instrument(span!(parent: &self.span, Level::TRACE, "task", kind = name)),
+            .instrument(self.task_span(name)),
         );
     }
 
     // Run the closure with the specified period on the handle
     // and execute the resulting fallible async closure.
-    // If the closure returns an Err, log it.
+    // If the closure returns an Err, log it and count the task as restarted,
+    // it runs again at the next period.
     pub fn run_periodic_fallible<F, U, E>(&self, name: &'static str, period: Duration, mut f: F)
     where
         F: FnMut() -> U,
@@ -367,6 +388,7 @@ impl TokioServiceInfo {
         E: Debug,
         U: Future<Output = Result<(), E>> + Send + 'static,
     {
+        let (service, metrics) = (self.name, self.metrics.clone());
         self.spawn(
             name,
             async move {
@@ -392,6 +414,7 @@ impl TokioServiceInfo {
                             );
                         }
                         Err(e) => {
+                            metrics.add_task_restart(service, name);
                             tracing::error!(
                                 triggered_at = ?t_now,
                                 error = ?e,
@@ -401,7 +424,7 @@ impl TokioServiceInfo {
                     };
                 }
             }
-            .instrument(span!(parent: &self.span, Level::TRACE, "task", kind = name)),
+            .instrument(self.task_span(name)),
         );
     }
 }
@@ -0,0 +1,35 @@
+//! Token bucket rate limiting, allowing bursts of up to one second worth of
+//! the rate.
+use std::time::Instant;
+
+pub struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: f64) -> Self {
+        TokenBucket {
+            rate,
+            tokens: rate,
+            last_refill: Instant::now(),
+        }
+    }
+
+    pub fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.rate);
+        self.last_refill = now;
+    }
+
+    // An amount larger than the rate is let through once the bucket is
+    // full, and paid back before anything else is let through.
+    pub fn allows(&self, amount: f64) -> bool {
+        self.tokens >= amount.min(self.rate)
+    }
+
+    pub fn take(&mut self, amount: f64) {
+        self.tokens -= amount;
+    }
+}
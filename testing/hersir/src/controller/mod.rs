@@ -285,6 +285,7 @@ impl Controller {
                 .unwrap_or(&LogLevel::DEBUG)
                 .to_string(),
             output: LogOutput::Stdout,
+            trace_collector_endpoint: None,
         }));
 
         if let PersistenceMode::Persistent = spawn_params.get_persistence_mode() {
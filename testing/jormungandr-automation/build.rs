@@ -1,6 +1,15 @@
 fn main() {
     tonic_build::compile_protos("proto/node.proto").unwrap();
     tonic_build::compile_protos("proto/watch.proto").unwrap();
+    // only the messages are needed to decode the traces exported by the node
+    tonic_build::configure()
+        .build_client(false)
+        .build_server(false)
+        .compile(
+            &["proto/opentelemetry/proto/collector/trace/v1/trace_service.proto"],
+            &["proto"],
+        )
+        .unwrap();
 
     let jor_cli_name = option_env!("JOR_CLI_NAME").unwrap_or("jcli");
     let jormungandr_name = option_env!("JORMUNGANDR_NAME").unwrap_or("jormungandr");
@@ -0,0 +1,49 @@
+use crate::jcli::command::GovernanceCommand;
+use assert_cmd::assert::OutputAssertExt;
+use jormungandr_lib::interfaces::BlockDate;
+use jortestkit::prelude::ProcessOutput;
+use std::path::Path;
+
+pub struct Governance {
+    governance_command: GovernanceCommand,
+}
+
+impl Governance {
+    pub fn new(governance_command: GovernanceCommand) -> Self {
+        Self { governance_command }
+    }
+
+    /// propose the changes of `config_file`, returns the ID of the proposal
+    pub fn propose<P: AsRef<Path>, Q: AsRef<Path>, S: Into<String>>(
+        self,
+        config_file: P,
+        secret: Q,
+        valid_until: BlockDate,
+        host: S,
+    ) -> String {
+        self.governance_command
+            .propose(config_file, secret, valid_until, host)
+            .build()
+            .assert()
+            .success()
+            .get_output()
+            .as_single_line()
+    }
+
+    /// vote for the proposal, returns the ID of the vote fragment
+    pub fn vote<S: Into<String>, P: AsRef<Path>, T: Into<String>>(
+        self,
+        proposal_id: S,
+        secret: P,
+        valid_until: BlockDate,
+        host: T,
+    ) -> String {
+        self.governance_command
+            .vote(proposal_id, secret, valid_until, host)
+            .build()
+            .assert()
+            .success()
+            .get_output()
+            .as_single_line()
+    }
+}
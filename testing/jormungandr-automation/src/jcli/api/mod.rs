@@ -1,6 +1,7 @@
 mod address;
 mod certificate;
 mod genesis;
+mod governance;
 mod key;
 mod rest;
 mod transaction;
@@ -9,6 +10,7 @@ mod votes;
 pub use address::Address;
 pub use certificate::Certificate;
 pub use genesis::Genesis;
+pub use governance::Governance;
 pub use key::Key;
 pub use rest::Rest;
 pub use transaction::Transaction;
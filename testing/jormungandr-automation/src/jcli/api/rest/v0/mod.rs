@@ -7,7 +7,9 @@ mod vote;
 use crate::jcli::command::rest::V0Command;
 use assert_cmd::assert::OutputAssertExt;
 use block::Block;
-use jormungandr_lib::interfaces::{AccountState, LeadershipLog, SettingsDto, StakePoolStats};
+use jormungandr_lib::interfaces::{
+    AccountState, LeadershipLog, SettingsDto, StakePoolStats, UpdateProposalStatus,
+};
 use jortestkit::prelude::ProcessOutput;
 use message::Message;
 use node::Node;
@@ -54,6 +56,18 @@ impl RestV0 {
         serde_yaml::from_str(&content).expect("Failed to parse settings")
     }
 
+    pub fn governance_proposals<S: Into<String>>(self, host: S) -> Vec<UpdateProposalStatus> {
+        let content = self
+            .v0_command
+            .governance_proposals(host)
+            .build()
+            .assert()
+            .success()
+            .get_output()
+            .as_lossy_string();
+        serde_yaml::from_str(&content).expect("Failed to parse update proposals")
+    }
+
     pub fn stake_pools<S: Into<String>>(self, host: S) -> Vec<String> {
         let content = self
             .v0_command
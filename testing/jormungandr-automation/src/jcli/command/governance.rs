@@ -0,0 +1,55 @@
+use jormungandr_lib::interfaces::BlockDate;
+use std::{path::Path, process::Command};
+
+pub struct GovernanceCommand {
+    command: Command,
+}
+
+impl GovernanceCommand {
+    pub fn new(command: Command) -> Self {
+        Self { command }
+    }
+
+    pub fn propose<P: AsRef<Path>, Q: AsRef<Path>, S: Into<String>>(
+        mut self,
+        config_file: P,
+        secret: Q,
+        valid_until: BlockDate,
+        host: S,
+    ) -> Self {
+        self.command
+            .arg("propose")
+            .arg(config_file.as_ref())
+            .arg("--secret")
+            .arg(secret.as_ref())
+            .arg("--valid-until")
+            .arg(valid_until.to_string())
+            .arg("--host")
+            .arg(host.into());
+        self
+    }
+
+    pub fn vote<S: Into<String>, P: AsRef<Path>, T: Into<String>>(
+        mut self,
+        proposal_id: S,
+        secret: P,
+        valid_until: BlockDate,
+        host: T,
+    ) -> Self {
+        self.command
+            .arg("vote")
+            .arg(proposal_id.into())
+            .arg("--secret")
+            .arg(secret.as_ref())
+            .arg("--valid-until")
+            .arg(valid_until.to_string())
+            .arg("--host")
+            .arg(host.into());
+        self
+    }
+
+    pub fn build(self) -> Command {
+        println!("{:?}", self.command);
+        self.command
+    }
+}
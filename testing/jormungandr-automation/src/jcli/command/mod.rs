@@ -3,6 +3,7 @@ use std::process::Command;
 mod address;
 mod certificate;
 mod genesis;
+mod governance;
 mod key;
 pub mod rest;
 mod transaction;
@@ -11,6 +12,7 @@ pub mod votes;
 pub use address::AddressCommand;
 pub use certificate::CertificateCommand;
 pub use genesis::GenesisCommand;
+pub use governance::GovernanceCommand;
 pub use key::KeyCommand;
 pub use rest::RestCommand;
 pub use transaction::TransactionCommand;
@@ -30,6 +32,11 @@ impl JCliCommand {
         GenesisCommand::new(self.command)
     }
 
+    pub fn governance(mut self) -> GovernanceCommand {
+        self.command.arg("governance");
+        GovernanceCommand::new(self.command)
+    }
+
     pub fn key(mut self) -> KeyCommand {
         self.command.arg("key");
         KeyCommand::new(self.command)
@@ -98,6 +98,16 @@ impl V0Command {
         self
     }
 
+    pub fn governance_proposals<S: Into<String>>(mut self, host: S) -> Self {
+        self.command
+            .arg("governance")
+            .arg("proposals")
+            .arg("get")
+            .arg("--host")
+            .arg(host.into());
+        self
+    }
+
     pub fn stake_pools<S: Into<String>>(mut self, host: S) -> Self {
         self.command
             .arg("stake-pools")
@@ -5,7 +5,7 @@ mod services;
 
 use super::jormungandr::JormungandrProcess;
 use crate::testing::configuration;
-use api::{Address, Certificate, Genesis, Key, Rest, Transaction, Votes};
+use api::{Address, Certificate, Genesis, Governance, Key, Rest, Transaction, Votes};
 pub use command::JCliCommand;
 pub use data::{Witness, WitnessData, WitnessType};
 use jormungandr_lib::crypto::hash::Hash;
@@ -43,6 +43,12 @@ impl JCli {
         Genesis::new(jcli_command.genesis())
     }
 
+    pub fn governance(&self) -> Governance {
+        let command = Command::new(self.exe.clone());
+        let jcli_command = JCliCommand::new(command);
+        Governance::new(jcli_command.governance())
+    }
+
     pub fn key(&self) -> Key {
         let command = Command::new(self.exe.clone());
         let jcli_command = JCliCommand::new(command);
@@ -11,10 +11,10 @@ use jormungandr_lib::{
     crypto::key::KeyPair,
     interfaces::{
         ActiveSlotCoefficient, Block0Configuration, BlockContentMaxSize, CommitteeIdDef,
-        ConsensusLeaderId, Cors, EpochStabilityDepth, FeesGoTo, Initial, InitialToken, InitialUTxO,
-        KesUpdateSpeed, Log, LogEntry, LogOutput, Mempool, NodeConfig, NodeSecret,
-        NumberOfSlotsPerEpoch, Policy, ProposalExpiration, RewardParams, SignedCertificate,
-        SlotDuration, TaxType, Tls, TrustedPeer, Value,
+        ConsensusLeaderId, Cors, EpochStabilityDepth, FeesGoTo, Health, Initial, InitialToken,
+        InitialUTxO, KesUpdateSpeed, Log, LogEntry, LogOutput, Mempool, NodeConfig, NodeSecret,
+        NumberOfSlotsPerEpoch, Policy, ProposalExpiration, RestAuth, RewardParams,
+        SignedCertificate, SlotDuration, TaxType, Tls, TrustedPeer, Value,
     },
 };
 use std::path::PathBuf;
@@ -166,6 +166,16 @@ impl ConfigurationBuilder {
         self
     }
 
+    pub fn with_rest_health_config(&mut self, health: Health) -> &mut Self {
+        self.node_config_builder.with_rest_health_config(health);
+        self
+    }
+
+    pub fn with_rest_auth_config(&mut self, auth: RestAuth) -> &mut Self {
+        self.node_config_builder.with_rest_auth_config(auth);
+        self
+    }
+
     pub fn with_storage(&mut self, temp_dir: &ChildPath) -> &mut Self {
         self.node_config_builder
             .with_storage(temp_dir.path().into());
@@ -231,6 +241,7 @@ impl ConfigurationBuilder {
             format: "json".to_string(),
             level,
             output: LogOutput::File(path),
+            trace_collector_endpoint: None,
         }))
     }
 
@@ -390,6 +401,7 @@ impl ConfigurationBuilder {
                     level: "trace".to_string(),
                     format: "json".to_string(),
                     output: LogOutput::Stdout,
+                    trace_collector_endpoint: None,
                 }));
                 path
             }
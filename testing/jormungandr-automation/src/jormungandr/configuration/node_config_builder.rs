@@ -2,8 +2,8 @@
 
 use jormungandr_lib::{
     interfaces::{
-        Cors, JRpc, LayersConfig, Log, Mempool, NodeConfig, P2p, Policy, Rest, Tls,
-        TopicsOfInterest, TrustedPeer,
+        Cors, Health, JRpc, LayersConfig, Log, Mempool, NodeConfig, P2p, Policy, Rest, RestAuth,
+        Tls, TopicsOfInterest, TrustedPeer,
     },
     time::Duration,
 };
@@ -46,9 +46,14 @@ impl NodeConfigBuilder {
                 listen: format!("{}:{}", DEFAULT_HOST, rest_port).parse().unwrap(),
                 tls: None,
                 cors: None,
+                health: None,
+                auth: None,
+                limits: None,
+                max_fragments_per_batch: None,
             },
             jrpc: JRpc {
                 listen: format!("{}:{}", DEFAULT_HOST, jrpc_port).parse().unwrap(),
+                limits: None,
             },
             p2p: P2p {
                 node_key_file: None,
@@ -72,6 +77,7 @@ impl NodeConfigBuilder {
                 gossip_interval: None,
                 max_bootstrap_attempts: None,
                 network_stuck_check: None,
+                rate_limits: None,
             },
             mempool: Some(Mempool::default()),
         }
@@ -112,6 +118,16 @@ impl NodeConfigBuilder {
         self
     }
 
+    pub fn with_rest_health_config(&mut self, health: Health) -> &mut Self {
+        self.rest.health = Some(health);
+        self
+    }
+
+    pub fn with_rest_auth_config(&mut self, auth: RestAuth) -> &mut Self {
+        self.rest.auth = Some(auth);
+        self
+    }
+
     pub fn with_mempool(&mut self, mempool: Mempool) -> &mut Self {
         self.mempool = Some(mempool);
         self
@@ -43,6 +43,7 @@ impl SecretModelFactory {
                 node_id: Hash::from_str(node_id).unwrap(),
                 sig_key: signing_key,
                 vrf_key,
+                kes_period: 0,
             }),
             bft: None,
         }
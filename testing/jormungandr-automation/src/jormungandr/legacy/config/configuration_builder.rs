@@ -102,6 +102,10 @@ impl LegacyNodeConfigConverter {
                 listen: source.rest.listen,
                 cors: None,
                 tls: None,
+                health: None,
+                auth: None,
+                limits: None,
+                max_fragments_per_batch: None,
             },
             jrpc: source.jrpc.clone(),
             p2p: P2p {
@@ -141,6 +145,10 @@ impl LegacyNodeConfigConverter {
                 listen: source.rest.listen,
                 cors: None,
                 tls: None,
+                health: None,
+                auth: None,
+                limits: None,
+                max_fragments_per_batch: None,
             },
             jrpc: source.jrpc.clone(),
             p2p: P2p {
@@ -197,6 +205,10 @@ impl LegacyNodeConfigConverter {
                 listen: source.rest.listen,
                 cors: None,
                 tls: None,
+                health: None,
+                auth: None,
+                limits: None,
+                max_fragments_per_batch: None,
             },
             jrpc: source.jrpc.clone(),
             p2p: P2p {
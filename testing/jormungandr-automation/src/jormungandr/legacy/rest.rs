@@ -173,6 +173,14 @@ impl BackwardCompatibleRest {
         self.raw().leaders_log()?.text()
     }
 
+    pub fn leaders_schedule(&self) -> Result<String, reqwest::Error> {
+        self.raw().leaders_schedule()?.text()
+    }
+
+    pub fn leaders_dry_run(&self) -> Result<String, reqwest::Error> {
+        self.raw().leaders_dry_run()?.text()
+    }
+
     pub fn tip(&self) -> Result<Hash, RestError> {
         let tip = self.raw().tip()?.text()?;
         tip.parse().map_err(RestError::HashParseError)
@@ -281,4 +289,8 @@ impl BackwardCompatibleRest {
     pub fn set_origin<S: Into<String>>(&mut self, origin: S) {
         self.raw.rest_settings_mut().cors = Some(origin.into());
     }
+
+    pub fn set_token<S: Into<String>>(&mut self, token: S) {
+        self.raw.rest_settings_mut().token = Some(token.into());
+    }
 }
@@ -14,9 +14,10 @@ use chain_impl_mockchain::{
 use jormungandr_lib::{
     crypto::{account::Identifier, hash::Hash},
     interfaces::{
-        AccountState, AccountVotes, Address, EpochRewardsInfo, FragmentLog, FragmentStatus,
-        FragmentsProcessingSummary, LeadershipLog, NodeStatsDto, PeerRecord, PeerStats,
-        SettingsDto, StakeDistributionDto, Value, VotePlanId, VotePlanStatus,
+        AccountState, AccountVotes, Address, BlockDryRun, EpochRewardsInfo, FragmentLog,
+        FragmentStatus, FragmentsProcessingSummary, LeadershipLog, LeadershipSchedule,
+        NodeStatsDto, PeerRecord, PeerStats, SettingsDto, StakeDistributionDto, Value, VotePlanId,
+        VotePlanStatus,
     },
 };
 pub use raw::RawRest;
@@ -228,6 +229,14 @@ impl JormungandrRest {
         serde_json::from_str(&self.inner.leaders_log()?).map_err(RestError::CannotDeserialize)
     }
 
+    pub fn leaders_schedule(&self) -> Result<Vec<LeadershipSchedule>, RestError> {
+        serde_json::from_str(&self.inner.leaders_schedule()?).map_err(RestError::CannotDeserialize)
+    }
+
+    pub fn leaders_dry_run(&self) -> Result<BlockDryRun, RestError> {
+        serde_json::from_str(&self.inner.leaders_dry_run()?).map_err(RestError::CannotDeserialize)
+    }
+
     pub fn send_fragment(&self, fragment: Fragment) -> Result<MemPoolCheck, RestError> {
         self.inner.send_fragment(fragment).map_err(Into::into)
     }
@@ -282,4 +291,8 @@ impl JormungandrRest {
     pub fn set_origin<S: Into<String>>(&mut self, origin: S) {
         self.inner.set_origin(origin);
     }
+
+    pub fn set_token<S: Into<String>>(&mut self, token: S) {
+        self.inner.set_token(token);
+    }
 }
@@ -10,12 +10,12 @@ use chain_impl_mockchain::account::Identifier as JorAddress;
 use chain_impl_mockchain::{account, fragment::Fragment, header::HeaderId};
 use jormungandr_lib::{
     crypto::account::Identifier,
-    interfaces::{Address, FragmentsBatch, VotePlanId},
+    interfaces::{Address, BanRequest, FragmentsBatch, VotePlanId},
 };
 use jortestkit::process::Wait;
 use reqwest::{
     blocking::{Client, Response},
-    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
+    header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE},
 };
 use std::fmt;
 
@@ -144,6 +144,10 @@ impl RawRest {
             res = res.header(ORIGIN, origin.to_string());
         }
 
+        if let Some(token) = self.rest_settings().token.as_ref() {
+            res = res.bearer_auth(token);
+        }
+
         res.send()
     }
 
@@ -151,6 +155,22 @@ impl RawRest {
         format!("{}/{}/{}", self.uri, api_version, path)
     }
 
+    /// the liveness probe, served next to the API
+    pub fn health_live(&self) -> Result<Response, reqwest::Error> {
+        self.health("live")
+    }
+
+    /// the readiness probe, served next to the API
+    pub fn health_ready(&self) -> Result<Response, reqwest::Error> {
+        self.health("ready")
+    }
+
+    fn health(&self, probe: &str) -> Result<Response, reqwest::Error> {
+        let request = format!("{}/health/{}", self.uri.trim_end_matches("/api"), probe);
+        self.print_request_path(&request);
+        self.client.get(&request).send()
+    }
+
     pub fn stake_distribution(&self) -> Result<Response, reqwest::Error> {
         self.get("stake")
     }
@@ -230,10 +250,41 @@ impl RawRest {
         self.get("network/p2p/view")
     }
 
+    pub fn p2p_bans(&self) -> Result<Response, reqwest::Error> {
+        self.client
+            .get(&self.path(ApiVersion::V1, "network/p2p/bans"))
+            .headers(self.construct_headers())
+            .send()
+    }
+
+    pub fn p2p_ban(&self, request: &BanRequest) -> Result<Response, reqwest::Error> {
+        self.client
+            .post(&self.path(ApiVersion::V1, "network/p2p/bans"))
+            .headers(self.construct_headers())
+            .json(request)
+            .send()
+    }
+
+    pub fn p2p_unban(&self, target: &str) -> Result<Response, reqwest::Error> {
+        let request = format!("network/p2p/bans/{}", target);
+        self.client
+            .delete(&self.path(ApiVersion::V1, &request))
+            .headers(self.construct_headers())
+            .send()
+    }
+
     pub fn leaders_log(&self) -> Result<Response, reqwest::Error> {
         self.get("leaders/logs")
     }
 
+    pub fn leaders_schedule(&self) -> Result<Response, reqwest::Error> {
+        self.get("leaders/schedule")
+    }
+
+    pub fn leaders_dry_run(&self) -> Result<Response, reqwest::Error> {
+        self.post("leaders/dry_run", Vec::new())
+    }
+
     pub fn tip(&self) -> Result<Response, reqwest::Error> {
         self.get("tip")
     }
@@ -242,6 +293,10 @@ impl RawRest {
         self.get("settings")
     }
 
+    pub fn diagnostic(&self) -> Result<Response, reqwest::Error> {
+        self.get("diagnostic")
+    }
+
     pub fn shutdown(&self) -> Result<Response, reqwest::Error> {
         self.get("shutdown")
     }
@@ -260,6 +315,12 @@ impl RawRest {
             CONTENT_TYPE,
             HeaderValue::from_static("application/octet-stream"),
         );
+        if let Some(token) = self.rest_settings().token.as_ref() {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+            );
+        }
         headers
     }
 
@@ -287,6 +348,22 @@ impl RawRest {
         self.post("message", body)
     }
 
+    /// send the fragment as part of the trace described by the W3C
+    /// `traceparent` header value
+    pub fn send_fragment_with_traceparent(
+        &self,
+        fragment: Fragment,
+        traceparent: &str,
+    ) -> Result<Response, reqwest::Error> {
+        let mut headers = self.construct_headers();
+        headers.insert("traceparent", HeaderValue::from_str(traceparent).unwrap());
+        self.client
+            .post(&self.path(ApiVersion::V0, "message"))
+            .headers(headers)
+            .body(fragment.serialize_as_vec().unwrap())
+            .send()
+    }
+
     pub fn send_raw_fragments(&self, bodies: Vec<Vec<u8>>) -> Result<(), reqwest::Error> {
         let clients: Vec<reqwest::blocking::RequestBuilder> = bodies
             .into_iter()
@@ -6,6 +6,8 @@ pub struct RestSettings {
     pub use_https: bool,
     pub certificate: Option<Certificate>,
     pub cors: Option<String>,
+    /// bearer token sent to the nodes requiring authentication
+    pub token: Option<String>,
 }
 
 impl RestSettings {
@@ -15,6 +17,7 @@ impl RestSettings {
             use_https: true,
             certificate: None,
             cors: None,
+            token: None,
         }
     }
 }
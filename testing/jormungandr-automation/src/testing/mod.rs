@@ -11,6 +11,7 @@ pub mod process;
 pub mod resources;
 pub mod storage;
 pub mod time;
+pub mod trace_collector;
 pub mod verify;
 pub mod vit;
 
@@ -32,5 +33,6 @@ pub use jortestkit::{
     web::download_file,
 };
 pub use storage::{BranchCount, StopCriteria, StorageBuilder};
+pub use trace_collector::TraceCollector;
 pub use verify::{assert, assert_equals, Error as VerificationError};
 pub use vit::{VoteCastCounter, VotePlanBuilder, VotePlanExtension};
@@ -0,0 +1,154 @@
+use self::proto::collector::trace::v1::ExportTraceServiceRequest;
+use prost::Message as _;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Messages of the OpenTelemetry protocol, generated from the definitions
+/// of the version the node exports
+mod proto {
+    pub mod common {
+        pub mod v1 {
+            tonic::include_proto!("opentelemetry.proto.common.v1");
+        }
+    }
+
+    pub mod resource {
+        pub mod v1 {
+            tonic::include_proto!("opentelemetry.proto.resource.v1");
+        }
+    }
+
+    pub mod trace {
+        pub mod v1 {
+            tonic::include_proto!("opentelemetry.proto.trace.v1");
+        }
+    }
+
+    pub mod collector {
+        pub mod trace {
+            pub mod v1 {
+                tonic::include_proto!("opentelemetry.proto.collector.trace.v1");
+            }
+        }
+    }
+}
+
+/// Local stand-in for an OTLP/HTTP trace collector.
+///
+/// It accepts every export request and keeps the decoded requests.
+pub struct TraceCollector {
+    address: SocketAddr,
+    exports: Arc<Mutex<Vec<ExportTraceServiceRequest>>>,
+}
+
+impl TraceCollector {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let exports = Arc::new(Mutex::new(Vec::new()));
+
+        let collected = Arc::clone(&exports);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let collected = Arc::clone(&collected);
+                thread::spawn(move || serve(stream, collected));
+            }
+        });
+
+        Self { address, exports }
+    }
+
+    /// the endpoint to configure as the node's trace collector
+    pub fn endpoint(&self) -> String {
+        format!("http://{}/v1/traces", self.address)
+    }
+
+    /// the names of the spans exported so far as part of the given trace
+    pub fn spans_in_trace(&self, trace_id: &[u8; 16]) -> Vec<String> {
+        self.exports
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|export| &export.resource_spans)
+            .flat_map(|resource| &resource.instrumentation_library_spans)
+            .flat_map(|library| &library.spans)
+            .filter(|span| span.trace_id == trace_id)
+            .map(|span| span.name.clone())
+            .collect()
+    }
+
+    /// whether a span with the given name has been exported as part of
+    /// the given trace
+    pub fn has_span_in_trace(&self, name: &str, trace_id: &[u8; 16]) -> bool {
+        self.spans_in_trace(trace_id)
+            .iter()
+            .any(|span| span == name)
+    }
+
+    /// wait until a span with the given name has been exported as part of
+    /// the given trace, the spans being exported in batches by the node
+    pub fn wait_for_span_in_trace(&self, name: &str, trace_id: &[u8; 16], timeout: Duration) {
+        let started = Instant::now();
+        while !self.has_span_in_trace(name, trace_id) {
+            if started.elapsed() > timeout {
+                panic!(
+                    "span '{}' of trace {} not exported after {:?}, exported spans: {:?}",
+                    name,
+                    hex::encode(trace_id),
+                    timeout,
+                    self.spans_in_trace(trace_id)
+                );
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+    }
+}
+
+fn serve(stream: TcpStream, exports: Arc<Mutex<Vec<ExportTraceServiceRequest>>>) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    let mut reader = BufReader::new(stream);
+
+    // the exporter keeps the connection alive between the batches
+    while let Some(body) = read_request(&mut reader) {
+        let response = match ExportTraceServiceRequest::decode(body.as_slice()) {
+            Ok(export) => {
+                exports.lock().unwrap().push(export);
+                "HTTP/1.1 200 OK\r\ncontent-type: application/x-protobuf\r\ncontent-length: 0\r\n\r\n"
+            }
+            Err(_) => "HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\n\r\n",
+        };
+        if writer.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+fn read_request(reader: &mut BufReader<TcpStream>) -> Option<Vec<u8>> {
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok()?;
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+    Some(body)
+}
@@ -0,0 +1,151 @@
+use assert_fs::{
+    fixture::{FileWriteStr, PathChild},
+    TempDir,
+};
+use jormungandr_automation::{
+    jcli::JCli,
+    jormungandr::{ConfigurationBuilder, JormungandrProcess, Starter},
+    testing::time::{get_current_date, wait_for_epoch},
+};
+use jormungandr_lib::interfaces::{
+    BlockContentMaxSize, BlockDate, ConfigParam, ConfigParams, ConsensusLeaderId,
+    UpdateProposalPhase, UpdateProposalStatus,
+};
+use jortestkit::process::Wait;
+use std::time::Duration;
+
+/// wait until the node lists the update proposal in a state matching
+/// `predicate`
+fn wait_for_proposal<F: Fn(&UpdateProposalStatus) -> bool>(
+    jcli: &JCli,
+    jormungandr: &JormungandrProcess,
+    proposal_id: &str,
+    wait: &Wait,
+    predicate: F,
+) -> UpdateProposalStatus {
+    for _ in 0..wait.attempts() {
+        let proposal = jcli
+            .rest()
+            .v0()
+            .governance_proposals(jormungandr.rest_uri())
+            .into_iter()
+            .find(|proposal| proposal.id.to_string() == proposal_id);
+        if let Some(proposal) = proposal.filter(&predicate) {
+            return proposal;
+        }
+        std::thread::sleep(wait.sleep_duration());
+    }
+    panic!(
+        "the update proposal {} did not reach the expected state",
+        proposal_id
+    );
+}
+
+#[test]
+fn propose_list_and_vote_with_governance_commands() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let jcli: JCli = Default::default();
+    let wallet_initial_funds = 1_000_000;
+
+    let alice = thor::Wallet::default();
+    let alice_sk = temp_dir.child("alice_sk");
+    alice.save_to_path(alice_sk.path()).unwrap();
+
+    let bob = thor::Wallet::default();
+    let bob_sk = temp_dir.child("bob_sk");
+    bob.save_to_path(bob_sk.path()).unwrap();
+
+    let config = ConfigurationBuilder::new()
+        .with_funds(vec![
+            alice.to_initial_fund(wallet_initial_funds),
+            bob.to_initial_fund(wallet_initial_funds),
+        ])
+        .with_consensus_leaders_ids(vec![
+            ConsensusLeaderId::from(bob.public_key()),
+            ConsensusLeaderId::from(alice.public_key()),
+        ])
+        .build(&temp_dir);
+
+    let new_block_context_max_size = 1000;
+    let change_params = ConfigParams::new(vec![ConfigParam::BlockContentMaxSize(
+        BlockContentMaxSize::from(new_block_context_max_size),
+    )]);
+    let change_param_path = temp_dir.child("change_param_file.yaml");
+    change_param_path
+        .write_str(&serde_yaml::to_string(&change_params).unwrap())
+        .unwrap();
+
+    let jormungandr = Starter::new()
+        .temp_dir(temp_dir)
+        .config(config)
+        .start()
+        .unwrap();
+
+    let current_epoch = get_current_date(&mut jormungandr.rest()).epoch();
+    let wait = Wait::new(Duration::from_secs(5), 10);
+    let valid_until = BlockDate::new(3, 0);
+
+    let proposal_id = jcli.governance().propose(
+        change_param_path.path(),
+        alice_sk.path(),
+        valid_until,
+        jormungandr.rest_uri(),
+    );
+    let proposal = wait_for_proposal(&jcli, &jormungandr, &proposal_id, &wait, |_| true);
+    assert_eq!(
+        proposal.proposer,
+        ConsensusLeaderId::from(alice.public_key())
+    );
+    assert_eq!(proposal.changes, change_params);
+    assert!(proposal.votes.is_empty());
+    assert_eq!(proposal.votes_needed, 2);
+    assert!(matches!(
+        proposal.phase,
+        UpdateProposalPhase::Pending { .. }
+    ));
+
+    jcli.governance().vote(
+        &proposal_id,
+        alice_sk.path(),
+        valid_until,
+        jormungandr.rest_uri(),
+    );
+    let proposal = wait_for_proposal(&jcli, &jormungandr, &proposal_id, &wait, |proposal| {
+        !proposal.votes.is_empty()
+    });
+    assert_eq!(
+        proposal.votes,
+        vec![ConsensusLeaderId::from(alice.public_key())]
+    );
+    assert!(matches!(
+        proposal.phase,
+        UpdateProposalPhase::Pending { .. }
+    ));
+
+    jcli.governance().vote(
+        &proposal_id,
+        bob_sk.path(),
+        valid_until,
+        jormungandr.rest_uri(),
+    );
+    let proposal = wait_for_proposal(&jcli, &jormungandr, &proposal_id, &wait, |proposal| {
+        proposal.votes.len() == 2
+    });
+    assert_eq!(proposal.phase, UpdateProposalPhase::Accepted);
+
+    // the accepted proposal is applied at the start of the next epoch
+    wait_for_epoch(current_epoch + 2, jormungandr.rest());
+    assert!(jcli
+        .rest()
+        .v0()
+        .governance_proposals(jormungandr.rest_uri())
+        .is_empty());
+    assert_eq!(
+        jcli.rest()
+            .v0()
+            .settings(jormungandr.rest_uri())
+            .block_content_max_size,
+        new_block_context_max_size
+    );
+}
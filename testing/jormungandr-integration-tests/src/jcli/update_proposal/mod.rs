@@ -1 +1,2 @@
 mod e2e;
+mod governance;
@@ -0,0 +1,48 @@
+use assert_fs::{prelude::*, TempDir};
+use jormungandr_automation::jormungandr::{ConfigurationBuilder, Starter};
+use jormungandr_lib::interfaces::{RestAuth, RestRole};
+use reqwest::StatusCode;
+
+const CREDENTIALS: &str = r#"
+- token: reader-token
+  role: read_only
+- token: admin-token
+  role: admin
+"#;
+
+#[test]
+pub fn rest_endpoints_require_role() {
+    let temp_dir = TempDir::new().unwrap();
+    let credentials_file = temp_dir.child("rest_credentials.yaml");
+    credentials_file.write_str(CREDENTIALS).unwrap();
+
+    let config = ConfigurationBuilder::new()
+        .with_rest_auth_config(RestAuth {
+            credentials_file: credentials_file.path().to_path_buf(),
+            anonymous_role: Some(RestRole::ReadOnly),
+        })
+        .build(&temp_dir);
+    let jormungandr = Starter::new().config(config).start().unwrap();
+
+    let mut rest = jormungandr.rest();
+    assert_eq!(rest.raw().stats().unwrap().status(), StatusCode::OK);
+    assert_eq!(
+        rest.raw().diagnostic().unwrap().status(),
+        StatusCode::FORBIDDEN
+    );
+
+    rest.set_token("unknown-token");
+    assert_eq!(
+        rest.raw().stats().unwrap().status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    rest.set_token("reader-token");
+    assert_eq!(
+        rest.raw().diagnostic().unwrap().status(),
+        StatusCode::FORBIDDEN
+    );
+
+    rest.set_token("admin-token");
+    assert_eq!(rest.raw().diagnostic().unwrap().status(), StatusCode::OK);
+}
@@ -85,6 +85,7 @@ pub fn test_jormungandr_with_wrong_logger_fails_to_start() {
             format: "xml".to_string(),
             level: "info".to_string(),
             output: LogOutput::Stderr,
+            trace_collector_endpoint: None,
         }))
         .build(&temp_dir);
     Starter::new()
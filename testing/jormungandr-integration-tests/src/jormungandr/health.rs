@@ -0,0 +1,39 @@
+use assert_fs::TempDir;
+use jormungandr_automation::jormungandr::{ConfigurationBuilder, Starter};
+use jormungandr_lib::interfaces::{Health, HealthCheckName, HealthReport};
+use reqwest::StatusCode;
+
+#[test]
+pub fn lone_node_is_live_but_not_ready() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ConfigurationBuilder::new().build(&temp_dir);
+    let jormungandr = Starter::new().config(config).start().unwrap();
+
+    let response = jormungandr.rest().raw().health_live().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // a connected peer is required by default
+    let response = jormungandr.rest().raw().health_ready().unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let report: HealthReport = response.json().unwrap();
+    assert!(!report.healthy);
+    let failures: Vec<_> = report.failures().map(|check| check.name).collect();
+    assert_eq!(failures, vec![HealthCheckName::Peers]);
+}
+
+#[test]
+pub fn node_is_ready_within_thresholds() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ConfigurationBuilder::new()
+        .with_rest_health_config(Health {
+            max_tip_age: None,
+            min_connected_peers: Some(0),
+        })
+        .build(&temp_dir);
+    let jormungandr = Starter::new().config(config).start().unwrap();
+
+    let response = jormungandr.rest().raw().health_ready().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: HealthReport = response.json().unwrap();
+    assert!(report.healthy, "{:?}", report);
+}
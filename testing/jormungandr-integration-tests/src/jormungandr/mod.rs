@@ -1,3 +1,4 @@
+pub mod auth;
 pub mod bft;
 pub mod block;
 pub mod cors;
@@ -9,13 +10,16 @@ pub mod explorer;
 pub mod fragments;
 pub mod genesis;
 pub mod grpc;
+pub mod health;
 mod leadership;
 pub mod legacy;
 pub mod mempool;
+pub mod peer_rules;
 pub mod persistent_log;
 pub mod recovery;
 pub mod rest;
 pub mod tls;
 pub mod tokens;
+pub mod trace_export;
 pub mod transactions;
 pub mod vit;
@@ -0,0 +1,58 @@
+use assert_fs::TempDir;
+use jormungandr_automation::jormungandr::{ConfigurationBuilder, Starter};
+use jormungandr_lib::interfaces::{BanRequest, PeerBan, PeerTarget};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+const BANNED_IP: &str = "10.0.0.1";
+
+fn ban_request(duration: Option<Duration>) -> BanRequest {
+    BanRequest {
+        target: PeerTarget::Ip(BANNED_IP.parse().unwrap()),
+        duration: duration.map(Into::into),
+        reason: Some("misbehaving".to_owned()),
+    }
+}
+
+#[test]
+pub fn ban_and_unban_peer() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ConfigurationBuilder::new().build(&temp_dir);
+    let jormungandr = Starter::new().config(config).start().unwrap();
+    let rest = jormungandr.rest().raw();
+
+    let response = rest
+        .p2p_ban(&ban_request(Some(Duration::from_secs(3600))))
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = rest.p2p_bans().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bans: Vec<PeerBan> = response.json().unwrap();
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0].target, ban_request(None).target);
+    assert!(bans[0].until.is_some());
+
+    let response = rest.p2p_unban(BANNED_IP).unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bans: Vec<PeerBan> = rest.p2p_bans().unwrap().json().unwrap();
+    assert!(bans.is_empty());
+
+    let response = rest.p2p_unban(BANNED_IP).unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+pub fn too_long_ban_is_refused() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ConfigurationBuilder::new().build(&temp_dir);
+    let jormungandr = Starter::new().config(config).start().unwrap();
+    let rest = jormungandr.rest().raw();
+
+    let response = rest
+        .p2p_ban(&ban_request(Some(Duration::from_secs(u64::MAX))))
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let bans: Vec<PeerBan> = rest.p2p_bans().unwrap().json().unwrap();
+    assert!(bans.is_empty());
+}
@@ -0,0 +1,63 @@
+use crate::startup;
+use chain_impl_mockchain::block::BlockDate;
+use jormungandr_automation::{
+    jormungandr::{ConfigurationBuilder, MemPoolCheck},
+    testing::TraceCollector,
+};
+use jormungandr_lib::interfaces::{Log, LogEntry, LogOutput};
+use std::time::Duration;
+use thor::FragmentVerifier;
+
+const TRACE_ID: [u8; 16] = [
+    0x4b, 0xf9, 0x2f, 0x35, 0x77, 0xb3, 0x4d, 0xa6, 0xa3, 0xce, 0x92, 0x9d, 0x0e, 0x0e, 0x47, 0x36,
+];
+const PARENT_SPAN_ID: &str = "00f067aa0ba902b7";
+
+#[test]
+fn fragment_submission_is_traced_up_to_block_inclusion() {
+    let collector = TraceCollector::start();
+    let sender = thor::Wallet::default();
+    let receiver = thor::Wallet::default();
+
+    let (jormungandr, _) = startup::start_stake_pool(
+        &[sender.clone()],
+        &[receiver.clone()],
+        ConfigurationBuilder::new().with_log(Log(LogEntry {
+            format: "json".to_string(),
+            level: "debug".to_string(),
+            output: LogOutput::Stdout,
+            trace_collector_endpoint: Some(collector.endpoint()),
+        })),
+    )
+    .unwrap();
+
+    let fragment = thor::FragmentBuilder::new(
+        &jormungandr.genesis_block_hash(),
+        &jormungandr.fees(),
+        BlockDate::first().next_epoch(),
+    )
+    .transaction(&sender, receiver.address(), 100.into())
+    .unwrap();
+    let fragment_id = fragment.hash();
+
+    let traceparent = format!("00-{}-{}-01", hex::encode(TRACE_ID), PARENT_SPAN_ID);
+    let response = jormungandr
+        .rest()
+        .raw()
+        .send_fragment_with_traceparent(fragment, &traceparent)
+        .unwrap();
+    assert!(response.status().is_success());
+
+    FragmentVerifier::wait_and_verify_is_in_block(
+        Duration::from_secs(10),
+        MemPoolCheck::new(fragment_id),
+        &jormungandr,
+    )
+    .unwrap();
+
+    // the request, its processing by the fragment pool and the fragment
+    // pending until its inclusion in a block all belong to the caller's trace
+    for span in ["rest_api_request", "incoming_fragments", "pending_fragment"] {
+        collector.wait_for_span_in_trace(span, &TRACE_ID, Duration::from_secs(30));
+    }
+}
@@ -4,7 +4,7 @@ use hersir::{
     config::{BlockchainBuilder, SpawnParams},
 };
 use jormungandr_automation::testing::time;
-use jormungandr_lib::interfaces::BlockDate;
+use jormungandr_lib::interfaces::{BlockDate, LeadershipLogStatus};
 const LEADER_1: &str = "Leader1";
 const LEADER_2: &str = "Leader2";
 
@@ -53,3 +53,79 @@ pub fn leader_late_start_preserves_leadership_log() {
         "leadership log should NOT be empty in new epoch",
     );
 }
+
+#[test]
+pub fn bft_leaders_schedule_covers_current_and_next_epoch() {
+    let mut controller = NetworkBuilder::default()
+        .topology(
+            Topology::default()
+                .with_node(Node::new(LEADER_1))
+                .with_node(Node::new(LEADER_2).with_trusted_peer(LEADER_1)),
+        )
+        .blockchain_config(
+            BlockchainBuilder::default()
+                .consensus(ConsensusVersion::Bft)
+                .slots_per_epoch(60)
+                .slot_duration(2)
+                .leader(LEADER_1)
+                .leader(LEADER_2)
+                .build(),
+        )
+        .build()
+        .unwrap();
+
+    let leader_1 = controller.spawn(SpawnParams::new(LEADER_1)).unwrap();
+
+    time::wait_for_date(BlockDate::new(0, 5), leader_1.rest());
+
+    let schedules = leader_1.rest().leaders_schedule().unwrap();
+    let epochs: Vec<u32> = schedules.iter().map(|s| s.epoch()).collect();
+    assert_eq!(epochs, vec![0, 1]);
+
+    // the two BFT leaders take turns, so each of them is elected for half
+    // of the slots of every epoch
+    for schedule in schedules {
+        assert_eq!(schedule.slots().len(), 30);
+        assert!(schedule
+            .slots()
+            .iter()
+            .all(|slot| slot.scheduled_at_date().epoch() == schedule.epoch()));
+    }
+}
+
+#[test]
+pub fn leadership_log_records_block_assembly_and_dry_run() {
+    let mut controller = NetworkBuilder::default()
+        .topology(Topology::default().with_node(Node::new(LEADER_1)))
+        .blockchain_config(
+            BlockchainBuilder::default()
+                .consensus(ConsensusVersion::Bft)
+                .slots_per_epoch(60)
+                .slot_duration(2)
+                .leader(LEADER_1)
+                .build(),
+        )
+        .build()
+        .unwrap();
+
+    let leader_1 = controller.spawn(SpawnParams::new(LEADER_1)).unwrap();
+
+    time::wait_for_date(BlockDate::new(0, 5), leader_1.rest());
+
+    let logs = leader_1.rest().leaders_log().unwrap();
+    let produced: Vec<_> = logs
+        .iter()
+        .filter(|log| matches!(log.status(), LeadershipLogStatus::Block { .. }))
+        .collect();
+    assert!(!produced.is_empty(), "some blocks should be produced");
+    for log in produced {
+        assert!(log.deadline().is_some());
+        assert!(log.hard_deadline().is_some());
+        assert!(log.block_assembly().is_some());
+    }
+
+    // nothing was sent to the mempool, the candidate block is empty
+    let dry_run = leader_1.rest().leaders_dry_run().unwrap();
+    assert!(dry_run.chain_length > 0);
+    assert_eq!(dry_run.assembly.fragment_count, 0);
+}
@@ -99,6 +99,7 @@ impl TestnetConfig {
                 format: "json".to_string(),
                 level: "info".to_string(),
                 output: LogOutput::File(temp_dir.child("leader.log").path().to_path_buf()),
+                trace_collector_endpoint: None,
             }))
             .with_trusted_peers(self.trusted_peers.clone())
             .with_public_address(format!("/ip4/{}/tcp/{}", self.public_ip, self.public_port))
@@ -113,6 +114,7 @@ impl TestnetConfig {
                 format: "json".to_string(),
                 level: "info".to_string(),
                 output: LogOutput::File(temp_dir.child("passive.log").path().to_path_buf()),
+                trace_collector_endpoint: None,
             }))
             .with_trusted_peers(self.trusted_peers.clone())
             .build(temp_dir)